@@ -0,0 +1,173 @@
+// the boundary and portal math that runs for every actor or every portal
+// every frame - wrapping, working out which faces a portal spills onto, and
+// drawing it folded over them. then the systems themselves over a couple of
+// thousand drifting cubes, one thread against all of them:
+//
+// cargo bench --bench boundary
+use bevy::prelude::*;
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    Criterion,
+};
+use nateroids::{
+    stress_schedule,
+    stress_world,
+    Boundary,
+    BoundaryFace,
+    CameraOrientation,
+    EntityBatching,
+    LineListBuilder,
+    Portal,
+    STRESS_CUBE_COUNT,
+};
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+const POSITIONS: usize = 1000;
+const PORTAL_RADIUS: f32 = 20.;
+// what PortalConfig draws them with
+const PORTAL_RESOLUTION: u32 = 128;
+
+// spread over a box a bit bigger than the boundary, so some of them are out
+// past the walls and wrap - the same every run
+fn positions(boundary: &Boundary) -> Vec<Vec3> {
+    let mut rng = StdRng::seed_from_u64(487);
+    let reach = boundary.transform.scale * 0.6;
+
+    (0..POSITIONS)
+        .map(|_| {
+            boundary.transform.translation
+                + Vec3::new(
+                    rng.random_range(-reach.x..reach.x),
+                    rng.random_range(-reach.y..reach.y),
+                    rng.random_range(-reach.z..reach.z),
+                )
+        })
+        .collect()
+}
+
+// the default boundary is 220 x 110 x 110 around the origin - a portal in the
+// middle of the right face, one over its top edge and one over its corner
+fn portals() -> [(&'static str, Portal); 3] {
+    [
+        (
+            "on_face",
+            Portal::on_face(BoundaryFace::Right, Vec3::new(110., 0., 0.), PORTAL_RADIUS),
+        ),
+        (
+            "over_edge",
+            Portal::on_face(BoundaryFace::Right, Vec3::new(110., 45., 0.), PORTAL_RADIUS),
+        ),
+        (
+            "at_corner",
+            Portal::on_face(BoundaryFace::Right, Vec3::new(110., 45., 45.), PORTAL_RADIUS),
+        ),
+    ]
+}
+
+fn teleport(c: &mut Criterion) {
+    let boundary = Boundary::default();
+    let positions = positions(&boundary);
+
+    let mut group = c.benchmark_group("teleport_1000");
+
+    group.bench_function("calculate_teleport_position_on", |b| {
+        b.iter(|| {
+            black_box(&positions)
+                .iter()
+                .filter(|position| boundary.calculate_teleport_position_on(**position, BVec3::TRUE).1)
+                .count()
+        })
+    });
+
+    // the spaceship bounces off the top and bottom, so it only wraps on x
+    // and z
+    group.bench_function("calculate_teleport_position_on/two_axes", |b| {
+        b.iter(|| {
+            black_box(&positions)
+                .iter()
+                .filter(|position| {
+                    boundary
+                        .calculate_teleport_position_on(**position, BVec3::new(true, false, true))
+                        .1
+                })
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+// the faces a portal spills onto and where its circle crosses their edges
+fn face_intersections(c: &mut Criterion) {
+    let boundary = Boundary::default();
+    let mut group = c.benchmark_group("portal_folds");
+
+    for (name, portal) in portals() {
+        group.bench_function(name, |b| {
+            b.iter(|| boundary.portal_folds(black_box(&portal)).count())
+        });
+    }
+
+    group.finish();
+}
+
+// the whole portal into a line list, the way update_portal_lines does it - the
+// builder is kept and cleared so this is the math and not the allocator
+fn draw_portal(c: &mut Criterion) {
+    let boundary = Boundary::default();
+    let orientation = CameraOrientation::default();
+    let color = Color::WHITE;
+    let mut lines = LineListBuilder::default();
+    let mut group = c.benchmark_group("draw_portal");
+
+    for (name, portal) in portals() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                lines.clear();
+                boundary.draw_portal(
+                    &mut lines,
+                    black_box(&portal),
+                    color,
+                    PORTAL_RESOLUTION,
+                    &orientation,
+                );
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// teleport_at_boundary and the portal tracking as they run in the game, every
+// cube done by one thread and then split up the way bevy splits them - the
+// difference is what spreading them across threads buys
+fn teleport_and_portal_systems(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("teleport_and_portals_{STRESS_CUBE_COUNT}"));
+
+    for (name, batching) in [
+        ("serial", EntityBatching::serial()),
+        ("parallel", EntityBatching::default()),
+    ] {
+        let mut world = stress_world(STRESS_CUBE_COUNT);
+        world.insert_resource(batching);
+        let mut schedule = stress_schedule();
+        group.bench_function(name, |b| b.iter(|| schedule.run(&mut world)));
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    teleport,
+    face_intersections,
+    draw_portal,
+    teleport_and_portal_systems
+);
+criterion_main!(benches);
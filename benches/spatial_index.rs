@@ -0,0 +1,152 @@
+// the spatial index against the scans it replaced, with 500 actors spread over
+// the default boundary:
+//
+// cargo bench --bench spatial_index
+use bevy::prelude::*;
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    Criterion,
+};
+use nateroids::{
+    ActorKind,
+    Boundary,
+    SpatialIndex,
+};
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+const ACTORS: usize = 500;
+// about the danger music's radius
+const WIDE_RADIUS: f32 = 60.;
+// about a near miss
+const NARROW_RADIUS: f32 = 10.;
+
+// mostly nateroids with a spaceship and some missiles - the same every run
+fn actors(boundary: &Boundary) -> Vec<(Entity, ActorKind, Vec3)> {
+    let mut rng = StdRng::seed_from_u64(486);
+    let half_size = boundary.transform.scale / 2.;
+
+    (0..ACTORS)
+        .map(|i| {
+            let kind = match i {
+                0 => ActorKind::Spaceship,
+                i if i % 10 == 0 => ActorKind::Missile,
+                _ => ActorKind::Nateroid,
+            };
+            let position = Vec3::new(
+                rng.random_range(-half_size.x..half_size.x),
+                rng.random_range(-half_size.y..half_size.y),
+                rng.random_range(-half_size.z..half_size.z),
+            );
+            (
+                Entity::from_raw(i as u32),
+                kind,
+                boundary.transform.translation + position,
+            )
+        })
+        .collect()
+}
+
+// what music and the threat indicators did before the index - look at every
+// actor, the short way round through the walls
+fn naive_within(
+    boundary: &Boundary,
+    actors: &[(Entity, ActorKind, Vec3)],
+    center: Vec3,
+    radius: f32,
+    kind: ActorKind,
+) -> usize {
+    actors
+        .iter()
+        .filter(|(_, actor_kind, position)| {
+            *actor_kind == kind
+                && boundary.wrapped_delta(center, *position).length_squared() <= radius * radius
+        })
+        .count()
+}
+
+fn spatial_index(c: &mut Criterion) {
+    let boundary = Boundary::default();
+    let actors = actors(&boundary);
+    let spaceship = actors[0].2;
+
+    let mut index = SpatialIndex::default();
+    index.rebuild(&boundary, actors.iter().copied());
+
+    // the counts have to agree or the timings don't mean anything
+    assert_eq!(
+        index
+            .within_of_kind(spaceship, WIDE_RADIUS, ActorKind::Nateroid)
+            .count(),
+        naive_within(&boundary, &actors, spaceship, WIDE_RADIUS, ActorKind::Nateroid),
+    );
+
+    let mut group = c.benchmark_group("spatial_index_500");
+
+    // once a frame, whatever the queries are
+    group.bench_function("rebuild", |b| {
+        b.iter(|| index.rebuild(&boundary, black_box(&actors).iter().copied()))
+    });
+
+    // the danger music - one query around the spaceship
+    group.bench_function("one_query/naive", |b| {
+        b.iter(|| {
+            naive_within(
+                &boundary,
+                black_box(&actors),
+                black_box(spaceship),
+                WIDE_RADIUS,
+                ActorKind::Nateroid,
+            )
+        })
+    });
+    group.bench_function("one_query/index", |b| {
+        b.iter(|| {
+            index
+                .within_of_kind(black_box(spaceship), WIDE_RADIUS, ActorKind::Nateroid)
+                .count()
+        })
+    });
+
+    // something like near misses - every actor asks what's close to it, which
+    // is where the scans add up. the index pays for its rebuild here
+    group.bench_function("every_actor/naive", |b| {
+        b.iter(|| {
+            actors
+                .iter()
+                .map(|(_, _, position)| {
+                    naive_within(
+                        &boundary,
+                        black_box(&actors),
+                        *position,
+                        NARROW_RADIUS,
+                        ActorKind::Nateroid,
+                    )
+                })
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("every_actor/index", |b| {
+        b.iter(|| {
+            index.rebuild(&boundary, black_box(&actors).iter().copied());
+            actors
+                .iter()
+                .map(|(_, _, position)| {
+                    index
+                        .within_of_kind(*position, NARROW_RADIUS, ActorKind::Nateroid)
+                        .count()
+                })
+                .sum::<usize>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, spatial_index);
+criterion_main!(benches);
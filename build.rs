@@ -0,0 +1,14 @@
+// the resource inspectors are a dev tool - debug builds always get them and
+// release builds only get them with `--features dev-tools`. code checks for
+// them with #[cfg(dev_tools)]
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(dev_tools)");
+    println!("cargo::rerun-if-changed=build.rs");
+
+    let debug_assertions = std::env::var_os("CARGO_CFG_DEBUG_ASSERTIONS").is_some();
+    let dev_tools_feature = std::env::var_os("CARGO_FEATURE_DEV_TOOLS").is_some();
+
+    if debug_assertions || dev_tools_feature {
+        println!("cargo::rustc-cfg=dev_tools");
+    }
+}
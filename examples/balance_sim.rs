@@ -0,0 +1,7 @@
+// plays the game headless for tuning - the same as the headless feature but
+// without rebuilding the game for it. see HeadlessPlugin for the arguments:
+//
+// cargo run --release --example balance_sim -- --waves 50 --seed 42
+use bevy::prelude::*;
+
+fn main() -> AppExit { nateroids::app(true).run() }
@@ -1,6 +1,6 @@
-use crate::global_input::{
-    toggle_active,
-    GlobalAction,
+use crate::{
+    physics::showing_aabbs,
+    schedule::FrameSet,
 };
 use bevy::{
     color::palettes::tailwind,
@@ -13,7 +13,7 @@ impl Plugin for AabbPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            draw_aabb_system.run_if(toggle_active(false, GlobalAction::AABBs)),
+            draw_aabb_system.in_set(FrameSet::Visual).run_if(showing_aabbs),
         );
     }
 }
@@ -84,7 +84,35 @@ pub fn get_scene_aabb(scenes: &Assets<Scene>, meshes: &Assets<Mesh>, handle: &Ha
     }
 }
 
-fn get_mesh_aabb(mesh: &Mesh) -> Aabb {
+/// every vertex of every mesh in the scene, in the same space as
+/// get_scene_aabb - what a convex hull collider gets wrapped around
+pub fn get_scene_vertices(
+    scenes: &Assets<Scene>,
+    meshes: &Assets<Mesh>,
+    handle: &Handle<Scene>,
+) -> Vec<Vec3> {
+    let Some(scene) = scenes.get(handle) else {
+        return Vec::new();
+    };
+    scene
+        .world
+        .iter_entities()
+        .filter_map(|entity| entity.get::<Mesh3d>())
+        .filter_map(|mesh_handle| meshes.get(mesh_handle))
+        .flat_map(get_mesh_vertices)
+        .collect()
+}
+
+pub fn get_mesh_vertices(mesh: &Mesh) -> Vec<Vec3> {
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => {
+            positions.iter().copied().map(Vec3::from).collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+pub fn get_mesh_aabb(mesh: &Mesh) -> Aabb {
     if let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
         let mut min = Vec3::splat(f32::MAX);
         let mut max = Vec3::splat(f32::MIN);
@@ -1,20 +1,29 @@
 use crate::{
     actor::{
         actor_template::{MissileConfig, NateroidConfig, SpaceshipConfig},
-        get_scene_aabb, Aabb, Teleporter,
+        get_mesh_aabb, get_mesh_vertices, get_scene_aabb, get_scene_vertices, Aabb, Drag, MaxSpeed,
+        Teleporter,
     },
-    asset_loader::{AssetsState, SceneAssets},
+    asset_loader::{ModelSource, SceneAssets, SceneReloaded},
+    manifest::SizeWeights,
     camera::RenderLayer,
-    global_input::{toggle_active, GlobalAction},
+    dump::DumpAppExt,
+    global_input::{GlobalAction, InspectorAppExt},
+    interpolation::InterpolatedTransform,
+    mutators::Mutators,
     playfield::{ActorPortals, Boundary},
+    rng::GameRng,
+    schedule::FrameSet,
+    state::GameState,
 };
 use bevy::{ecs::system::EntityCommands, prelude::*, render::view::RenderLayers};
 use bevy_inspector_egui::{
-    inspector_options::std_options::NumberDisplay, prelude::*, quick::ResourceInspectorPlugin,
+    inspector_options::std_options::NumberDisplay, prelude::*,
 };
 use bevy_rapier3d::prelude::*;
 use rand::Rng;
-use std::{fmt, ops::Range};
+use serde::Deserialize;
+use std::{borrow::Cow, fmt, ops::Range};
 
 // this is how far off we are from blender for the assets we're loading
 // we need to get them scaled up to generate a usable aabb
@@ -30,19 +39,29 @@ impl Plugin for ActorSpawner {
         app.register_type::<MissileConfig>()
             .register_type::<NateroidConfig>()
             .register_type::<SpaceshipConfig>()
-            .add_systems(OnEnter(AssetsState::Loaded), initialize_actor_configs)
-            .add_plugins(
-                ResourceInspectorPlugin::<MissileConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::MissileInspector)),
+            .init_resource::<GameRng>()
+            .register_type::<GameRng>()
+            // load-dump carries on from the same draw
+            .add_dump_resource::<GameRng>()
+            // only once the models are actually there - the configs get their
+            // sizes from the meshes
+            .add_systems(
+                OnTransition {
+                    exited:  GameState::Loading,
+                    entered: GameState::Splash,
+                },
+                initialize_actor_configs,
             )
-            .add_plugins(
-                ResourceInspectorPlugin::<NateroidConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::NateroidInspector)),
+            .add_systems(
+                Update,
+                (resize_reloaded_actors, rebuild_actor_colliders)
+                    .chain()
+                    .in_set(FrameSet::Input)
+                    .run_if(resource_exists::<SpaceshipConfig>),
             )
-            .add_plugins(
-                ResourceInspectorPlugin::<SpaceshipConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::SpaceshipInspector)),
-            );
+            .add_inspector::<MissileConfig>(GlobalAction::MissileInspector)
+            .add_inspector::<NateroidConfig>(GlobalAction::NateroidInspector)
+            .add_inspector::<SpaceshipConfig>(GlobalAction::SpaceshipInspector);
     }
 }
 
@@ -52,10 +71,24 @@ pub struct Health(pub f32);
 #[derive(Reflect, Component, Clone, Debug)]
 pub struct CollisionDamage(pub f32);
 
-#[derive(Reflect, Debug, Clone, PartialEq, Eq)]
+// what shape the collider is - its size comes from the model. a convex hull
+// wraps the model's own vertices, so it fits a lot closer than the other two
+// but it's dearer for physics
+#[derive(Reflect, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum ColliderType {
     Ball,
     Cuboid,
+    ConvexHull,
+}
+
+impl ColliderType {
+    fn matches(&self, collider: &Collider) -> bool {
+        match self {
+            ColliderType::Ball => collider.as_ball().is_some(),
+            ColliderType::Cuboid => collider.as_cuboid().is_some(),
+            ColliderType::ConvexHull => collider.as_convex_polyhedron().is_some(),
+        }
+    }
 }
 
 #[derive(Reflect, Debug, Clone)]
@@ -83,12 +116,13 @@ impl VelocityBehavior {
         &self,
         parent_velocity: Option<&Velocity>,
         parent_transform: Option<&Transform>,
+        rng: &mut impl Rng,
     ) -> Velocity {
         match self {
             VelocityBehavior::Fixed(velocity) => Velocity::linear(*velocity),
             VelocityBehavior::Random { linvel, angvel } => Velocity {
-                linvel: random_vec3(-*linvel..*linvel, -*linvel..*linvel, 0.0..0.0),
-                angvel: random_vec3(-*angvel..*angvel, -*angvel..*angvel, -*angvel..*angvel),
+                linvel: random_vec3(rng, -*linvel..*linvel, -*linvel..*linvel, 0.0..0.0),
+                angvel: random_vec3(rng, -*angvel..*angvel, -*angvel..*angvel, -*angvel..*angvel),
             },
             VelocityBehavior::RelativeToParent {
                 base_velocity,
@@ -109,6 +143,22 @@ impl VelocityBehavior {
     }
 }
 
+/// one of the other looks an actor can spawn with, sized and fitted with a
+/// collider the same way its own model is
+#[derive(Clone, Debug)]
+pub struct ActorVariant {
+    pub name:     String,
+    pub model:    ModelSource,
+    pub aabb:     Aabb,
+    pub collider: Collider,
+    pub weights:  SizeWeights,
+}
+
+/// which look a spawned actor has - see ActorConfig::with_variant. without one
+/// it's the actor's own model
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Variant(pub usize);
+
 #[derive(Resource, Reflect, InspectorOptions, Clone, Debug)]
 #[reflect(Resource, InspectorOptions)]
 pub struct ActorConfig {
@@ -123,11 +173,13 @@ pub struct ActorConfig {
     pub collision_damage: f32,
     #[reflect(ignore)]
     pub collision_groups: CollisionGroups,
+    pub drag: Drag,
     pub gravity_scale: f32,
     pub health: f32,
     pub locked_axes: LockedAxes,
     #[inspector(min = 0.0, max = 20.0, display = NumberDisplay::Slider)]
     pub mass: f32,
+    pub max_speed: MaxSpeed,
     pub render_layer: RenderLayer,
     #[inspector(min = 0.1, max = 1.0, display = NumberDisplay::Slider)]
     pub restitution: f32,
@@ -137,12 +189,14 @@ pub struct ActorConfig {
     #[inspector(min = 0.1, max = 10.0, display = NumberDisplay::Slider)]
     pub scalar: f32,
     #[reflect(ignore)]
-    pub scene: Handle<Scene>,
+    pub model: ModelSource,
     pub spawn_position_behavior: SpawnPositionBehavior,
     pub spawn_timer_seconds: Option<f32>,
     #[reflect(ignore)]
     pub spawn_timer: Option<Timer>,
     pub velocity_behavior: VelocityBehavior,
+    #[reflect(ignore)]
+    pub variants: Vec<ActorVariant>,
 }
 
 impl Default for ActorConfig {
@@ -155,30 +209,79 @@ impl Default for ActorConfig {
             collider_type: ColliderType::Cuboid,
             collision_damage: 0.,
             collision_groups: CollisionGroups::default(),
+            drag: Drag::default(),
             gravity_scale: 0.,
             health: 0.,
             locked_axes: LockedAxes::TRANSLATION_LOCKED_Z,
             mass: 1.,
-            render_layer: RenderLayer::Both,
+            max_speed: MaxSpeed::default(),
+            render_layer: RenderLayer::Game,
             restitution: 1.,
             restitution_combine_rule: CoefficientCombineRule::Max,
             rigid_body: RigidBody::Dynamic,
             rotation: None,
             scalar: 1.,
-            scene: Handle::default(),
+            model: ModelSource::default(),
             spawn_position_behavior: SpawnPositionBehavior::Fixed(Vec3::ZERO),
             spawn_timer_seconds: None,
             spawn_timer: None,
             velocity_behavior: VelocityBehavior::Fixed(Vec3::ZERO),
+            variants: Vec::new(),
         }
     }
 }
 
 impl ActorConfig {
+    /// what to actually spawn this run - the config as the inspector left it
+    /// unless a mutator changes this kind of actor
+    pub fn with_mutators(&self, mutators: &Mutators) -> Cow<'_, Self> {
+        let (scale, speed) = match self.actor_kind {
+            ActorKind::Missile => (1., mutators.missile_speed_multiplier()),
+            ActorKind::Nateroid => (mutators.rock_scale_multiplier(), 1.),
+            ActorKind::Spaceship => (mutators.ship_scale_multiplier(), 1.),
+        };
+        if scale == 1. && speed == 1. {
+            return Cow::Borrowed(self);
+        }
+
+        let mut config = self.clone();
+        config.scalar *= scale;
+        match &mut config.velocity_behavior {
+            VelocityBehavior::Fixed(velocity) => *velocity *= speed,
+            VelocityBehavior::Random { linvel, .. } => *linvel *= speed,
+            VelocityBehavior::RelativeToParent { base_velocity, .. } => *base_velocity *= speed,
+        }
+        Cow::Owned(config)
+    }
+
+    /// this config in one of its looks - 0 is its own model, after that it's
+    /// the variants in order
+    pub fn with_variant(&self, variant: Variant) -> Cow<'_, Self> {
+        let Some(look) = variant.0.checked_sub(1).and_then(|index| self.variants.get(index)) else {
+            return Cow::Borrowed(self);
+        };
+
+        let mut config = self.clone();
+        config.model = look.model.clone();
+        config.aabb = look.aabb.clone();
+        config.collider = look.collider.clone();
+        Cow::Owned(config)
+    }
+
+    /// this config with where it spawns already picked - it's turned at
+    /// random the way RandomWithinBounds would, unless it has a rotation of
+    /// its own
+    pub fn placed_at(mut self, position: Vec3, rng: &mut impl Rng) -> Self {
+        self.spawn_position_behavior = SpawnPositionBehavior::Fixed(position);
+        self.rotation = self.rotation.or_else(|| Some(get_random_rotation(rng)));
+        self
+    }
+
     fn calculate_spawn_transform(
         &self,
         parent: Option<(&Transform, &Aabb)>,
         boundary: Option<Res<Boundary>>,
+        rng: &mut impl Rng,
     ) -> Transform {
         let transform = match &self.spawn_position_behavior {
             SpawnPositionBehavior::Fixed(position) => Transform::from_translation(*position),
@@ -190,14 +293,14 @@ impl ActorConfig {
 
                 let bounds = Transform {
                     translation: boundary.transform.translation,
-                    scale: boundary.transform.scale * *scale_factor,
-                    ..default()
+                    rotation:    boundary.transform.rotation,
+                    scale:       boundary.transform.scale * *scale_factor,
                 };
-                let position = get_random_position_within_bounds(&bounds);
+                let position = get_random_position_within_bounds(&bounds, rng);
 
                 let mut transform = Transform::from_translation(position);
 
-                transform.rotation = get_random_rotation();
+                transform.rotation = get_random_rotation(rng);
 
                 transform
             },
@@ -240,14 +343,17 @@ pub struct ActorBundle {
     pub collider: Collider,
     pub collision_damage: CollisionDamage,
     pub collision_groups: CollisionGroups,
+    pub drag: Drag,
     pub gravity_scale: GravityScale,
     pub health: Health,
+    pub interpolation: InterpolatedTransform,
     pub locked_axes: LockedAxes,
+    pub max_speed: MaxSpeed,
     pub rigid_body: RigidBody,
     pub restitution: Restitution,
     pub mass_properties: ColliderMassProperties,
     pub render_layers: RenderLayers,
-    pub scene_root: SceneRoot,
+    pub model: ModelSource,
     pub teleporter: Teleporter,
     pub transform: Transform,
     pub velocity: Velocity,
@@ -255,48 +361,138 @@ pub struct ActorBundle {
 }
 
 impl ActorBundle {
-    pub fn new(
+    /// an actor of `kind` drawn with `model`, starting from what every kind
+    /// does - it bounces off things as hard as it hits them, there's no
+    /// gravity and it stays on the play plane. the `with_` methods change the
+    /// rest, one at a time
+    pub fn new(actor_kind: ActorKind, model: ModelSource) -> Self {
+        let defaults = ActorConfig::default();
+        let transform = Transform::default();
+
+        Self {
+            actor_kind,
+            aabb: defaults.aabb,
+            active_events: ActiveEvents::COLLISION_EVENTS,
+            collider: defaults.collider,
+            collision_damage: CollisionDamage(defaults.collision_damage),
+            collision_groups: defaults.collision_groups,
+            drag: defaults.drag,
+            gravity_scale: GravityScale(defaults.gravity_scale),
+            health: Health(defaults.health),
+            interpolation: InterpolatedTransform::new(transform),
+            locked_axes: defaults.locked_axes,
+            max_speed: defaults.max_speed,
+            rigid_body: defaults.rigid_body,
+            restitution: Restitution {
+                coefficient: defaults.restitution,
+                combine_rule: defaults.restitution_combine_rule,
+            },
+            mass_properties: ColliderMassProperties::Mass(defaults.mass),
+            render_layers: RenderLayers::from_layers(defaults.render_layer.layers()),
+            model,
+            teleporter: Teleporter::default(),
+            transform,
+            velocity: Velocity::zero(),
+            wall_visualizer: ActorPortals::default(),
+        }
+    }
+
+    /// what `config` says this kind of actor is, placed and set moving the way
+    /// its spawn behaviors say - off `parent` if it has one
+    pub fn from_config(
         config: &ActorConfig,
         parent: Option<(&Transform, &Velocity, &Aabb)>,
         boundary: Option<Res<Boundary>>,
+        rng: &mut impl Rng,
     ) -> Self {
         let parent_aabb = parent.map(|(_, _, a)| a);
         let parent_transform = parent.map(|(t, _, _)| t);
         let parent_velocity = parent.map(|(_, v, _)| v);
 
-        let mut transform = config.calculate_spawn_transform(parent_transform.zip(parent_aabb), boundary);
+        let mut transform =
+            config.calculate_spawn_transform(parent_transform.zip(parent_aabb), boundary, rng);
 
         Self::apply_rotations(config, parent_transform, &mut transform);
 
         let velocity = config
             .velocity_behavior
-            .calculate_velocity(parent_velocity, parent_transform);
+            .calculate_velocity(parent_velocity, parent_transform, rng);
+
+        let bundle = Self::new(config.actor_kind, config.model.clone())
+            .with_collider(config.collider.clone())
+            .with_mass(config.mass)
+            .with_collision_group(
+                config.collision_groups.memberships,
+                config.collision_groups.filters,
+            )
+            .with_locked_axes(config.locked_axes)
+            .with_transform(transform)
+            .with_velocity(velocity.linvel)
+            .with_angular_velocity(velocity.angvel);
 
+        // the rest is only ever set from a config
         Self {
-            actor_kind: config.actor_kind,
             aabb: config.aabb.clone(),
-            active_events: ActiveEvents::COLLISION_EVENTS,
-            collider: config.collider.clone(),
             collision_damage: CollisionDamage(config.collision_damage),
-            collision_groups: config.collision_groups,
+            drag: config.drag,
             gravity_scale: GravityScale(config.gravity_scale),
             health: Health(config.health),
-            locked_axes: config.locked_axes,
+            max_speed: config.max_speed,
             rigid_body: config.rigid_body,
             restitution: Restitution {
                 coefficient: config.restitution,
                 combine_rule: config.restitution_combine_rule,
             },
-            mass_properties: ColliderMassProperties::Mass(config.mass),
             render_layers: RenderLayers::from_layers(config.render_layer.layers()),
-            scene_root: SceneRoot(config.scene.clone()),
-            teleporter: Teleporter::default(),
-            transform,
-            velocity,
-            wall_visualizer: ActorPortals::default(),
+            ..bundle
         }
     }
 
+    pub fn with_collider(mut self, collider: Collider) -> Self {
+        self.collider = collider;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass_properties = ColliderMassProperties::Mass(mass);
+        self
+    }
+
+    pub fn with_velocity(mut self, linvel: Vec3) -> Self {
+        self.velocity.linvel = linvel;
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, angvel: Vec3) -> Self {
+        self.velocity.angvel = angvel;
+        self
+    }
+
+    /// what it is and what it runs into - see the GROUP_ constants
+    pub fn with_collision_group(mut self, memberships: Group, filters: Group) -> Self {
+        self.collision_groups = CollisionGroups::new(memberships, filters);
+        self
+    }
+
+    pub fn with_locked_axes(mut self, locked_axes: LockedAxes) -> Self {
+        self.locked_axes = locked_axes;
+        self
+    }
+
+    /// free to leave the play plane
+    pub fn unlocked_z(mut self) -> Self {
+        self.locked_axes.remove(LockedAxes::TRANSLATION_LOCKED_Z);
+        self
+    }
+
+    /// where it starts - interpolation starts there too, so it doesn't slide
+    /// in from the origin
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self.interpolation = InterpolatedTransform::new(transform);
+        self
+    }
+
     // Combine rotations from optional parent with optional supplied rotation
     // missiles need this to get oriented correctly
     // both parent and actor_config.rotation are optional so we have to unpack both
@@ -323,17 +519,39 @@ impl ActorBundle {
     }
 }
 
-fn get_random_position_within_bounds(bounds: &Transform) -> Vec3 {
-    let mut rng = rand::rng();
+fn get_random_position_within_bounds(bounds: &Transform, rng: &mut impl Rng) -> Vec3 {
     let half_scale = bounds.scale.abs() / 2.0; // Use absolute value to ensure positive scale
-    let min = bounds.translation - half_scale;
-    let max = bounds.translation + half_scale;
 
-    Vec3::new(
-        get_random_component(min.x, max.x, &mut rng),
-        get_random_component(min.y, max.y, &mut rng),
-        get_random_component(min.z, max.z, &mut rng),
-    )
+    // picked in the bounds' own frame so a turned boundary spawns inside itself
+    let local = Vec3::new(
+        get_random_component(-half_scale.x, half_scale.x, rng),
+        get_random_component(-half_scale.y, half_scale.y, rng),
+        get_random_component(-half_scale.z, half_scale.z, rng),
+    );
+    bounds.translation + bounds.rotation * local
+}
+
+/// somewhere in the boundary that a ball of `radius` can go without touching
+/// any collider already there - or the middle of the boundary if `attempts`
+/// random tries don't turn one up. only sees colliders physics has stepped
+/// with, so two spawned on the same tick can still land on each other
+pub fn find_clear_spawn_position(
+    boundary: &Boundary,
+    rapier_context: &RapierContext,
+    radius: f32,
+    attempts: u32,
+    rng: &mut impl Rng,
+) -> Vec3 {
+    let ball = Collider::ball(radius);
+
+    (0..attempts)
+        .map(|_| boundary.random_interior_point(rng, radius))
+        .find(|&position| {
+            rapier_context
+                .intersection_with_shape(position, Quat::IDENTITY, &ball, QueryFilter::default())
+                .is_none()
+        })
+        .unwrap_or(boundary.transform.translation)
 }
 
 fn get_random_component(min: f32, max: f32, rng: &mut impl Rng) -> f32 {
@@ -345,8 +563,7 @@ fn get_random_component(min: f32, max: f32, rng: &mut impl Rng) -> f32 {
     }
 }
 
-fn get_random_rotation() -> Quat {
-    let mut rng = rand::rng();
+fn get_random_rotation(rng: &mut impl Rng) -> Quat {
     Quat::from_euler(
         EulerRot::XYZ,
         rng.random_range(-std::f32::consts::PI..std::f32::consts::PI),
@@ -355,7 +572,7 @@ fn get_random_rotation() -> Quat {
     )
 }
 
-#[derive(Component, Reflect, Copy, Clone, Debug, Default)]
+#[derive(Component, Reflect, Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub enum ActorKind {
     #[default]
     Missile,
@@ -363,6 +580,10 @@ pub enum ActorKind {
     Spaceship,
 }
 
+impl ActorKind {
+    pub const ALL: [ActorKind; 3] = [ActorKind::Missile, ActorKind::Nateroid, ActorKind::Spaceship];
+}
+
 impl fmt::Display for ActorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -379,28 +600,16 @@ fn initialize_actor_configs(
     scenes: Res<Assets<Scene>>,
     scene_assets: Res<SceneAssets>,
 ) {
-    let nateroid_config = initialize_actor_config(
-        NateroidConfig::default().0,
-        &scenes,
-        &meshes,
-        &scene_assets.nateroid,
-    );
+    let nateroid_config =
+        initialize_actor_config(NateroidConfig::default().0, &scenes, &meshes, &scene_assets);
     commands.insert_resource(NateroidConfig(nateroid_config));
 
-    let missile_config = initialize_actor_config(
-        MissileConfig::default().0,
-        &scenes,
-        &meshes,
-        &scene_assets.missile,
-    );
+    let missile_config =
+        initialize_actor_config(MissileConfig::default().0, &scenes, &meshes, &scene_assets);
     commands.insert_resource(MissileConfig(missile_config));
 
-    let spaceship_config = initialize_actor_config(
-        SpaceshipConfig::default().0,
-        &scenes,
-        &meshes,
-        &scene_assets.spaceship,
-    );
+    let spaceship_config =
+        initialize_actor_config(SpaceshipConfig::default().0, &scenes, &meshes, &scene_assets);
     commands.insert_resource(SpaceshipConfig(spaceship_config));
 }
 
@@ -408,36 +617,160 @@ fn initialize_actor_config(
     mut config: ActorConfig,
     scenes: &Assets<Scene>,
     meshes: &Assets<Mesh>,
-    scene_handle: &Handle<Scene>,
+    scene_assets: &SceneAssets,
 ) -> ActorConfig {
-    let aabb = get_scene_aabb(scenes, meshes, scene_handle);
-    let adjusted_aabb = aabb.scale(BLENDER_SCALE);
-
-    // Calculate the size based on the adjusted AABB
-    let size = adjusted_aabb.size();
-    let half_extents = adjusted_aabb.half_extents();
+    config.model = scene_assets.model(config.actor_kind);
 
-    let collider = match config.collider_type {
-        ColliderType::Ball => {
-            let radius = size.length() / 3.;
-            Collider::ball(radius)
-        },
-        ColliderType::Cuboid => Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
-    };
+    // the manifest's say over the defaults - the inspector still gets the
+    // last word
+    if let Some(assets) = scene_assets.get(config.actor_kind) {
+        if let Some(scale) = assets.scale {
+            config.scalar = scale;
+        }
+        if let Some(collider_type) = &assets.collider {
+            config.collider_type = collider_type.clone();
+        }
+    }
 
     let spawn_timer = config
         .spawn_timer_seconds
         .map(|seconds| Timer::from_seconds(seconds, TimerMode::Repeating));
 
-    config.aabb = adjusted_aabb;
-    config.collider = collider;
+    config.aabb = model_aabb(&config.model, scenes, meshes);
+    config.collider = build_collider(&config, &config.model, &config.aabb, scenes, meshes);
+    config.variants = scene_assets
+        .variants(config.actor_kind)
+        .iter()
+        .map(|variant| {
+            let aabb = model_aabb(&variant.model, scenes, meshes);
+            ActorVariant {
+                name:     variant.name.clone(),
+                model:    variant.model.clone(),
+                collider: build_collider(&config, &variant.model, &aabb, scenes, meshes),
+                aabb,
+                weights:  variant.weights,
+            }
+        })
+        .collect();
     config.spawn_timer = spawn_timer;
-    config.scene = scene_handle.clone();
     config
 }
 
-pub fn random_vec3(range_x: Range<f32>, range_y: Range<f32>, range_z: Range<f32>) -> Vec3 {
-    let mut rng = rand::rng();
+// in world units - the models need scaling up, the plain shapes that stand in
+// for a missing one are already the right size
+fn model_aabb(model: &ModelSource, scenes: &Assets<Scene>, meshes: &Assets<Mesh>) -> Aabb {
+    match model {
+        ModelSource::Scene(scene) => get_scene_aabb(scenes, meshes, scene).scale(BLENDER_SCALE),
+        ModelSource::Mesh { mesh, .. } => meshes.get(mesh).map(get_mesh_aabb).unwrap_or_default(),
+    }
+}
+
+fn model_vertices(model: &ModelSource, scenes: &Assets<Scene>, meshes: &Assets<Mesh>) -> Vec<Vec3> {
+    match model {
+        ModelSource::Scene(scene) => get_scene_vertices(scenes, meshes, scene)
+            .into_iter()
+            .map(|vertex| vertex * BLENDER_SCALE)
+            .collect(),
+        ModelSource::Mesh { mesh, .. } => meshes.get(mesh).map(get_mesh_vertices).unwrap_or_default(),
+    }
+}
+
+// sized from the model, which is why it needs the scene and meshes - a hull
+// that won't build (a flat model, say) falls back to the cuboid. the shape is
+// the config's, the model and aabb can be its own or a variant's
+fn build_collider(
+    config: &ActorConfig,
+    model: &ModelSource,
+    aabb: &Aabb,
+    scenes: &Assets<Scene>,
+    meshes: &Assets<Mesh>,
+) -> Collider {
+    let size = aabb.size();
+    let half_extents = aabb.half_extents();
+    let cuboid = || Collider::cuboid(half_extents.x, half_extents.y, half_extents.z);
+
+    match config.collider_type {
+        ColliderType::Ball => {
+            let radius = size.length() / 3.;
+            Collider::ball(radius)
+        },
+        ColliderType::Cuboid => cuboid(),
+        ColliderType::ConvexHull => {
+            let vertices = model_vertices(model, scenes, meshes);
+            Collider::convex_hull(&vertices).unwrap_or_else(|| {
+                warn!("unable to build a convex hull for the {}, using a cuboid", config.actor_kind);
+                cuboid()
+            })
+        },
+    }
+}
+
+// a model that's been reloaded may not be the size it was - the aabb and the
+// collider are worked out again so whatever spawns next fits the new one.
+// anything already out there keeps the old size until it's gone
+fn resize_reloaded_actors(
+    mut reloaded: EventReader<SceneReloaded>,
+    meshes: Res<Assets<Mesh>>,
+    scenes: Res<Assets<Scene>>,
+    mut missile: ResMut<MissileConfig>,
+    mut nateroid: ResMut<NateroidConfig>,
+    mut spaceship: ResMut<SpaceshipConfig>,
+) {
+    for SceneReloaded(kind) in reloaded.read() {
+        let config = match kind {
+            ActorKind::Missile => &mut missile.0,
+            ActorKind::Nateroid => &mut nateroid.0,
+            ActorKind::Spaceship => &mut spaceship.0,
+        };
+        config.aabb = model_aabb(&config.model, &scenes, &meshes);
+        for variant in config.variants.iter_mut() {
+            variant.aabb = model_aabb(&variant.model, &scenes, &meshes);
+        }
+        rebuild_colliders(config, &scenes, &meshes);
+    }
+}
+
+// the config's own collider and each of its variants', from the aabbs they
+// have now
+fn rebuild_colliders(config: &mut ActorConfig, scenes: &Assets<Scene>, meshes: &Assets<Mesh>) {
+    config.collider = build_collider(config, &config.model, &config.aabb, scenes, meshes);
+    let mut variants = std::mem::take(&mut config.variants);
+    for variant in variants.iter_mut() {
+        variant.collider = build_collider(config, &variant.model, &variant.aabb, scenes, meshes);
+    }
+    config.variants = variants;
+}
+
+// so switching collider_type in an inspector takes effect on the next spawn.
+// only when it's been changed and doesn't match - a hull is too dear to build
+// every time some other field moves. it's put back without marking the
+// config changed, or a hull that fell back to a cuboid would never stop
+// rebuilding
+fn rebuild_actor_colliders(
+    meshes: Res<Assets<Mesh>>,
+    scenes: Res<Assets<Scene>>,
+    mut missile: ResMut<MissileConfig>,
+    mut nateroid: ResMut<NateroidConfig>,
+    mut spaceship: ResMut<SpaceshipConfig>,
+) {
+    let configs = [
+        (missile.is_changed(), &mut missile.bypass_change_detection().0),
+        (nateroid.is_changed(), &mut nateroid.bypass_change_detection().0),
+        (spaceship.is_changed(), &mut spaceship.bypass_change_detection().0),
+    ];
+    for (changed, config) in configs {
+        if changed && !config.collider_type.matches(&config.collider) {
+            rebuild_colliders(config, &scenes, &meshes);
+        }
+    }
+}
+
+pub fn random_vec3(
+    rng: &mut impl Rng,
+    range_x: Range<f32>,
+    range_y: Range<f32>,
+    range_z: Range<f32>,
+) -> Vec3 {
     let x = if range_x.start < range_x.end {
         rng.random_range(range_x)
     } else {
@@ -457,18 +790,14 @@ pub fn random_vec3(range_x: Range<f32>, range_y: Range<f32>, range_z: Range<f32>
     Vec3::new(x, y, z)
 }
 
-pub fn spawn_actor<'a>(
-    commands: &'a mut Commands,
-    config: &ActorConfig,
-    boundary: Option<Res<Boundary>>,
-    parent: Option<(&Transform, &Velocity, &Aabb)>,
-) -> EntityCommands<'a> {
-    let bundle = ActorBundle::new(config, parent, boundary);
+/// spawns `bundle` with a name and whatever draws it - build it with
+/// `ActorBundle::from_config` or the `with_` methods
+pub fn spawn_actor<'a>(commands: &'a mut Commands, bundle: ActorBundle) -> EntityCommands<'a> {
+    let name = Name::new(bundle.actor_kind.to_string());
+    let model = bundle.model.clone();
 
-    let entity = commands
-        .spawn(bundle)
-        .insert(Name::new(config.actor_kind.to_string()))
-        .id();
+    let mut entity = commands.spawn((bundle, name));
+    model.insert_into(&mut entity);
 
-    commands.entity(entity)
+    entity
 }
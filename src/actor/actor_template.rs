@@ -12,6 +12,8 @@ use crate::actor::{
         VelocityBehavior,
     },
     ColliderType,
+    Drag,
+    MaxSpeed,
 };
 use bevy::prelude::*;
 use bevy_inspector_egui::InspectorOptions;
@@ -89,8 +91,21 @@ impl Default for SpaceshipConfig {
             actor_kind: ActorKind::Spaceship,
             collision_damage: 50.,
             collision_groups: CollisionGroups::new(GROUP_SPACESHIP, GROUP_ASTEROID),
+            // enough that it drifts to a stop after a few seconds off the
+            // thrust, and stops spinning sooner than that
+            drag: Drag {
+                linear:  0.4,
+                angular: 2.,
+            },
             health: 500.,
             mass: 10.0,
+            // over the fastest the settings menu lets thrust go, so flying
+            // never hits it and only a hard knock does
+            max_speed: MaxSpeed {
+                linear:  320.,
+                angular: 10.,
+                soft:    true,
+            },
             locked_axes: LockedAxes::ROTATION_LOCKED_X
                 | LockedAxes::ROTATION_LOCKED_Y
                 | LockedAxes::TRANSLATION_LOCKED_Z,
@@ -104,3 +119,78 @@ impl Default for SpaceshipConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actor::actor_spawner::ActorBundle,
+        asset_loader::ModelSource,
+    };
+    use bevy_rapier3d::{
+        dynamics::CoefficientCombineRule,
+        prelude::ColliderMassProperties,
+    };
+
+    // what ActorBundle is built from - each kind only spells out what differs,
+    // so a change to ActorConfig's defaults would quietly change all three
+    #[test]
+    fn actor_physics_defaults() {
+        let defaults = ActorConfig::default();
+        assert_eq!(defaults.restitution, 1.);
+        assert_eq!(defaults.restitution_combine_rule, CoefficientCombineRule::Max);
+        assert_eq!(defaults.gravity_scale, 0.);
+        assert_eq!(defaults.locked_axes, LockedAxes::TRANSLATION_LOCKED_Z);
+
+        let kinds = [
+            MissileConfig::default().0,
+            NateroidConfig::default().0,
+            SpaceshipConfig::default().0,
+        ];
+        for config in &kinds {
+            assert_eq!(config.gravity_scale, 0., "{}", config.actor_kind);
+            assert_eq!(
+                config.restitution_combine_rule,
+                CoefficientCombineRule::Max,
+                "{}",
+                config.actor_kind
+            );
+            assert!(
+                config.locked_axes.contains(LockedAxes::TRANSLATION_LOCKED_Z),
+                "{} can leave the play plane",
+                config.actor_kind
+            );
+        }
+
+        let [missile, nateroid, spaceship] = kinds;
+        assert_eq!(missile.restitution, 1.);
+        assert_eq!(nateroid.restitution, 0.3);
+        assert_eq!(spaceship.restitution, 0.1);
+    }
+
+    // the builder starts from the same defaults, and each `with_` only
+    // changes its own part
+    #[test]
+    fn actor_bundle_builder() {
+        let plain = ActorBundle::new(ActorKind::Missile, ModelSource::default());
+        assert_eq!(plain.restitution.coefficient, 1.);
+        assert_eq!(plain.restitution.combine_rule, CoefficientCombineRule::Max);
+        assert_eq!(plain.gravity_scale.0, 0.);
+        assert_eq!(plain.locked_axes, LockedAxes::TRANSLATION_LOCKED_Z);
+
+        let built = ActorBundle::new(ActorKind::Missile, ModelSource::default())
+            .with_mass(0.1)
+            .with_velocity(Vec3::X)
+            .with_collision_group(GROUP_MISSILE, GROUP_ASTEROID)
+            .with_locked_axes(LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Z)
+            .unlocked_z();
+        assert!(matches!(built.mass_properties, ColliderMassProperties::Mass(mass) if mass == 0.1));
+        assert_eq!(built.velocity.linvel, Vec3::X);
+        assert_eq!(
+            built.collision_groups,
+            CollisionGroups::new(GROUP_MISSILE, GROUP_ASTEROID)
+        );
+        assert_eq!(built.locked_axes, LockedAxes::ROTATION_LOCKED);
+        assert_eq!(built.restitution.coefficient, 1.);
+    }
+}
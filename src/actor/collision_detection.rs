@@ -3,7 +3,14 @@ use bevy_rapier3d::prelude::CollisionEvent;
 
 use crate::{
     actor::{
-        actor_spawner::CollisionDamage,
+        actor_spawner::{
+            ActorKind,
+            CollisionDamage,
+        },
+        shield::{
+            Shield,
+            ShieldBroken,
+        },
         Health,
     },
     schedule::InGameSet,
@@ -13,56 +20,93 @@ pub struct CollisionDetectionPlugin;
 
 impl Plugin for CollisionDetectionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_event::<ActorDamaged>().add_systems(
             FixedUpdate,
             handle_collision_events.in_set(InGameSet::CollisionDetection),
         );
     }
 }
 
+/// sent for every bit of collision damage that lands - `source` is the kind of
+/// actor that dealt the damage so a missile hitting a nateroid can be told
+/// apart from the spaceship ramming one. `health` is what's left afterwards
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ActorDamaged {
+    pub kind:     ActorKind,
+    pub source:   ActorKind,
+    pub health:   f32,
+    pub position: Vec3,
+}
+
 fn handle_collision_events(
     mut collision_events: EventReader<CollisionEvent>,
-    mut health_query: Query<&mut Health>,
+    mut health_query: Query<(&mut Health, Option<&mut Shield>)>,
+    mut damaged: EventWriter<ActorDamaged>,
+    mut shield_broken: EventWriter<ShieldBroken>,
     name_query: Query<&Name>,
+    kind_query: Query<(&ActorKind, &Transform)>,
     collision_damage_query: Query<&CollisionDamage>,
 ) {
     for &collision_event in collision_events.read() {
         if let CollisionEvent::Started(entity1, entity2, ..) = collision_event {
             if let Ok(name1) = name_query.get(entity1) {
                 if let Ok(name2) = name_query.get(entity2) {
-                    apply_collision_damage(
-                        &mut health_query,
-                        &collision_damage_query,
-                        entity1,
-                        name1,
-                        entity2,
-                        name2,
-                    );
-                    apply_collision_damage(
-                        &mut health_query,
-                        &collision_damage_query,
-                        entity2,
-                        name2,
-                        entity1,
-                        name1,
-                    );
+                    for (applying, applying_name, receiving, receiving_name) in
+                        [(entity1, name1, entity2, name2), (entity2, name2, entity1, name1)]
+                    {
+                        let Some((health, broke_shield)) = apply_collision_damage(
+                            &mut health_query,
+                            &collision_damage_query,
+                            applying,
+                            applying_name,
+                            receiving,
+                            receiving_name,
+                        ) else {
+                            continue;
+                        };
+
+                        if let (Ok((&source, _)), Ok((&kind, transform))) =
+                            (kind_query.get(applying), kind_query.get(receiving))
+                        {
+                            damaged.send(ActorDamaged {
+                                kind,
+                                source,
+                                health,
+                                position: transform.translation,
+                            });
+                            if broke_shield {
+                                shield_broken.send(ShieldBroken {
+                                    position: transform.translation,
+                                });
+                            }
+                        }
+                    }
                 }
             }
         }
     }
 }
 
+// a shield takes what it can first. returns the health left over and whether
+// this was the hit that broke the shield, so the caller can announce both
 fn apply_collision_damage(
-    health_query: &mut Query<&mut Health>,
+    health_query: &mut Query<(&mut Health, Option<&mut Shield>)>,
     collision_damage_query: &Query<&CollisionDamage>,
     applying_entity: Entity,
     _applying_entity_name: &Name,
     receiving_entity: Entity,
     _receiving_entity_name: &Name,
-) {
-    if let Ok(mut health) = health_query.get_mut(receiving_entity) {
-        if let Ok(collision_damage) = collision_damage_query.get(applying_entity) {
-            health.0 -= collision_damage.0;
-        }
+) -> Option<(f32, bool)> {
+    let (mut health, shield) = health_query.get_mut(receiving_entity).ok()?;
+    let collision_damage = collision_damage_query.get(applying_entity).ok()?;
+
+    let mut damage = collision_damage.0;
+    let mut broke_shield = false;
+    if let Some(mut shield) = shield.filter(|shield| shield.is_up()) {
+        damage = shield.absorb(damage);
+        broke_shield = !shield.is_up();
     }
+
+    health.0 -= damage;
+    Some((health.0, broke_shield))
 }
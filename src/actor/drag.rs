@@ -0,0 +1,50 @@
+use crate::{
+    actor::teleport::teleport_at_boundary,
+    schedule::InGameSet,
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::{
+    inspector_options::std_options::NumberDisplay,
+    prelude::*,
+};
+use bevy_rapier3d::dynamics::Velocity;
+
+/// there's no gravity and no air, so without this anything that's pushed
+/// coasts forever. drag takes a share of the speed off every second - the
+/// same share however long the tick is
+pub struct DragPlugin;
+
+impl Plugin for DragPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Drag>().add_systems(
+            FixedUpdate,
+            apply_drag
+                .before(teleport_at_boundary)
+                .in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+/// how quickly movement and spin die away - at 1 about two thirds of it is
+/// gone each second. 0 coasts, which is what everything gets unless its
+/// ActorConfig says otherwise
+#[derive(Component, Reflect, InspectorOptions, Debug, Default, Clone, Copy)]
+#[reflect(Component, InspectorOptions)]
+pub struct Drag {
+    #[inspector(min = 0., max = 5., display = NumberDisplay::Slider)]
+    pub linear:  f32,
+    #[inspector(min = 0., max = 10., display = NumberDisplay::Slider)]
+    pub angular: f32,
+}
+
+pub(super) fn apply_drag(time: Res<Time>, mut query: Query<(&Drag, &mut Velocity)>) {
+    let delta_secs = time.delta_secs();
+
+    for (drag, mut velocity) in query.iter_mut() {
+        if drag.linear <= 0. && drag.angular <= 0. {
+            continue;
+        }
+        velocity.linvel *= (-drag.linear * delta_secs).exp();
+        velocity.angvel *= (-drag.angular * delta_secs).exp();
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    actor::{
+        drag::apply_drag,
+        teleport::keep_inside_resized_boundary,
+        ActorKind,
+    },
+    mutators::{
+        mutator_active,
+        Mutator,
+        Mutators,
+    },
+    playfield::Boundary,
+    schedule::InGameSet,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::dynamics::Velocity;
+
+/// the gravity well mutator - every actor is pulled toward the middle of the
+/// boundary
+pub struct GravityWellPlugin;
+
+impl Plugin for GravityWellPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            pull_toward_gravity_well
+                .before(apply_drag)
+                .before(keep_inside_resized_boundary)
+                .in_set(InGameSet::EntityUpdates)
+                .run_if(mutator_active(Mutator::GravityWell)),
+        );
+    }
+}
+
+// strongest out at the walls and fading to nothing at the center, so that
+// whatever falls in doesn't jitter back and forth across the middle
+fn pull_toward_gravity_well(
+    mutators: Res<Mutators>,
+    boundary: Res<Boundary>,
+    time: Res<Time>,
+    mut q_actors: Query<(&Transform, &mut Velocity), With<ActorKind>>,
+) {
+    let center = boundary.transform.translation;
+    let reach = boundary.transform.scale.max_element() / 2.;
+    let pull = mutators.gravity_well_strength * time.delta_secs();
+
+    for (transform, mut velocity) in q_actors.iter_mut() {
+        let offset = center - transform.translation;
+        let falloff = (offset.length() / reach).min(1.);
+        velocity.linvel += offset.normalize_or_zero() * pull * falloff;
+    }
+}
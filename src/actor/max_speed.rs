@@ -0,0 +1,124 @@
+use crate::{
+    actor::{
+        drag::apply_drag,
+        teleport::teleport_at_boundary,
+    },
+    schedule::InGameSet,
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::{
+    inspector_options::std_options::NumberDisplay,
+    prelude::*,
+};
+use bevy_rapier3d::dynamics::Velocity;
+
+// how quickly a soft limit pulls the speed over it back down - about two
+// thirds of the way each fifth of a second
+const SOFT_DAMPING: f32 = 5.;
+
+/// a collision or two can leave something going far faster than anything
+/// should, and at that speed it crosses half the boundary a tick and the wrap
+/// can't keep up. the limit goes on before the wrap so it only ever sees
+/// sensible speeds, and after drag so what's left is what gets clamped -
+/// the other way round a soft limit would ease down a speed drag was about
+/// to take off anyway
+pub struct MaxSpeedPlugin;
+
+impl Plugin for MaxSpeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MaxSpeed>().add_systems(
+            FixedUpdate,
+            clamp_speed
+                .after(apply_drag)
+                .before(teleport_at_boundary)
+                .in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+/// the fastest something is allowed to move and spin. 0 is no limit, which
+/// is what everything gets unless its ActorConfig says otherwise. soft lets it
+/// go over and eases it back down, rather than cutting it off
+#[derive(Component, Reflect, InspectorOptions, Debug, Default, Clone, Copy)]
+#[reflect(Component, InspectorOptions)]
+pub struct MaxSpeed {
+    #[inspector(min = 0., max = 600., display = NumberDisplay::Slider)]
+    pub linear:  f32,
+    #[inspector(min = 0., max = 50., display = NumberDisplay::Slider)]
+    pub angular: f32,
+    pub soft:    bool,
+}
+
+impl MaxSpeed {
+    fn limit(&self, velocity: Vec3, limit: f32, delta_secs: f32) -> Vec3 {
+        let speed = velocity.length();
+        if limit <= 0. || speed <= limit {
+            return velocity;
+        }
+
+        let limited = if self.soft {
+            limit + (speed - limit) * (-SOFT_DAMPING * delta_secs).exp()
+        } else {
+            limit
+        };
+        velocity * (limited / speed)
+    }
+}
+
+fn clamp_speed(time: Res<Time>, mut query: Query<(&MaxSpeed, &mut Velocity)>) {
+    let delta_secs = time.delta_secs();
+
+    for (max_speed, mut velocity) in query.iter_mut() {
+        let linvel = max_speed.limit(velocity.linvel, max_speed.linear, delta_secs);
+        let angvel = max_speed.limit(velocity.angvel, max_speed.angular, delta_secs);
+
+        // only written when it changes so rapier isn't told about every body
+        // every tick
+        if linvel != velocity.linvel || angvel != velocity.angvel {
+            velocity.linvel = linvel;
+            velocity.angvel = angvel;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::drag::{
+        Drag,
+        DragPlugin,
+    };
+    use std::time::Duration;
+
+    // drag first - a hard limit under what's left after drag is exactly the
+    // limit. clamped first, drag would take it under
+    #[test]
+    fn drag_then_clamp() {
+        let mut app = App::new();
+        app.add_plugins((DragPlugin, MaxSpeedPlugin));
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(100));
+        app.insert_resource(time);
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                Drag {
+                    linear:  1.,
+                    angular: 0.,
+                },
+                MaxSpeed {
+                    linear:  50.,
+                    angular: 0.,
+                    soft:    false,
+                },
+                Velocity::linear(Vec3::X * 100.),
+            ))
+            .id();
+
+        app.world_mut().run_schedule(FixedUpdate);
+
+        let speed = app.world().get::<Velocity>(entity).unwrap().linvel.length();
+        assert!((speed - 50.).abs() < 1e-3, "ended up at {speed}");
+    }
+}
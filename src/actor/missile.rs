@@ -1,9 +1,24 @@
-use bevy::prelude::*;
+use bevy::{
+    diagnostic::{
+        Diagnostic,
+        Diagnostics,
+        RegisterDiagnostic,
+    },
+    prelude::*,
+};
 use bevy_rapier3d::prelude::*;
+use std::time::Duration;
 
 use crate::{
+    diagnostics::MISSILE_COUNT,
+    input_buffer::BufferedAction,
+    mutators::Mutators,
     playfield::Boundary,
-    schedule::InGameSet,
+    rng::GameRng,
+    schedule::{
+        FrameSet,
+        InGameSet,
+    },
 };
 
 use crate::actor::{
@@ -14,24 +29,47 @@ use crate::actor::{
         ContinuousFire,
         Spaceship,
     },
+    weapon_heat::{
+        WeaponHeat,
+        WeaponOverheated,
+    },
     Teleporter,
 };
 
 use crate::actor::{
-    actor_spawner::spawn_actor,
+    actor_spawner::{
+        spawn_actor,
+        ActorBundle,
+    },
     spaceship_control::SpaceshipControl,
+    teleport::teleport_at_boundary,
 };
 use leafwing_input_manager::prelude::*;
 
+// how early a press can come and still fire once the spawn timer allows
+const FIRE_BUFFER: Duration = Duration::from_millis(120);
+
 pub struct MissilePlugin;
 
 impl Plugin for MissilePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, fire_missile.in_set(InGameSet::UserInput))
-            .add_systems(Update, missile_movement.in_set(InGameSet::EntityUpdates));
+        app.add_event::<MissileFired>()
+            .register_diagnostic(Diagnostic::new(MISSILE_COUNT))
+            .add_systems(FixedUpdate, fire_missile.in_set(InGameSet::UserInput))
+            .add_systems(
+                FixedUpdate,
+                missile_movement
+                    .after(teleport_at_boundary)
+                    .in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(Update, measure_missiles.in_set(FrameSet::Ui));
     }
 }
 
+fn measure_missiles(mut diagnostics: Diagnostics, q_missiles: Query<(), With<Missile>>) {
+    diagnostics.add_measurement(&MISSILE_COUNT, || q_missiles.iter().len() as f64);
+}
+
 // todo: #rustquestion - how can i make it so that new has to be used and
 // DrawDirection isn't constructed directly - i still need the fields visible
 #[derive(Copy, Clone, Component, Debug)]
@@ -57,13 +95,26 @@ impl Missile {
     }
 }
 
+/// sent whenever the spaceship successfully fires so that anything interested
+/// (sound for now) doesn't have to watch for newly spawned missiles
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MissileFired {
+    pub position: Vec3,
+}
+
 /// Logic to handle whether we're in continuous fire mode or just regular fire
 /// mode if continuous we want to make sure that enough time has passed and that
 /// we're holding down the fire button
+///
+/// presses come from the buffer rather than just_pressed - a tap that lands
+/// just before the timer is done still fires when it is, and a press only
+/// ever fires once
 fn should_fire(
     continuous_fire: Option<&ContinuousFire>,
     missile_config: &mut ActorConfig,
-    time: Res<Time>,
+    time: &Time,
+    now: Duration,
+    buffer: &mut BufferedAction<SpaceshipControl>,
     q_input_map: Query<&ActionState<SpaceshipControl>>,
 ) -> bool {
     if !missile_config.spawnable {
@@ -82,9 +133,10 @@ InitialEnsembleConfig",
         if !timer.just_finished() {
             return false;
         }
-        action_state.pressed(&SpaceshipControl::Fire)
+        let buffered = buffer.consume_if_buffered(&SpaceshipControl::Fire, now, FIRE_BUFFER);
+        buffered || action_state.pressed(&SpaceshipControl::Fire)
     } else {
-        action_state.just_pressed(&SpaceshipControl::Fire)
+        buffer.consume_if_buffered(&SpaceshipControl::Fire, now, FIRE_BUFFER)
     }
 }
 
@@ -93,32 +145,68 @@ InitialEnsembleConfig",
 // missile logic so i have it setup in missile                       so should i
 // have a simple fire method in method in spaceship that in turn calls this
 //                       fn or is having it here fine?
-fn fire_missile(
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn fire_missile(
     mut commands: Commands,
     q_input_map: Query<&ActionState<SpaceshipControl>>,
-    q_spaceship: Query<(&Transform, &Velocity, &Aabb, Option<&ContinuousFire>), With<Spaceship>>,
+    mut q_spaceship: Query<
+        (&Transform, &Velocity, &Aabb, Option<&ContinuousFire>, &mut WeaponHeat),
+        With<Spaceship>,
+    >,
     boundary_config: Res<Boundary>,
     mut missile_config: ResMut<MissileConfig>,
+    mutators: Res<Mutators>,
+    mut missile_fired: EventWriter<MissileFired>,
+    mut overheated: EventWriter<WeaponOverheated>,
+    mut buffer: ResMut<BufferedAction<SpaceshipControl>>,
+    mut rng: ResMut<GameRng>,
     time: Res<Time>,
+    virtual_time: Res<Time<Virtual>>,
 ) {
-    let Ok((spaceship_transform, spaceship_velocity, aabb, continuous_fire)) = q_spaceship.get_single()
+    let Ok((spaceship_transform, spaceship_velocity, aabb, continuous_fire, mut heat)) =
+        q_spaceship.get_single_mut()
     else {
         return;
     };
 
-    if !should_fire(continuous_fire, &mut missile_config.0, time, q_input_map) {
+    if !should_fire(
+        continuous_fire,
+        &mut missile_config.0,
+        &time,
+        virtual_time.elapsed(),
+        &mut buffer,
+        q_input_map,
+    ) {
+        return;
+    }
+
+    // an overheated gun still uses up the press, the missile just doesn't
+    // come out
+    if heat.overheated {
         return;
     }
+    if heat.fire() {
+        overheated.send(WeaponOverheated {
+            position: spaceship_transform.translation,
+        });
+    }
 
     let missile = Missile::new(boundary_config.max_missile_distance());
 
     spawn_actor(
         &mut commands,
-        &missile_config.0,
-        None,
-        Some((spaceship_transform, spaceship_velocity, aabb)),
+        ActorBundle::from_config(
+            &missile_config.0.with_mutators(&mutators),
+            Some((spaceship_transform, spaceship_velocity, aabb)),
+            None,
+            &mut rng.rng,
+        ),
     )
     .insert(missile);
+
+    missile_fired.send(MissileFired {
+        position: spaceship_transform.translation,
+    });
 }
 
 /// we update missile movement so that it can be despawned after it has traveled
@@ -2,32 +2,88 @@ mod aabb;
 mod actor_spawner;
 mod actor_template;
 mod collision_detection;
+mod drag;
+mod gravity_well;
+mod max_speed;
 pub mod missile;
 mod nateroid;
+mod shield;
 mod spaceship;
 mod spaceship_control;
+#[cfg(dev_tools)]
+mod stress_test;
+mod stress_world;
 mod teleport;
+mod weapon_heat;
+mod wrap_ghost;
 
+#[cfg(test)]
+pub(crate) use crate::actor::teleport::{
+    teleport_at_boundary,
+    TeleportStats,
+};
+pub use crate::actor::stress_world::{
+    stress_schedule,
+    stress_world,
+    STRESS_CUBE_COUNT,
+};
 use crate::actor::{
     aabb::AabbPlugin,
     actor_spawner::ActorSpawner,
     collision_detection::CollisionDetectionPlugin,
+    drag::DragPlugin,
+    gravity_well::GravityWellPlugin,
+    max_speed::MaxSpeedPlugin,
     missile::MissilePlugin,
     nateroid::NateroidPlugin,
+    shield::ShieldPlugin,
     spaceship::SpaceshipPlugin,
     spaceship_control::SpaceshipControlPlugin,
     teleport::TeleportPlugin,
+    weapon_heat::WeaponHeatPlugin,
+    wrap_ghost::WrapGhostPlugin,
 };
 pub use crate::actor::{
     aabb::{
+        get_mesh_aabb,
+        get_mesh_vertices,
         get_scene_aabb,
+        get_scene_vertices,
         Aabb,
     },
     actor_spawner::{
+        ActorBundle,
+        ActorKind,
         ColliderType,
         Health,
     },
-    teleport::Teleporter,
+    actor_template::{
+        MissileConfig,
+        NateroidConfig,
+        SpaceshipConfig,
+    },
+    collision_detection::ActorDamaged,
+    drag::Drag,
+    max_speed::MaxSpeed,
+    missile::MissileFired,
+    shield::ShieldBroken,
+    spaceship::{
+        ContinuousFire,
+        PlayerLives,
+        Spaceship,
+    },
+    spaceship_control::{
+        screen_side,
+        SpaceshipControl,
+        SpaceshipControlConfig,
+        SteeringMode,
+    },
+    teleport::{
+        EntityLeftBoundary,
+        EntityTeleported,
+        Teleporter,
+    },
+    weapon_heat::WeaponOverheated,
 };
 
 use bevy::prelude::*;
@@ -39,10 +95,19 @@ impl Plugin for ActorPlugin {
         app.add_plugins(AabbPlugin)
             .add_plugins(ActorSpawner)
             .add_plugins(CollisionDetectionPlugin)
+            .add_plugins(DragPlugin)
+            .add_plugins(GravityWellPlugin)
+            .add_plugins(MaxSpeedPlugin)
             .add_plugins(MissilePlugin)
             .add_plugins(NateroidPlugin)
+            .add_plugins(ShieldPlugin)
             .add_plugins(SpaceshipPlugin)
             .add_plugins(SpaceshipControlPlugin)
-            .add_plugins(TeleportPlugin);
+            .add_plugins(TeleportPlugin::default())
+            .add_plugins(WeaponHeatPlugin)
+            .add_plugins(WrapGhostPlugin);
+
+        #[cfg(dev_tools)]
+        app.add_plugins(stress_test::StressTestPlugin);
     }
 }
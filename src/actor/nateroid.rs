@@ -1,36 +1,109 @@
 use crate::{
     actor::{
-        actor_spawner::spawn_actor,
+        actor_spawner::{
+            find_clear_spawn_position,
+            spawn_actor,
+            ActorBundle,
+            ActorConfig,
+            SpawnPositionBehavior,
+            Variant,
+        },
         actor_template::NateroidConfig,
+        ActorKind,
+        Spaceship,
     },
+    console::{
+        Args,
+        CommandResult,
+        ConsoleAppExt,
+        ConsoleArgs,
+    },
+    diagnostics::NATEROID_COUNT,
+    manifest::SizeWeights,
+    mutators::Mutators,
     playfield::Boundary,
-    schedule::InGameSet,
+    rng::GameRng,
+    schedule::{
+        FrameSet,
+        InGameSet,
+    },
 };
 
 use crate::global_input::{
     toggle_active,
     GlobalAction,
 };
-use bevy::prelude::*;
+use bevy::{
+    diagnostic::{
+        Diagnostic,
+        Diagnostics,
+        RegisterDiagnostic,
+    },
+    prelude::*,
+};
+use bevy_rapier3d::prelude::ReadDefaultRapierContext;
+use leafwing_input_manager::prelude::ActionState;
+use rand::{
+    seq::IndexedRandom,
+    Rng,
+};
+use strum::{
+    AsRefStr,
+    EnumIter,
+    EnumString,
+    IntoEnumIterator,
+};
+
+// enough to make a mess without locking up the physics
+const MAX_CONSOLE_SPAWN: u32 = 50;
+// random spots tried for somewhere clear before giving up on the middle
+const SPAWN_ATTEMPTS: u32 = 16;
 
 pub struct NateroidPlugin;
 
 impl Plugin for NateroidPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            spawn_nateroid
-                .in_set(InGameSet::EntityUpdates)
-                .run_if(toggle_active(true, GlobalAction::SuppressNateroids)),
-        );
+        app.register_diagnostic(Diagnostic::new(NATEROID_COUNT))
+            .add_systems(
+                FixedUpdate,
+                spawn_nateroid
+                    .in_set(InGameSet::EntityUpdates)
+                    .run_if(toggle_active(true, GlobalAction::SuppressNateroids)),
+            )
+            .add_systems(Update, measure_nateroids.in_set(FrameSet::Ui))
+            .add_systems(
+                Update,
+                cycle_nateroid_variant
+                    .in_set(FrameSet::Input)
+                    .run_if(resource_exists::<NateroidConfig>),
+            )
+            .add_console_command(
+                "spawn",
+                "spawn nateroids the way the spawn timer would, but right now",
+                spawn_from_console,
+            );
     }
 }
 
+fn measure_nateroids(mut diagnostics: Diagnostics, q_actors: Query<&ActorKind>) {
+    diagnostics.add_measurement(&NATEROID_COUNT, || {
+        q_actors
+            .iter()
+            .filter(|kind| **kind == ActorKind::Nateroid)
+            .count() as f64
+    });
+}
+
+// a random spot clear of everything else - of the spaceship above all, which
+// used to have them appear right on top of it
 fn spawn_nateroid(
     mut commands: Commands,
     mut config: ResMut<NateroidConfig>,
     boundary: Res<Boundary>,
+    mutators: Res<Mutators>,
+    rapier_context: ReadDefaultRapierContext,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
 ) {
     let nateroid_config = &mut config.0;
 
@@ -45,5 +118,220 @@ fn spawn_nateroid(
         return;
     }
 
-    spawn_actor(&mut commands, nateroid_config, Some(boundary), None);
+    // the look goes with the size it actually comes out - giant rocks makes
+    // them large
+    let sized = nateroid_config.with_mutators(&mutators);
+    let size = NateroidSize::of(sized.scalar / nateroid_config.scalar);
+    let variant = pick_variant(&sized, size, &mut rng.rng);
+    let mut nateroid_config = sized.with_variant(variant).into_owned();
+    if let SpawnPositionBehavior::RandomWithinBounds { scale_factor } =
+        nateroid_config.spawn_position_behavior
+    {
+        // the same part of the boundary RandomWithinBounds picks from
+        let spawn_area = Boundary {
+            transform: boundary
+                .transform
+                .with_scale(boundary.transform.scale * scale_factor),
+            ..boundary.clone()
+        };
+        let radius = nateroid_config.aabb.half_extents().length() * nateroid_config.scalar;
+        let position = find_clear_spawn_position(
+            &spawn_area,
+            rapier_context.single(),
+            radius,
+            SPAWN_ATTEMPTS,
+            &mut rng.rng,
+        );
+        nateroid_config = nateroid_config.placed_at(position, &mut rng.rng);
+    }
+
+    spawn_actor(
+        &mut commands,
+        ActorBundle::from_config(&nateroid_config, None, Some(boundary), &mut rng.rng),
+    )
+    .insert(variant);
+}
+
+// one of the nateroid's looks, as likely as the manifest says for this size -
+// its own model counts 1 at every size. weights that don't add up to anything
+// leave it its own model
+fn pick_variant(config: &ActorConfig, size: NateroidSize, rng: &mut impl Rng) -> Variant {
+    let looks: Vec<(usize, f32)> = std::iter::once(SizeWeights::default())
+        .chain(config.variants.iter().map(|variant| variant.weights))
+        .map(|weights| size.weight(weights))
+        .enumerate()
+        .collect();
+
+    looks
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map_or_else(|_| Variant::default(), |(index, _)| Variant(*index))
+}
+
+// for trying out the variants - the nateroid nearest the spaceship (or the
+// middle, without one) goes on to its next look where it is, with the size and
+// collider to go with it
+fn cycle_nateroid_variant(
+    mut commands: Commands,
+    user_input: Res<ActionState<GlobalAction>>,
+    config: Res<NateroidConfig>,
+    q_spaceship: Query<&Transform, With<Spaceship>>,
+    q_nateroids: Query<(Entity, &ActorKind, &Transform, Option<&Variant>)>,
+) {
+    if !user_input.just_pressed(&GlobalAction::CycleNateroidVariant) {
+        return;
+    }
+
+    let origin = q_spaceship
+        .get_single()
+        .map_or(Vec3::ZERO, |transform| transform.translation);
+    let Some((entity, variant)) = q_nateroids
+        .iter()
+        .filter(|(_, kind, ..)| **kind == ActorKind::Nateroid)
+        .min_by(|(_, _, a, _), (_, _, b, _)| {
+            a.translation
+                .distance_squared(origin)
+                .total_cmp(&b.translation.distance_squared(origin))
+        })
+        .map(|(entity, _, _, variant)| (entity, variant.copied().unwrap_or_default()))
+    else {
+        return;
+    };
+
+    let next = Variant((variant.0 + 1) % (config.0.variants.len() + 1));
+    let look = config.0.with_variant(next);
+    let mut nateroid = commands.entity(entity);
+    nateroid.insert((next, look.aabb.clone(), look.collider.clone(), look.model.clone()));
+    look.model.insert_into(&mut nateroid);
+
+    let name = next
+        .0
+        .checked_sub(1)
+        .map_or("its own model", |index| config.0.variants[index].name.as_str());
+    info!("nateroid {entity} is now {name}");
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AsRefStr, EnumIter, EnumString)]
+#[strum(serialize_all = "lowercase")]
+enum NateroidSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl NateroidSize {
+    fn weight(self, weights: SizeWeights) -> f32 {
+        match self {
+            NateroidSize::Small => weights.small,
+            NateroidSize::Medium => weights.medium,
+            NateroidSize::Large => weights.large,
+        }
+    }
+
+    fn scale(self) -> f32 {
+        match self {
+            NateroidSize::Small => 0.5,
+            NateroidSize::Medium => 1.,
+            NateroidSize::Large => 2.,
+        }
+    }
+
+    // whichever size is closest to `scale` times the config's own, going by
+    // ratio - halfway between small and medium is 0.71, not 0.75
+    fn of(scale: f32) -> Self {
+        NateroidSize::iter()
+            .min_by(|a, b| {
+                let off = |size: &NateroidSize| (scale / size.scale()).ln().abs();
+                off(a).total_cmp(&off(b))
+            })
+            .unwrap_or_default()
+    }
+}
+
+struct SpawnArgs {
+    size:  NateroidSize,
+    count: u32,
+}
+
+impl ConsoleArgs for SpawnArgs {
+    const USAGE: &'static str = "nateroid [small|medium|large] [count]";
+
+    fn parse(args: &mut Args) -> Result<Self, String> {
+        let kind: String = args.required("what to spawn")?;
+        if kind != "nateroid" {
+            return Err(format!("can't spawn {kind} - only nateroid"));
+        }
+
+        Ok(Self {
+            size:  args.optional("size")?.unwrap_or_default(),
+            count: args.optional("count")?.unwrap_or(1),
+        })
+    }
+
+    fn complete(index: usize, _partial: &str, _world: &World) -> Vec<String> {
+        match index {
+            0 => vec!["nateroid".to_string()],
+            1 => NateroidSize::iter()
+                .map(|size| size.as_ref().to_string())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// goes through spawn_actor with the same config, mutators and boundary as the
+// timer does - only the size is the console's
+fn spawn_from_console(
+    In(args): In<SpawnArgs>,
+    mut commands: Commands,
+    config: Res<NateroidConfig>,
+    boundary: Res<Boundary>,
+    mutators: Res<Mutators>,
+    mut rng: ResMut<GameRng>,
+) -> CommandResult {
+    if args.count > MAX_CONSOLE_SPAWN {
+        return Err(format!("at most {MAX_CONSOLE_SPAWN} at a time"));
+    }
+
+    let mut nateroid_config = config.0.with_mutators(&mutators).into_owned();
+    nateroid_config.scalar *= args.size.scale();
+    // a small one with giant rocks on is no longer small
+    let size = NateroidSize::of(nateroid_config.scalar / config.0.scalar);
+
+    for _ in 0..args.count {
+        let variant = pick_variant(&nateroid_config, size, &mut rng.rng);
+        spawn_actor(
+            &mut commands,
+            ActorBundle::from_config(
+                &nateroid_config.with_variant(variant),
+                None,
+                Some(Res::clone(&boundary)),
+                &mut rng.rng,
+            ),
+        )
+        .insert(variant);
+    }
+
+    Ok(format!(
+        "spawned {} {} nateroid(s)",
+        args.count,
+        args.size.as_ref()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_is_whichever_is_closest() {
+        assert_eq!(NateroidSize::of(1.), NateroidSize::Medium);
+        assert_eq!(NateroidSize::of(0.5), NateroidSize::Small);
+        assert_eq!(NateroidSize::of(0.6), NateroidSize::Small);
+        assert_eq!(NateroidSize::of(0.75), NateroidSize::Medium);
+        // giant rocks
+        assert_eq!(NateroidSize::of(2.5), NateroidSize::Large);
+        // a small one with giant rocks on
+        assert_eq!(NateroidSize::of(1.25), NateroidSize::Medium);
+    }
 }
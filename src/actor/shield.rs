@@ -0,0 +1,91 @@
+use crate::schedule::InGameSet;
+use bevy::prelude::*;
+
+// five nateroid knocks' worth - it tops back up after a few seconds clear of
+// anything
+const SHIELD_STRENGTH: f32 = 50.;
+const RECHARGE_DELAY: f32 = 3.;
+const RECHARGE_PER_SECOND: f32 = 10.;
+
+pub struct ShieldPlugin;
+
+impl Plugin for ShieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShieldBroken>()
+            .add_systems(FixedUpdate, recharge_shields.in_set(InGameSet::EntityUpdates));
+    }
+}
+
+/// takes collision damage before the spaceship's health does. once it's down
+/// the health takes the lot until it's had RECHARGE_DELAY without a hit to
+/// start coming back
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Shield {
+    pub strength: f32,
+    since_hit:    f32,
+}
+
+impl Default for Shield {
+    fn default() -> Self {
+        Self {
+            strength:  SHIELD_STRENGTH,
+            since_hit: RECHARGE_DELAY,
+        }
+    }
+}
+
+impl Shield {
+    pub fn is_up(&self) -> bool { self.strength > 0. }
+
+    // takes what it can and hands back what gets through
+    pub(super) fn absorb(&mut self, damage: f32) -> f32 {
+        self.since_hit = 0.;
+        let absorbed = damage.clamp(0., self.strength);
+        self.strength -= absorbed;
+        damage - absorbed
+    }
+
+    fn recharge(&mut self, delta_secs: f32) {
+        self.since_hit += delta_secs;
+        if self.since_hit >= RECHARGE_DELAY {
+            self.strength = (self.strength + RECHARGE_PER_SECOND * delta_secs).min(SHIELD_STRENGTH);
+        }
+    }
+}
+
+/// sent by the hit that takes the spaceship's shield down - a hit while it's
+/// already down doesn't send another
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ShieldBroken {
+    pub position: Vec3,
+}
+
+fn recharge_shields(time: Res<Time>, mut query: Query<&mut Shield>) {
+    let delta_secs = time.delta_secs();
+
+    for mut shield in query.iter_mut() {
+        if shield.strength < SHIELD_STRENGTH {
+            shield.recharge(delta_secs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaks_then_recharges_after_the_delay() {
+        let mut shield = Shield::default();
+
+        assert_eq!(shield.absorb(30.), 0.);
+        assert_eq!(shield.absorb(30.), 10.);
+        assert!(!shield.is_up());
+
+        shield.recharge(RECHARGE_DELAY / 2.);
+        assert!(!shield.is_up(), "recharged before the delay was up");
+
+        shield.recharge(RECHARGE_DELAY);
+        assert!(shield.is_up());
+    }
+}
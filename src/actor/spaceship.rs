@@ -1,14 +1,41 @@
 use crate::{
     actor::{
-        actor_spawner::spawn_actor,
+        actor_spawner::{
+            spawn_actor,
+            ActorBundle,
+        },
         actor_template::SpaceshipConfig,
+        shield::Shield,
         spaceship_control::SpaceshipControl,
+        teleport::{
+            Teleporter,
+            WrapMode,
+        },
+        weapon_heat::WeaponHeat,
+        wrap_ghost::WrapGhost,
     },
+    mutators::Mutators,
+    playfield::Boundary,
+    rng::GameRng,
     schedule::InGameSet,
-    state::GameState,
+    state::{
+        GameState,
+        PLAY_AGAIN,
+    },
+    ui::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+    },
 };
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
+use std::borrow::Cow;
+
+// how much of its speed into the top or bottom the spaceship keeps coming off
+// it - enough to feel like a wall without flinging it back across the screen
+const VERTICAL_RESTITUTION: f32 = 0.5;
+const STARTING_LIVES: u32 = 3;
 
 #[derive(Component, Debug)]
 pub struct Spaceship;
@@ -16,44 +43,155 @@ pub struct Spaceship;
 #[derive(Component, Default)]
 pub struct ContinuousFire;
 
+/// spaceships left this run, counting the one that's out there - losing the
+/// last one is game over. the hud shows one ship icon for each
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerLives(pub u32);
+
+impl Default for PlayerLives {
+    fn default() -> Self { Self(STARTING_LIVES) }
+}
+
 pub struct SpaceshipPlugin;
 impl Plugin for SpaceshipPlugin {
     // make sure this is done after asset_loader has run
     fn build(&self, app: &mut App) {
-        // we can enter InGame a couple of ways - when we do, spawn a spaceship
-        app.add_systems(OnExit(GameState::Splash), spawn_spaceship)
-            .add_systems(OnExit(GameState::GameOver), spawn_spaceship)
+        // every run starts from the run setup screen - the spaceship is spawned
+        // once its mutators are settled. a restart from the pause menu goes
+        // through there as well, so it's where the lives are topped up.
+        // playing again from game over skips it and starts in the middle
+        app.init_resource::<PlayerLives>()
+            .add_systems(OnExit(GameState::RunSetup), (reset_lives, spawn_spaceship))
+            .add_systems(PLAY_AGAIN, (reset_lives, respawn_spaceship))
             // check if spaceship is destroyed...this will change the GameState
             .add_systems(Update, spaceship_destroyed.in_set(InGameSet::EntityUpdates));
     }
 }
 
-fn spawn_spaceship(mut commands: Commands, spaceship_config: Res<SpaceshipConfig>) {
+fn reset_lives(mut lives: ResMut<PlayerLives>) { *lives = PlayerLives::default(); }
+
+fn spawn_spaceship(
+    mut commands: Commands,
+    spaceship_config: Res<SpaceshipConfig>,
+    mutators: Res<Mutators>,
+    input_map: Res<InputMap<SpaceshipControl>>,
+    mut rng: ResMut<GameRng>,
+) {
+    spawn_ship(
+        &mut commands,
+        &spaceship_config,
+        &mutators,
+        &input_map,
+        &mut rng,
+        None,
+    );
+}
+
+// the last run's actors are still there until the end of the frame, but
+// they're already on their way out - see despawn_all_entities
+fn respawn_spaceship(
+    mut commands: Commands,
+    spaceship_config: Res<SpaceshipConfig>,
+    mutators: Res<Mutators>,
+    input_map: Res<InputMap<SpaceshipControl>>,
+    boundary: Res<Boundary>,
+    mut rng: ResMut<GameRng>,
+) {
+    spawn_ship(
+        &mut commands,
+        &spaceship_config,
+        &mutators,
+        &input_map,
+        &mut rng,
+        Some(boundary.transform.translation),
+    );
+}
+
+// the input map is the one KeyBindings built, so a rebind made before the run
+// started is already on it. without a position it's wherever the config
+// says
+fn spawn_ship(
+    commands: &mut Commands,
+    spaceship_config: &SpaceshipConfig,
+    mutators: &Mutators,
+    input_map: &InputMap<SpaceshipControl>,
+    rng: &mut GameRng,
+    position: Option<Vec3>,
+) {
     if !spaceship_config.0.spawnable {
         return;
     }
 
-    let spaceship_input = InputManagerBundle::with_map(SpaceshipControl::generate_input_map());
+    let spaceship_input = InputManagerBundle::with_map(input_map.clone());
+    let mut config = spaceship_config.0.with_mutators(mutators);
+    if let Some(position) = position {
+        config = Cow::Owned(config.into_owned().placed_at(position, &mut rng.rng));
+    }
 
-    spawn_actor(&mut commands, &spaceship_config.0, None, None)
+    spawn_actor(commands, ActorBundle::from_config(&config, None, None, &mut rng.rng))
         .insert(spaceship_input)
-        .insert(Spaceship);
+        .insert(Spaceship)
+        .insert(WeaponHeat::default())
+        .insert(Shield::default())
+        .insert(WrapGhost)
+        // the spaceship stays in the vertical band and bounces off the top and
+        // bottom - it still wraps left and right
+        .insert(Teleporter {
+            mode_per_axis: [
+                WrapMode::Wrap,
+                WrapMode::Bounce {
+                    restitution: VERTICAL_RESTITUTION,
+                },
+                WrapMode::Wrap,
+            ],
+            ..default()
+        });
 }
 
 // check if spaceship exists or not - query if get_single()
 // there should only be one - if it returns an error then the
-// spaceship doesn't exist
+// spaceship doesn't exist. a new one goes where the run's first one did, until
+// there are none left
+#[allow(clippy::too_many_arguments)]
 fn spaceship_destroyed(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
+    mut lives: ResMut<PlayerLives>,
+    mut announcements: EventWriter<Announcement>,
+    spaceship_config: Res<SpaceshipConfig>,
+    mutators: Res<Mutators>,
+    input_map: Res<InputMap<SpaceshipControl>>,
+    mut rng: ResMut<GameRng>,
     query: Query<Entity, With<Spaceship>>,
     state: Res<State<GameState>>,
 ) {
-    if query.get_single().is_err() {
-        println!(
-            "spaceship destroyed: {:?}, count {:?}",
-            state,
-            query.iter().count()
-        );
+    if query.get_single().is_ok() {
+        return;
+    }
+
+    lives.0 = lives.0.saturating_sub(1);
+    debug!(state = ?state.get(), lives = lives.0, "spaceship destroyed");
+    if lives.0 == 0 {
         next_state.set(GameState::GameOver);
+        return;
     }
+
+    let text = if lives.0 == 1 {
+        "last spaceship".to_string()
+    } else {
+        format!("{} spaceships left", lives.0)
+    };
+    announcements.send(Announcement::new(
+        text,
+        AnnouncementStyle::Warning,
+        AnnouncementPriority::High,
+    ));
+    spawn_ship(
+        &mut commands,
+        &spaceship_config,
+        &mutators,
+        &input_map,
+        &mut rng,
+        None,
+    );
 }
@@ -1,60 +1,113 @@
 use crate::{
     actor::{
         actor_template::SpaceshipConfig,
+        missile::fire_missile,
         spaceship::{
             ContinuousFire,
             Spaceship,
         },
     },
-    camera::PrimaryCamera,
+    camera::{
+        CameraShake,
+        CameraShakeConfig,
+        PrimaryCamera,
+    },
+    config::SettingsAppExt,
+    dump::DumpAppExt,
     global_input::{
-        toggle_active,
         GlobalAction,
+        InspectorAppExt,
+    },
+    input_buffer::{
+        BufferedAction,
+        DoubleTapDetector,
+    },
+    key_bindings::Binding,
+    mutators::{
+        Mutator,
+        Mutators,
     },
     orientation::{
         CameraOrientation,
         OrientationType,
     },
-    schedule::InGameSet,
+    playfield::Boundary,
+    schedule::{
+        FrameSet,
+        InGameSet,
+    },
+    state::InputContext,
+    ui::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+    },
+};
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
 };
-use bevy::prelude::*;
 use bevy_inspector_egui::{
     inspector_options::std_options::NumberDisplay,
     prelude::*,
-    quick::ResourceInspectorPlugin,
 };
-use bevy_rapier3d::dynamics::Velocity;
+use bevy_rapier3d::dynamics::{
+    LockedAxes,
+    Velocity,
+};
 use leafwing_input_manager::{
     action_state::ActionState,
-    input_map::InputMap,
     plugin::InputManagerPlugin,
     Actionlike,
+    InputControlKind,
+};
+use serde::{
+    Deserialize,
+    Serialize,
 };
+use std::time::Duration;
 use strum::{
     EnumIter,
     IntoEnumIterator,
 };
 
+// the most two taps of a side can be apart and still be a dash
+const DOUBLE_TAP_GAP: Duration = Duration::from_millis(250);
+// how long a press of the dash key waits for a side to go with it
+const DASH_BUFFER: Duration = Duration::from_millis(150);
+
 pub struct SpaceshipControlPlugin;
 
 impl Plugin for SpaceshipControlPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<SpaceshipControlConfig>()
-            .add_plugins(
-                ResourceInspectorPlugin::<SpaceshipControlConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::SpaceshipControlInspector)),
-            )
-            .init_resource::<SpaceshipControlConfig>()
+        app.register_settings::<SpaceshipControlConfig>("controls")
+            .register_settings::<ThrustConfig>("thrust")
+            .add_inspector::<SpaceshipControlConfig>(GlobalAction::SpaceshipControlInspector)
+            .add_inspector::<ThrustConfig>(GlobalAction::SpaceshipControlInspector)
+            .init_resource::<SteeringMode>()
+            .register_type::<SteeringMode>()
+            .add_dump_resource::<SteeringMode>()
             // spaceship will have input attached to it when spawning a spaceship
             .add_plugins(InputManagerPlugin::<SpaceshipControl>::default())
             .init_resource::<ActionState<SpaceshipControl>>()
-            .insert_resource(SpaceshipControl::generate_input_map())
+            .add_dump_action_log::<SpaceshipControl>("spaceship_actions")
             .add_systems(
-                Update,
-                (spaceship_movement_controls, toggle_continuous_fire)
+                FixedUpdate,
+                (
+                    aim_at_cursor
+                        .run_if(mouse_aim)
+                        .run_if(in_state(InputContext::Gameplay)),
+                    spaceship_movement_controls,
+                    dash,
+                )
                     .chain()
+                    // a missile goes out the way the spaceship's facing after
+                    // this tick's turn
+                    .before(fire_missile)
                     .in_set(InGameSet::UserInput),
-            );
+            )
+            .add_systems(Update, toggle_continuous_fire.in_set(InGameSet::UserInput))
+            .add_systems(Update, toggle_steering_mode.in_set(FrameSet::Input));
     }
 }
 
@@ -67,6 +120,25 @@ pub struct SpaceshipControlConfig {
     pub max_speed:      f32,
     #[inspector(min = 1.0, max = 10.0, display = NumberDisplay::Slider)]
     pub rotation_speed: f32,
+    // how fast mouse aim swings the ship round to the cursor
+    #[inspector(min = 1.0, max = 20.0, display = NumberDisplay::Slider)]
+    pub aim_turn_speed: f32,
+    // how much speed a dash adds all at once, and the seconds before the
+    // next one
+    #[inspector(min = 20.0, max = 200.0, display = NumberDisplay::Slider)]
+    pub dash_impulse:   f32,
+    #[inspector(min = 0.1, max = 5.0, display = NumberDisplay::Slider)]
+    pub dash_cooldown:  f32,
+}
+
+/// tank controls turn the ship with left / right, mouse aim points it at the
+/// cursor and left / right strafe instead. every run starts on tank controls
+#[derive(Resource, Reflect, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[reflect(Resource)]
+pub enum SteeringMode {
+    #[default]
+    TankControls,
+    MouseAim,
 }
 
 impl Default for SpaceshipControlConfig {
@@ -74,56 +146,137 @@ impl Default for SpaceshipControlConfig {
         Self {
             acceleration:   60.,
             rotation_speed: 5.0,
+            aim_turn_speed: 8.0,
             max_speed:      80.,
+            dash_impulse:   90.,
+            dash_cooldown:  1.0,
+        }
+    }
+}
+
+/// how far the throttle is pushed turns into how hard the ship thrusts. a key
+/// is always all the way, so it's only a stick that feels the difference
+#[derive(Resource, Reflect, Debug, Default, PartialEq, Clone, Copy)]
+#[reflect(Resource)]
+pub struct ThrustConfig {
+    pub curve: ThrustCurve,
+}
+
+// squared leaves more room at the bottom of the stick for small corrections
+#[derive(Reflect, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum ThrustCurve {
+    #[default]
+    Linear,
+    Squared,
+}
+
+impl ThrustCurve {
+    // keeps the sign, so pulling back is still backwards
+    fn apply(self, throttle: f32) -> f32 {
+        match self {
+            ThrustCurve::Linear => throttle,
+            ThrustCurve::Squared => throttle * throttle.abs(),
         }
     }
 }
 
 // This is the list of "things I want the spaceship to be able to do based on
-// input"
-#[derive(Actionlike, EnumIter, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+// input" - serde so a replay can be saved (see src/replay)
+#[derive(EnumIter, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum SpaceshipControl {
-    Accelerate,
+    // forwards is positive and backwards negative - a stick can push it part
+    // of the way, a key is all or nothing
+    Thrust,
     ContinuousFire,
     Fire,
     TurnLeft,
     TurnRight,
+    // the rest are for 3d - strafing and rolling work in 2d as well, thrusting
+    // up and down doesn't while the ship is locked to the plane
+    StrafeLeft,
+    StrafeRight,
+    ThrustUp,
+    ThrustDown,
+    RollLeft,
+    RollRight,
+    // held with a side - a double tap of the side does the same without it
+    Dash,
 }
 
-// #todo handle clash-strategy across InstantMap instances https://github.com/Leafwing-Studios/leafwing-input-manager/issues/617
+impl Actionlike for SpaceshipControl {
+    fn input_control_kind(&self) -> InputControlKind {
+        match self {
+            SpaceshipControl::Thrust => InputControlKind::Axis,
+            SpaceshipControl::ContinuousFire
+            | SpaceshipControl::Fire
+            | SpaceshipControl::TurnLeft
+            | SpaceshipControl::TurnRight
+            | SpaceshipControl::StrafeLeft
+            | SpaceshipControl::StrafeRight
+            | SpaceshipControl::ThrustUp
+            | SpaceshipControl::ThrustDown
+            | SpaceshipControl::RollLeft
+            | SpaceshipControl::RollRight
+            | SpaceshipControl::Dash => InputControlKind::Button,
+        }
+    }
+}
+
+// leafwing only resolves clashes within one input map - the shift chords in
+// the global map are kept off these keys by suppress_chorded_ship_actions
 impl SpaceshipControl {
-    pub fn generate_input_map() -> InputMap<Self> {
-        Self::iter().fold(InputMap::default(), |input_map, action| match action {
-            Self::Accelerate => input_map
-                .with(action, KeyCode::KeyW)
-                .with(action, KeyCode::ArrowUp),
-            Self::TurnLeft => input_map
-                .with(action, KeyCode::KeyA)
-                .with(action, KeyCode::ArrowLeft),
-            Self::TurnRight => input_map
-                .with(action, KeyCode::KeyD)
-                .with(action, KeyCode::ArrowRight),
-            Self::Fire => input_map.with(action, KeyCode::Space),
-            Self::ContinuousFire => input_map.with(action, KeyCode::KeyF),
-        })
+    /// what each action is bound to until KeyBindings says otherwise
+    pub fn default_bindings(self) -> Vec<Binding> {
+        match self {
+            Self::Thrust => vec![
+                Binding::KeyAxis(KeyCode::KeyS, KeyCode::KeyW),
+                Binding::KeyAxis(KeyCode::ArrowDown, KeyCode::ArrowUp),
+                Binding::GamepadAxis(GamepadAxis::LeftStickY),
+            ],
+            Self::TurnLeft => vec![Binding::Key(KeyCode::KeyA), Binding::Key(KeyCode::ArrowLeft)],
+            Self::TurnRight => vec![Binding::Key(KeyCode::KeyD), Binding::Key(KeyCode::ArrowRight)],
+            Self::Fire => vec![Binding::Key(KeyCode::Space)],
+            Self::ContinuousFire => vec![Binding::Key(KeyCode::KeyF)],
+            Self::StrafeLeft => vec![Binding::Key(KeyCode::Comma)],
+            Self::StrafeRight => vec![Binding::Key(KeyCode::Period)],
+            // F would be the other half of R but it's continuous fire
+            Self::ThrustUp => vec![Binding::Key(KeyCode::KeyR)],
+            Self::ThrustDown => vec![Binding::Key(KeyCode::KeyV)],
+            Self::RollLeft => vec![Binding::Key(KeyCode::KeyQ)],
+            Self::RollRight => vec![Binding::Key(KeyCode::KeyE)],
+            // shift would be the obvious one but shift D is debug. an alt
+            // chord in the global map switches it off while it's held - see
+            // suppress_chorded_ship_actions
+            Self::Dash => vec![Binding::Key(KeyCode::AltLeft)],
+        }
+    }
+
+    /// everything but the throttle - leafwing won't press or release an axis
+    pub fn buttons() -> impl Iterator<Item = Self> {
+        Self::iter().filter(|action| action.input_control_kind() == InputControlKind::Button)
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spaceship_movement_controls(
-    mut q_spaceship: Query<(&mut Transform, &mut Velocity), With<Spaceship>>,
+    mut q_spaceship: Query<(&mut Transform, &mut Velocity, Option<&LockedAxes>), With<Spaceship>>,
     q_camera: Query<&Transform, (With<PrimaryCamera>, Without<Spaceship>)>,
     q_input_map: Query<&ActionState<SpaceshipControl>>,
     spaceship_config: Res<SpaceshipConfig>,
     movement_config: Res<SpaceshipControlConfig>,
+    steering: Res<SteeringMode>,
+    thrust_config: Res<ThrustConfig>,
+    mutators: Res<Mutators>,
     time: Res<Time>,
     orientation_mode: Res<CameraOrientation>,
 ) {
     if let Ok(camera_transform) = q_camera.get_single() {
         // we can use this because there is only exactly one spaceship - so we're not
         // looping over the query
-        if let Ok((mut spaceship_transform, mut velocity)) = q_spaceship.get_single_mut() {
+        if let Ok((mut spaceship_transform, mut velocity, locked_axes)) = q_spaceship.get_single_mut() {
             // dynamically update from inspector while game is running to change size
-            spaceship_transform.scale = Vec3::splat(spaceship_config.0.scalar);
+            spaceship_transform.scale =
+                Vec3::splat(spaceship_config.0.scalar * mutators.ship_scale_multiplier());
 
             let controls = q_input_map.single();
 
@@ -131,11 +284,15 @@ fn spaceship_movement_controls(
             let delta_seconds = time.delta_secs();
             let rotation_speed = movement_config.rotation_speed;
 
-            if controls.pressed(&SpaceshipControl::TurnRight) {
+            // with mouse aim the cursor does the turning (see aim_at_cursor)
+            // and the turn keys strafe instead
+            let mouse_aim = *steering == SteeringMode::MouseAim;
+
+            if !mouse_aim && controls.pressed(&SpaceshipControl::TurnRight) {
                 // right
                 velocity.angvel.z = 0.0;
                 rotation = rotation_speed * delta_seconds;
-            } else if controls.pressed(&SpaceshipControl::TurnLeft) {
+            } else if !mouse_aim && controls.pressed(&SpaceshipControl::TurnLeft) {
                 // left
                 velocity.angvel.z = 0.0;
                 rotation = -rotation_speed * delta_seconds;
@@ -148,36 +305,241 @@ fn spaceship_movement_controls(
                 rotation = -rotation;
             }
 
+            if mutators.is_active(Mutator::MirrorControls) {
+                rotation = -rotation;
+            }
+
+            let side = screen_side(camera_transform, &mutators);
+
             // rotate around the z-axis
             spaceship_transform.rotate_z(rotation);
 
             let max_speed = movement_config.max_speed;
             let accel = movement_config.acceleration;
 
-            if controls.pressed(&SpaceshipControl::Accelerate) {
+            // the ship's nose, with its right hand side and the way out of the
+            // plane to go with it
+            let heading = -spaceship_transform.forward().as_vec3();
+            let right = spaceship_transform.right().as_vec3() * side;
+
+            let throttle = controls.value(&SpaceshipControl::Thrust).clamp(-1.0, 1.0);
+            let mut thrust = heading * thrust_config.curve.apply(throttle);
+
+            let strafe_right = controls.pressed(&SpaceshipControl::StrafeRight)
+                || (mouse_aim && controls.pressed(&SpaceshipControl::TurnRight));
+            let strafe_left = controls.pressed(&SpaceshipControl::StrafeLeft)
+                || (mouse_aim && controls.pressed(&SpaceshipControl::TurnLeft));
+
+            if strafe_right {
+                thrust += right;
+            } else if strafe_left {
+                thrust -= right;
+            }
+
+            // a ship locked to the plane would only have the lock take it back
+            // off again - so up and down do nothing rather than fight it
+            let locked_to_plane =
+                locked_axes.is_some_and(|locked| locked.contains(LockedAxes::TRANSLATION_LOCKED_Z));
+
+            if !locked_to_plane {
+                if controls.pressed(&SpaceshipControl::ThrustUp) {
+                    thrust += Vec3::Z;
+                } else if controls.pressed(&SpaceshipControl::ThrustDown) {
+                    thrust -= Vec3::Z;
+                }
+            }
+
+            // strafing and thrusting together is no faster than either one -
+            // a part pushed throttle is still only part of the way
+            if thrust != Vec3::ZERO {
                 apply_acceleration(
                     &mut velocity,
-                    -spaceship_transform.forward().as_vec3(),
+                    thrust.clamp_length_max(1.0),
                     accel,
                     max_speed,
                     delta_seconds,
-                    orientation_mode,
+                    &orientation_mode,
                 );
             }
 
-            /* let mut roll = 0.0;
+            let mut roll = 0.0;
 
-               if keyboard_input.pressed(ShiftLeft) {
-                roll = -SPACESHIP_ROLL_SPEED * time.delta_seconds();
-            } else if keyboard_input.pressed(ControlLeft) {
-                roll = SPACESHIP_ROLL_SPEED * time.delta_seconds();
-            }*/
+            if controls.pressed(&SpaceshipControl::RollRight) {
+                roll = rotation_speed * delta_seconds;
+            } else if controls.pressed(&SpaceshipControl::RollLeft) {
+                roll = -rotation_speed * delta_seconds;
+            }
+
+            // roll around the nose - it doesn't change where the ship is
+            // headed, only which way up it is
+            if roll != 0.0 {
+                if let Ok(nose) = Dir3::new(heading) {
+                    spaceship_transform.rotate_axis(nose, roll);
+                }
+            }
+        }
+    }
+}
+
+/// the ship's right is the screen's right from the usual side, the one
+/// turning has to be flipped for - from the other side, or with the controls
+/// mirrored, left is right. turning right goes round z the other way
+pub fn screen_side(camera_transform: &Transform, mutators: &Mutators) -> f32 {
+    let facing_opposite = camera_transform.forward().dot(Vec3::new(0.0, 0.0, -1.0)) > 0.0;
+    let mut side = if facing_opposite { 1.0 } else { -1.0 };
+    if mutators.is_active(Mutator::MirrorControls) {
+        side = -side;
+    }
+    side
+}
+
+/// left on the spaceship after a dash until it can dash again
+#[derive(Component, Debug)]
+struct DashCooldown(Timer);
+
+// a double tap of either left or either right - turning or strafing - or the
+// dash key with a side, kicks the ship that way all at once. either
+// direction's double tap is read every tick, cooling down or not, so one that
+// came in during the cooldown doesn't go off the moment it ends
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn dash(
+    mut commands: Commands,
+    mut q_spaceship: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &ActionState<SpaceshipControl>,
+            Option<&mut DashCooldown>,
+        ),
+        With<Spaceship>,
+    >,
+    q_camera: Query<&Transform, (With<PrimaryCamera>, Without<Spaceship>)>,
+    mut taps: ResMut<DoubleTapDetector<SpaceshipControl>>,
+    mut buffer: ResMut<BufferedAction<SpaceshipControl>>,
+    control: Res<SpaceshipControlConfig>,
+    mutators: Res<Mutators>,
+    time: Res<Time>,
+    virtual_time: Res<Time<Virtual>>,
+    mut shake: ResMut<CameraShake>,
+    shake_config: Res<CameraShakeConfig>,
+) {
+    let (Ok((entity, transform, mut velocity, controls, cooldown)), Ok(camera_transform)) =
+        (q_spaceship.get_single_mut(), q_camera.get_single())
+    else {
+        return;
+    };
+
+    // the clock the presses were buffered on
+    let now = virtual_time.elapsed();
+    let mut tapped = |actions: [SpaceshipControl; 2]| {
+        // both read, so neither is left waiting
+        let read: Vec<bool> = actions
+            .iter()
+            .map(|action| taps.double_tapped(action, DOUBLE_TAP_GAP))
+            .collect();
+        read.contains(&true)
+    };
+    let tapped_left = tapped([SpaceshipControl::TurnLeft, SpaceshipControl::StrafeLeft]);
+    let tapped_right = tapped([SpaceshipControl::TurnRight, SpaceshipControl::StrafeRight]);
 
-            // rotate around the local z-axis
-            // the rotation is relative to the current rotation
-            // transform.rotate_local_z(roll);
+    // the dash key and a side, whichever went down first
+    let dash_key = buffer.consume_if_buffered(&SpaceshipControl::Dash, now, DASH_BUFFER);
+    let holding_dash = controls.pressed(&SpaceshipControl::Dash);
+    let mut side_with_dash = |actions: [SpaceshipControl; 2]| {
+        actions.iter().any(|action| {
+            (dash_key && controls.pressed(action))
+                || (holding_dash && buffer.consume_if_buffered(action, now, DASH_BUFFER))
+        })
+    };
+    let left = tapped_left || side_with_dash([SpaceshipControl::TurnLeft, SpaceshipControl::StrafeLeft]);
+    let right = tapped_right || side_with_dash([SpaceshipControl::TurnRight, SpaceshipControl::StrafeRight]);
+
+    if let Some(mut cooldown) = cooldown {
+        if !cooldown.0.tick(time.delta()).finished() {
+            return;
         }
+        commands.entity(entity).remove::<DashCooldown>();
     }
+
+    let direction = match (left, right) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => return,
+    };
+
+    let right_hand = transform.right().as_vec3() * screen_side(camera_transform, &mutators);
+    velocity.linvel += right_hand * direction * control.dash_impulse;
+
+    commands.entity(entity).insert(DashCooldown(Timer::from_seconds(
+        control.dash_cooldown,
+        TimerMode::Once,
+    )));
+    shake.add_trauma(shake_config.dash);
+}
+
+fn mouse_aim(steering: Res<SteeringMode>) -> bool { *steering == SteeringMode::MouseAim }
+
+// the cursor goes through the camera as it is - orbited, zoomed, whatever -
+// onto the plane the ship flies in, and the ship swings round to face that
+// point no faster than the aim turn speed
+fn aim_at_cursor(
+    mut q_spaceship: Query<(&mut Transform, &mut Velocity), With<Spaceship>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    boundary: Res<Boundary>,
+    control: Res<SpaceshipControlConfig>,
+    time: Res<Time>,
+) {
+    let (Ok((mut transform, mut velocity)), Ok(window), Ok((camera, camera_transform))) = (
+        q_spaceship.get_single_mut(),
+        q_window.get_single(),
+        q_camera.get_single(),
+    ) else {
+        return;
+    };
+    let Some(target) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
+        .and_then(|ray| boundary.play_plane_point(ray))
+    else {
+        return;
+    };
+
+    let heading = (-transform.forward()).truncate();
+    let to_target = (target - transform.translation).truncate();
+    if to_target.length_squared() < f32::EPSILON || heading.length_squared() < f32::EPSILON {
+        return;
+    }
+
+    let max_turn = control.aim_turn_speed * time.delta_secs();
+    let turn = heading.angle_to(to_target).clamp(-max_turn, max_turn);
+
+    velocity.angvel.z = 0.0;
+    transform.rotate_z(turn);
+}
+
+// M flips between the two and says which it's on now - the same resource the
+// controls tab applies
+fn toggle_steering_mode(
+    user_input: Res<ActionState<GlobalAction>>,
+    mut steering: ResMut<SteeringMode>,
+    mut announcements: EventWriter<Announcement>,
+) {
+    if !user_input.just_pressed(&GlobalAction::ToggleSteeringMode) {
+        return;
+    }
+
+    let (next, text) = match *steering {
+        SteeringMode::TankControls => (SteeringMode::MouseAim, "mouse aim steering"),
+        SteeringMode::MouseAim => (SteeringMode::TankControls, "tank controls"),
+    };
+    *steering = next;
+    announcements.send(Announcement::new(
+        text,
+        AnnouncementStyle::Info,
+        AnnouncementPriority::Low,
+    ));
 }
 
 fn apply_acceleration(
@@ -186,14 +548,16 @@ fn apply_acceleration(
     acceleration: f32,
     max_speed: f32,
     delta_seconds: f32,
-    orientation: Res<CameraOrientation>,
+    orientation: &CameraOrientation,
 ) {
     let proposed_velocity = velocity.linvel + direction * (acceleration * delta_seconds);
     let proposed_speed = proposed_velocity.length();
 
-    // Ensure we're not exceeding max velocity
-    if proposed_speed > max_speed {
-        velocity.linvel = proposed_velocity.normalize() * max_speed;
+    // Ensure we're not exceeding max velocity - thrusting can't take back a
+    // dash over it either, only drag does that
+    let limit = max_speed.max(velocity.linvel.length());
+    if proposed_speed > limit {
+        velocity.linvel = proposed_velocity.normalize() * limit;
     } else {
         velocity.linvel = proposed_velocity;
     }
@@ -212,16 +576,46 @@ fn apply_acceleration(
 fn toggle_continuous_fire(
     mut commands: Commands,
     q_spaceship: Query<(Entity, &ActionState<SpaceshipControl>, Option<&ContinuousFire>), With<Spaceship>>,
+    mut announcements: EventWriter<Announcement>,
 ) {
     if let Ok((entity, control, continuous)) = q_spaceship.get_single() {
         if control.just_pressed(&SpaceshipControl::ContinuousFire) {
-            if continuous.is_some() {
-                println!("removing continuous");
+            let text = if continuous.is_some() {
+                debug!("removing continuous fire");
                 commands.entity(entity).remove::<ContinuousFire>();
+                "continuous fire off"
             } else {
-                println!("adding continuous");
+                debug!("adding continuous fire");
                 commands.entity(entity).insert(ContinuousFire);
-            }
+                "continuous fire on"
+            };
+            announcements.send(Announcement::new(
+                text,
+                AnnouncementStyle::Info,
+                AnnouncementPriority::Low,
+            ));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn spaceship_control_config_round_trips() {
+        assert_settings_round_trip(nudged::<SpaceshipControlConfig>());
+    }
+
+    // the curve's an enum, which nudged leaves alone
+    #[test]
+    fn thrust_config_round_trips() {
+        assert_settings_round_trip(ThrustConfig {
+            curve: ThrustCurve::Squared,
+        });
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    actor::stress_world::{
+        drifting_cube,
+        STRESS_CUBE_COUNT,
+        STRESS_CUBE_SIZE,
+    },
+    camera::RenderLayer,
+    despawn::{
+        DestructionQueue,
+        DestructionReason,
+    },
+    global_input::GlobalAction,
+    interpolation::InterpolatedTransform,
+    playfield::Boundary,
+    schedule::FrameSet,
+    state::PlayingGame,
+};
+use bevy::{
+    color::palettes::tailwind,
+    prelude::*,
+    render::view::RenderLayers,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+// shift + t fills the playfield with cubes that do nothing but drift and wrap.
+// they have no collider so they don't get in the way of playing - press it
+// again to clear them out
+pub struct StressTestPlugin;
+
+impl Plugin for StressTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            toggle_stress_cubes
+                .in_set(FrameSet::Input)
+                .run_if(in_state(PlayingGame)),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+struct StressCube;
+
+fn toggle_stress_cubes(
+    mut commands: Commands,
+    user_input: Res<ActionState<GlobalAction>>,
+    boundary: Res<Boundary>,
+    mut queue: ResMut<DestructionQueue>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_cubes: Query<Entity, With<StressCube>>,
+) {
+    if !user_input.just_pressed(&GlobalAction::StressTest) {
+        return;
+    }
+
+    if !q_cubes.is_empty() {
+        for entity in q_cubes.iter() {
+            queue.mark(entity, DestructionReason::Expired);
+        }
+        return;
+    }
+
+    let mesh = meshes.add(Cuboid::from_length(STRESS_CUBE_SIZE));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::from(tailwind::AMBER_400),
+        unlit: true,
+        ..default()
+    });
+
+    // not the GameRng - a profiling aid shouldn't change how the run plays out
+    let mut rng = rand::rng();
+    let cubes: Vec<_> = (0..STRESS_CUBE_COUNT)
+        .map(|_| {
+            let (transform, cube) = drifting_cube(&mut rng, &boundary);
+            (
+                StressCube,
+                transform,
+                InterpolatedTransform::new(transform),
+                cube,
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                RenderLayers::from_layers(RenderLayer::Game.layers()),
+                Name::new("StressCube"),
+            )
+        })
+        .collect();
+
+    commands.spawn_batch(cubes);
+}
@@ -0,0 +1,124 @@
+use crate::{
+    actor::{
+        actor_spawner::random_vec3,
+        teleport::{
+            teleport_at_boundary,
+            TeleportStats,
+        },
+        Aabb,
+        EntityLeftBoundary,
+        EntityTeleported,
+        Teleporter,
+    },
+    despawn::DestructionQueue,
+    mutators::Mutators,
+    playfield::{
+        init_portals,
+        update_approaching_portals,
+        update_emerging_portals,
+        ActorPortals,
+        Boundary,
+        PortalConfig,
+    },
+    schedule::EntityBatching,
+    time_controls::GameTime,
+};
+use bevy::{
+    prelude::*,
+    tasks::{
+        ComputeTaskPool,
+        TaskPool,
+    },
+};
+use bevy_rapier3d::prelude::{
+    RigidBody,
+    Velocity,
+};
+use rand::{
+    rngs::StdRng,
+    Rng,
+    SeedableRng,
+};
+
+// far more than a game ever has - enough that teleporting and the portals show
+// up in a profile
+pub const STRESS_CUBE_COUNT: usize = 2_000;
+pub(super) const STRESS_CUBE_SIZE: f32 = 1.;
+const STRESS_CUBE_SPEED: f32 = 20.;
+// how far the cubes drift each time the stress schedule runs - one tick's
+// worth at STRESS_CUBE_SPEED
+const STRESS_STEP: f32 = STRESS_CUBE_SPEED / 60.;
+
+/// a cube that does nothing but drift and wrap - where it starts, somewhere
+/// flat in the boundary, and the rest of it heading any which way. it has no
+/// collider so it doesn't get in the way of playing, and nothing to draw it
+/// with - the stress test key adds that
+pub(super) fn drifting_cube(rng: &mut impl Rng, boundary: &Boundary) -> (Transform, impl Bundle) {
+    let half_size = Vec3::splat(STRESS_CUBE_SIZE / 2.);
+    let min = boundary.transform.translation - boundary.transform.scale / 2.;
+    let max = boundary.transform.translation + boundary.transform.scale / 2.;
+    let transform = Transform::from_translation(random_vec3(rng, min.x..max.x, min.y..max.y, 0.0..0.0));
+    let direction = random_vec3(rng, -1.0..1.0, -1.0..1.0, 0.0..0.0).normalize_or(Vec3::X);
+
+    let cube = (
+        RigidBody::KinematicVelocityBased,
+        Velocity::linear(direction * STRESS_CUBE_SPEED),
+        Aabb {
+            min: -half_size,
+            max: half_size,
+        },
+        Teleporter::default(),
+        ActorPortals::default(),
+    );
+    (transform, cube)
+}
+
+/// just what teleporting and portal tracking need, with `count` drifting cubes
+/// in it - the same cubes every time. benches/boundary.rs runs
+/// `stress_schedule` over it
+pub fn stress_world(count: usize) -> World {
+    // they're split across threads
+    ComputeTaskPool::get_or_init(TaskPool::default);
+
+    let mut world = World::new();
+    world.init_resource::<Boundary>();
+    world.init_resource::<Mutators>();
+    world.init_resource::<Time>();
+    world.init_resource::<GameTime>();
+    world.init_resource::<TeleportStats>();
+    world.init_resource::<DestructionQueue>();
+    world.init_resource::<PortalConfig>();
+    world.init_resource::<EntityBatching>();
+    world.init_resource::<Events<EntityTeleported>>();
+    world.init_resource::<Events<EntityLeftBoundary>>();
+
+    let boundary = world.resource::<Boundary>().clone();
+    let mut rng = StdRng::seed_from_u64(492);
+    let cubes: Vec<_> = (0..count).map(|_| drifting_cube(&mut rng, &boundary)).collect();
+    world.spawn_batch(cubes);
+    world
+}
+
+/// teleporting and the portals in the order their plugins run them, after a
+/// step that moves the cubes along in place of physics - so some of them wrap
+/// every time
+pub fn stress_schedule() -> Schedule {
+    let mut schedule = Schedule::default();
+    schedule.add_systems(
+        (
+            drift,
+            teleport_at_boundary,
+            init_portals,
+            update_approaching_portals,
+            update_emerging_portals,
+        )
+            .chain(),
+    );
+    schedule
+}
+
+fn drift(mut q_cubes: Query<(&mut Transform, &Velocity), With<Teleporter>>) {
+    for (mut transform, velocity) in q_cubes.iter_mut() {
+        transform.translation += velocity.linvel.normalize_or_zero() * STRESS_STEP;
+    }
+}
@@ -1,42 +1,824 @@
 use crate::{
-    playfield::Boundary,
-    schedule::InGameSet,
+    actor::Aabb,
+    despawn::{
+        DestructionQueue,
+        DestructionReason,
+    },
+    dump::{
+        ron_list,
+        DumpAppExt,
+        DUMP_FRAMES,
+    },
+    global_input::{
+        GlobalAction,
+        InspectorAppExt,
+    },
+    mutators::{
+        Mutator,
+        Mutators,
+    },
+    playfield::{
+        Boundary,
+        BoundaryFace,
+        BoundaryResized,
+        BoundaryShape,
+    },
+    schedule::{
+        EntityBatching,
+        InGameSet,
+    },
+    state::{
+        GameState,
+        PLAY_AGAIN,
+    },
 };
-use bevy::prelude::*;
+use bevy::{
+    core::FrameCount,
+    ecs::schedule::{
+        InternedScheduleLabel,
+        ScheduleLabel,
+    },
+    prelude::*,
+    utils::{
+        HashMap,
+        Parallel,
+    },
+};
+use bevy_rapier3d::dynamics::Velocity;
+use std::collections::VecDeque;
+
+/// wrapping, and pulling things back in when the boundary shrinks
+pub struct TeleportPlugin {
+    /// on the simulation's tick along with physics, so nothing can get a
+    /// tick past a face before it's wrapped - the default. off it's once a
+    /// frame in Update, the way it used to be
+    pub run_in_fixed_update: bool,
+}
+
+impl Default for TeleportPlugin {
+    fn default() -> Self {
+        Self {
+            run_in_fixed_update: true,
+        }
+    }
+}
 
-pub struct TeleportPlugin;
+impl TeleportPlugin {
+    fn schedule(&self) -> InternedScheduleLabel {
+        if self.run_in_fixed_update {
+            FixedUpdate.intern()
+        } else {
+            Update.intern()
+        }
+    }
+}
 
 impl Plugin for TeleportPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, teleport_at_boundary.in_set(InGameSet::EntityUpdates));
+        app.add_event::<EntityTeleported>()
+            .add_event::<EntityLeftBoundary>()
+            .register_type::<WrapMode>()
+            .init_resource::<WrapLog>()
+            .init_resource::<TeleportStats>()
+            .register_type::<TeleportStats>()
+            .add_inspector::<TeleportStats>(GlobalAction::TeleportStatsInspector)
+            .add_dump_section("wraps", dump_wraps)
+            .add_systems(
+                self.schedule(),
+                (keep_inside_resized_boundary, teleport_at_boundary, record_wraps)
+                    .chain()
+                    .in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(Update, forget_removed_teleporters)
+            .add_systems(OnExit(GameState::RunSetup), reset_teleport_stats)
+            .add_systems(PLAY_AGAIN, reset_teleport_stats);
     }
 }
 
+/// the flags are still set for anything that reads them, but they're only
+/// good for the tick they were set in - EntityTeleported is the way to hear
+/// about a wrap, and the flags are going away in the next release
 #[derive(Component, Reflect, Debug, Default, Clone)]
 pub struct Teleporter {
     pub just_teleported:          bool,
     pub last_teleported_position: Option<Vec3>,
     pub last_teleported_normal:   Option<Dir3>,
+    // the axes it's just wrapped on and isn't yet far enough back inside to
+    // wrap on again - see Boundary::teleport_with_reentry
+    pub awaiting_reentry:         BVec3,
+    // what happens at the faces on each of the boundary's x, y and z axes
+    pub mode_per_axis:            [WrapMode; 3],
+}
+
+/// what an entity does when it reaches the faces on one axis. only the box
+/// has faces - in a sphere everything wraps. an axis the boundary itself
+/// doesn't wrap holds everything on its face whatever this says
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq)]
+pub enum WrapMode {
+    #[default]
+    Wrap,
+    // reflected off the face - restitution is how much of the speed into the
+    // face it keeps
+    Bounce {
+        restitution: f32,
+    },
+    Despawn,
+}
+
+/// sent by teleport_at_boundary for every wrap - where it went out and came
+/// back in, and through which faces. the exit face is worked out from where
+/// it was before the wrap, so nothing has to guess it afterwards
+#[derive(Event, Reflect, Debug, Clone, Copy)]
+pub struct EntityTeleported {
+    pub entity:     Entity,
+    pub from:       Vec3,
+    pub to:         Vec3,
+    pub exit_face:  BoundaryFace,
+    pub entry_face: BoundaryFace,
+}
+
+/// sent when something with a Despawn axis reaches one of its faces - it's
+/// marked for despawning at the same time, and is gone by the end of the frame
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EntityLeftBoundary {
+    pub entity:   Entity,
+    pub position: Vec3,
+    pub face:     BoundaryFace,
+}
+
+// how many of the latest wraps TeleportStats keeps in full
+const RECENT_WRAPS: usize = 32;
+
+/// how often things wrap - overall, per entity and when each last did, with
+/// the latest few wraps in full. teleport_at_boundary keeps it up to date and
+/// it starts again with each run
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct TeleportStats {
+    pub total:      u64,
+    pub per_entity: HashMap<Entity, EntityWrapStats>,
+    pub recent:     VecDeque<EntityTeleported>,
+}
+
+#[derive(Reflect, Debug, Default, Clone, Copy)]
+pub struct EntityWrapStats {
+    pub wraps:     u64,
+    // elapsed game time, in seconds
+    pub last_wrap: f32,
+}
+
+impl TeleportStats {
+    pub fn wraps_for(&self, entity: Entity) -> u64 {
+        self.per_entity.get(&entity).map_or(0, |stats| stats.wraps)
+    }
+
+    pub fn reset(&mut self) { *self = Self::default(); }
+
+    fn record(&mut self, wrap: EntityTeleported, now: f32) {
+        let wraps = self.wraps_for(wrap.entity) + 1;
+        self.per_entity.insert(
+            wrap.entity,
+            EntityWrapStats {
+                wraps,
+                last_wrap: now,
+            },
+        );
+        self.total += 1;
+
+        self.recent.push_back(wrap);
+        if self.recent.len() > RECENT_WRAPS {
+            self.recent.pop_front();
+        }
+    }
+}
+
+// how far inside the wall something that was left outside ends up, past
+// its own size - on the wall itself it would wrap straight away
+const RESIZE_MARGIN: f32 = 1.;
+
+// after the boundary shrinks anything left outside it is pulled back in to
+// just inside the nearest wall - its own walls, whichever way the boundary's
+// turned and whatever shape it is. it's a move, not a wrap, so it isn't
+// counted as teleporting
+pub(super) fn keep_inside_resized_boundary(
+    boundary: Res<Boundary>,
+    mut resized: EventReader<BoundaryResized>,
+    mut q_teleporters: Query<(&mut Transform, Option<&Aabb>), With<Teleporter>>,
+) {
+    // growing can't leave anything outside - and the boundary already has the
+    // latest size, so it's only whether any of these shrank it. counted rather
+    // than any() so every event gets read
+    let shrunk = resized
+        .read()
+        .filter(|resize| resize.new_scale.cmplt(resize.old_scale).any())
+        .count();
+    if shrunk == 0 {
+        return;
+    }
+
+    for (mut transform, aabb) in q_teleporters.iter_mut() {
+        let position = transform.translation;
+        if boundary.signed_distance(position) <= 0. {
+            continue;
+        }
+
+        // far enough in that none of it is left poking out, however it's turned
+        let reach = aabb.map_or(0., |aabb| {
+            aabb.half_extents().length() * transform.scale.max_element()
+        });
+        transform.translation = boundary.pull_inside(position, reach + RESIZE_MARGIN);
+    }
 }
 
-fn teleport_at_boundary(
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn teleport_at_boundary(
     boundary: Res<Boundary>,
-    mut teleporting_entities: Query<(&mut Transform, &mut Teleporter)>,
+    mutators: Res<Mutators>,
+    time: Res<Time>,
+    mut stats: ResMut<TeleportStats>,
+    mut wraps: Local<Parallel<Vec<EntityTeleported>>>,
+    mut exits: Local<Parallel<Vec<EntityLeftBoundary>>>,
+    mut teleported: EventWriter<EntityTeleported>,
+    mut left_boundary: EventWriter<EntityLeftBoundary>,
+    mut queue: ResMut<DestructionQueue>,
+    batching: Res<EntityBatching>,
+    mut teleporting_entities: Query<(Entity, &mut Transform, &mut Teleporter, Option<&mut Velocity>)>,
 ) {
-    for (mut transform, mut teleporter) in teleporting_entities.iter_mut() {
-        let original_position = transform.translation;
+    let no_wrap = mutators.is_active(Mutator::NoWrap);
 
-        let teleported_position = boundary.calculate_teleport_position(original_position);
+    // each entity only ever wraps itself, so this is split across threads
+    teleporting_entities
+        .par_iter_mut()
+        .batching_strategy(batching.0.clone())
+        .for_each(|(entity, mut transform, mut teleporter, mut velocity)| {
+            let original_position = transform.translation;
 
-        if teleported_position != original_position {
-            transform.translation = teleported_position;
-            teleporter.just_teleported = true;
-            teleporter.last_teleported_position = Some(teleported_position);
-            teleporter.last_teleported_normal = Some(boundary.get_normal_for_position(teleported_position));
-        } else {
-            teleporter.just_teleported = false;
-            teleporter.last_teleported_position = None;
-            teleporter.last_teleported_normal = None;
+            // with no wrap anything that moves bounces off every wall instead,
+            // keeping all its speed - it never counts as teleported
+            if let (true, Some(velocity)) = (no_wrap, velocity.as_mut()) {
+                let (mut position, mut linvel) = (original_position, velocity.linvel);
+                for axis in 0..3 {
+                    (position, linvel) = boundary.bounce_on(position, linvel, axis, 1.);
+                }
+                if position != original_position {
+                    transform.translation = position;
+                }
+                if linvel != velocity.linvel {
+                    velocity.linvel = linvel;
+                }
+                teleporter.just_teleported = false;
+                teleporter.last_teleported_position = None;
+                teleporter.last_teleported_normal = None;
+                teleporter.awaiting_reentry = BVec3::FALSE;
+                return;
+            }
+
+            let modes = teleporter.mode_per_axis;
+
+            // out through a despawn face it's gone, whatever the other axes
+            // were going to do with it
+            let exit = (0..3)
+                .filter(|&axis| modes[axis] == WrapMode::Despawn)
+                .find_map(|axis| boundary.face_reached_on(original_position, axis));
+            if let Some(face) = exit {
+                exits.scope(|exits| {
+                    exits.push(EntityLeftBoundary {
+                        entity,
+                        position: original_position,
+                        face,
+                    });
+                });
+                return;
+            }
+
+            // bounce axes first - they put it back on the face, so what's left
+            // for the wrap is only ever the axes that wrap. the sphere's
+            // surface isn't any one axis's face, so there they wrap with the
+            // rest - only no wrap bounces off it
+            let mut position = original_position;
+            let mut linvel = velocity.as_ref().map_or(Vec3::ZERO, |velocity| velocity.linvel);
+            for (axis, mode) in modes.into_iter().enumerate() {
+                if let (WrapMode::Bounce { restitution }, BoundaryShape::Cuboid) = (mode, boundary.shape) {
+                    (position, linvel) = boundary.bounce_on(position, linvel, axis, restitution);
+                }
+            }
+            if let Some(mut velocity) = velocity {
+                if linvel != velocity.linvel {
+                    velocity.linvel = linvel;
+                }
+            }
+
+            let wrap_axes = BVec3::from(modes.map(|mode| mode == WrapMode::Wrap));
+            let (teleported_position, wrapped) =
+                boundary.teleport_with_reentry(position, wrap_axes, &mut teleporter.awaiting_reentry);
+
+            if teleported_position != original_position {
+                transform.translation = teleported_position;
+            }
+
+            // held against a wall that doesn't wrap isn't a teleport - nothing
+            // jumped so there's nothing to interpolate around or draw a portal for
+            if wrapped {
+                let normal = boundary.get_normal_for_position(teleported_position);
+                teleporter.just_teleported = true;
+                teleporter.last_teleported_position = Some(teleported_position);
+                teleporter.last_teleported_normal = Some(normal);
+                let (exit_face, _) = boundary.nearest_face(original_position);
+                wraps.scope(|wraps| {
+                    wraps.push(EntityTeleported {
+                        entity,
+                        from: original_position,
+                        to: teleported_position,
+                        exit_face,
+                        entry_face: boundary.closest_face(normal),
+                    });
+                });
+            } else {
+                teleporter.just_teleported = false;
+                teleporter.last_teleported_position = None;
+                teleporter.last_teleported_normal = None;
+            }
+        });
+
+    // sorted so they come out in the same order whichever thread found them
+    let mut events = Vec::new();
+    wraps.drain_into(&mut events);
+    events.sort_by_key(|event| event.entity);
+    let now = time.elapsed_secs();
+    for &event in &events {
+        stats.record(event, now);
+    }
+    teleported.send_batch(events);
+
+    // a few fixed ticks can run before the end of the frame despawns it, so
+    // only the first one that finds it outside says so
+    let mut left = Vec::new();
+    exits.drain_into(&mut left);
+    left.sort_by_key(|event| event.entity);
+    for event in left {
+        if !queue.is_marked(event.entity) {
+            queue.mark(event.entity, DestructionReason::LeftBoundary);
+            left_boundary.send(event);
         }
     }
 }
+
+// per entity stats would otherwise pile up for every missile ever fired
+fn forget_removed_teleporters(mut removed: RemovedComponents<Teleporter>, mut stats: ResMut<TeleportStats>) {
+    for entity in removed.read() {
+        stats.per_entity.remove(&entity);
+    }
+}
+
+fn reset_teleport_stats(mut stats: ResMut<TeleportStats>) { stats.reset(); }
+
+// the last few hundred frames of wraps, for diagnostics dumps
+#[derive(Resource, Debug, Default)]
+struct WrapLog {
+    wraps: VecDeque<WrapRecord>,
+}
+
+#[derive(Debug)]
+struct WrapRecord {
+    frame:    u32,
+    entity:   Entity,
+    position: Vec3,
+    normal:   Option<Dir3>,
+}
+
+// teleporting runs in parallel so the wraps are sorted to come out the same
+// way every time
+fn record_wraps(
+    frame: Res<FrameCount>,
+    mut log: ResMut<WrapLog>,
+    q_teleporters: Query<(Entity, &Teleporter)>,
+) {
+    let mut wrapped: Vec<_> = q_teleporters
+        .iter()
+        .filter(|(_, teleporter)| teleporter.just_teleported)
+        .filter_map(|(entity, teleporter)| {
+            teleporter.last_teleported_position.map(|position| WrapRecord {
+                frame: frame.0,
+                entity,
+                position,
+                normal: teleporter.last_teleported_normal,
+            })
+        })
+        .collect();
+    wrapped.sort_by_key(|record| record.entity);
+    log.wraps.extend(wrapped);
+
+    while log
+        .wraps
+        .front()
+        .is_some_and(|record| frame.0.wrapping_sub(record.frame) >= DUMP_FRAMES)
+    {
+        log.wraps.pop_front();
+    }
+}
+
+fn dump_wraps(world: &World) -> String {
+    let Some(log) = world.get_resource::<WrapLog>() else {
+        return "[]".to_string();
+    };
+
+    ron_list(log.wraps.iter().map(|record| {
+        let position = record.position.to_array();
+        let normal = record.normal.map(|normal| normal.to_array());
+        format!(
+            "(frame: {}, entity: {:?}, position: {position:?}, normal: {normal:?})",
+            record.frame,
+            record.entity.to_string(),
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        schedule::SchedulePlugin,
+        state::{
+            IsPaused,
+            PlayingGame,
+        },
+        time_controls::{
+            GameTime,
+            SimulationStep,
+        },
+    };
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        state::app::StatesPlugin,
+        tasks::{
+            ComputeTaskPool,
+            TaskPool,
+        },
+    };
+
+    // the boundary is a couple of hundred units across so this is float noise
+    const EPSILON: f32 = 0.01;
+
+    // just what teleport_at_boundary reads and writes
+    fn teleport_world() -> World {
+        // it splits the teleporters across threads
+        ComputeTaskPool::get_or_init(TaskPool::default);
+
+        let mut world = World::new();
+        world.init_resource::<Boundary>();
+        world.init_resource::<Mutators>();
+        world.init_resource::<Time>();
+        world.init_resource::<TeleportStats>();
+        world.init_resource::<DestructionQueue>();
+        world.init_resource::<EntityBatching>();
+        world.init_resource::<Events<EntityTeleported>>();
+        world.init_resource::<Events<EntityLeftBoundary>>();
+        world
+    }
+
+    fn teleport(world: &mut World) { world.run_system_once(teleport_at_boundary).unwrap(); }
+
+    fn wrap_of(entity: Entity) -> EntityTeleported {
+        EntityTeleported {
+            entity,
+            from: Vec3::X,
+            to: Vec3::NEG_X,
+            exit_face: BoundaryFace::Right,
+            entry_face: BoundaryFace::Left,
+        }
+    }
+
+    #[test]
+    fn wraps_are_counted_per_entity() {
+        let mut stats = TeleportStats::default();
+        let (first, second) = (Entity::from_raw(1), Entity::from_raw(2));
+
+        stats.record(wrap_of(first), 1.);
+        stats.record(wrap_of(first), 2.5);
+        stats.record(wrap_of(second), 3.);
+
+        assert_eq!(stats.wraps_for(first), 2);
+        assert_eq!(stats.wraps_for(second), 1);
+        assert_eq!(stats.wraps_for(Entity::from_raw(3)), 0);
+        assert_eq!(stats.per_entity[&first].last_wrap, 2.5);
+        assert_eq!(stats.total, 3);
+    }
+
+    // only the latest few are kept in full, the counts keep going
+    #[test]
+    fn recent_wraps_are_capped() {
+        let mut stats = TeleportStats::default();
+        let entity = Entity::from_raw(1);
+        for _ in 0..RECENT_WRAPS + 5 {
+            stats.record(wrap_of(entity), 0.);
+        }
+
+        assert_eq!(stats.recent.len(), RECENT_WRAPS);
+        assert_eq!(stats.wraps_for(entity), RECENT_WRAPS as u64 + 5);
+    }
+
+    #[test]
+    fn reset_forgets_everything() {
+        let mut stats = TeleportStats::default();
+        let entity = Entity::from_raw(1);
+        stats.record(wrap_of(entity), 1.);
+
+        stats.reset();
+
+        assert_eq!(stats.wraps_for(entity), 0);
+        assert_eq!(stats.total, 0);
+        assert!(stats.per_entity.is_empty());
+        assert!(stats.recent.is_empty());
+    }
+
+    // something sitting still exactly on the right wall wraps to the left wall
+    // once and then stays put - it used to go back and forth every frame
+    #[test]
+    fn stationary_on_the_wall_wraps_once() {
+        let mut world = teleport_world();
+        let boundary = world.resource::<Boundary>().clone();
+        let half = boundary.transform.scale / 2.;
+        let on_wall = boundary.transform.translation + Vec3::new(half.x, 0., 0.);
+        let entity = world
+            .spawn((
+                Transform::from_translation(on_wall),
+                Velocity::zero(),
+                Teleporter::default(),
+            ))
+            .id();
+
+        for _ in 0..10 {
+            teleport(&mut world);
+        }
+
+        let position = world.get::<Transform>(entity).unwrap().translation;
+        let left_wall = boundary.transform.translation.x - half.x;
+        assert_eq!(world.resource::<TeleportStats>().wraps_for(entity), 1);
+        assert!(
+            (position.x - left_wall).abs() < EPSILON,
+            "ended up at {position}, not on the left wall"
+        );
+    }
+
+    // something grazing the top face, well within the re-entry margin of it,
+    // while it flies out through the right face twice - it's the right face
+    // it has to get clear of before wrapping again, not the nearest one, so it
+    // goes round both times instead of being held on the right wall
+    #[test]
+    fn sliding_along_a_face_still_wraps_through_another() {
+        let mut world = teleport_world();
+        let boundary = world.resource::<Boundary>().clone();
+        let center = boundary.transform.translation;
+        let half = boundary.transform.scale / 2.;
+        let grazing = half.y - boundary.reentry_margin / 4.;
+        let entity = world
+            .spawn((
+                Transform::from_translation(center + Vec3::Y * grazing),
+                Velocity::linear(Vec3::X),
+                Teleporter::default(),
+            ))
+            .id();
+
+        // two widths and a bit, a few units a step
+        let step = 10.;
+        let steps = (2. * boundary.transform.scale.x / step) as usize + 3;
+        for _ in 0..steps {
+            world.get_mut::<Transform>(entity).unwrap().translation.x += step;
+            teleport(&mut world);
+        }
+
+        let position = world.get::<Transform>(entity).unwrap().translation;
+        assert_eq!(world.resource::<TeleportStats>().wraps_for(entity), 2);
+        assert!(
+            (position.y - center.y - grazing).abs() < EPSILON,
+            "slid off the top face to {position}"
+        );
+        assert!(
+            (position.x - center.x).abs() < half.x - step,
+            "held at {position} instead of going round"
+        );
+    }
+
+    // with z a wall, only the one that actually wraps is flagged - the one
+    // just held on the z face moves but didn't teleport
+    #[test]
+    fn a_clamp_is_not_a_teleport() {
+        let mut world = teleport_world();
+        world.resource_mut::<Boundary>().wrap_axes = BVec3::new(true, true, false);
+        let half = world.resource::<Boundary>().transform.scale / 2.;
+        let mut spawn_at = |position: Vec3| {
+            world
+                .spawn((
+                    Transform::from_translation(position),
+                    Velocity::zero(),
+                    Teleporter::default(),
+                ))
+                .id()
+        };
+        let wrapping = spawn_at(Vec3::new(half.x + 1., 0., half.z + 5.));
+        let clamped = spawn_at(Vec3::new(0., 0., half.z + 5.));
+
+        teleport(&mut world);
+
+        let teleporter = world.get::<Teleporter>(wrapping).unwrap();
+        assert!(teleporter.just_teleported);
+        assert!(teleporter.last_teleported_position.is_some());
+
+        let teleporter = world.get::<Teleporter>(clamped).unwrap();
+        assert!(!teleporter.just_teleported);
+        assert!(teleporter.last_teleported_position.is_none());
+        let position = world.get::<Transform>(clamped).unwrap().translation;
+        assert!((position.z - half.z).abs() < EPSILON, "held at {position}");
+        assert_eq!(world.resource::<TeleportStats>().total, 1);
+    }
+
+    // with no wrap the walls are solid on every axis - something past a
+    // corner is put back on both walls with both of those speeds turned
+    // around, and nothing counts as a wrap
+    #[test]
+    fn no_wrap_bounces_off_every_wall() {
+        let mut world = teleport_world();
+        world.resource_mut::<Mutators>().active.set(Mutator::NoWrap, true);
+        let half = world.resource::<Boundary>().transform.scale / 2.;
+        let entity = world
+            .spawn((
+                Transform::from_translation(Vec3::new(half.x + 1., half.y + 2., 0.)),
+                Velocity::linear(Vec3::new(10., 5., 3.)),
+                Teleporter::default(),
+            ))
+            .id();
+
+        teleport(&mut world);
+
+        let position = world.get::<Transform>(entity).unwrap().translation;
+        let linvel = world.get::<Velocity>(entity).unwrap().linvel;
+        assert!(
+            position.distance(Vec3::new(half.x, half.y, 0.)) < EPSILON,
+            "held at {position}"
+        );
+        assert!(
+            linvel.distance(Vec3::new(-10., -5., 3.)) < EPSILON,
+            "bounced to {linvel}"
+        );
+        assert!(!world.get::<Teleporter>(entity).unwrap().just_teleported);
+        assert_eq!(world.resource::<TeleportStats>().total, 0);
+    }
+
+    // something that gets two and a half widths in one step, either way, has
+    // gone round twice and half again - it ends up where it would have if
+    // every wrap had been caught, not just inside the far face
+    #[test]
+    fn long_step_wraps_by_the_remainder() {
+        for (start, step) in [(0.2, 2.5), (-0.2, -2.5)] {
+            let mut world = teleport_world();
+            let boundary = world.resource::<Boundary>().clone();
+            let center = boundary.transform.translation;
+            let width = boundary.transform.scale.x;
+            let entity = world
+                .spawn((
+                    Transform::from_translation(center + Vec3::X * (start + step) * width),
+                    Velocity::linear(Vec3::X * step.signum()),
+                    Teleporter::default(),
+                ))
+                .id();
+
+            teleport(&mut world);
+
+            // 0.2 + 2.5 widths from the middle is 0.3 short of the middle once
+            // the whole widths are taken off
+            let expected = center + Vec3::X * (start + step - step.round()) * width;
+            let position = world.get::<Transform>(entity).unwrap().translation;
+            assert_eq!(world.resource::<TeleportStats>().wraps_for(entity), 1);
+            assert!(
+                position.distance(expected) < EPSILON,
+                "{step} widths on from {start} came in at {position}, not {expected}"
+            );
+        }
+    }
+
+    // the boundary's already been shrunk to `boundary` - a teleporter left
+    // outside it, with an aabb a unit each way, is pulled back in
+    fn pulled_in_after_shrinking(boundary: Boundary, outside: Vec3) -> Vec3 {
+        let mut world = World::new();
+        let old_scale = boundary.transform.scale * 2.;
+        let new_scale = boundary.transform.scale;
+        world.insert_resource(boundary);
+        world.init_resource::<Events<BoundaryResized>>();
+        world.send_event(BoundaryResized { old_scale, new_scale });
+        let entity = world
+            .spawn((
+                Transform::from_translation(outside),
+                Teleporter::default(),
+                Aabb {
+                    min: Vec3::NEG_ONE,
+                    max: Vec3::ONE,
+                },
+            ))
+            .id();
+
+        world.run_system_once(keep_inside_resized_boundary).unwrap();
+        world.get::<Transform>(entity).unwrap().translation
+    }
+
+    // out past the corner of a box turned 45 degrees - inside the world
+    // axis box around it, but not inside the box itself
+    #[test]
+    fn shrinking_a_turned_box_pulls_back_inside_it() {
+        let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_4);
+        let boundary = Boundary {
+            transform: Transform::from_rotation(rotation).with_scale(Vec3::new(100., 100., 40.)),
+            ..default()
+        };
+        let outside = rotation * Vec3::new(55., 0., 0.);
+        assert!(boundary.signed_distance(outside) > 0.);
+
+        let position = pulled_in_after_shrinking(boundary.clone(), outside);
+        let clearance = -boundary.signed_distance(position);
+        assert!(
+            clearance >= Vec3::ONE.length() + RESIZE_MARGIN - EPSILON,
+            "pulled in to {position}, only {clearance} from the wall"
+        );
+        let local = rotation.inverse() * position;
+        assert!(
+            local.y.abs() < EPSILON && local.z.abs() < EPSILON,
+            "pulled in along the wrong way, to {local} in the box's own frame"
+        );
+    }
+
+    #[test]
+    fn shrinking_a_sphere_pulls_back_inside_it() {
+        let boundary = Boundary {
+            shape: BoundaryShape::Sphere { radius: 50. },
+            ..default()
+        };
+        let center = boundary.transform.translation;
+        // inside the box around the sphere, outside the sphere
+        let outside = center + Vec3::new(40., 40., 0.);
+        assert!(boundary.signed_distance(outside) > 0.);
+
+        let position = pulled_in_after_shrinking(boundary.clone(), outside);
+        let clearance = -boundary.signed_distance(position);
+        assert!(
+            clearance >= Vec3::ONE.length() + RESIZE_MARGIN - EPSILON,
+            "pulled in to {position}, only {clearance} from the surface"
+        );
+        assert!(
+            (position - center).normalize().distance(Vec3::new(1., 1., 0.).normalize()) < EPSILON,
+            "pulled in off to the side, to {position}"
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct FixedTicks(u32);
+
+    // turned off it's back to once a frame - the first frame has no time to
+    // tick with so FixedUpdate never runs, and it still wraps in Update. the
+    // whole plugin would bring the inspector along, so it's just where the
+    // plugin puts the wrap
+    #[test]
+    fn teleporting_in_update_wraps_without_a_fixed_tick() {
+        let plugin = TeleportPlugin {
+            run_in_fixed_update: false,
+        };
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, SchedulePlugin))
+            .insert_state(GameState::InGame {
+                paused:     false,
+                inspecting: false,
+            })
+            .add_computed_state::<PlayingGame>()
+            .add_computed_state::<IsPaused>()
+            .add_event::<EntityTeleported>()
+            .add_event::<EntityLeftBoundary>()
+            .init_resource::<Boundary>()
+            .init_resource::<Mutators>()
+            .init_resource::<DestructionQueue>()
+            .init_resource::<TeleportStats>()
+            .init_resource::<SimulationStep>()
+            .init_resource::<GameTime>()
+            .init_resource::<EntityBatching>()
+            .init_resource::<FixedTicks>()
+            .add_systems(plugin.schedule(), teleport_at_boundary.in_set(InGameSet::EntityUpdates))
+            .add_systems(FixedUpdate, |mut ticks: ResMut<FixedTicks>| ticks.0 += 1);
+
+        let half_width = app.world().resource::<Boundary>().transform.scale.x / 2.;
+        let entity = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(half_width + 1., 0., 0.),
+                Velocity::linear(Vec3::X),
+                Teleporter::default(),
+            ))
+            .id();
+
+        app.update();
+
+        assert_eq!(app.world().resource::<FixedTicks>().0, 0);
+        assert_eq!(app.world().resource::<TeleportStats>().wraps_for(entity), 1);
+        let position = app.world().get::<Transform>(entity).unwrap().translation;
+        assert!(
+            (position.x + half_width).abs() < EPSILON,
+            "still at {position} after the frame"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    actor::missile::fire_missile,
+    schedule::InGameSet,
+};
+use bevy::prelude::*;
+
+// continuous fire puts out twenty a second, so it's a few seconds of holding
+// the trigger down before it overheats - tapping never gets near it
+const HEAT_PER_SHOT: f32 = 0.04;
+const COOLING_PER_SECOND: f32 = 0.5;
+// once it's overheated it won't fire again until it's cooled down to here
+const COOLED_DOWN: f32 = 0.5;
+
+pub struct WeaponHeatPlugin;
+
+impl Plugin for WeaponHeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WeaponOverheated>().add_systems(
+            FixedUpdate,
+            cool_weapons
+                .before(fire_missile)
+                .in_set(InGameSet::UserInput),
+        );
+    }
+}
+
+/// every shot warms the spaceship's gun up a bit and it cools off on its own.
+/// when it gets all the way to 1 it's overheated and swallows shots until it's
+/// cooled down again
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct WeaponHeat {
+    pub heat:       f32,
+    pub overheated: bool,
+}
+
+impl WeaponHeat {
+    // true for the shot that tips it over
+    pub(super) fn fire(&mut self) -> bool {
+        self.heat = (self.heat + HEAT_PER_SHOT).min(1.);
+        let tipped = !self.overheated && self.heat >= 1.;
+        self.overheated |= tipped;
+        tipped
+    }
+
+    fn cool(&mut self, delta_secs: f32) {
+        self.heat = (self.heat - COOLING_PER_SECOND * delta_secs).max(0.);
+        if self.overheated && self.heat <= COOLED_DOWN {
+            self.overheated = false;
+        }
+    }
+}
+
+/// sent the moment the spaceship's gun overheats - once, not for every shot
+/// it swallows afterwards
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WeaponOverheated {
+    pub position: Vec3,
+}
+
+fn cool_weapons(time: Res<Time>, mut query: Query<&mut WeaponHeat>) {
+    let delta_secs = time.delta_secs();
+
+    for mut heat in query.iter_mut() {
+        if heat.heat > 0. {
+            heat.cool(delta_secs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overheats_once_and_cools_back_down() {
+        let mut weapon = WeaponHeat::default();
+
+        let tipped: Vec<bool> = (0..40).map(|_| weapon.fire()).collect();
+        assert_eq!(tipped.iter().filter(|&&tipped| tipped).count(), 1);
+        assert!(weapon.overheated);
+
+        weapon.cool(0.5);
+        assert!(weapon.overheated, "still too hot at {}", weapon.heat);
+
+        weapon.cool(0.5);
+        assert!(!weapon.overheated, "should have cooled down at {}", weapon.heat);
+    }
+}
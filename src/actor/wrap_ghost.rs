@@ -0,0 +1,91 @@
+use crate::{
+    actor::Aabb,
+    asset_loader::ModelSource,
+    playfield::Boundary,
+    schedule::FrameSet,
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+    utils::HashMap,
+};
+
+/// classic asteroids - something crossing the edge shows up on the other side
+/// as well, part on each. anything with a WrapGhost gets a copy of its model
+/// at the wrapped position for each face it's near, turned the same way every
+/// frame
+///
+/// a ghost is only something to look at - no collider, no Teleporter, no
+/// ActorKind - so physics, wrapping, scoring and picking never see it. ghosts
+/// are matched to their actor every frame and any without one go, so they
+/// can't outlive it however it was despawned
+pub struct WrapGhostPlugin;
+
+impl Plugin for WrapGhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WrapGhost>()
+            .add_systems(Update, update_wrap_ghosts.in_set(FrameSet::Visual));
+    }
+}
+
+/// opt in to being drawn on both sides of the boundary while crossing it
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct WrapGhost;
+
+// which actor the ghost copies, and the axis it's wrapped along - an actor
+// near a corner has one per face it's near
+#[derive(Component, Debug, Clone, Copy)]
+struct GhostOf {
+    actor: Entity,
+    axis:  usize,
+}
+
+// the ghost shows up once any part of the actor could be over the face - its
+// bounds at their widest, however it's turned
+fn ghost_distance(aabb: &Aabb, transform: &Transform) -> f32 {
+    (aabb.half_extents() * transform.scale).length()
+}
+
+#[allow(clippy::type_complexity)]
+fn update_wrap_ghosts(
+    mut commands: Commands,
+    boundary: Res<Boundary>,
+    q_actors: Query<(Entity, &Transform, &Aabb, &ModelSource, Option<&RenderLayers>), With<WrapGhost>>,
+    mut q_ghosts: Query<(Entity, &GhostOf, &mut Transform), Without<WrapGhost>>,
+) {
+    let mut wanted: HashMap<(Entity, usize), Transform> = HashMap::default();
+    let mut models: HashMap<Entity, (&ModelSource, Option<&RenderLayers>)> = HashMap::default();
+
+    for (entity, transform, aabb, model, render_layers) in q_actors.iter() {
+        let distance = ghost_distance(aabb, transform);
+        for (axis, offset) in boundary.wrap_offsets_within(transform.translation, distance) {
+            wanted.insert(
+                (entity, axis),
+                transform.with_translation(transform.translation + offset),
+            );
+            models.insert(entity, (model, render_layers));
+        }
+    }
+
+    // the ones still wanted move along with their actor, the rest go
+    for (ghost, ghost_of, mut transform) in q_ghosts.iter_mut() {
+        match wanted.remove(&(ghost_of.actor, ghost_of.axis)) {
+            Some(wanted) => *transform = wanted,
+            None => commands.entity(ghost).despawn_recursive(),
+        }
+    }
+
+    for ((actor, axis), transform) in wanted {
+        let Some((model, render_layers)) = models.get(&actor) else {
+            continue;
+        };
+        let mut ghost = commands.spawn((
+            Name::new("WrapGhost"),
+            GhostOf { actor, axis },
+            transform,
+            render_layers.cloned().unwrap_or_default(),
+        ));
+        model.insert_into(&mut ghost);
+    }
+}
@@ -1,64 +1,670 @@
-use bevy::asset::LoadState;
+use crate::{
+    actor::{
+        ActorKind,
+        ColliderType,
+    },
+    camera::RenderLayer,
+    despawn::despawn,
+    manifest::{
+        Manifest,
+        ManifestEntry,
+        ManifestLoader,
+        ManifestVariant,
+        SizeWeights,
+        MANIFEST_PATH,
+    },
+    schedule::FrameSet,
+    state::GameState,
+    ui::UiTheme,
+};
 /// let's use just load assets once, amigos
-use bevy::prelude::*;
+use bevy::{
+    asset::{
+        io::AssetReaderError,
+        AssetLoadError,
+        LoadState,
+    },
+    color::palettes::tailwind,
+    ecs::system::EntityCommands,
+    gltf::GltfAssetLabel,
+    prelude::*,
+    render::view::RenderLayers,
+    utils::{
+        HashMap,
+        HashSet,
+    },
+};
 
+/// the game sits in GameState::Loading until the manifest (see manifest.rs)
+/// has said which models to use and every one of them has loaded, with a
+/// count on screen so a slow disk or a slow connection doesn't look like a
+/// hang. if one of them can't be loaded it goes to GameState::LoadFailed,
+/// which says which and stays there - there's nothing to play without them
 pub struct AssetLoaderPlugin;
 
 impl Plugin for AssetLoaderPlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<AssetsState>() // necessary to tell if they've finished loading
+        app.init_asset::<Manifest>()
+            .init_asset_loader::<ManifestLoader>()
             .init_resource::<SceneAssets>()
+            .init_resource::<AudioAssets>()
             // make sure this loads before the spaceship uses it - right now that is
             // handled by running this PreStartup and spaceship in Startup
             .add_systems(PreStartup, load_assets)
-            .add_systems(Update, check_asset_loading.run_if(in_state(AssetsState::Loading)));
+            .add_systems(OnEnter(GameState::Loading), spawn_loading_text)
+            .add_systems(
+                Update,
+                check_asset_loading
+                    .in_set(FrameSet::Input)
+                    .run_if(in_state(GameState::Loading)),
+            )
+            .add_systems(OnExit(GameState::Loading), despawn_loading_text)
+            .add_systems(OnEnter(GameState::LoadFailed), spawn_load_failed_screen)
+            .add_event::<SceneReloaded>()
+            .init_resource::<PendingSceneReloads>()
+            .add_systems(Update, reload_modified_scenes.in_set(FrameSet::Input))
+            .add_systems(Update, report_audio_failures.in_set(FrameSet::Input));
     }
 }
 
-#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
-pub enum AssetsState {
-    #[default]
-    Loading,
-    Loaded,
+// the model for an actor the manifest doesn't mention, or for all of them if
+// there's no manifest to be had
+fn default_model(kind: ActorKind) -> &'static str {
+    match kind {
+        ActorKind::Missile => "models/Bullets Pickup.glb",
+        ActorKind::Nateroid => "models/donut.glb",
+        ActorKind::Spaceship => "models/Spaceship.glb",
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct ManifestHandle(Handle<Manifest>);
+
+/// what an actor is drawn with - its model, or if the model's file isn't
+/// there at all, a plain shape standing in for it. it goes on the actor so
+/// anything that copies how it looks (wrap ghosts) can do it either way
+#[derive(Component, Clone, Debug)]
+pub enum ModelSource {
+    Scene(Handle<Scene>),
+    Mesh {
+        mesh:     Handle<Mesh>,
+        material: Handle<StandardMaterial>,
+    },
+}
+
+impl Default for ModelSource {
+    fn default() -> Self { ModelSource::Scene(Handle::default()) }
+}
+
+impl ModelSource {
+    /// adds whatever draws it, in place of whatever drew the entity before
+    pub fn insert_into(&self, entity: &mut EntityCommands) {
+        match self {
+            ModelSource::Scene(scene) => {
+                entity
+                    .remove::<(Mesh3d, MeshMaterial3d<StandardMaterial>)>()
+                    .insert(SceneRoot(scene.clone()));
+            },
+            ModelSource::Mesh { mesh, material } => {
+                entity
+                    .remove::<SceneRoot>()
+                    .insert((Mesh3d(mesh.clone()), MeshMaterial3d(material.clone())));
+            },
+        }
+    }
+
+    fn scene(&self) -> Option<&Handle<Scene>> {
+        match self {
+            ModelSource::Scene(scene) => Some(scene),
+            ModelSource::Mesh { .. } => None,
+        }
+    }
+}
+
+// roughly the size and shape of the real thing, in world units - the models
+// are exported a hundredth of the size (see BLENDER_SCALE) but these aren't.
+// the spaceship's nose is +z and the missile is stood on end, the way their
+// models are
+fn fallback_model(
+    kind: ActorKind,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> ModelSource {
+    let (mesh, color) = match kind {
+        ActorKind::Missile => (Capsule3d::new(0.3, 1.2).mesh().build(), tailwind::AMBER_300),
+        ActorKind::Nateroid => (
+            Sphere::new(5.)
+                .mesh()
+                .ico(2)
+                .unwrap_or_else(|_| Sphere::new(5.).mesh().build()),
+            tailwind::STONE_400,
+        ),
+        ActorKind::Spaceship => (
+            Cone::new(2., 6.)
+                .mesh()
+                .build()
+                .rotated_by(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+            tailwind::SKY_300,
+        ),
+    };
+    ModelSource::Mesh {
+        mesh:     meshes.add(mesh),
+        material: materials.add(StandardMaterial::from(Color::from(color))),
+    }
+}
+
+// the file itself isn't there - anything else wrong with a model is worth
+// stopping for
+fn is_missing(error: &AssetLoadError) -> bool {
+    matches!(
+        error,
+        AssetLoadError::AssetReaderError(AssetReaderError::NotFound(_))
+    )
+}
+
+/// an actor's model and whatever the manifest said about setting it up
+#[derive(Clone, Debug)]
+pub struct ActorAssets {
+    pub model:    ModelSource,
+    pub name:     String,
+    pub scale:    Option<f32>,
+    pub collider: Option<ColliderType>,
+    pub variants: Vec<ModelVariant>,
+}
+
+/// another look for an actor - see ManifestVariant
+#[derive(Clone, Debug)]
+pub struct ModelVariant {
+    pub model:   ModelSource,
+    pub name:    String,
+    pub weights: SizeWeights,
 }
 
 // all the models are loaded via SceneBundle - the models
 // can have multiple elements and scene makes all that possible
+//
+// empty until the manifest is in - see check_asset_loading
 #[derive(Resource, Clone, Debug, Default)]
 pub struct SceneAssets {
-    pub missile:   Handle<Scene>,
-    pub nateroid:  Handle<Scene>,
-    pub spaceship: Handle<Scene>, // pub sphere: Handle<Scene>,
+    actors: HashMap<ActorKind, ActorAssets>,
 }
 
+impl SceneAssets {
+    pub fn get(&self, kind: ActorKind) -> Option<&ActorAssets> { self.actors.get(&kind) }
+
+    /// the model for an actor - a default handle, which draws nothing, until
+    /// the manifest is in
+    pub fn model(&self, kind: ActorKind) -> ModelSource {
+        self.get(kind)
+            .map(|assets| assets.model.clone())
+            .unwrap_or_default()
+    }
+
+    /// the other looks an actor can have - only nateroids have any
+    pub fn variants(&self, kind: ActorKind) -> &[ModelVariant] {
+        self.get(kind)
+            .map(|assets| assets.variants.as_slice())
+            .unwrap_or_default()
+    }
+
+    // which actor a model is for, whether it's its own or a variant
+    fn kind_of(&self, id: AssetId<Scene>) -> Option<ActorKind> {
+        let is_it = |model: &ModelSource| model.scene().is_some_and(|scene| scene.id() == id);
+        self.actors.iter().find_map(|(kind, assets)| {
+            (is_it(&assets.model) || assets.variants.iter().any(|variant| is_it(&variant.model)))
+                .then_some(*kind)
+        })
+    }
+
+    fn from_manifest(manifest: Option<&Manifest>, asset_server: &AssetServer) -> Self {
+        if let Some(manifest) = manifest {
+            for name in manifest.unknown_entries() {
+                warn!("{MANIFEST_PATH} has an entry for {name}, which isn't an actor - ignoring it");
+            }
+        }
+
+        let actors = ActorKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let entry = manifest.and_then(|manifest| manifest.entry(kind)).cloned();
+                if manifest.is_some() && entry.is_none() {
+                    warn!(
+                        "{MANIFEST_PATH} has no entry for {kind}, using {}",
+                        default_model(kind)
+                    );
+                }
+                let ManifestEntry {
+                    model,
+                    name,
+                    scale,
+                    collider,
+                    mut variants,
+                } = entry.unwrap_or_else(|| ManifestEntry {
+                    model:    default_model(kind).to_string(),
+                    name:     None,
+                    scale:    None,
+                    collider: None,
+                    variants: Vec::new(),
+                });
+
+                if kind != ActorKind::Nateroid && !variants.is_empty() {
+                    warn!("{MANIFEST_PATH} has variants for {kind}, only nateroids use them - ignoring them");
+                    variants.clear();
+                }
+
+                let name = name.unwrap_or_else(|| kind.to_string().to_lowercase());
+                // every variant is loaded now, with the rest, so the first one to
+                // spawn isn't waiting on the disk
+                let variants = variants
+                    .into_iter()
+                    .enumerate()
+                    .map(
+                        |(
+                            index,
+                            ManifestVariant {
+                                model,
+                                name: variant_name,
+                                weights,
+                            },
+                        )| ModelVariant {
+                            model: load_scene(asset_server, model),
+                            name: variant_name.unwrap_or_else(|| format!("{name} variant {}", index + 1)),
+                            weights,
+                        },
+                    )
+                    .collect();
+
+                let assets = ActorAssets {
+                    model: load_scene(asset_server, model),
+                    name,
+                    scale,
+                    collider,
+                    variants,
+                };
+                (kind, assets)
+            })
+            .collect();
+
+        Self { actors }
+    }
+
+    // every model, variants and all, with what to call it
+    fn models(&self) -> impl Iterator<Item = (&str, &ModelSource)> {
+        self.actors.values().flat_map(|assets| {
+            std::iter::once((assets.name.as_str(), &assets.model)).chain(
+                assets
+                    .variants
+                    .iter()
+                    .map(|variant| (variant.name.as_str(), &variant.model)),
+            )
+        })
+    }
+
+    fn scenes(&self) -> impl Iterator<Item = &Handle<Scene>> {
+        self.models().filter_map(|(_, model)| model.scene())
+    }
+}
+
+fn load_scene(asset_server: &AssetServer, model: String) -> ModelSource {
+    ModelSource::Scene(asset_server.load(GltfAssetLabel::Scene(0).from_asset(model)))
+}
+
+// sound effects are loaded alongside the models but they don't gate
+// GameState::Loading - a missing or slow sound should never keep the game from
+// starting, it just plays nothing until it shows up. a file that can't be
+// loaded is logged by report_audio_failures. they're all made by
+// tools/synth_sounds.py
+#[derive(Resource, Clone, Debug, Default)]
+pub struct AudioAssets {
+    pub engine:             Handle<AudioSource>,
+    pub heartbeat:          Handle<AudioSource>,
+    pub missile_fired:      Handle<AudioSource>,
+    pub missile_hit:        Handle<AudioSource>,
+    pub music_danger:       Handle<AudioSource>,
+    pub music_game_over:    Handle<AudioSource>,
+    pub music_gameplay:     Handle<AudioSource>,
+    pub music_menu:         Handle<AudioSource>,
+    pub nateroid_destroyed: Handle<AudioSource>,
+    pub ship_damaged:       Handle<AudioSource>,
+    pub shield_broken:      Handle<AudioSource>,
+    pub ship_destroyed:     Handle<AudioSource>,
+    pub ui_pause_closed:    Handle<AudioSource>,
+    pub ui_pause_opened:    Handle<AudioSource>,
+    pub ui_toggle:          Handle<AudioSource>,
+    pub weapon_overheated:  Handle<AudioSource>,
+    pub wrap_pop:           Vec<Handle<AudioSource>>,
+    pub wrap_suck:          Vec<Handle<AudioSource>>,
+}
+
+impl AudioAssets {
+    fn handles(&self) -> impl Iterator<Item = &Handle<AudioSource>> {
+        self.sound_effects().chain([
+            &self.music_danger,
+            &self.music_game_over,
+            &self.music_gameplay,
+            &self.music_menu,
+        ])
+    }
+
+    // everything but the music
+    fn sound_effects(&self) -> impl Iterator<Item = &Handle<AudioSource>> {
+        [
+            &self.engine,
+            &self.heartbeat,
+            &self.missile_fired,
+            &self.missile_hit,
+            &self.nateroid_destroyed,
+            &self.ship_damaged,
+            &self.shield_broken,
+            &self.ship_destroyed,
+            &self.ui_pause_closed,
+            &self.ui_pause_opened,
+            &self.ui_toggle,
+            &self.weapon_overheated,
+        ]
+        .into_iter()
+        .chain(&self.wrap_pop)
+        .chain(&self.wrap_suck)
+    }
+}
+
+// the models themselves wait for the manifest, see check_asset_loading
 pub fn load_assets(
-    //    mut commands: Commands,
-    mut scene_assets: ResMut<SceneAssets>,
+    mut commands: Commands,
+    mut audio_assets: ResMut<AudioAssets>,
     asset_server: Res<AssetServer>,
 ) {
-    *scene_assets = SceneAssets {
-        missile:   asset_server.load("models/Bullets Pickup.glb#Scene0"),
-        nateroid:  asset_server.load("models/donut.glb#Scene0"),
-        spaceship: asset_server.load("models/Spaceship.glb#Scene0"),
+    commands.insert_resource(ManifestHandle(asset_server.load(MANIFEST_PATH)));
+
+    *audio_assets = AudioAssets {
+        engine:             asset_server.load("sounds/engine.wav"),
+        heartbeat:          asset_server.load("sounds/heartbeat.wav"),
+        missile_fired:      asset_server.load("sounds/missile_fired.wav"),
+        missile_hit:        asset_server.load("sounds/missile_hit.wav"),
+        music_danger:       asset_server.load("music/danger.wav"),
+        music_game_over:    asset_server.load("music/game_over.wav"),
+        music_gameplay:     asset_server.load("music/gameplay.wav"),
+        music_menu:         asset_server.load("music/menu.wav"),
+        nateroid_destroyed: asset_server.load("sounds/nateroid_destroyed.wav"),
+        ship_damaged:       asset_server.load("sounds/ship_damaged.wav"),
+        shield_broken:      asset_server.load("sounds/shield_broken.wav"),
+        ship_destroyed:     asset_server.load("sounds/ship_destroyed.wav"),
+        ui_pause_closed:    asset_server.load("sounds/ui_pause_closed.wav"),
+        ui_pause_opened:    asset_server.load("sounds/ui_pause_opened.wav"),
+        ui_toggle:          asset_server.load("sounds/ui_toggle.wav"),
+        weapon_overheated:  asset_server.load("sounds/weapon_overheated.wav"),
+        wrap_pop:           (1..=3)
+            .map(|i| asset_server.load(format!("sounds/wrap_pop_{i}.wav")))
+            .collect(),
+        wrap_suck:          (1..=3)
+            .map(|i| asset_server.load(format!("sounds/wrap_suck_{i}.wav")))
+            .collect(),
     };
 }
 
-pub fn check_asset_loading(
-    mut next_state: ResMut<NextState<AssetsState>>,
-    asset_server: Res<AssetServer>,
+// saving a model in blender can write the file more than once - each write is
+// another reload, so nothing's swapped until it's been quiet this long
+const SCENE_RELOAD_DEBOUNCE: f32 = 0.5;
+
+/// a model was changed on disk and everything using it has been redrawn with
+/// the new one - sizes worked out from the old one need doing again
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SceneReloaded(pub ActorKind);
+
+// models that have changed, and how long since they last did
+#[derive(Resource, Debug, Default)]
+struct PendingSceneReloads(HashMap<AssetId<Scene>, f32>);
+
+// with hot reloading on (the hot-reload feature) bevy reloads a model when
+// its file changes, but whatever's already been spawned from it keeps the
+// old one and only dynamic scenes get redrawn. marking SceneRoot changed
+// makes bevy throw away the children it spawned and spawn them again from
+// the new scene - the entity itself stays, so its Transform, Velocity and
+// Teleporter carry on as they were. wrap ghosts share the handle so they're
+// redrawn too
+fn reload_modified_scenes(
+    mut modified: EventReader<AssetEvent<Scene>>,
+    mut pending: ResMut<PendingSceneReloads>,
+    mut reloaded: EventWriter<SceneReloaded>,
     scene_assets: Res<SceneAssets>,
+    asset_server: Res<AssetServer>,
+    mut q_scenes: Query<&mut SceneRoot>,
+    time: Res<Time<Real>>,
+) {
+    for event in modified.read() {
+        if let AssetEvent::Modified { id } = event {
+            if scene_assets.scenes().any(|handle| handle.id() == *id) {
+                pending.0.insert(*id, 0.);
+            }
+        }
+    }
+    if pending.0.is_empty() {
+        return;
+    }
+
+    let delta_seconds = time.delta_secs();
+    let mut ready = Vec::new();
+    for (id, quiet) in pending.0.iter_mut() {
+        *quiet += delta_seconds;
+        // a half loaded model would leave things drawn without their meshes
+        if *quiet >= SCENE_RELOAD_DEBOUNCE && asset_server.is_loaded_with_dependencies(*id) {
+            ready.push(*id);
+        }
+    }
+
+    for id in ready {
+        pending.0.remove(&id);
+        for mut scene in q_scenes.iter_mut().filter(|scene| scene.0.id() == id) {
+            scene.set_changed();
+        }
+        if let Some(kind) = scene_assets.kind_of(id) {
+            info!("reloaded the {kind} model");
+            reloaded.send(SceneReloaded(kind));
+        }
+    }
+}
+
+// sounds don't go to the load failed screen - the game's fine without them -
+// but it shouldn't go quiet without saying why. each one's only mentioned once
+fn report_audio_failures(
+    audio_assets: Res<AudioAssets>,
+    asset_server: Res<AssetServer>,
+    mut reported: Local<HashSet<AssetId<AudioSource>>>,
+) {
+    for handle in audio_assets.handles() {
+        if reported.contains(&handle.id()) {
+            continue;
+        }
+        if let LoadState::Failed(error) = asset_server.load_state(handle) {
+            warn!("{error} - it won't be heard");
+            reported.insert(handle.id());
+        }
+    }
+}
+
+/// the models that couldn't be loaded, by name and path - what the load
+/// failed screen lists
+#[derive(Resource, Debug, Default)]
+pub struct FailedAssets(pub Vec<String>);
+
+#[derive(Component)]
+struct LoadingText;
+
+fn spawn_loading_text(mut commands: Commands, theme: Res<UiTheme>) {
+    commands.spawn((
+        LoadingText,
+        Text::new("loading"),
+        TextFont {
+            font_size: theme.font_size,
+            ..default()
+        },
+        TextColor(theme.text_muted),
+        Node {
+            align_self: AlignSelf::Center,
+            justify_self: JustifySelf::Center,
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        RenderLayers::from_layers(RenderLayer::Ui.layers()),
+    ));
+}
+
+fn despawn_loading_text(mut commands: Commands, q_text: Query<Entity, With<LoadingText>>) {
+    for entity in q_text.iter() {
+        despawn(&mut commands, entity);
+    }
+}
+
+// first the manifest, to find out which models - a manifest that's missing
+// or won't parse isn't fatal, it's the models the game has always used.
+// then a model counts once it and everything it pulls in - meshes, textures -
+// are there, so nothing spawns half drawn. a model whose file isn't there at
+// all (a fresh clone without the models, a cut down web build) gets a plain
+// shape instead - or if it's a variant, it's left out. one that's there and
+// won't load, or a texture that fails, stops at the load failed screen.
+// the sound effects hold it up too, so the first shot isn't silent - until
+// each one has loaded or failed, since the game's fine without them (see
+// report_audio_failures). the music can come in once it's already playing
+#[allow(clippy::too_many_arguments)]
+fn check_asset_loading(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    asset_server: Res<AssetServer>,
+    audio_assets: Res<AudioAssets>,
+    manifest_handle: Res<ManifestHandle>,
+    manifests: Res<Assets<Manifest>>,
+    mut scene_assets: ResMut<SceneAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q_text: Query<&mut Text, With<LoadingText>>,
 ) {
-    // Collect all asset IDs to check their load states
-    let all_assets_loaded = [
-        scene_assets.missile.id(),
-        scene_assets.nateroid.id(),
-        scene_assets.spaceship.id(),
-    ]
-    .iter()
-    .all(|&id| matches!(asset_server.get_load_state(id), Some(LoadState::Loaded)));
-
-    // Transition to the Loaded state if all assets are loaded
-    if all_assets_loaded {
-        next_state.set(AssetsState::Loaded);
+    if scene_assets.actors.is_empty() {
+        match asset_server.load_state(&manifest_handle.0) {
+            LoadState::Loaded => {
+                *scene_assets = SceneAssets::from_manifest(manifests.get(&manifest_handle.0), &asset_server);
+            },
+            LoadState::Failed(error) => {
+                warn!("unable to read {MANIFEST_PATH}, using the built in models: {error}");
+                *scene_assets = SceneAssets::from_manifest(None, &asset_server);
+            },
+            LoadState::NotLoaded | LoadState::Loading => return,
+        }
     }
+
+    for (kind, assets) in scene_assets.actors.iter_mut() {
+        let Some(scene) = assets.model.scene() else {
+            continue;
+        };
+        if let LoadState::Failed(error) = asset_server.load_state(scene) {
+            if is_missing(&error) {
+                warn!("{error} - drawing the {} as a plain shape", assets.name);
+                assets.model = fallback_model(*kind, &mut meshes, &mut materials);
+            }
+        }
+
+        assets.variants.retain(|variant| {
+            let Some(scene) = variant.model.scene() else {
+                return true;
+            };
+            match asset_server.load_state(scene) {
+                LoadState::Failed(error) if is_missing(&error) => {
+                    warn!("{error} - leaving out the {}", variant.name);
+                    false
+                },
+                _ => true,
+            }
+        });
+    }
+
+    let sounds_settled = audio_assets
+        .sound_effects()
+        .filter(|sound| {
+            matches!(
+                asset_server.load_state(*sound),
+                LoadState::Loaded | LoadState::Failed(_)
+            )
+        })
+        .count();
+    let total = scene_assets.models().count() + audio_assets.sound_effects().count();
+    let loaded = scene_assets
+        .models()
+        .filter(|(_, model)| {
+            model
+                .scene()
+                .is_none_or(|scene| asset_server.is_loaded_with_dependencies(scene))
+        })
+        .count()
+        + sounds_settled;
+
+    let failed: Vec<String> = scene_assets
+        .models()
+        .filter_map(|(name, model)| Some((name, model.scene()?)))
+        .filter(|(_, scene)| {
+            asset_server.load_state(*scene).is_failed()
+                || asset_server.recursive_dependency_load_state(*scene).is_failed()
+        })
+        .map(|(name, scene)| {
+            let path = asset_server
+                .get_path(scene)
+                .map_or_else(|| format!("{:?}", scene.id()), |path| path.to_string());
+            format!("{name} - {path}")
+        })
+        .collect();
+
+    if !failed.is_empty() {
+        for path in &failed {
+            error!("unable to load {path}");
+        }
+        commands.insert_resource(FailedAssets(failed));
+        next_state.set(GameState::LoadFailed);
+        return;
+    }
+
+    if loaded == total {
+        next_state.set(GameState::Splash);
+        return;
+    }
+
+    if let Ok(mut text) = q_text.get_single_mut() {
+        let progress = format!("{loaded}/{total} assets");
+        if text.0 != progress {
+            text.0 = progress;
+        }
+    }
+}
+
+fn spawn_load_failed_screen(mut commands: Commands, theme: Res<UiTheme>, failed: Res<FailedAssets>) {
+    commands
+        .spawn((
+            Node {
+                align_self: AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.margin / 2.),
+                ..default()
+            },
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+        ))
+        .with_children(|screen| {
+            screen.spawn((
+                Text::new("nateroids couldn't load"),
+                TextFont {
+                    font_size: theme.font_size_big,
+                    ..default()
+                },
+                TextColor(theme.danger),
+            ));
+            for path in &failed.0 {
+                screen.spawn((
+                    Text::new(path.clone()),
+                    TextFont {
+                        font_size: theme.font_size,
+                        ..default()
+                    },
+                    TextColor(theme.text),
+                ));
+            }
+        });
 }
@@ -0,0 +1,298 @@
+use crate::{
+    audio::{
+        ListenerTarget,
+        SoundEffectInstance,
+    },
+    config::SettingsAppExt,
+    global_input::{
+        GlobalAction,
+        InspectorAppExt,
+    },
+    schedule::FrameSet,
+    state::{
+        is_paused,
+        IsPaused,
+    },
+};
+use bevy::{
+    audio::{
+        AudioSinkPlayback,
+        SpatialAudioSink,
+    },
+    prelude::*,
+    window::WindowFocused,
+};
+use bevy_inspector_egui::{
+    inspector_options::std_options::NumberDisplay,
+    prelude::*,
+};
+
+pub struct AudioSettingsPlugin;
+
+impl Plugin for AudioSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // loaded from disk right here in build so the very first sound already
+        // plays at the right volume
+        app.register_settings::<AudioSettings>("audio")
+            .init_resource::<BackgroundMuted>()
+            .add_inspector::<AudioSettings>(GlobalAction::AudioInspector)
+            .add_systems(
+                Update,
+                (
+                    mute_in_background,
+                    (
+                        pause_in_background::<AudioSink>,
+                        pause_in_background::<SpatialAudioSink>,
+                    )
+                        .run_if(resource_changed::<BackgroundMuted>),
+                )
+                    .chain()
+                    .in_set(FrameSet::Ui),
+            );
+    }
+}
+
+// volumes are 0..=100 and multiply together - master at 50 and sfx at 50 plays
+// sound effects at a quarter volume. ui_volume is for menu clicks and the like
+//
+// ui_sound_cooldown is the minimum number of seconds between two of the same ui
+// sound
+//
+// mute_in_background silences everything while the window doesn't have focus
+//
+// max_voices_per_effect is the polyphony cap - when 20 nateroids blow up on the
+// same frame we only want to hear a handful of them, otherwise it just clips
+//
+// the wrap_ settings are in seconds - wraps inside the merge window become one
+// composite sound, the pop follows the suck after pop_delay, and a single
+// actor can't trigger another wrap sound until its cooldown runs out
+//
+// spatial turns on panning that respects the wrap - if you find it confusing
+// to hear something on your right that is drawn on the left, turn it off and
+// you get plain stereo
+//
+// the engine_ settings shape the thrust loop - attack and release are how many
+// seconds it takes to go from idle to full thrust and back, the pitch range is
+// a playback speed multiplier, and coast_volume is how loud the rumble gets
+// at max speed with your finger off the throttle
+//
+// low_health_threshold is the fraction of starting health where the heartbeat
+// kicks in, it stops again once you're back above threshold + hysteresis. the
+// heartbeat_ values are seconds between beats at the threshold and at zero
+//
+// music_danger_* decide when the danger stem comes in - either enough nateroids
+// inside the radius around the spaceship, or health below the given fraction
+//
+// attenuation_distance is how far away, in spatial audio units, the far side
+// of the boundary sounds - it's rescaled whenever the boundary changes size
+#[derive(Resource, Reflect, InspectorOptions, Debug, PartialEq, Clone)]
+#[reflect(Resource, InspectorOptions)]
+pub struct AudioSettings {
+    #[inspector(min = 0.0, max = 100.0, display = NumberDisplay::Slider)]
+    pub master_volume:           f32,
+    #[inspector(min = 0.0, max = 100.0, display = NumberDisplay::Slider)]
+    pub sfx_volume:              f32,
+    #[inspector(min = 0.0, max = 100.0, display = NumberDisplay::Slider)]
+    pub music_volume:            f32,
+    #[inspector(min = 0.0, max = 100.0, display = NumberDisplay::Slider)]
+    pub ui_volume:               f32,
+    #[inspector(min = 0.0, max = 0.5, display = NumberDisplay::Slider)]
+    pub ui_sound_cooldown:       f32,
+    pub mute_in_background:      bool,
+    #[inspector(min = 0.0, max = 0.5, display = NumberDisplay::Slider)]
+    pub pitch_jitter:            f32,
+    #[inspector(min = 1, max = 32, display = NumberDisplay::Slider)]
+    pub max_voices_per_effect:   u32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub engine_volume:           f32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub engine_coast_volume:     f32,
+    #[inspector(min = 0.25, max = 2.0, display = NumberDisplay::Slider)]
+    pub engine_pitch_min:        f32,
+    #[inspector(min = 0.5, max = 3.0, display = NumberDisplay::Slider)]
+    pub engine_pitch_max:        f32,
+    #[inspector(min = 0.0, max = 2.0, display = NumberDisplay::Slider)]
+    pub engine_attack:           f32,
+    #[inspector(min = 0.0, max = 2.0, display = NumberDisplay::Slider)]
+    pub engine_release:          f32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub low_health_threshold:    f32,
+    #[inspector(min = 0.0, max = 0.2, display = NumberDisplay::Slider)]
+    pub low_health_hysteresis:   f32,
+    #[inspector(min = 0.2, max = 2.0, display = NumberDisplay::Slider)]
+    pub heartbeat_slowest:       f32,
+    #[inspector(min = 0.1, max = 1.0, display = NumberDisplay::Slider)]
+    pub heartbeat_fastest:       f32,
+    pub music_muted:             bool,
+    #[inspector(min = 0.0, max = 4.0, display = NumberDisplay::Slider)]
+    pub music_crossfade_seconds: f32,
+    #[inspector(min = 10.0, max = 200.0, display = NumberDisplay::Slider)]
+    pub music_danger_radius:     f32,
+    #[inspector(min = 1, max = 20, display = NumberDisplay::Slider)]
+    pub music_danger_nateroids:  u32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub music_danger_health:     f32,
+    #[inspector(min = 0.0, max = 0.5, display = NumberDisplay::Slider)]
+    pub wrap_merge_seconds:      f32,
+    #[inspector(min = 0.0, max = 0.5, display = NumberDisplay::Slider)]
+    pub wrap_pop_delay:          f32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub wrap_entity_cooldown:    f32,
+    pub spatial:                 bool,
+    pub listener:                ListenerTarget,
+    #[inspector(min = 1.0, max = 20.0, display = NumberDisplay::Slider)]
+    pub attenuation_distance:    f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume:           80.,
+            sfx_volume:              70.,
+            music_volume:            50.,
+            ui_volume:               70.,
+            ui_sound_cooldown:       0.08,
+            mute_in_background:      true,
+            pitch_jitter:            0.08,
+            max_voices_per_effect:   6,
+            engine_volume:           0.6,
+            engine_coast_volume:     0.3,
+            engine_pitch_min:        0.7,
+            engine_pitch_max:        1.4,
+            engine_attack:           0.15,
+            engine_release:          0.4,
+            low_health_threshold:    0.3,
+            low_health_hysteresis:   0.05,
+            heartbeat_slowest:       0.9,
+            heartbeat_fastest:       0.35,
+            music_muted:             false,
+            music_crossfade_seconds: 1.5,
+            music_danger_radius:     60.,
+            music_danger_nateroids:  4,
+            music_danger_health:     0.3,
+            wrap_merge_seconds:      0.08,
+            wrap_pop_delay:          0.06,
+            wrap_entity_cooldown:    0.25,
+            spatial:                 true,
+            listener:                ListenerTarget::Camera,
+            attenuation_distance:    4.,
+        }
+    }
+}
+
+impl AudioSettings {
+    fn master_gain(&self) -> f32 { self.master_volume.clamp(0.0, 100.0) / 100.0 }
+
+    pub fn sfx_gain(&self) -> f32 { self.master_gain() * self.sfx_volume.clamp(0.0, 100.0) / 100.0 }
+
+    pub fn music_gain(&self) -> f32 {
+        if self.music_muted {
+            return 0.0;
+        }
+        self.master_gain() * self.music_volume.clamp(0.0, 100.0) / 100.0
+    }
+
+    pub fn ui_gain(&self) -> f32 { self.master_gain() * self.ui_volume.clamp(0.0, 100.0) / 100.0 }
+}
+
+/// true while the window is in the background and mute_in_background is on -
+/// anything that sets a sink's volume every frame should multiply by gain()
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct BackgroundMuted(pub bool);
+
+impl BackgroundMuted {
+    pub fn gain(&self) -> f32 {
+        if self.0 {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+// what the last WindowFocused said - the window has focus until one says
+// otherwise
+#[derive(Debug)]
+struct LastFocus(bool);
+
+impl Default for LastFocus {
+    fn default() -> Self { Self(true) }
+}
+
+// the engine and music loops pick this up through BackgroundMuted::gain - one
+// shot sound effects get paused instead so they don't keep going in the dark.
+// turning the setting off (or on) while in the background takes effect right
+// away rather than at the next change of focus
+fn mute_in_background(
+    mut focus_events: EventReader<WindowFocused>,
+    settings: Res<AudioSettings>,
+    mut muted: ResMut<BackgroundMuted>,
+    mut last_focus: Local<LastFocus>,
+) {
+    match focus_events.read().last() {
+        Some(focus) => last_focus.0 = focus.focused,
+        None if !settings.is_changed() => return,
+        None => (),
+    }
+
+    muted.set_if_neq(BackgroundMuted(
+        settings.mute_in_background && !last_focus.0,
+    ));
+}
+
+// a spatial sound effect has a SpatialAudioSink rather than an AudioSink, so
+// this is added once for each. coming back into focus doesn't resume anything
+// the game's own pause is holding
+fn pause_in_background<S: AudioSinkPlayback + Component>(
+    muted: Res<BackgroundMuted>,
+    paused: Option<Res<State<IsPaused>>>,
+    q_sound_effects: Query<&S, With<SoundEffectInstance>>,
+) {
+    let game_paused = is_paused(paused);
+
+    for sink in q_sound_effects.iter() {
+        if muted.0 {
+            sink.pause();
+        } else if !game_paused {
+            sink.play();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn audio_settings_round_trips() { assert_settings_round_trip(nudged::<AudioSettings>()); }
+
+    // turned off while the window's in the background, it unmutes straight
+    // away - there's no focus event coming to do it
+    #[test]
+    fn turning_background_mute_off_unmutes_without_a_focus_change() {
+        let mut app = App::new();
+        app.add_event::<WindowFocused>()
+            .init_resource::<AudioSettings>()
+            .init_resource::<BackgroundMuted>()
+            .add_systems(Update, mute_in_background);
+
+        app.world_mut().send_event(WindowFocused {
+            window:  Entity::PLACEHOLDER,
+            focused: false,
+        });
+        app.update();
+        assert!(app.world().resource::<BackgroundMuted>().0);
+
+        app.world_mut().resource_mut::<AudioSettings>().mute_in_background = false;
+        app.update();
+        assert!(!app.world().resource::<BackgroundMuted>().0);
+
+        app.world_mut().resource_mut::<AudioSettings>().mute_in_background = true;
+        app.update();
+        assert!(app.world().resource::<BackgroundMuted>().0);
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+// everything but AudioUnlock itself only exists in the browser
+#[cfg(target_arch = "wasm32")]
+use crate::{
+    audio::{
+        PlaySoundEffect,
+        UiSoundEvent,
+    },
+    schedule::FrameSet,
+};
+#[cfg(target_arch = "wasm32")]
+use std::collections::VecDeque;
+
+// anything older than the last handful would just be noise all at once
+#[cfg(target_arch = "wasm32")]
+const MAX_HELD_SOUNDS: usize = 8;
+
+// browsers won't let a page make a sound until it's been clicked, tapped or
+// typed into. index.html resumes the audio context on that first gesture - in
+// the meantime the sounds that were asked for are held here rather than piling
+// up in a suspended context, and the last few play once we're allowed to
+#[cfg(target_arch = "wasm32")]
+pub struct AudioUnlockPlugin;
+
+#[cfg(target_arch = "wasm32")]
+impl Plugin for AudioUnlockPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AudioUnlock(false))
+            .init_resource::<HeldSounds>()
+            .add_systems(
+                Update,
+                unlock_on_first_gesture
+                    .in_set(FrameSet::Input)
+                    .run_if(not(audio_unlocked)),
+            )
+            .add_systems(PostUpdate, hold_sounds.run_if(not(audio_unlocked)));
+    }
+}
+
+/// whether the browser lets us play sound yet - always true natively
+#[derive(Resource, Debug)]
+pub struct AudioUnlock(pub bool);
+
+impl Default for AudioUnlock {
+    fn default() -> Self { Self(true) }
+}
+
+pub fn audio_unlocked(unlock: Res<AudioUnlock>) -> bool { unlock.0 }
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Resource, Debug, Default)]
+struct HeldSounds {
+    effects: VecDeque<PlaySoundEffect>,
+    ui:      VecDeque<UiSoundEvent>,
+}
+
+#[cfg(target_arch = "wasm32")]
+fn hold<T>(held: &mut VecDeque<T>, sounds: impl Iterator<Item = T>) {
+    held.extend(sounds);
+    while held.len() > MAX_HELD_SOUNDS {
+        held.pop_front();
+    }
+}
+
+// drained rather than read so that the players don't find them again once
+// they start running - they're sent back out on unlock
+#[cfg(target_arch = "wasm32")]
+fn hold_sounds(
+    mut held: ResMut<HeldSounds>,
+    mut effects: ResMut<Events<PlaySoundEffect>>,
+    mut ui_sounds: ResMut<Events<UiSoundEvent>>,
+) {
+    hold(&mut held.effects, effects.drain());
+    hold(&mut held.ui, ui_sounds.drain());
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unlock_on_first_gesture(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    mut unlock: ResMut<AudioUnlock>,
+    mut held: ResMut<HeldSounds>,
+    mut effects: EventWriter<PlaySoundEffect>,
+    mut ui_sounds: EventWriter<UiSoundEvent>,
+) {
+    let gesture = keys.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || touches.any_just_pressed();
+    if !gesture {
+        return;
+    }
+
+    unlock.0 = true;
+    effects.send_batch(held.effects.drain(..));
+    ui_sounds.send_batch(held.ui.drain(..));
+}
@@ -0,0 +1,123 @@
+use crate::{
+    actor::{
+        Spaceship,
+        SpaceshipControl,
+        SpaceshipControlConfig,
+    },
+    asset_loader::AudioAssets,
+    audio::{
+        AudioSettings,
+        BackgroundMuted,
+    },
+    schedule::FrameSet,
+    state::{
+        is_running,
+        IsPaused,
+    },
+};
+use bevy::{
+    audio::Volume,
+    prelude::*,
+};
+use bevy_rapier3d::dynamics::Velocity;
+use leafwing_input_manager::action_state::ActionState;
+
+// how long the engine takes to fade out on pause / death and back in again
+const ENGINE_FADE_SECONDS: f32 = 0.2;
+
+pub struct EngineSoundPlugin;
+
+impl Plugin for EngineSoundPlugin {
+    fn build(&self, app: &mut App) {
+        // audio handles are created in PreStartup so they're there for us in
+        // Startup - the engine keeps running (silently) through pause and game
+        // over so the fades have something to work with
+        app.add_systems(Startup, spawn_engine_sound)
+            .add_systems(Update, update_engine_sound.in_set(FrameSet::Ui));
+    }
+}
+
+// the engine loop is its own entity rather than a child of the spaceship - the
+// spaceship gets despawned when it dies and we still want to hear the fade out
+#[derive(Component, Debug, Default)]
+struct EngineSound {
+    // smoothed 0..=1 thrust, driven by attack / release
+    thrust: f32,
+    // 0..=1, drops to zero while paused or when there's no spaceship
+    fade:   f32,
+}
+
+fn spawn_engine_sound(mut commands: Commands, audio_assets: Res<AudioAssets>) {
+    commands.spawn((
+        AudioPlayer::new(audio_assets.engine.clone()),
+        PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+        EngineSound::default(),
+        Name::new("EngineSound"),
+    ));
+}
+
+// controls the sink each frame rather than restarting the clip - starting and
+// stopping a loop every time you tap thrust sounds terrible
+#[allow(clippy::type_complexity)]
+fn update_engine_sound(
+    mut q_engine: Query<(&mut EngineSound, &AudioSink)>,
+    q_spaceship: Query<(&Velocity, &ActionState<SpaceshipControl>), With<Spaceship>>,
+    paused: Option<Res<State<IsPaused>>>,
+    control_config: Res<SpaceshipControlConfig>,
+    settings: Res<AudioSettings>,
+    background: Res<BackgroundMuted>,
+    time: Res<Time>,
+) {
+    let Ok((mut engine, sink)) = q_engine.get_single_mut() else {
+        return;
+    };
+
+    let delta_seconds = time.delta_secs();
+    let running = is_running(paused);
+
+    // backwards is as loud as forwards
+    let (throttle, speed) = match q_spaceship.get_single() {
+        Ok((velocity, controls)) => (
+            controls.value(&SpaceshipControl::Thrust).abs().min(1.0),
+            (velocity.linvel.length() / control_config.max_speed).clamp(0.0, 1.0),
+        ),
+        Err(_) => (0.0, 0.0),
+    };
+
+    let response_seconds = if throttle > engine.thrust {
+        settings.engine_attack
+    } else {
+        settings.engine_release
+    };
+    engine.thrust = approach(engine.thrust, throttle, delta_seconds, response_seconds);
+
+    let target_fade = if running && q_spaceship.get_single().is_ok() {
+        1.0
+    } else {
+        0.0
+    };
+    engine.fade = approach(engine.fade, target_fade, delta_seconds, ENGINE_FADE_SECONDS);
+
+    // coasting gives a low rumble that grows with speed, thrust brings it up to
+    // full - sitting still with no thrust is silent
+    let level = (speed * settings.engine_coast_volume).max(engine.thrust);
+    let pitch = settings.engine_pitch_min
+        + (settings.engine_pitch_max - settings.engine_pitch_min) * (0.5 * speed + 0.5 * engine.thrust);
+
+    sink.set_volume(settings.sfx_gain() * background.gain() * settings.engine_volume * level * engine.fade);
+    sink.set_speed(pitch);
+}
+
+// move `current` toward `target` so that a full 0 -> 1 swing takes `seconds`
+fn approach(current: f32, target: f32, delta_seconds: f32, seconds: f32) -> f32 {
+    if seconds <= 0.0 {
+        return target;
+    }
+
+    let step = delta_seconds / seconds;
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    actor::{
+        ActorDamaged,
+        ActorKind,
+        ShieldBroken,
+        Spaceship,
+        SpaceshipConfig,
+        WeaponOverheated,
+    },
+    audio::{
+        AudioSettings,
+        PlaySoundEffect,
+        SoundEffect,
+    },
+    despawn::ActorDestroyed,
+    schedule::InGameSet,
+    state::GameState,
+};
+use bevy::prelude::*;
+
+// the weapon overheat and shield break one-shots go here too, driven off their
+// own events the way the heartbeat is off ActorDamaged
+pub struct LowHealthPlugin;
+
+impl Plugin for LowHealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LowHealthAlarm>()
+            .add_systems(
+                Update,
+                (
+                    (track_spaceship_health, play_heartbeat).chain(),
+                    play_warnings,
+                )
+                    .in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(OnExit(GameState::GameOver), reset_alarm);
+    }
+}
+
+// the alarm is driven entirely by ActorDamaged / ActorDestroyed - nothing here
+// looks at Health every frame. InGameSet doesn't run while paused so the beats
+// stop right away and the one that's already playing gets paused along with
+// every other sound effect
+#[derive(Resource, Debug, Default)]
+struct LowHealthAlarm {
+    active:     bool,
+    // 0..=1 of the spaceship's starting health
+    health:     f32,
+    until_next: f32,
+}
+
+fn track_spaceship_health(
+    mut alarm: ResMut<LowHealthAlarm>,
+    mut damaged: EventReader<ActorDamaged>,
+    mut destroyed: EventReader<ActorDestroyed>,
+    spaceship_config: Res<SpaceshipConfig>,
+    settings: Res<AudioSettings>,
+) {
+    for event in damaged.read() {
+        if event.kind != ActorKind::Spaceship {
+            continue;
+        }
+
+        alarm.health = (event.health / spaceship_config.0.health.max(1.)).max(0.0);
+
+        // hysteresis - it takes a bit more than the threshold to switch the alarm
+        // back off so it doesn't flap when you're sitting right on it
+        let threshold = settings.low_health_threshold;
+        if !alarm.active && alarm.health < threshold {
+            alarm.active = true;
+            alarm.until_next = 0.0;
+        } else if alarm.active && alarm.health >= threshold + settings.low_health_hysteresis {
+            alarm.active = false;
+        }
+    }
+
+    if destroyed.read().any(|event| event.kind == ActorKind::Spaceship) {
+        alarm.active = false;
+    }
+}
+
+// tempo goes from heartbeat_slowest at the threshold to heartbeat_fastest at
+// zero health - each beat is an ordinary sound effect so it goes through the
+// sfx volume and the polyphony cap like everything else
+fn play_heartbeat(
+    mut alarm: ResMut<LowHealthAlarm>,
+    mut sounds: EventWriter<PlaySoundEffect>,
+    q_spaceship: Query<&Transform, With<Spaceship>>,
+    settings: Res<AudioSettings>,
+    time: Res<Time>,
+) {
+    if !alarm.active {
+        return;
+    }
+
+    let Ok(transform) = q_spaceship.get_single() else {
+        alarm.active = false;
+        return;
+    };
+
+    alarm.until_next -= time.delta_secs();
+    if alarm.until_next > 0.0 {
+        return;
+    }
+
+    let danger = 1.0 - (alarm.health / settings.low_health_threshold.max(f32::EPSILON)).clamp(0.0, 1.0);
+    alarm.until_next = settings
+        .heartbeat_slowest
+        .lerp(settings.heartbeat_fastest, danger);
+
+    sounds.send(PlaySoundEffect::new(
+        SoundEffect::Heartbeat,
+        transform.translation,
+    ));
+}
+
+// one each time it happens - they go through the sfx volume and the polyphony
+// cap like the heartbeat does
+fn play_warnings(
+    mut overheated: EventReader<WeaponOverheated>,
+    mut shield_broken: EventReader<ShieldBroken>,
+    mut sounds: EventWriter<PlaySoundEffect>,
+) {
+    for event in overheated.read() {
+        sounds.send(PlaySoundEffect::new(
+            SoundEffect::WeaponOverheated,
+            event.position,
+        ));
+    }
+    for event in shield_broken.read() {
+        sounds.send(PlaySoundEffect::new(SoundEffect::ShieldBroken, event.position));
+    }
+}
+
+fn reset_alarm(mut alarm: ResMut<LowHealthAlarm>) { *alarm = LowHealthAlarm::default(); }
@@ -0,0 +1,84 @@
+mod audio_settings;
+mod audio_unlock;
+mod engine_sound;
+mod low_health;
+mod music;
+mod sound_effects;
+mod spatial_audio;
+mod ui_sounds;
+mod wrap_sounds;
+
+pub use crate::audio::{
+    audio_settings::{
+        AudioSettings,
+        BackgroundMuted,
+    },
+    audio_unlock::audio_unlocked,
+    sound_effects::{
+        PlaySoundEffect,
+        SoundEffect,
+        SoundEffectInstance,
+    },
+    spatial_audio::ListenerTarget,
+    ui_sounds::UiSoundEvent,
+};
+
+#[cfg(target_arch = "wasm32")]
+use crate::audio::audio_unlock::AudioUnlockPlugin;
+use crate::{
+    audio::{
+        audio_settings::AudioSettingsPlugin,
+        audio_unlock::AudioUnlock,
+        engine_sound::EngineSoundPlugin,
+        low_health::LowHealthPlugin,
+        music::MusicPlugin,
+        sound_effects::SoundEffectsPlugin,
+        spatial_audio::SpatialAudioPlugin,
+        ui_sounds::UiSoundsPlugin,
+        wrap_sounds::WrapSoundsPlugin,
+    },
+    despawn::despawn,
+};
+use bevy::{
+    asset::LoadState,
+    audio::SpatialAudioSink,
+    prelude::*,
+};
+
+// not to be confused with bevy's own AudioPlugin which comes in with
+// DefaultPlugins - this one decides what to play and when
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AudioSettingsPlugin)
+            .add_plugins(EngineSoundPlugin)
+            .add_plugins(LowHealthPlugin)
+            .add_plugins(MusicPlugin)
+            .add_plugins(SoundEffectsPlugin)
+            .add_plugins(SpatialAudioPlugin)
+            .add_plugins(UiSoundsPlugin)
+            .add_plugins(WrapSoundsPlugin)
+            .init_resource::<AudioUnlock>()
+            .add_systems(Update, despawn_failed_players);
+
+        #[cfg(target_arch = "wasm32")]
+        app.add_plugins(AudioUnlockPlugin);
+    }
+}
+
+// bevy leaves a player waiting until its sound has loaded, so one whose file
+// failed never gets a sink - it would sit there for good, DESPAWN or not. the
+// loops (music, the engine) are spawned once at startup and go the same way
+#[allow(clippy::type_complexity)]
+fn despawn_failed_players(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    q_players: Query<(Entity, &AudioPlayer), (Without<AudioSink>, Without<SpatialAudioSink>)>,
+) {
+    for (entity, player) in q_players.iter() {
+        if matches!(asset_server.load_state(&player.0), LoadState::Failed(_)) {
+            despawn(&mut commands, entity);
+        }
+    }
+}
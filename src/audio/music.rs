@@ -0,0 +1,185 @@
+use crate::{
+    actor::{
+        ActorKind,
+        Health,
+        Spaceship,
+        SpaceshipConfig,
+    },
+    asset_loader::AudioAssets,
+    audio::{
+        AudioSettings,
+        BackgroundMuted,
+    },
+    playfield::SpatialIndex,
+    schedule::FrameSet,
+    state::GameState,
+};
+use bevy::{
+    audio::Volume,
+    prelude::*,
+};
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicDirector>()
+            .register_type::<MusicDirector>()
+            .add_systems(Startup, spawn_music_layers)
+            .add_systems(OnEnter(GameState::GameOver), play_game_over_sting)
+            .add_systems(
+                Update,
+                (
+                    // the spaceship's starting health only comes in once the
+                    // models have loaded
+                    direct_music.run_if(resource_exists::<SpaceshipConfig>),
+                    fade_music_layers,
+                )
+                    .chain()
+                    .in_set(FrameSet::Ui),
+            );
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MusicTrack {
+    #[default]
+    Menu,
+    Gameplay,
+}
+
+/// what the music should be doing right now - direct_music decides, and
+/// fade_music_layers crossfades the sinks toward it
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Resource)]
+pub struct MusicDirector {
+    pub track:  MusicTrack,
+    /// 0..=1 - how much of the danger stem is layered on top of gameplay
+    pub danger: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MusicLayer {
+    Menu,
+    Gameplay,
+    Danger,
+}
+
+#[derive(Component, Debug)]
+struct MusicLayerSink {
+    layer: MusicLayer,
+    gain:  f32,
+}
+
+// all of the looping layers start on the same frame and never stop - they just
+// get faded up and down. that keeps the danger stem sample locked to the
+// gameplay track, and means we never restart a loop by hand.
+//
+// the loop point itself is the end of the file: PlaybackSettings::LOOP hands
+// the decoded source to rodio's repeat_infinite which jumps straight back to
+// the first sample. so the tracks need to be gapless - wav, or ogg (mp3 pads
+// the start and end with silence) - and cut exactly on the loop boundary, the
+// way tools/synth_sounds.py makes them
+fn spawn_music_layers(mut commands: Commands, audio_assets: Res<AudioAssets>) {
+    for (layer, handle, name) in [
+        (MusicLayer::Menu, &audio_assets.music_menu, "MusicMenu"),
+        (
+            MusicLayer::Gameplay,
+            &audio_assets.music_gameplay,
+            "MusicGameplay",
+        ),
+        (MusicLayer::Danger, &audio_assets.music_danger, "MusicDanger"),
+    ] {
+        commands.spawn((
+            AudioPlayer::new(handle.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+            MusicLayerSink { layer, gain: 0.0 },
+            Name::new(name),
+        ));
+    }
+}
+
+fn direct_music(
+    mut director: ResMut<MusicDirector>,
+    state: Res<State<GameState>>,
+    settings: Res<AudioSettings>,
+    spaceship_config: Res<SpaceshipConfig>,
+    q_spaceship: Query<(&Transform, &Health), With<Spaceship>>,
+    spatial_index: Res<SpatialIndex>,
+) {
+    director.track = match state.get() {
+        GameState::Loading
+        | GameState::LoadFailed
+        | GameState::Splash
+        | GameState::MainMenu
+        | GameState::RunSetup => MusicTrack::Menu,
+        GameState::InGame { .. } | GameState::GameOver => MusicTrack::Gameplay,
+    };
+
+    director.danger = match q_spaceship.get_single() {
+        Ok((spaceship_transform, health)) => {
+            let nearby = spatial_index
+                .within_of_kind(
+                    spaceship_transform.translation,
+                    settings.music_danger_radius,
+                    ActorKind::Nateroid,
+                )
+                .count() as f32;
+
+            let crowded = nearby / settings.music_danger_nateroids.max(1) as f32;
+
+            // ramps up from nothing at the low health threshold to all in at zero
+            let health_fraction = health.0 / spaceship_config.0.health.max(1.);
+            let wounded = 1.0 - health_fraction / settings.music_danger_health.max(f32::EPSILON);
+
+            crowded.max(wounded).clamp(0.0, 1.0)
+        },
+        Err(_) => 0.0,
+    };
+}
+
+fn fade_music_layers(
+    director: Res<MusicDirector>,
+    settings: Res<AudioSettings>,
+    background: Res<BackgroundMuted>,
+    time: Res<Time>,
+    mut q_layers: Query<(&mut MusicLayerSink, &AudioSink)>,
+) {
+    let step = if settings.music_crossfade_seconds > 0.0 {
+        time.delta_secs() / settings.music_crossfade_seconds
+    } else {
+        1.0
+    };
+
+    let volume = settings.music_gain() * background.gain();
+
+    for (mut layer, sink) in q_layers.iter_mut() {
+        let target = match (layer.layer, director.track) {
+            (MusicLayer::Menu, MusicTrack::Menu) => 1.0,
+            (MusicLayer::Gameplay, MusicTrack::Gameplay) => 1.0,
+            (MusicLayer::Danger, MusicTrack::Gameplay) => director.danger,
+            _ => 0.0,
+        };
+
+        layer.gain += (target - layer.gain).clamp(-step, step);
+        sink.set_volume(volume * layer.gain);
+    }
+}
+
+// the sting is a one shot on top of whatever is playing - game over flips
+// straight back into a new game so it plays out over the start of that
+fn play_game_over_sting(
+    mut commands: Commands,
+    audio_assets: Res<AudioAssets>,
+    settings: Res<AudioSettings>,
+) {
+    if settings.music_gain() <= 0.0 {
+        return;
+    }
+
+    commands.spawn((
+        AudioPlayer::new(audio_assets.music_game_over.clone()),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.music_gain())),
+        Name::new("MusicGameOverSting"),
+    ));
+}
@@ -0,0 +1,346 @@
+use crate::{
+    actor::{
+        ActorDamaged,
+        ActorKind,
+        MissileFired,
+    },
+    asset_loader::AudioAssets,
+    audio::{
+        audio_unlocked,
+        spatial_audio::wrapped_emitter_position,
+        AudioSettings,
+        BackgroundMuted,
+    },
+    despawn::ActorDestroyed,
+    playfield::Boundary,
+    rng::GameRng,
+    schedule::FrameSet,
+    state::{
+        IsPaused,
+        PlayingGame,
+    },
+};
+use bevy::{
+    audio::{
+        AudioSinkPlayback,
+        SpatialAudioSink,
+        Volume,
+    },
+    prelude::*,
+};
+use rand::Rng;
+use std::collections::HashMap;
+
+pub struct SoundEffectsPlugin;
+
+impl Plugin for SoundEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySoundEffect>()
+            .add_systems(
+                Update,
+                (
+                    (missile_fired_sounds, actor_damaged_sounds, actor_destroyed_sounds),
+                    play_sound_effects.run_if(audio_unlocked),
+                )
+                    .chain()
+                    .in_set(FrameSet::Ui)
+                    .run_if(in_state(PlayingGame)),
+            )
+            .add_systems(
+                OnEnter(IsPaused::Paused),
+                (
+                    pause_sound_effects::<AudioSink>,
+                    pause_sound_effects::<SpatialAudioSink>,
+                ),
+            )
+            .add_systems(
+                OnEnter(IsPaused::NotPaused),
+                (
+                    resume_sound_effects::<AudioSink>,
+                    resume_sound_effects::<SpatialAudioSink>,
+                ),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEffect {
+    Heartbeat,
+    MissileFired,
+    MissileHit,
+    NateroidDestroyed,
+    ShieldBroken,
+    ShipDamaged,
+    ShipDestroyed,
+    WeaponOverheated,
+    WrapPop,
+    WrapSuck,
+}
+
+impl SoundEffect {
+    // the mapping table - some effects have a small pool of alternatives that
+    // get picked from at random so repeats don't sound identical
+    fn handles(self, audio_assets: &AudioAssets) -> &[Handle<AudioSource>] {
+        match self {
+            SoundEffect::Heartbeat => std::slice::from_ref(&audio_assets.heartbeat),
+            SoundEffect::MissileFired => std::slice::from_ref(&audio_assets.missile_fired),
+            SoundEffect::MissileHit => std::slice::from_ref(&audio_assets.missile_hit),
+            SoundEffect::NateroidDestroyed => std::slice::from_ref(&audio_assets.nateroid_destroyed),
+            SoundEffect::ShieldBroken => std::slice::from_ref(&audio_assets.shield_broken),
+            SoundEffect::ShipDamaged => std::slice::from_ref(&audio_assets.ship_damaged),
+            SoundEffect::ShipDestroyed => std::slice::from_ref(&audio_assets.ship_destroyed),
+            SoundEffect::WeaponOverheated => std::slice::from_ref(&audio_assets.weapon_overheated),
+            SoundEffect::WrapPop => &audio_assets.wrap_pop,
+            SoundEffect::WrapSuck => &audio_assets.wrap_suck,
+        }
+    }
+
+    // the portal sounds belong to a specific face - hopping to the nearest copy
+    // on the torus would put the exit and entry on top of each other
+    fn follows_wrap(self) -> bool { !matches!(self, SoundEffect::WrapPop | SoundEffect::WrapSuck) }
+}
+
+/// ask for a sound to be played - intensity of 1.0 is a "normal" sized sound,
+/// bigger is louder and lower pitched, smaller is quieter and higher
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlaySoundEffect {
+    pub effect:    SoundEffect,
+    pub position:  Vec3,
+    pub intensity: f32,
+}
+
+impl PlaySoundEffect {
+    pub fn new(effect: SoundEffect, position: Vec3) -> Self {
+        Self {
+            effect,
+            position,
+            intensity: 1.0,
+        }
+    }
+}
+
+// lives on every entity spawned to play a sound so we can count voices per
+// effect and pause them all when the game pauses
+#[derive(Component, Debug)]
+pub struct SoundEffectInstance(pub SoundEffect);
+
+// the size of a default nateroid - used to turn ActorDestroyed::size into an
+// intensity around 1.0
+const REFERENCE_NATEROID_SIZE: f32 = 10.;
+
+fn missile_fired_sounds(mut fired: EventReader<MissileFired>, mut sounds: EventWriter<PlaySoundEffect>) {
+    for event in fired.read() {
+        sounds.send(PlaySoundEffect::new(SoundEffect::MissileFired, event.position));
+    }
+}
+
+fn actor_damaged_sounds(mut damaged: EventReader<ActorDamaged>, mut sounds: EventWriter<PlaySoundEffect>) {
+    for event in damaged.read() {
+        let effect = match (event.kind, event.source) {
+            (ActorKind::Spaceship, _) => SoundEffect::ShipDamaged,
+            (ActorKind::Nateroid, ActorKind::Missile) => SoundEffect::MissileHit,
+            _ => continue,
+        };
+        sounds.send(PlaySoundEffect::new(effect, event.position));
+    }
+}
+
+fn actor_destroyed_sounds(
+    mut destroyed: EventReader<ActorDestroyed>,
+    mut sounds: EventWriter<PlaySoundEffect>,
+) {
+    for event in destroyed.read() {
+        match event.kind {
+            ActorKind::Nateroid => {
+                sounds.send(PlaySoundEffect {
+                    effect:    SoundEffect::NateroidDestroyed,
+                    position:  event.position,
+                    intensity: event.size / REFERENCE_NATEROID_SIZE,
+                });
+            },
+            ActorKind::Spaceship => {
+                sounds.send(PlaySoundEffect::new(SoundEffect::ShipDestroyed, event.position));
+            },
+            ActorKind::Missile => (),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_sound_effects(
+    mut commands: Commands,
+    mut requests: EventReader<PlaySoundEffect>,
+    audio_assets: Res<AudioAssets>,
+    asset_server: Res<AssetServer>,
+    boundary: Res<Boundary>,
+    settings: Res<AudioSettings>,
+    background: Res<BackgroundMuted>,
+    mut rng: ResMut<GameRng>,
+    // kept from frame to frame so that a busy frame doesn't allocate
+    mut voices: Local<HashMap<SoundEffect, u32>>,
+    mut loaded: Local<Vec<usize>>,
+    q_listener: Query<&GlobalTransform, With<SpatialListener>>,
+    q_playing: Query<&SoundEffectInstance>,
+) {
+    let listener = q_listener
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation());
+
+    voices.clear();
+    for instance in q_playing.iter() {
+        *voices.entry(instance.0).or_default() += 1;
+    }
+
+    let rng = &mut rng.sounds;
+
+    for request in requests.read() {
+        // one that hasn't loaded - or never will - is left out rather than
+        // spawned to wait, where it would count against the voice cap
+        let handles = request.effect.handles(&audio_assets);
+        loaded.clear();
+        loaded.extend(
+            handles
+                .iter()
+                .enumerate()
+                .filter(|(_, handle)| asset_server.is_loaded(*handle))
+                .map(|(index, _)| index),
+        );
+        if loaded.is_empty() {
+            continue;
+        }
+
+        let playing = voices.entry(request.effect).or_default();
+        if *playing >= settings.max_voices_per_effect {
+            continue;
+        }
+        *playing += 1;
+
+        let jitter = if settings.pitch_jitter > 0.0 {
+            rng.random_range(-settings.pitch_jitter..settings.pitch_jitter)
+        } else {
+            0.0
+        };
+
+        // sqrt keeps a huge nateroid from being deafening or subsonic
+        let size = request.intensity.clamp(0.25, 4.0).sqrt();
+        let speed = (1.0 + jitter) / size;
+        let volume = settings.sfx_gain() * background.gain() * size;
+
+        let handle = handles[loaded[rng.random_range(0..loaded.len())]].clone();
+
+        let position = if request.effect.follows_wrap() {
+            wrapped_emitter_position(&boundary, listener, request.position)
+        } else {
+            request.position
+        };
+
+        commands.spawn((
+            AudioPlayer::new(handle),
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(volume))
+                .with_speed(speed)
+                .with_spatial(settings.spatial && listener.is_some()),
+            Transform::from_translation(position),
+            SoundEffectInstance(request.effect),
+            Name::new("SoundEffect"),
+        ));
+    }
+}
+
+// a spatial sound effect gets a SpatialAudioSink rather than an AudioSink -
+// with the default settings that's nearly all of them - so anything that
+// pauses or resumes them is added once for each
+fn pause_sound_effects<S: AudioSinkPlayback + Component>(q_sinks: Query<&S, With<SoundEffectInstance>>) {
+    for sink in q_sinks.iter() {
+        sink.pause();
+    }
+}
+
+fn resume_sound_effects<S: AudioSinkPlayback + Component>(q_sinks: Query<&S, With<SoundEffectInstance>>) {
+    for sink in q_sinks.iter() {
+        sink.play();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::{
+        schedule::ScheduleLabel,
+        system::RunSystemOnce,
+    };
+    use std::sync::atomic::{
+        AtomicBool,
+        Ordering,
+    };
+
+    // bevy only hands out real sinks when there's a sound card to play on -
+    // this stands in for whichever one an effect got
+    #[derive(Component, Default)]
+    struct FakeSink {
+        paused: AtomicBool,
+    }
+
+    impl AudioSinkPlayback for FakeSink {
+        fn volume(&self) -> f32 { 1. }
+
+        fn set_volume(&self, _volume: f32) {}
+
+        fn speed(&self) -> f32 { 1. }
+
+        fn set_speed(&self, _speed: f32) {}
+
+        fn play(&self) { self.paused.store(false, Ordering::Relaxed); }
+
+        fn pause(&self) { self.paused.store(true, Ordering::Relaxed); }
+
+        fn is_paused(&self) -> bool { self.paused.load(Ordering::Relaxed) }
+
+        fn stop(&self) {}
+
+        fn empty(&self) -> bool { false }
+    }
+
+    fn system_names(app: &App, label: impl ScheduleLabel) -> Vec<String> {
+        app.get_schedule(label).map_or_else(Vec::new, |schedule| {
+            schedule
+                .graph()
+                .systems()
+                .map(|(_, system, _)| system.name().into_owned())
+                .collect()
+        })
+    }
+
+    // what the plugin adds has to cover the sink a spatial effect gets, and
+    // the systems themselves have to find the effects
+    #[test]
+    fn pausing_reaches_spatial_sound_effects() {
+        let mut app = App::new();
+        app.add_plugins(SoundEffectsPlugin);
+        let paused = system_names(&app, OnEnter(IsPaused::Paused));
+        let resumed = system_names(&app, OnEnter(IsPaused::NotPaused));
+        assert!(
+            paused.iter().any(|name| name.contains("SpatialAudioSink")),
+            "{paused:?}"
+        );
+        assert!(
+            resumed.iter().any(|name| name.contains("SpatialAudioSink")),
+            "{resumed:?}"
+        );
+
+        let mut world = World::new();
+        let effect = world
+            .spawn((SoundEffectInstance(SoundEffect::NateroidDestroyed), FakeSink::default()))
+            .id();
+        // not a sound effect - a music layer, say - so it's left playing
+        let other = world.spawn(FakeSink::default()).id();
+
+        world.run_system_once(pause_sound_effects::<FakeSink>).unwrap();
+        assert!(world.get::<FakeSink>(effect).unwrap().is_paused());
+        assert!(!world.get::<FakeSink>(other).unwrap().is_paused());
+
+        world.run_system_once(resume_sound_effects::<FakeSink>).unwrap();
+        assert!(!world.get::<FakeSink>(effect).unwrap().is_paused());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    actor::Spaceship,
+    audio::AudioSettings,
+    camera::PrimaryCamera,
+    playfield::Boundary,
+    schedule::FrameSet,
+};
+use bevy::{
+    audio::{
+        DefaultSpatialScale,
+        SpatialScale,
+    },
+    prelude::*,
+};
+
+// distance between the listener's ears in world units - wide enough that
+// something to the side is clearly louder in one ear
+const LISTENER_EAR_GAP: f32 = 20.;
+
+pub struct SpatialAudioPlugin;
+
+impl Plugin for SpatialAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (attach_listener, update_spatial_scale).in_set(FrameSet::Ui),
+        );
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListenerTarget {
+    #[default]
+    Camera,
+    Spaceship,
+}
+
+/// where a sound at `position` should be emitted from so that it pans the way
+/// it would if you could hear through the walls. we anchor on the listener's
+/// position clamped into the boundary so that a camera sitting out in front
+/// of the playfield still hears left and right correctly - then hop to the
+/// nearest copy of the sound on the torus
+pub fn wrapped_emitter_position(boundary: &Boundary, listener: Option<Vec3>, position: Vec3) -> Vec3 {
+    match listener {
+        Some(listener) => {
+            let anchor = boundary.clamp_to_bounds(listener);
+            anchor + boundary.wrapped_delta(anchor, position)
+        },
+        None => position,
+    }
+}
+
+// keep exactly one SpatialListener around, on whichever entity the settings ask
+// for - the spaceship comes and goes so this is checked every frame
+fn attach_listener(
+    mut commands: Commands,
+    settings: Res<AudioSettings>,
+    q_camera: Query<(Entity, Has<SpatialListener>), With<PrimaryCamera>>,
+    q_spaceship: Query<(Entity, Has<SpatialListener>), With<Spaceship>>,
+) {
+    let (wanted, other) = match settings.listener {
+        ListenerTarget::Camera => (q_camera.get_single(), q_spaceship.get_single()),
+        ListenerTarget::Spaceship => (q_spaceship.get_single(), q_camera.get_single()),
+    };
+
+    if let Ok((entity, false)) = wanted {
+        commands
+            .entity(entity)
+            .insert(SpatialListener::new(LISTENER_EAR_GAP));
+    }
+
+    if let Ok((entity, true)) = other {
+        commands.entity(entity).remove::<SpatialListener>();
+    }
+}
+
+// rodio attenuates by 1 / distance² so we shrink the world until the far side
+// of the boundary sits about attenuation_distance units away
+fn update_spatial_scale(
+    boundary: Res<Boundary>,
+    settings: Res<AudioSettings>,
+    mut spatial_scale: ResMut<DefaultSpatialScale>,
+) {
+    if !boundary.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let size = boundary.max_missile_distance().max(1.);
+    spatial_scale.0 = SpatialScale::new(settings.attenuation_distance / size);
+}
@@ -0,0 +1,80 @@
+use crate::{
+    asset_loader::AudioAssets,
+    audio::{
+        audio_unlocked,
+        AudioSettings,
+        BackgroundMuted,
+    },
+    schedule::FrameSet,
+};
+use bevy::{
+    audio::Volume,
+    prelude::*,
+};
+use std::collections::HashMap;
+
+pub struct UiSoundsPlugin;
+
+impl Plugin for UiSoundsPlugin {
+    fn build(&self, app: &mut App) {
+        // not gated on any state - the pause menu needs to be heard while paused
+        app.add_event::<UiSoundEvent>()
+            .add_systems(Update, play_ui_sounds.in_set(FrameSet::Ui).run_if(audio_unlocked));
+    }
+}
+
+/// ui systems send these rather than playing sounds themselves so that every
+/// click goes through the ui volume and the cooldown
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiSoundEvent {
+    PauseClosed,
+    PauseOpened,
+    Toggle,
+}
+
+impl UiSoundEvent {
+    fn handle(self, audio_assets: &AudioAssets) -> Handle<AudioSource> {
+        match self {
+            UiSoundEvent::PauseClosed => audio_assets.ui_pause_closed.clone(),
+            UiSoundEvent::PauseOpened => audio_assets.ui_pause_opened.clone(),
+            UiSoundEvent::Toggle => audio_assets.ui_toggle.clone(),
+        }
+    }
+}
+
+// ui sounds aren't SoundEffectInstances - they ignore pause and the polyphony
+// cap, the cooldown is what keeps a held key from machine gunning clicks.
+// real time so it keeps counting while the game is paused
+#[allow(clippy::too_many_arguments)]
+fn play_ui_sounds(
+    mut commands: Commands,
+    mut events: EventReader<UiSoundEvent>,
+    mut last_played: Local<HashMap<UiSoundEvent, f32>>,
+    audio_assets: Res<AudioAssets>,
+    asset_server: Res<AssetServer>,
+    settings: Res<AudioSettings>,
+    background: Res<BackgroundMuted>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+
+    for &event in events.read() {
+        if let Some(&last) = last_played.get(&event) {
+            if now - last < settings.ui_sound_cooldown {
+                continue;
+            }
+        }
+        last_played.insert(event, now);
+
+        let handle = event.handle(&audio_assets);
+        if !asset_server.is_loaded(&handle) {
+            continue;
+        }
+
+        commands.spawn((
+            AudioPlayer::new(handle),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.ui_gain() * background.gain())),
+            Name::new("UiSound"),
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    actor::EntityTeleported,
+    audio::{
+        AudioSettings,
+        PlaySoundEffect,
+        SoundEffect,
+    },
+    schedule::FrameSet,
+    state::PlayingGame,
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// every extra actor wrapping in the same window makes the composite a bit
+// bigger, up to this
+const MAX_COMPOSITE_INTENSITY: f32 = 2.5;
+
+pub struct WrapSoundsPlugin;
+
+impl Plugin for WrapSoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WrapSounds>().add_systems(
+            Update,
+            (collect_wraps, play_wrap_sounds)
+                .chain()
+                .in_set(FrameSet::Ui)
+                .run_if(in_state(PlayingGame)),
+        );
+    }
+}
+
+// a wrap sounds like a "suck" where the actor left and a "pop" where it comes
+// back in a moment later. wraps that land close together in time are merged
+// into one composite so a spread of missiles crossing together is a single
+// bigger whoosh instead of eight pops
+#[derive(Resource, Debug, Default)]
+struct WrapSounds {
+    // per entity so something sitting right on the boundary can't retrigger
+    cooldowns: HashMap<Entity, f32>,
+    gathering: Option<Composite>,
+    pops:      Vec<DelayedPop>,
+}
+
+#[derive(Debug)]
+struct Composite {
+    remaining: f32,
+    count:     u32,
+    exit_sum:  Vec3,
+    entry_sum: Vec3,
+}
+
+#[derive(Debug)]
+struct DelayedPop {
+    remaining: f32,
+    position:  Vec3,
+    intensity: f32,
+}
+
+fn collect_wraps(
+    mut wraps: ResMut<WrapSounds>,
+    mut teleported: EventReader<EntityTeleported>,
+    settings: Res<AudioSettings>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_secs();
+    wraps.cooldowns.retain(|_, remaining| {
+        *remaining -= delta_seconds;
+        *remaining > 0.0
+    });
+
+    for event in teleported.read() {
+        if wraps.cooldowns.contains_key(&event.entity) {
+            continue;
+        }
+        wraps
+            .cooldowns
+            .insert(event.entity, settings.wrap_entity_cooldown);
+
+        let composite = wraps.gathering.get_or_insert(Composite {
+            remaining: settings.wrap_merge_seconds,
+            count:     0,
+            exit_sum:  Vec3::ZERO,
+            entry_sum: Vec3::ZERO,
+        });
+        composite.count += 1;
+        composite.exit_sum += event.from;
+        composite.entry_sum += event.to;
+    }
+}
+
+fn play_wrap_sounds(
+    mut wraps: ResMut<WrapSounds>,
+    mut sounds: EventWriter<PlaySoundEffect>,
+    settings: Res<AudioSettings>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_secs();
+
+    let finished = wraps.gathering.as_mut().is_some_and(|composite| {
+        composite.remaining -= delta_seconds;
+        composite.remaining <= 0.0
+    });
+
+    if finished {
+        if let Some(composite) = wraps.gathering.take() {
+            let count = composite.count as f32;
+            let intensity = (1.0 + 0.25 * (count - 1.0)).min(MAX_COMPOSITE_INTENSITY);
+
+            sounds.send(PlaySoundEffect {
+                effect: SoundEffect::WrapSuck,
+                position: composite.exit_sum / count,
+                intensity,
+            });
+
+            wraps.pops.push(DelayedPop {
+                remaining: settings.wrap_pop_delay,
+                position: composite.entry_sum / count,
+                intensity,
+            });
+        }
+    }
+
+    wraps.pops.retain_mut(|pop| {
+        pop.remaining -= delta_seconds;
+        if pop.remaining > 0.0 {
+            return true;
+        }
+
+        sounds.send(PlaySoundEffect {
+            effect:    SoundEffect::WrapPop,
+            position:  pop.position,
+            intensity: pop.intensity,
+        });
+        false
+    });
+}
@@ -0,0 +1,280 @@
+use crate::{
+    actor::{
+        ActorKind,
+        Teleporter,
+    },
+    playfield::LineMesh,
+    schedule::FrameSet,
+};
+use bevy::{
+    prelude::*,
+    render::mesh::Indices,
+    time::common_conditions::on_timer,
+};
+use std::time::Duration;
+
+// counting everything walks a lot of entities and every mesh - once a second
+// is plenty to spot something creeping up over a long session
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const BYTES_PER_MEGABYTE: f64 = 1024. * 1024.;
+
+/// keeps an eye on the things that pile up if something forgets to clean up
+/// after itself - entities, meshes, textures, scene instances - and warns when
+/// one of them goes over its budget. the diagnostics overlay lists whichever
+/// are closest to their budget
+pub struct BudgetPlugin;
+
+impl Plugin for BudgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MemoryBudgets>()
+            .init_resource::<BudgetUsage>()
+            .register_type::<MemoryBudgets>()
+            .add_systems(
+                Update,
+                (measure_usage, warn_over_budget)
+                    .chain()
+                    .in_set(FrameSet::Ui)
+                    .run_if(on_timer(CHECK_INTERVAL)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetCategory {
+    Entities,
+    Nateroids,
+    Missiles,
+    // the wrapping stress cubes and anything else that teleports without being
+    // an actor
+    Teleporters,
+    LineMeshes,
+    SceneInstances,
+    Meshes,
+    MeshMemory,
+    Images,
+    ImageMemory,
+}
+
+impl BudgetCategory {
+    const ALL: [BudgetCategory; 10] = [
+        BudgetCategory::Entities,
+        BudgetCategory::Nateroids,
+        BudgetCategory::Missiles,
+        BudgetCategory::Teleporters,
+        BudgetCategory::LineMeshes,
+        BudgetCategory::SceneInstances,
+        BudgetCategory::Meshes,
+        BudgetCategory::MeshMemory,
+        BudgetCategory::Images,
+        BudgetCategory::ImageMemory,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BudgetCategory::Entities => "entities",
+            BudgetCategory::Nateroids => "nateroids",
+            BudgetCategory::Missiles => "missiles",
+            BudgetCategory::Teleporters => "teleporters",
+            BudgetCategory::LineMeshes => "line meshes",
+            BudgetCategory::SceneInstances => "scenes",
+            BudgetCategory::Meshes => "meshes",
+            BudgetCategory::MeshMemory => "mesh mb",
+            BudgetCategory::Images => "images",
+            BudgetCategory::ImageMemory => "image mb",
+        }
+    }
+}
+
+/// how much of each category is reasonable. a normal game sits well under all
+/// of these - going over means something is leaking, not that the game got busy
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct MemoryBudgets {
+    pub entities:        usize,
+    pub nateroids:       usize,
+    pub missiles:        usize,
+    pub teleporters:     usize,
+    pub line_meshes:     usize,
+    pub scene_instances: usize,
+    pub meshes:          usize,
+    pub mesh_megabytes:  f32,
+    pub images:          usize,
+    pub image_megabytes: f32,
+}
+
+impl Default for MemoryBudgets {
+    fn default() -> Self {
+        Self {
+            entities:        20_000,
+            nateroids:       500,
+            missiles:        500,
+            teleporters:     1_000,
+            line_meshes:     2_000,
+            scene_instances: 1_000,
+            meshes:          3_000,
+            mesh_megabytes:  128.,
+            images:          200,
+            image_megabytes: 512.,
+        }
+    }
+}
+
+impl MemoryBudgets {
+    fn limit(&self, category: BudgetCategory) -> f64 {
+        match category {
+            BudgetCategory::Entities => self.entities as f64,
+            BudgetCategory::Nateroids => self.nateroids as f64,
+            BudgetCategory::Missiles => self.missiles as f64,
+            BudgetCategory::Teleporters => self.teleporters as f64,
+            BudgetCategory::LineMeshes => self.line_meshes as f64,
+            BudgetCategory::SceneInstances => self.scene_instances as f64,
+            BudgetCategory::Meshes => self.meshes as f64,
+            BudgetCategory::MeshMemory => self.mesh_megabytes as f64,
+            BudgetCategory::Images => self.images as f64,
+            BudgetCategory::ImageMemory => self.image_megabytes as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BudgetEntry {
+    pub category: BudgetCategory,
+    pub current:  f64,
+    // the most it has been since the game started - a soak run cares about
+    // this more than where it happens to be at the end
+    pub peak:     f64,
+    pub limit:    f64,
+    over:         bool,
+}
+
+impl BudgetEntry {
+    /// how much of the budget is used - over 1 is over budget
+    pub fn fraction(&self) -> f64 {
+        if self.limit > 0. {
+            self.current / self.limit
+        } else {
+            0.
+        }
+    }
+
+    pub fn peaked_over(&self) -> bool { self.peak > self.limit }
+}
+
+/// the latest count for every category, refreshed once a second
+#[derive(Resource, Debug)]
+pub struct BudgetUsage {
+    pub entries: Vec<BudgetEntry>,
+}
+
+impl Default for BudgetUsage {
+    fn default() -> Self {
+        Self {
+            entries: BudgetCategory::ALL
+                .into_iter()
+                .map(|category| BudgetEntry {
+                    category,
+                    current: 0.,
+                    peak: 0.,
+                    limit: 0.,
+                    over: false,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl BudgetUsage {
+    /// the categories closest to (or furthest over) their budgets, worst first
+    pub fn top_offenders(&self, count: usize) -> Vec<BudgetEntry> {
+        let mut entries = self.entries.clone();
+        entries.sort_by(|a, b| b.fraction().total_cmp(&a.fraction()));
+        entries.truncate(count);
+        entries
+    }
+}
+
+// meshes and images that were only wanted on the gpu (RenderAssetUsages::
+// RENDER_WORLD) have already given their data up, so this undercounts them -
+// the point is to catch growth, not to match a memory profiler
+fn mesh_bytes(mesh: &Mesh) -> usize {
+    let vertices: usize = mesh
+        .attributes()
+        .map(|(_, values)| values.get_bytes().len())
+        .sum();
+    let indices = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.len() * size_of::<u16>(),
+        Some(Indices::U32(indices)) => indices.len() * size_of::<u32>(),
+        None => 0,
+    };
+    vertices + indices
+}
+
+#[allow(clippy::too_many_arguments)]
+fn measure_usage(
+    budgets: Res<MemoryBudgets>,
+    mut usage: ResMut<BudgetUsage>,
+    meshes: Res<Assets<Mesh>>,
+    images: Res<Assets<Image>>,
+    q_entities: Query<()>,
+    q_actors: Query<&ActorKind>,
+    q_teleporters: Query<(), (With<Teleporter>, Without<ActorKind>)>,
+    q_line_meshes: Query<(), With<LineMesh>>,
+    q_scenes: Query<(), With<SceneRoot>>,
+) {
+    let (mut nateroids, mut missiles) = (0, 0);
+    for kind in q_actors.iter() {
+        match kind {
+            ActorKind::Nateroid => nateroids += 1,
+            ActorKind::Missile => missiles += 1,
+            ActorKind::Spaceship => {},
+        }
+    }
+
+    let mesh_memory = meshes.iter().map(|(_, mesh)| mesh_bytes(mesh)).sum::<usize>() as f64;
+    let image_memory = images.iter().map(|(_, image)| image.data.len()).sum::<usize>() as f64;
+
+    for entry in usage.entries.iter_mut() {
+        entry.current = match entry.category {
+            BudgetCategory::Entities => q_entities.iter().len() as f64,
+            BudgetCategory::Nateroids => nateroids as f64,
+            BudgetCategory::Missiles => missiles as f64,
+            BudgetCategory::Teleporters => q_teleporters.iter().len() as f64,
+            BudgetCategory::LineMeshes => q_line_meshes.iter().len() as f64,
+            BudgetCategory::SceneInstances => q_scenes.iter().len() as f64,
+            BudgetCategory::Meshes => meshes.len() as f64,
+            BudgetCategory::MeshMemory => mesh_memory / BYTES_PER_MEGABYTE,
+            BudgetCategory::Images => images.len() as f64,
+            BudgetCategory::ImageMemory => image_memory / BYTES_PER_MEGABYTE,
+        };
+        entry.peak = entry.peak.max(entry.current);
+        entry.limit = budgets.limit(entry.category);
+    }
+}
+
+// once when a category goes over and once when it comes back - a leak would
+// otherwise fill the log with the same warning every second
+fn warn_over_budget(mut usage: ResMut<BudgetUsage>) {
+    for entry in usage.entries.iter_mut() {
+        let over = entry.current > entry.limit;
+        if over == entry.over {
+            continue;
+        }
+        entry.over = over;
+
+        if over {
+            warn!(
+                "{} over budget: {:.0} of {:.0}",
+                entry.category.label(),
+                entry.current,
+                entry.limit
+            );
+        } else {
+            info!(
+                "{} back under budget: {:.0} of {:.0}",
+                entry.category.label(),
+                entry.current,
+                entry.limit
+            );
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    camera::{
+        camera_control::{
+            CameraControl,
+            CameraHomeAnimation,
+        },
+        cameras::home_transform,
+        PrimaryCamera,
+    },
+    config::SettingsAppExt,
+    global_input::{
+        GlobalAction,
+        InspectorAppExt,
+    },
+    orientation::CameraOrientation,
+    playfield::Boundary,
+    schedule::FrameSet,
+    state::{
+        GameState,
+        PlayingGame,
+    },
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::{
+    inspector_options::std_options::NumberDisplay,
+    prelude::*,
+};
+use leafwing_input_manager::prelude::*;
+use std::f32::consts::TAU;
+
+/// while nobody's playing the camera drifts slowly round the boundary, bobbing
+/// up and down a little, and when a game starts it eases back to where the
+/// game wants it. orbiting, panning or zooming stops the drift and leaves the
+/// camera to the player
+pub struct AttractCameraPlugin;
+
+impl Plugin for AttractCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings::<AttractCameraConfig>("attract_camera")
+            .add_inspector::<AttractCameraConfig>(GlobalAction::CameraConfigInspector)
+            .init_resource::<AttractCamera>()
+            .add_systems(OnEnter(GameState::Splash), start_attract_camera)
+            .add_systems(
+                Update,
+                attract_camera.in_set(FrameSet::Camera).run_if(in_attract_state),
+            )
+            .add_systems(OnEnter(PlayingGame), blend_to_gameplay);
+    }
+}
+
+#[derive(Resource, Reflect, InspectorOptions, Debug, PartialEq, Clone, Copy)]
+#[reflect(Resource, InspectorOptions)]
+pub struct AttractCameraConfig {
+    #[inspector(min = 0.0, max = 90.0, display = NumberDisplay::Slider)]
+    pub degrees_per_second: f32,
+    // how far above and below the home view it bobs, in degrees
+    #[inspector(min = 0.0, max = 45.0, display = NumberDisplay::Slider)]
+    pub pitch_amplitude:    f32,
+    // seconds for one bob up and back down
+    #[inspector(min = 1.0, max = 60.0, display = NumberDisplay::Slider)]
+    pub pitch_period:       f32,
+    // how long the camera takes to get back to the game's view
+    #[inspector(min = 0.0, max = 3.0, display = NumberDisplay::Slider)]
+    pub blend_seconds:      f32,
+}
+
+impl Default for AttractCameraConfig {
+    fn default() -> Self {
+        Self {
+            degrees_per_second: 6.,
+            pitch_amplitude:    12.,
+            pitch_period:       20.,
+            blend_seconds:      1.,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct AttractCamera {
+    elapsed:  f32,
+    drifting: bool,
+    // whether the camera is somewhere the game didn't put it
+    moved:    bool,
+}
+
+// the splash screen and the main menu behind it - an attract mode between runs
+// would go here too
+fn in_attract_state(state: Res<State<GameState>>) -> bool {
+    matches!(state.get(), GameState::Splash | GameState::MainMenu)
+}
+
+fn start_attract_camera(mut attract: ResMut<AttractCamera>) {
+    attract.elapsed = 0.;
+    attract.drifting = true;
+}
+
+fn attract_camera(
+    mut attract: ResMut<AttractCamera>,
+    config: Res<AttractCameraConfig>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    mut q_camera: Query<(&mut Transform, &ActionState<CameraControl>), With<PrimaryCamera>>,
+    time: Res<Time>,
+) {
+    if !attract.drifting {
+        return;
+    }
+    let Ok((mut transform, action_state)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    if action_state.just_pressed(&CameraControl::Home)
+        || action_state.axis_pair(&CameraControl::Orbit) != Vec2::ZERO
+        || action_state.axis_pair(&CameraControl::Pan) != Vec2::ZERO
+        || action_state.value(&CameraControl::Zoom) != 0.
+    {
+        attract.drifting = false;
+        attract.moved = false;
+        return;
+    }
+
+    attract.elapsed += time.delta_secs();
+    let yaw = (attract.elapsed * config.degrees_per_second).to_radians();
+    let pitch = config.pitch_amplitude.to_radians()
+        * (attract.elapsed * TAU / config.pitch_period.max(f32::EPSILON)).sin();
+
+    // round the middle of the boundary at the distance the game looks from
+    let center = boundary.transform.translation;
+    let up = orientation.config.axis_mundi;
+    let offset = home_transform(&boundary, &orientation).translation - center;
+    let rotation =
+        Quat::from_axis_angle(up, yaw) * Quat::from_axis_angle(orientation.config.axis_orbis, -pitch);
+
+    *transform = Transform::from_translation(center + rotation * offset).looking_at(center, up);
+    attract.moved = true;
+}
+
+fn blend_to_gameplay(
+    mut commands: Commands,
+    mut attract: ResMut<AttractCamera>,
+    config: Res<AttractCameraConfig>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    q_camera: Query<(Entity, &Transform), With<PrimaryCamera>>,
+) {
+    attract.drifting = false;
+    if !attract.moved {
+        return;
+    }
+    attract.moved = false;
+
+    if let Ok((entity, transform)) = q_camera.get_single() {
+        commands.entity(entity).insert(CameraHomeAnimation::new(
+            *transform,
+            home_transform(&boundary, &orientation),
+            config.blend_seconds,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn attract_camera_config_round_trips() { assert_settings_round_trip(nudged::<AttractCameraConfig>()); }
+}
@@ -1,12 +1,20 @@
 use crate::{
-    camera::PrimaryCamera,
+    camera::{
+        cameras::home_transform,
+        zoom_to_fit::ZoomToFit,
+        PrimaryCamera,
+    },
+    config::SettingsAppExt,
     global_input::{
-        toggle_active,
         GlobalAction,
+        InspectorAppExt,
     },
     orientation::CameraOrientation,
+    playfield::Boundary,
+    schedule::FrameSet,
     state::{
         GameState,
+        InputContext,
         IsInspecting,
     },
 };
@@ -19,15 +27,22 @@ use bevy::{
             MouseWheel,
         },
     },
+    math::curve::{
+        Curve,
+        EaseFunction,
+        EasingCurve,
+    },
     prelude::*,
 };
 use bevy_inspector_egui::{
     bevy_egui::EguiContext,
     inspector_options::std_options::NumberDisplay,
     prelude::*,
-    quick::ResourceInspectorPlugin,
 };
-use leafwing_input_manager::prelude::*;
+use leafwing_input_manager::{
+    plugin::InputManagerSystem,
+    prelude::*,
+};
 use strum::{
     EnumIter,
     IntoEnumIterator,
@@ -37,31 +52,69 @@ pub struct CameraControlPlugin;
 
 impl Plugin for CameraControlPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<CameraConfig>()
-            .add_plugins(
-                ResourceInspectorPlugin::<CameraConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::CameraConfigInspector)),
-            )
-            .init_resource::<CameraConfig>()
+        app.register_settings::<CameraConfig>("camera")
+            .register_settings::<CameraOrbitConfig>("camera_orbit")
+            .add_inspector::<CameraConfig>(GlobalAction::CameraConfigInspector)
+            .add_inspector::<CameraOrbitConfig>(GlobalAction::CameraConfigInspector)
             .add_plugins(InputManagerPlugin::<CameraControl>::default())
-            .add_systems(Update, check_inspector_state)
+            .init_resource::<CameraTarget>()
+            .add_systems(Update, check_inspector_state.in_set(FrameSet::Input))
+            .add_systems(
+                PreUpdate,
+                suppress_camera_input.in_set(InputManagerSystem::ManualControl),
+            )
             .add_systems(
                 Update,
                 (
                     // order matters because we hack around the input manager
                     // that doesn't yet support trackpads
                     home_camera,
+                    animate_camera_home,
                     pinch_to_zoom,
                     zoom_camera,
                     orbit_camera,
                     pan_camera,
                 )
                     .chain()
-                    .run_if(in_state(IsInspecting::NotInspecting)),
+                    .in_set(FrameSet::Camera)
+                    // outside a game too - the splash screen's attract
+                    // camera hands over to the player as soon as they touch it
+                    .run_if(not(in_state(IsInspecting::Inspecting))),
+            )
+            // keeps going while the pointer is over egui - that's where follow
+            // gets clicked
+            .add_systems(
+                Update,
+                follow_camera_target.in_set(FrameSet::Camera).after(pan_camera),
             );
     }
 }
 
+/// something for the camera to move along with - it keeps whatever offset it
+/// had when following started, and orbiting, zooming and panning still work
+/// on top. home lets go
+#[derive(Resource, Debug, Default)]
+pub struct CameraTarget {
+    entity:        Option<Entity>,
+    last_position: Option<Vec3>,
+}
+
+impl CameraTarget {
+    #[cfg(any(dev_tools, test))]
+    pub fn follow(&mut self, entity: Entity) {
+        self.entity = Some(entity);
+        self.last_position = None;
+    }
+
+    pub fn stop(&mut self) {
+        self.entity = None;
+        self.last_position = None;
+    }
+
+    #[cfg(dev_tools)]
+    pub fn is_following(&self, entity: Entity) -> bool { self.entity == Some(entity) }
+}
+
 #[derive(Resource, Reflect, InspectorOptions, Debug, PartialEq, Clone, Copy)]
 #[reflect(Resource, InspectorOptions)]
 pub struct CameraConfig {
@@ -74,12 +127,116 @@ pub struct CameraConfig {
     pub bloom_low_frequency_boost: f32,
     #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
     pub bloom_high_pass_frequency: f32,
+    // how much of the game camera's orbit the stars turn through - lower
+    // looks further away, 1 is stuck to the game camera
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub background_parallax:       f32,
     #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
     pub orbit_speed:               f32,
     #[inspector(min = 10.0, max = 200.0, display = NumberDisplay::Slider)]
     pub zoom_sensitivity_pinch:    f32,
     #[inspector(min = 1.0, max = 20.0, display = NumberDisplay::Slider)]
     pub zoom_sensitivity_mouse:    f32,
+    // seconds for home to get the camera back - 0 is straight there
+    #[inspector(min = 0.0, max = 3.0, display = NumberDisplay::Slider)]
+    pub home_duration:             f32,
+    pub home_easing:               HomeEasing,
+    pub projection:                ProjectionMode,
+    // keeps everything that wraps on screen - see zoom_to_fit.rs
+    pub zoom_to_fit:               bool,
+    // world units around the actors that still have to be in view
+    #[inspector(min = 0.0, max = 100.0, display = NumberDisplay::Slider)]
+    pub zoom_to_fit_margin:        f32,
+    // how quickly it catches up - higher is snappier, lower pumps less
+    #[inspector(min = 0.1, max = 10.0, display = NumberDisplay::Slider)]
+    pub zoom_to_fit_smoothing:     f32,
+}
+
+/// orthographic frames the whole playfield flat on, the way the arcade game
+/// looked - perspective is for flying about in 3d
+#[derive(Reflect, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum ProjectionMode {
+    #[default]
+    Perspective,
+    Orthographic,
+}
+
+impl ProjectionMode {
+    pub fn toggled(self) -> Self {
+        match self {
+            ProjectionMode::Perspective => ProjectionMode::Orthographic,
+            ProjectionMode::Orthographic => ProjectionMode::Perspective,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum HomeEasing {
+    Linear,
+    #[default]
+    Cubic,
+    // overshoots and springs back
+    Elastic,
+}
+
+/// how far the camera can go around and in and out. pitch is how far above
+/// (or below) the middle of the playfield the camera sits, in degrees - never
+/// quite straight over the top, where which way is up stops making sense
+#[derive(Resource, Reflect, InspectorOptions, Debug, PartialEq, Clone, Copy)]
+#[reflect(Resource, InspectorOptions)]
+pub struct CameraOrbitConfig {
+    #[inspector(min = -89.0, max = 89.0, display = NumberDisplay::Slider)]
+    pub min_pitch:           f32,
+    #[inspector(min = -89.0, max = 89.0, display = NumberDisplay::Slider)]
+    pub max_pitch:           f32,
+    // off keeps the world's up at the top of the screen however it's orbited
+    pub allow_roll:          bool,
+    pub invert_y:            bool,
+    #[inspector(min = 1.0, max = 200.0, display = NumberDisplay::Slider)]
+    pub min_distance:        f32,
+    // times the boundary's longest diagonal, so it keeps up with a resize
+    #[inspector(min = 1.0, max = 10.0, display = NumberDisplay::Slider)]
+    pub max_distance_factor: f32,
+}
+
+impl Default for CameraOrbitConfig {
+    fn default() -> Self {
+        Self {
+            min_pitch:           -89.,
+            max_pitch:           89.,
+            allow_roll:          false,
+            invert_y:            false,
+            min_distance:        20.,
+            max_distance_factor: 2.,
+        }
+    }
+}
+
+const PITCH_LIMIT: f32 = 89.;
+
+impl CameraOrbitConfig {
+    fn pitch_range(&self) -> (f32, f32) {
+        let min = self.min_pitch.clamp(-PITCH_LIMIT, PITCH_LIMIT);
+        let max = self.max_pitch.clamp(min, PITCH_LIMIT);
+        (min.to_radians(), max.to_radians())
+    }
+
+    // zoom to fit keeps to it as well
+    pub(super) fn distance_range(&self, boundary: &Boundary) -> (f32, f32) {
+        let max = boundary.longest_diagonal() * self.max_distance_factor;
+        (self.min_distance.min(max), max)
+    }
+}
+
+impl HomeEasing {
+    fn ease(self, t: f32) -> f32 {
+        let function = match self {
+            HomeEasing::Linear => EaseFunction::Linear,
+            HomeEasing::Cubic => EaseFunction::CubicInOut,
+            HomeEasing::Elastic => EaseFunction::ElasticOut,
+        };
+        EasingCurve::new(0.0, 1.0, function).sample_clamped(t)
+    }
 }
 
 impl Default for CameraConfig {
@@ -90,9 +247,16 @@ impl Default for CameraConfig {
             bloom_intensity:           0.9,
             bloom_low_frequency_boost: 0.5,
             bloom_high_pass_frequency: 0.5,
+            background_parallax:       0.2,
             orbit_speed:               0.01,
             zoom_sensitivity_pinch:    100.,
             zoom_sensitivity_mouse:    5.,
+            home_duration:             0.6,
+            home_easing:               HomeEasing::Cubic,
+            projection:                ProjectionMode::Perspective,
+            zoom_to_fit:               false,
+            zoom_to_fit_margin:        15.,
+            zoom_to_fit_smoothing:     2.,
         }
     }
 }
@@ -169,65 +333,266 @@ fn check_inspector_state(
 
         let is_inspecting = is_mouse_over_inspector || dragged;
 
-        if let GameState::InGame { paused, inspecting } = state.get() {
-            if *inspecting != is_inspecting {
-                next_state.set(GameState::InGame {
-                    paused:     *paused,
-                    inspecting: is_mouse_over_inspector,
-                });
-            }
+        if let Some(inspecting) = state.get().with_inspecting(is_inspecting) {
+            next_state.set(inspecting);
+        }
+    }
+}
+
+// egui doesn't stop the mouse getting through to everything else - scrolling
+// an inspector would zoom and dragging a slider would pan. IsInspecting turns
+// the camera systems off, but only from the frame after the state changes, so
+// the camera's actions are switched off here as well, as soon as leafwing has
+// read them and before anything uses them. a menu - the pause menu included -
+// has the camera to itself the whole time it's open
+fn suppress_camera_input(
+    mut q_egui: Query<&mut EguiContext>,
+    mut q_camera: Query<&mut ActionState<CameraControl>>,
+    input_context: Res<State<InputContext>>,
+) {
+    let Ok(mut context) = q_egui.get_single_mut() else {
+        return;
+    };
+    let context = context.get_mut();
+    let over_egui =
+        context.is_pointer_over_area() || context.wants_pointer_input() || context.dragged_id().is_some();
+    let in_menu = *input_context.get() == InputContext::Menu;
+
+    for mut action_state in q_camera.iter_mut() {
+        if over_egui || in_menu {
+            action_state.disable();
+        } else if action_state.disabled() {
+            action_state.enable();
+        }
+    }
+}
+
+/// the camera on its way back home - from where it was when home was pressed
+/// to the framing for the boundary as it was then
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CameraHomeAnimation {
+    start:    Transform,
+    end:      Transform,
+    duration: f32,
+    elapsed:  f32,
+}
+
+impl CameraHomeAnimation {
+    pub fn new(start: Transform, end: Transform, duration: f32) -> Self {
+        Self {
+            start,
+            end,
+            duration,
+            elapsed: 0.,
         }
     }
 }
 
 fn home_camera(
+    mut commands: Commands,
+    boundary: Res<Boundary>,
     orientation: Res<CameraOrientation>,
-    mut camera_transform: Query<(&mut Transform, &ActionState<CameraControl>), With<PrimaryCamera>>,
+    config: Res<CameraConfig>,
+    mut target: ResMut<CameraTarget>,
+    mut q_camera: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Projection,
+            &ActionState<CameraControl>,
+        ),
+        With<PrimaryCamera>,
+    >,
 ) {
-    if let Ok((mut transform, action_state)) = camera_transform.get_single_mut() {
+    if let Ok((entity, mut transform, mut projection, action_state)) = q_camera.get_single_mut() {
         if action_state.just_pressed(&CameraControl::Home) {
-            *transform = orientation.config.locus;
+            let home = home_transform(&boundary, &orientation);
+            target.stop();
+
+            // orthographic zooms with the scale rather than the distance
+            if let Projection::Orthographic(orthographic) = projection.as_mut() {
+                orthographic.scale = 1.;
+            }
+
+            if config.home_duration <= 0. {
+                *transform = home;
+                return;
+            }
+            commands
+                .entity(entity)
+                .insert(CameraHomeAnimation::new(*transform, home, config.home_duration));
         }
     }
 }
 
+// position and rotation each go their own way there, so orbit, pan and zoom
+// all unwind together. touching any of them on the way takes the camera back
+// and leaves it wherever it had got to
+fn animate_camera_home(
+    mut commands: Commands,
+    mut q_camera: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut CameraHomeAnimation,
+            &ActionState<CameraControl>,
+        ),
+        With<PrimaryCamera>,
+    >,
+    mut pinch_gesture_events: EventReader<PinchGesture>,
+    config: Res<CameraConfig>,
+    time: Res<Time<Real>>,
+) {
+    let Ok((entity, mut transform, mut animation, action_state)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let pinched = pinch_gesture_events.read().count() > 0;
+    if pinched
+        || action_state.axis_pair(&CameraControl::Orbit) != Vec2::ZERO
+        || action_state.axis_pair(&CameraControl::Pan) != Vec2::ZERO
+        || action_state.value(&CameraControl::Zoom) != 0.
+    {
+        commands.entity(entity).remove::<CameraHomeAnimation>();
+        return;
+    }
+
+    animation.elapsed += time.delta_secs();
+    let t = (animation.elapsed / animation.duration.max(f32::EPSILON)).min(1.);
+    let eased = config.home_easing.ease(t);
+
+    transform.translation = animation.start.translation.lerp(animation.end.translation, eased);
+    transform.rotation = animation.start.rotation.slerp(animation.end.rotation, eased);
+
+    if t >= 1. {
+        *transform = animation.end;
+        commands.entity(entity).remove::<CameraHomeAnimation>();
+    }
+}
+
+// moves the camera by however far the target moved since last frame. when the
+// target wraps the camera goes with it - it's following, after all. once the
+// target is gone the camera stays where it was
+pub(crate) fn follow_camera_target(
+    mut target: ResMut<CameraTarget>,
+    q_targets: Query<&Transform, Without<PrimaryCamera>>,
+    mut q_camera: Query<&mut Transform, With<PrimaryCamera>>,
+) {
+    let Some(entity) = target.entity else {
+        return;
+    };
+    let Ok(target_transform) = q_targets.get(entity) else {
+        target.stop();
+        return;
+    };
+    let Ok(mut camera_transform) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let position = target_transform.translation;
+    if let Some(last_position) = target.last_position {
+        camera_transform.translation += position - last_position;
+    }
+    target.last_position = Some(position);
+}
+
 fn pinch_to_zoom(
-    mut query: Query<&mut Transform, With<PrimaryCamera>>,
+    mut query: Query<(&mut Transform, &mut Projection), With<PrimaryCamera>>,
     mut pinch_gesture_events: EventReader<PinchGesture>,
     config: Res<CameraConfig>,
+    orbit_config: Res<CameraOrbitConfig>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    mut fit: ResMut<ZoomToFit>,
 ) {
+    let distance_range = orbit_config.distance_range(&boundary);
     for event in pinch_gesture_events.read() {
-        if let Ok(mut transform) = query.get_single_mut() {
-            impl_zoom(config.zoom_sensitivity_pinch, &mut transform, event.0);
+        if config.zoom_to_fit {
+            fit.offset -= config.zoom_sensitivity_pinch * event.0;
+        } else if let Ok((mut transform, mut projection)) = query.get_single_mut() {
+            impl_zoom(
+                config.zoom_sensitivity_pinch,
+                &mut transform,
+                &mut projection,
+                event.0,
+                orientation.config.nexus,
+                distance_range,
+            );
         }
     }
 }
 
 fn zoom_camera(
-    mut query: Query<(&mut Transform, &mut ActionState<CameraControl>), With<PrimaryCamera>>,
+    mut query: Query<(&mut Transform, &mut Projection, &mut ActionState<CameraControl>), With<PrimaryCamera>>,
     mut mouse_wheel_events: EventReader<MouseWheel>,
     config: Res<CameraConfig>,
+    orbit_config: Res<CameraOrbitConfig>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    mut fit: ResMut<ZoomToFit>,
 ) {
-    if let Ok((mut transform, mut action_state)) = query.get_single_mut() {
+    if let Ok((mut transform, mut projection, mut action_state)) = query.get_single_mut() {
         let zoom_delta = match should_zoom(&mut mouse_wheel_events, &mut action_state) {
             Some(value) => value,
             None => return,
         };
 
-        impl_zoom(config.zoom_sensitivity_mouse, &mut transform, zoom_delta);
+        // zoom to fit owns the distance - this is how far off it the player
+        // wants to be
+        if config.zoom_to_fit {
+            fit.offset -= config.zoom_sensitivity_mouse * zoom_delta;
+        } else {
+            impl_zoom(
+                config.zoom_sensitivity_mouse,
+                &mut transform,
+                &mut projection,
+                zoom_delta,
+                orientation.config.nexus,
+                orbit_config.distance_range(&boundary),
+            );
+        }
 
         // cleanup any dual_axis propagating from orbit so that Pan doesn't see it
         elide_dual_axis_data(&mut action_state);
     }
 }
 
-fn impl_zoom(sensitivity: f32, transform: &mut Mut<Transform>, zoom_delta: f32) {
+fn impl_zoom(
+    sensitivity: f32,
+    transform: &mut Mut<Transform>,
+    projection: &mut Mut<Projection>,
+    zoom_delta: f32,
+    target: Vec3,
+    (min_distance, max_distance): (f32, f32),
+) {
     // Calculate zoom direction based on camera's current orientation
     let zoom_direction = transform.forward();
 
     // Calculate zoom amount
     let zoom_speed = sensitivity; // Adjust this value to control zoom sensitivity
-    let zoom_amount = zoom_delta * zoom_speed;
+    let mut zoom_amount = zoom_delta * zoom_speed;
+
+    // how far in front of the camera the target is. stop at either end - and
+    // if a resize has left the camera outside, it can still come back in
+    let distance = (target - transform.translation).dot(*zoom_direction);
+
+    // moving closer doesn't make anything bigger in orthographic, so shrink
+    // the view instead - by as much as the same dolly would have, with the
+    // same limits
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        if distance > 0. {
+            let apparent = distance * orthographic.scale - zoom_amount;
+            orthographic.scale = apparent.clamp(min_distance, max_distance) / distance;
+        }
+        return;
+    }
+
+    if distance > 0. {
+        zoom_amount = zoom_amount.clamp(
+            (distance - max_distance).min(0.),
+            (distance - min_distance).max(0.),
+        );
+    }
 
     // Apply zoom
     transform.translation += zoom_direction * zoom_amount;
@@ -276,7 +641,7 @@ fn should_zoom(
     Some(zoom_delta)
 }
 
-fn pan_camera(
+pub(super) fn pan_camera(
     mut query: Query<(&mut Transform, &ActionState<CameraControl>), With<PrimaryCamera>>,
     keycode: Res<ButtonInput<KeyCode>>,
     orientation: Res<CameraOrientation>,
@@ -324,14 +689,18 @@ fn should_pan(keycode: Res<ButtonInput<KeyCode>>, action_state: &ActionState<Cam
 fn orbit_camera(
     mut q_camera: Query<(&mut Transform, &mut ActionState<CameraControl>), With<PrimaryCamera>>,
     camera_config: Res<CameraConfig>,
+    orbit_config: Res<CameraOrbitConfig>,
     keycode: Res<ButtonInput<KeyCode>>,
     orientation: Res<CameraOrientation>,
 ) {
     if let Ok((mut camera_transform, mut action_state)) = q_camera.get_single_mut() {
-        let orbit_vector = match should_orbit(&mut action_state, keycode) {
+        let mut orbit_vector = match should_orbit(&mut action_state, keycode) {
             Some(value) => value,
             None => return,
         };
+        if orbit_config.invert_y {
+            orbit_vector.y = -orbit_vector.y;
+        }
 
         let rotation_speed = camera_config.orbit_speed; //0.005;
                                                         // Assuming the target is at the origin - this may change in the future
@@ -342,21 +711,38 @@ fn orbit_camera(
         let up = orientation.config.axis_mundi.normalize();
         let right = camera_transform.right().normalize();
 
+        // pitching about the camera's right takes the camera down by the
+        // angle, so only let it go as far as the limits. it's exact while the
+        // camera looks at the target and a little short once it's panned off
+        let relative_position = camera_transform.translation - target;
+        let pitch = relative_position
+            .normalize_or_zero()
+            .dot(up)
+            .clamp(-1., 1.)
+            .asin();
+        let (min_pitch, max_pitch) = orbit_config.pitch_range();
+        let pitch_angle = pitch - (pitch + orbit_vector.y * rotation_speed).clamp(min_pitch, max_pitch);
+
         // Create rotation quaternions
-        let pitch_rotation = Quat::from_axis_angle(right, -orbit_vector.y * rotation_speed);
+        let pitch_rotation = Quat::from_axis_angle(right, pitch_angle);
         let yaw_rotation = Quat::from_axis_angle(up, -orbit_vector.x * rotation_speed);
 
         // Combine rotations
         let rotation = yaw_rotation * pitch_rotation;
 
         // Apply rotation to the camera's position relative to the target
-        let relative_position = camera_transform.translation - target;
         let new_relative_position = rotation * relative_position;
 
         // Update the camera's position and orientation
         camera_transform.translation = target + new_relative_position;
         camera_transform.rotation = rotation * camera_transform.rotation;
 
+        // still looking the same way, with any roll that crept in taken out
+        if !orbit_config.allow_roll {
+            let forward = camera_transform.forward();
+            camera_transform.look_to(forward, up);
+        }
+
         elide_dual_axis_data(&mut action_state);
     }
 }
@@ -396,3 +782,18 @@ fn elide_dual_axis_data(action_state: &mut Mut<ActionState<CameraControl>>) {
         dual_axis_data.fixed_update_pair = Vec2::ZERO;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn camera_config_round_trips() { assert_settings_round_trip(nudged::<CameraConfig>()); }
+
+    #[test]
+    fn camera_orbit_config_round_trips() { assert_settings_round_trip(nudged::<CameraOrbitConfig>()); }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    actor::{
+        ActorKind,
+        EntityTeleported,
+        Spaceship,
+    },
+    camera::{
+        camera_control::follow_camera_target,
+        PrimaryCamera,
+        RenderLayer,
+    },
+    config::SettingsAppExt,
+    despawn::ActorDestroyed,
+    global_input::{
+        GlobalAction,
+        InspectorAppExt,
+    },
+    schedule::FrameSet,
+    state::{
+        GameState,
+        PLAY_AGAIN,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+use bevy_inspector_egui::{
+    inspector_options::std_options::NumberDisplay,
+    prelude::*,
+};
+
+/// jolts the game camera about when things blow up and lets it settle. trauma
+/// piles up from whatever's going on - call add_trauma on CameraShake - and
+/// drains away on its own. the jolt comes off again before anything else
+/// moves the camera the next frame, so orbiting, zooming and following never
+/// see it
+///
+/// the wobble is noise read at the shake's own clock, which starts over with
+/// every run - the same seed and the same run shake the same way, so a
+/// replay does too
+pub struct CameraShakePlugin;
+
+impl Plugin for CameraShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings::<CameraShakeConfig>("camera_shake")
+            .add_inspector::<CameraShakeConfig>(GlobalAction::CameraConfigInspector)
+            .init_resource::<CameraShake>()
+            .add_systems(OnEnter(GameState::RunSetup), reset_camera_shake)
+            .add_systems(PLAY_AGAIN, reset_camera_shake)
+            .add_systems(
+                Update,
+                (settle_camera, (destruction_trauma, near_miss_trauma)).in_set(FrameSet::Visual),
+            )
+            .add_systems(
+                Update,
+                shake_camera.in_set(FrameSet::Camera).after(follow_camera_target),
+            );
+    }
+}
+
+/// how much each thing shakes the camera, and how a full shake looks. trauma
+/// runs from 0 to 1 and the shake goes with its square, so small knocks stay
+/// small and they still add up
+#[derive(Resource, Reflect, InspectorOptions, Debug, PartialEq, Clone, Copy)]
+#[reflect(Resource, InspectorOptions)]
+pub struct CameraShakeConfig {
+    pub seed:                u32,
+    // across the screen, as a share of the camera's distance from the middle
+    #[inspector(min = 0.0, max = 0.1, display = NumberDisplay::Slider)]
+    pub max_offset:          f32,
+    // degrees
+    #[inspector(min = 0.0, max = 10.0, display = NumberDisplay::Slider)]
+    pub max_angle:           f32,
+    // how fast it wobbles, roughly per second
+    #[inspector(min = 1.0, max = 60.0, display = NumberDisplay::Slider)]
+    pub frequency:           f32,
+    // how quickly trauma drains - e to the minus this, per second
+    #[inspector(min = 0.1, max = 10.0, display = NumberDisplay::Slider)]
+    pub decay:               f32,
+    // a nateroid the size of a default one - bigger ones shake more
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub nateroid_destroyed:  f32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub spaceship_destroyed: f32,
+    // a nateroid wrapping back in within near_miss_distance of the spaceship
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub near_miss:           f32,
+    #[inspector(min = 0.0, max = 100.0, display = NumberDisplay::Slider)]
+    pub near_miss_distance:  f32,
+    #[inspector(min = 0.0, max = 1.0, display = NumberDisplay::Slider)]
+    pub dash:                f32,
+}
+
+impl Default for CameraShakeConfig {
+    fn default() -> Self {
+        Self {
+            seed:                0x5eed,
+            max_offset:          0.02,
+            max_angle:           2.,
+            frequency:           15.,
+            decay:               2.5,
+            nateroid_destroyed:  0.2,
+            spaceship_destroyed: 0.8,
+            near_miss:           0.3,
+            near_miss_distance:  20.,
+            dash:                0.15,
+        }
+    }
+}
+
+// the size of a default nateroid - the same one the explosion sounds use
+const REFERENCE_NATEROID_SIZE: f32 = 10.;
+
+/// how shaken the camera is right now, and what was done to it last frame so
+/// it can be taken off again
+#[derive(Resource, Debug, Default)]
+pub struct CameraShake {
+    trauma:   f32,
+    clock:    f32,
+    offset:   Vec3,
+    rotation: Quat,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) { self.trauma = (self.trauma + amount).clamp(0., 1.); }
+}
+
+fn reset_camera_shake(mut shake: ResMut<CameraShake>) {
+    shake.trauma = 0.;
+    shake.clock = 0.;
+}
+
+fn destruction_trauma(
+    mut destroyed: EventReader<ActorDestroyed>,
+    mut shake: ResMut<CameraShake>,
+    config: Res<CameraShakeConfig>,
+) {
+    for event in destroyed.read() {
+        match event.kind {
+            ActorKind::Nateroid => {
+                shake.add_trauma(config.nateroid_destroyed * event.size / REFERENCE_NATEROID_SIZE);
+            },
+            ActorKind::Spaceship => shake.add_trauma(config.spaceship_destroyed),
+            ActorKind::Missile => (),
+        }
+    }
+}
+
+// a nateroid coming back in right next to the ship is a fright even if it
+// misses
+fn near_miss_trauma(
+    mut teleported: EventReader<EntityTeleported>,
+    mut shake: ResMut<CameraShake>,
+    config: Res<CameraShakeConfig>,
+    q_kinds: Query<&ActorKind>,
+    q_spaceship: Query<&Transform, With<Spaceship>>,
+) {
+    let Ok(spaceship) = q_spaceship.get_single() else {
+        teleported.clear();
+        return;
+    };
+    for event in teleported.read() {
+        if matches!(q_kinds.get(event.entity), Ok(ActorKind::Nateroid))
+            && event.to.distance(spaceship.translation) <= config.near_miss_distance
+        {
+            shake.add_trauma(config.near_miss);
+        }
+    }
+}
+
+// only the game camera - the background camera follows it at its own pace and
+// the ui camera is left alone
+fn game_layers() -> RenderLayers { RenderLayers::from_layers(RenderLayer::Game.layers()) }
+
+fn settle_camera(
+    mut shake: ResMut<CameraShake>,
+    mut q_camera: Query<(&mut Transform, &RenderLayers), With<PrimaryCamera>>,
+) {
+    if shake.offset == Vec3::ZERO && shake.rotation == Quat::IDENTITY {
+        return;
+    }
+    for (mut transform, layers) in q_camera.iter_mut() {
+        if layers.intersects(&game_layers()) {
+            transform.translation -= shake.offset;
+            transform.rotation *= shake.rotation.inverse();
+        }
+    }
+    shake.offset = Vec3::ZERO;
+    shake.rotation = Quat::IDENTITY;
+}
+
+fn shake_camera(
+    mut shake: ResMut<CameraShake>,
+    mut q_camera: Query<(&mut Transform, &RenderLayers), With<PrimaryCamera>>,
+    config: Res<CameraShakeConfig>,
+    time: Res<Time>,
+) {
+    if shake.trauma <= 0. {
+        return;
+    }
+    let delta_seconds = time.delta_secs();
+    shake.clock += delta_seconds;
+    shake.trauma = (shake.trauma * (-config.decay * delta_seconds).exp()).max(0.);
+    if shake.trauma < 0.001 {
+        shake.trauma = 0.;
+        return;
+    }
+
+    let Some((mut transform, _)) = q_camera
+        .iter_mut()
+        .find(|(_, layers)| layers.intersects(&game_layers()))
+    else {
+        return;
+    };
+
+    let amount = shake.trauma * shake.trauma;
+    let at = shake.clock * config.frequency;
+    let wobble = |channel: u32| perlin(config.seed.wrapping_add(channel.wrapping_mul(0x9e37_79b9)), at);
+
+    // across the screen rather than in and out of it
+    let reach = amount * config.max_offset * transform.translation.length();
+    let offset = (transform.right() * wobble(0) + transform.up() * wobble(1)) * reach;
+
+    let angle = amount * config.max_angle.to_radians();
+    let rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        wobble(2) * angle,
+        wobble(3) * angle,
+        wobble(4) * angle,
+    );
+
+    transform.translation += offset;
+    transform.rotation *= rotation;
+    shake.offset = offset;
+    shake.rotation = rotation;
+}
+
+// one dimensional perlin noise - a random slope at every whole number, eased
+// between. roughly -1 to 1, and 0 at every whole number
+fn perlin(seed: u32, x: f32) -> f32 {
+    let cell = x.floor();
+    let fraction = x - cell;
+    let slope = |lattice: i32| {
+        let mut hash = seed ^ (lattice as u32).wrapping_mul(0x27d4_eb2d);
+        hash = (hash ^ (hash >> 15)).wrapping_mul(0x85eb_ca6b);
+        hash = (hash ^ (hash >> 13)).wrapping_mul(0xc2b2_ae35);
+        hash ^= hash >> 16;
+        hash as f32 / u32::MAX as f32 * 2. - 1.
+    };
+
+    let left = slope(cell as i32) * fraction;
+    let right = slope(cell as i32 + 1) * (fraction - 1.);
+    let eased = fraction * fraction * fraction * (fraction * (fraction * 6. - 15.) + 10.);
+    (left + (right - left) * eased) * 2.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn camera_shake_config_round_trips() { assert_settings_round_trip(nudged::<CameraShakeConfig>()); }
+}
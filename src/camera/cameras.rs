@@ -1,15 +1,21 @@
 use crate::{
     camera::{
         camera_control::{
+            follow_camera_target,
             CameraConfig,
             CameraControl,
+            ProjectionMode,
         },
         CameraOrder,
         RenderLayer,
     },
     global_input::GlobalAction,
     orientation::CameraOrientation,
-    playfield::Boundary,
+    playfield::{
+        Boundary,
+        BoundaryResized,
+    },
+    schedule::FrameSet,
 };
 use bevy::{
     core_pipeline::{
@@ -17,7 +23,10 @@ use bevy::{
         tonemapping::Tonemapping,
     },
     prelude::*,
-    render::view::RenderLayers,
+    render::{
+        camera::ScalingMode,
+        view::RenderLayers,
+    },
 };
 use leafwing_input_manager::prelude::*;
 
@@ -25,36 +34,73 @@ pub struct CamerasPlugin;
 
 impl Plugin for CamerasPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_star_camera.before(spawn_primary_camera))
+        app.add_systems(Startup, spawn_background_camera)
             .add_systems(Startup, spawn_primary_camera)
-            .add_systems(Update, update_clear_color)
-            .add_systems(Update, (toggle_stars, update_bloom_settings));
+            .add_systems(Startup, spawn_ui_camera)
+            .add_systems(
+                Update,
+                (update_clear_color, toggle_stars, update_bloom_settings).in_set(FrameSet::Visual),
+            )
+            .add_systems(Update, toggle_projection.in_set(FrameSet::Input))
+            .add_systems(Update, update_projection.in_set(FrameSet::Visual))
+            .add_systems(
+                Update,
+                parallax_background_camera
+                    .in_set(FrameSet::Camera)
+                    .after(follow_camera_target),
+            );
     }
 }
 
 #[derive(Component)]
-pub struct StarsCamera;
+pub struct BackgroundCamera;
 
-// star camera uses bloom so it needs to be in its own layer as we don't
-// want that effect on the colliders
-fn spawn_star_camera(mut commands: Commands, camera_config: Res<CameraConfig>) {
+// the background camera uses bloom so it needs to be in its own layer as we
+// don't want that effect on the colliders. it goes first, so it's the one that
+// clears to the clear color - the game camera draws over the top
+fn spawn_background_camera(mut commands: Commands, camera_config: Res<CameraConfig>) {
     commands
         .spawn(Camera3d::default())
         .insert(Camera {
-            order: CameraOrder::Stars.order(),
+            order: CameraOrder::Background.order(),
             hdr: true, // 1. HDR is required for bloom
             ..default()
         })
         .insert(Tonemapping::BlenderFilmic)
-        .insert(RenderLayers::from_layers(RenderLayer::Stars.layers()))
+        .insert(RenderLayers::from_layers(RenderLayer::Background.layers()))
         .insert(get_bloom_settings(camera_config))
-        .insert(StarsCamera);
+        .insert(BackgroundCamera)
+        .insert(Name::new("BackgroundCamera"));
+}
+
+// the stars are a long way off, so they shouldn't swing round as fast as the
+// playfield does - the background camera turns through only part of however
+// far the game camera has orbited from home, and sits that much of the way
+// out from the middle of the star field
+fn parallax_background_camera(
+    camera_config: Res<CameraConfig>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    q_primary: Query<&Transform, With<PrimaryCamera>>,
+    mut q_background: Query<&mut Transform, (With<BackgroundCamera>, Without<PrimaryCamera>)>,
+) {
+    let (Ok(primary), Ok(mut background)) = (q_primary.get_single(), q_background.get_single_mut())
+    else {
+        return;
+    };
+
+    let home = home_transform(&boundary, &orientation).rotation;
+    let orbited = primary.rotation * home.inverse();
+    let parallax = camera_config.background_parallax;
+
+    background.rotation = Quat::IDENTITY.slerp(orbited, parallax) * home;
+    background.translation = primary.translation * parallax;
 }
 
 // propagate bloom settings back to the camera
 fn update_bloom_settings(
     camera_config: Res<CameraConfig>,
-    mut q_current_settings: Query<&mut Bloom, With<StarsCamera>>,
+    mut q_current_settings: Query<&mut Bloom, With<BackgroundCamera>>,
 ) {
     if camera_config.is_changed() {
         if let Ok(mut old_bloom_settings) = q_current_settings.get_single_mut() {
@@ -76,7 +122,7 @@ fn get_bloom_settings(camera_config: Res<CameraConfig>) -> Bloom {
 // this can probably be removed now that bloom is pretty well working...
 fn toggle_stars(
     mut commands: Commands,
-    mut camera: Query<(Entity, Option<&mut Bloom>), With<StarsCamera>>,
+    mut camera: Query<(Entity, Option<&mut Bloom>), With<BackgroundCamera>>,
     user_input: Res<ActionState<GlobalAction>>,
     camera_config: Res<CameraConfig>,
 ) {
@@ -85,13 +131,13 @@ fn toggle_stars(
     match current_bloom_settings {
         (entity, Some(_)) => {
             if user_input.just_pressed(&GlobalAction::Stars) {
-                println!("stars off");
+                debug!("stars off");
                 commands.entity(entity).remove::<Bloom>();
             }
         },
         (entity, None) => {
             if user_input.just_pressed(&GlobalAction::Stars) {
-                println!("stars on");
+                debug!("stars on");
                 commands.entity(entity).insert(get_bloom_settings(camera_config));
             }
         },
@@ -101,23 +147,19 @@ fn toggle_stars(
 #[derive(Component, Debug)]
 pub struct PrimaryCamera;
 
+// far enough back to see the whole boundary, looking at the middle of it -
+// worked out from the boundary as it is now, so it keeps up with a resize
+pub(super) fn home_transform(boundary: &Boundary, orientation: &CameraOrientation) -> Transform {
+    Transform::from_xyz(0.0, 0.0, boundary.scale().z * 2.)
+        .looking_at(orientation.config.nexus, orientation.config.axis_mundi)
+}
+
 pub fn spawn_primary_camera(
-    camera_config: Res<CameraConfig>,
     config: Res<Boundary>,
     mut commands: Commands,
     mut orientation: ResMut<CameraOrientation>,
-    mut q_stars_camera: Query<Entity, With<StarsCamera>>,
 ) {
-    // we know we have one because we spawn the stars camera prior to this system
-    // we're going to attach it to the primary as a child so it always has the same
-    // view as the primary camera but can show the stars with bloom while the
-    // primary shows everything else
-    let stars_camera_entity = q_stars_camera
-        .get_single_mut()
-        .expect("why in god's name is there no star's camera?");
-
-      let transform = Transform::from_xyz(0.0, 0.0, config.scale().z * 2.)
-        .looking_at(orientation.config.nexus, orientation.config.axis_mundi);
+    let transform = home_transform(&config, &orientation);
 
     orientation.config.locus = transform;
 
@@ -127,18 +169,69 @@ pub fn spawn_primary_camera(
         .insert(Camera {
             hdr: true,
             order: CameraOrder::Game.order(),
-            clear_color: ClearColorConfig::Custom(
-                camera_config.clear_color.darker(camera_config.darkening_factor),
-            ),
+            // the background camera has already cleared - clearing again
+            // would wipe out the stars
+            clear_color: ClearColorConfig::None,
             ..default()
         })
         .insert(Tonemapping::TonyMcMapface)
-        .insert(RenderLayers::from_layers(RenderLayer::Game.layers()))
+        .insert(
+            RenderLayers::from_layers(RenderLayer::Game.layers())
+                .union(&RenderLayers::from_layers(RenderLayer::Debug.layers())),
+        )
         .insert(InputManagerBundle::with_map(CameraControl::camera_input_map()))
-        .add_child(stars_camera_entity)
         .insert(PrimaryCamera);
 }
 
+fn toggle_projection(user_input: Res<ActionState<GlobalAction>>, mut camera_config: ResMut<CameraConfig>) {
+    if user_input.just_pressed(&GlobalAction::ToggleProjection) {
+        camera_config.projection = camera_config.projection.toggled();
+    }
+}
+
+// orthographic is sized so the boundary's width and height just fit, and
+// keeps whatever zoom it had when the boundary is resized - switching back
+// to perspective puts the default one back
+fn update_projection(
+    camera_config: Res<CameraConfig>,
+    boundary: Res<Boundary>,
+    mut resized: EventReader<BoundaryResized>,
+    mut q_camera: Query<&mut Projection, With<PrimaryCamera>>,
+) {
+    let resized = resized.read().count() > 0;
+    if !camera_config.is_changed() && !resized {
+        return;
+    }
+    let Ok(mut projection) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    match (camera_config.projection, projection.as_ref()) {
+        (ProjectionMode::Perspective, Projection::Perspective(_)) => (),
+        (ProjectionMode::Perspective, _) => *projection = Projection::default(),
+        (ProjectionMode::Orthographic, current) => {
+            let scale = match current {
+                Projection::Orthographic(orthographic) => orthographic.scale,
+                _ => 1.,
+            };
+            // the camera is well back from the boundary - deep enough either
+            // way that nothing gets clipped however it's orbited
+            let depth = boundary.longest_diagonal() * 4.;
+            let boundary_scale = boundary.scale();
+            *projection = Projection::Orthographic(OrthographicProjection {
+                scale,
+                near: -depth,
+                far: depth,
+                scaling_mode: ScalingMode::AutoMin {
+                    min_width:  boundary_scale.x,
+                    min_height: boundary_scale.y,
+                },
+                ..OrthographicProjection::default_3d()
+            });
+        },
+    }
+}
+
 // this allows us to use Inspector reflection to manually update ClearColor to
 // different values while the game is running from the ui_for_resources provided
 // by bevy_inspector_egui
@@ -147,3 +240,23 @@ fn update_clear_color(camera_config: Res<CameraConfig>, mut clear_color: ResMut<
         clear_color.0 = camera_config.clear_color.darker(camera_config.darkening_factor);
     }
 }
+
+#[derive(Component, Debug)]
+pub struct UiCamera;
+
+// the hud gets its own camera drawn on top of everything else - it doesn't
+// clear so the game shows through, and nothing but ui lives on its layer so
+// the game camera (or anything else we add later) never picks the hud up
+fn spawn_ui_camera(mut commands: Commands) {
+    commands
+        .spawn(Camera2d)
+        .insert(Camera {
+            hdr: true,
+            order: CameraOrder::Ui.order(),
+            clear_color: ClearColorConfig::None,
+            ..default()
+        })
+        .insert(RenderLayers::from_layers(RenderLayer::Ui.layers()))
+        .insert(IsDefaultUiCamera)
+        .insert(UiCamera);
+}
@@ -1,9 +1,10 @@
 use crate::{
     global_input::{
-        toggle_active,
         GlobalAction,
+        InspectorAppExt,
     },
     orientation::CameraOrientation,
+    schedule::FrameSet,
 };
 use bevy::{
     color::palettes::tailwind,
@@ -12,7 +13,6 @@ use bevy::{
 use bevy_inspector_egui::{
     inspector_options::std_options::NumberDisplay,
     prelude::*,
-    quick::ResourceInspectorPlugin,
 };
 
 pub struct DirectionalLightsPlugin;
@@ -20,13 +20,10 @@ pub struct DirectionalLightsPlugin;
 impl Plugin for DirectionalLightsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<AmbientLight>()
-            .add_plugins(
-                ResourceInspectorPlugin::<LightConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::LightsInspector)),
-            )
+            .add_inspector::<LightConfig>(GlobalAction::LightsInspector)
             .init_resource::<LightConfig>()
             .register_type::<LightConfig>()
-            .add_systems(Update, manage_lighting);
+            .add_systems(Update, manage_lighting.in_set(FrameSet::Visual));
     }
 }
 
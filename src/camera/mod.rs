@@ -1,44 +1,75 @@
+mod attract_camera;
 mod camera_control;
+mod camera_shake;
 mod cameras;
 mod lights;
+mod pip_camera;
 mod star_twinkling;
 mod stars;
+mod zoom_to_fit;
 
 use bevy::{
     prelude::*,
     render::view::Layer,
 };
 
+use attract_camera::AttractCameraPlugin;
+#[cfg(test)]
+pub(crate) use camera_control::follow_camera_target;
 use camera_control::CameraControlPlugin;
+pub use camera_control::{
+    CameraConfig,
+    CameraControl,
+};
+// only picking starts the camera following something
+#[cfg(any(dev_tools, test))]
+pub use camera_control::CameraTarget;
+use camera_shake::CameraShakePlugin;
+pub use camera_shake::{
+    CameraShake,
+    CameraShakeConfig,
+};
 use cameras::CamerasPlugin;
 pub use cameras::PrimaryCamera;
 use lights::DirectionalLightsPlugin;
+use pip_camera::PipCameraPlugin;
 use star_twinkling::StarTwinklingPlugin;
 use stars::StarsPlugin;
+use zoom_to_fit::ZoomToFitPlugin;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(CameraControlPlugin)
+        app.add_plugins(AttractCameraPlugin)
+            .add_plugins(CameraControlPlugin)
+            .add_plugins(CameraShakePlugin)
             .add_plugins(DirectionalLightsPlugin)
             .add_plugins(CamerasPlugin)
+            .add_plugins(PipCameraPlugin)
             .add_plugins(StarsPlugin)
-            .add_plugins(StarTwinklingPlugin);
+            .add_plugins(StarTwinklingPlugin)
+            .add_plugins(ZoomToFitPlugin);
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CameraOrder {
     Game,
-    Stars,
+    // the picture in picture inset goes over the game and under the hud
+    Pip,
+    // the starfield, drawn first so everything else goes over it
+    Background,
+    Ui,
 }
 
 impl CameraOrder {
     pub const fn order(self) -> isize {
         match self {
             CameraOrder::Game => 1,
-            CameraOrder::Stars => 0,
+            CameraOrder::Pip => 2,
+            CameraOrder::Background => 0,
+            CameraOrder::Ui => 3,
         }
     }
 }
@@ -51,9 +82,13 @@ impl CameraOrder {
 // used for both camera order and render layer
 #[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RenderLayer {
-    Both,
     Game,
-    Stars,
+    // the stars - only the background camera sees them, so they can have
+    // bloom and parallax the game doesn't
+    Background,
+    Ui,
+    // physics debug drawing - only the game camera sees it
+    Debug,
 }
 
 // returning the array rather than just one in case we have more complex
@@ -61,9 +96,10 @@ pub enum RenderLayer {
 impl RenderLayer {
     pub const fn layers(self) -> &'static [Layer] {
         match self {
-            RenderLayer::Both => &[0, 1],
             RenderLayer::Game => &[0],
-            RenderLayer::Stars => &[1],
+            RenderLayer::Background => &[1],
+            RenderLayer::Ui => &[2],
+            RenderLayer::Debug => &[3],
         }
     }
 }
@@ -0,0 +1,149 @@
+use crate::{
+    actor::Spaceship,
+    camera::{
+        CameraConfig,
+        CameraOrder,
+        RenderLayer,
+    },
+    global_input::{
+        toggle_active,
+        GlobalAction,
+    },
+    orientation::CameraOrientation,
+    playfield::{
+        Boundary,
+        BoundaryFace,
+    },
+    schedule::FrameSet,
+};
+use bevy::{
+    core_pipeline::tonemapping::Tonemapping,
+    prelude::*,
+    render::{
+        camera::Viewport,
+        view::RenderLayers,
+    },
+    window::{
+        PrimaryWindow,
+        WindowResized,
+    },
+};
+
+// how much of the window's width the inset takes, and how far it sits in
+// from the corner in logical pixels
+const PIP_SHARE: f32 = 0.28;
+const PIP_MARGIN: f32 = 16.;
+// how far back from the face it looks from, as a multiple of the face's size
+const PIP_DISTANCE: f32 = 1.5;
+
+/// a little window in the corner onto the far side of the boundary - the face
+/// opposite the one the spaceship is nearest, where whatever it's chasing
+/// comes back in. it only sees the game's own layer, so no debug lines and no
+/// hud in it
+pub struct PipCameraPlugin;
+
+impl Plugin for PipCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_pip_camera).add_systems(
+            Update,
+            toggle_active(false, GlobalAction::TogglePip)
+                .pipe(update_pip_camera)
+                .in_set(FrameSet::Camera),
+        );
+    }
+}
+
+// the face it's looking at now, so it only moves when that changes
+#[derive(Component, Debug, Default)]
+struct PipCamera {
+    face: Option<BoundaryFace>,
+}
+
+fn spawn_pip_camera(mut commands: Commands, camera_config: Res<CameraConfig>) {
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            hdr: true,
+            order: CameraOrder::Pip.order(),
+            is_active: false,
+            clear_color: ClearColorConfig::Custom(
+                camera_config.clear_color.darker(camera_config.darkening_factor),
+            ),
+            ..default()
+        },
+        Tonemapping::TonyMcMapface,
+        RenderLayers::from_layers(RenderLayer::Game.layers()),
+        PipCamera::default(),
+        Name::new("PipCamera"),
+    ));
+}
+
+// the top right corner, in the window's physical pixels
+fn pip_viewport(window: &Window) -> Viewport {
+    let window_size = window.physical_size();
+    let width = (window_size.x as f32 * PIP_SHARE) as u32;
+    let height = (width as f32 * window_size.y as f32 / window_size.x.max(1) as f32) as u32;
+    let margin = (PIP_MARGIN * window.scale_factor()) as u32;
+
+    Viewport {
+        physical_position: UVec2::new(window_size.x.saturating_sub(width + margin), margin),
+        physical_size: UVec2::new(width.max(1), height.max(1)),
+        ..default()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_pip_camera(
+    In(enabled): In<bool>,
+    mut resized: EventReader<WindowResized>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_spaceship: Query<&Transform, With<Spaceship>>,
+    mut q_pip: Query<(&mut Camera, &mut Transform, &mut PipCamera), Without<Spaceship>>,
+) {
+    let Ok((mut camera, mut transform, mut pip)) = q_pip.get_single_mut() else {
+        return;
+    };
+    let spaceship = q_spaceship.get_single().ok();
+
+    // nothing to look past without a spaceship
+    let active = enabled && spaceship.is_some();
+    if camera.is_active != active {
+        camera.is_active = active;
+    }
+    let Some(spaceship) = spaceship.filter(|_| active) else {
+        // worked out again when it's back, in case the window changed
+        pip.face = None;
+        if camera.viewport.is_some() {
+            camera.viewport = None;
+        }
+        resized.clear();
+        return;
+    };
+
+    if resized.read().count() > 0 || camera.viewport.is_none() {
+        if let Ok(window) = q_window.get_single() {
+            camera.viewport = Some(pip_viewport(window));
+        }
+    }
+
+    let (nearest, _) = boundary.nearest_face(spaceship.translation);
+    let (axis, positive) = nearest.axis();
+    let opposite = BoundaryFace::from_axis(axis, !positive);
+    if pip.face == Some(opposite) && !boundary.is_changed() {
+        return;
+    }
+    pip.face = Some(opposite);
+
+    // from outside the face, looking back in through it
+    let center = boundary.face_center(opposite);
+    let normal = boundary.get_normal_for_position(center);
+    let distance = boundary.face_half_extents(opposite).max_element() * 2. * PIP_DISTANCE;
+    let up = if normal.dot(orientation.config.axis_mundi).abs() > 0.99 {
+        orientation.config.axis_profundus
+    } else {
+        orientation.config.axis_mundi
+    };
+    *transform = Transform::from_translation(center + *normal * distance).looking_at(center, up);
+}
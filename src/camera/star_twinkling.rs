@@ -1,6 +1,7 @@
 use crate::{
     camera::stars::{Star, StarConfig},
-    schedule::InGameSet,
+    schedule::FrameSet,
+    state::IsPaused,
 };
 use bevy::prelude::*;
 use rand::Rng;
@@ -18,7 +19,9 @@ impl Plugin for StarTwinklingPlugin {
             })
             .add_systems(
                 Update,
-                ((start_twinkling, update_twinkling),).in_set(InGameSet::EntityUpdates),
+                (start_twinkling, update_twinkling)
+                    .in_set(FrameSet::Visual)
+                    .run_if(in_state(IsPaused::NotPaused)),
             );
     }
 }
@@ -80,7 +80,7 @@ fn spawn_stars(mut commands: Commands, config: Res<StarConfig>, boundary_config:
                 radius,
                 emissive,
             },
-            RenderLayers::from_layers(RenderLayer::Stars.layers()),
+            RenderLayers::from_layers(RenderLayer::Background.layers()),
         ));
     }
 }
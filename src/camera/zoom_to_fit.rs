@@ -0,0 +1,142 @@
+use crate::{
+    actor::Teleporter,
+    camera::{
+        camera_control::{
+            follow_camera_target,
+            pan_camera,
+            CameraHomeAnimation,
+            CameraOrbitConfig,
+        },
+        CameraConfig,
+        PrimaryCamera,
+    },
+    orientation::CameraOrientation,
+    playfield::Boundary,
+    schedule::FrameSet,
+    state::IsInspecting,
+};
+use bevy::prelude::*;
+
+/// pulls the camera in or out so that everything that wraps - the ship, the
+/// nateroids, the missiles - stays in the picture. it only goes along the way
+/// the camera's looking, so orbiting and panning still belong to the player,
+/// and zooming by hand while it's on moves it in or out from wherever the fit
+/// would put it
+pub struct ZoomToFitPlugin;
+
+impl Plugin for ZoomToFitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ZoomToFit>().add_systems(
+            Update,
+            (
+                zoom_to_fit
+                    .after(pan_camera)
+                    .before(follow_camera_target)
+                    .run_if(zoom_to_fit_enabled)
+                    .run_if(not(in_state(IsInspecting::Inspecting))),
+                forget_zoom_offset.run_if(not(zoom_to_fit_enabled)),
+            )
+                .in_set(FrameSet::Camera),
+        );
+    }
+}
+
+/// how far the player has zoomed in (negative) or out from the fit, in the
+/// same units as a dolly - the zoom systems add to it instead of moving the
+/// camera while zoom to fit is on
+#[derive(Resource, Debug, Default)]
+pub struct ZoomToFit {
+    pub offset: f32,
+}
+
+fn zoom_to_fit_enabled(config: Res<CameraConfig>) -> bool { config.zoom_to_fit }
+
+// turned off, the offset goes - it's from a fit that's no longer there
+fn forget_zoom_offset(mut fit: ResMut<ZoomToFit>) {
+    if fit.offset != 0. {
+        fit.offset = 0.;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn zoom_to_fit(
+    fit: Res<ZoomToFit>,
+    config: Res<CameraConfig>,
+    orbit_config: Res<CameraOrbitConfig>,
+    boundary: Res<Boundary>,
+    orientation: Res<CameraOrientation>,
+    q_teleporters: Query<&GlobalTransform, With<Teleporter>>,
+    mut q_camera: Query<
+        (&mut Transform, &mut Projection),
+        (With<PrimaryCamera>, Without<CameraHomeAnimation>),
+    >,
+    time: Res<Time>,
+) {
+    let Ok((mut transform, mut projection)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let mut positions = q_teleporters.iter().map(GlobalTransform::translation);
+    let Some(first) = positions.next() else {
+        return;
+    };
+    let (min, max) = positions.fold((first, first), |(min, max), position| {
+        (min.min(position), max.max(position))
+    });
+    let min = min - Vec3::splat(config.zoom_to_fit_margin);
+    let max = max + Vec3::splat(config.zoom_to_fit_margin);
+
+    // the box's corners as the camera sees them, measured from the camera's
+    // line of sight
+    let center = (min + max) / 2.;
+    let forward = transform.forward();
+    let depth = (center - transform.translation).dot(*forward);
+    let axis_point = transform.translation + *forward * depth;
+    let to_view = transform.rotation.inverse();
+    let half_extents = [
+        Vec3::new(min.x, min.y, min.z),
+        Vec3::new(max.x, min.y, min.z),
+        Vec3::new(min.x, max.y, min.z),
+        Vec3::new(max.x, max.y, min.z),
+        Vec3::new(min.x, min.y, max.z),
+        Vec3::new(max.x, min.y, max.z),
+        Vec3::new(min.x, max.y, max.z),
+        Vec3::new(max.x, max.y, max.z),
+    ]
+    .into_iter()
+    .fold(Vec3::ZERO, |extents, corner| {
+        extents.max((to_view * (corner - axis_point)).abs())
+    });
+
+    let smoothing = 1. - (-config.zoom_to_fit_smoothing * time.delta_secs()).exp();
+    let (min_distance, max_distance) = orbit_config.distance_range(&boundary);
+
+    match projection.as_mut() {
+        Projection::Perspective(perspective) => {
+            // far enough back that the sides fit, and the near side of the box
+            // is still in front of the camera
+            let tan_y = (perspective.fov / 2.).tan();
+            let tan_x = tan_y * perspective.aspect_ratio;
+            let fit_distance = (half_extents.x / tan_x).max(half_extents.y / tan_y) + half_extents.z;
+
+            let wanted = (fit_distance + fit.offset).clamp(min_distance, max_distance);
+            let distance = depth + (wanted - depth) * smoothing;
+            transform.translation = axis_point - *forward * distance;
+        },
+        // zooming doesn't move an orthographic camera, so it's the scale -
+        // worked out from the same distance impl_zoom uses
+        Projection::Orthographic(orthographic) => {
+            let distance = (orientation.config.nexus - transform.translation).dot(*forward);
+            if distance <= 0. || orthographic.scale <= 0. {
+                return;
+            }
+            let unit_half_size = orthographic.area.half_size() / orthographic.scale;
+            let fit_scale = (half_extents.x / unit_half_size.x).max(half_extents.y / unit_half_size.y);
+
+            let apparent = distance * orthographic.scale;
+            let wanted = (fit_scale * distance + fit.offset).clamp(min_distance, max_distance);
+            orthographic.scale = (apparent + (wanted - apparent) * smoothing) / distance;
+        },
+    }
+}
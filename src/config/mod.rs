@@ -0,0 +1,305 @@
+mod overrides;
+#[cfg(test)]
+mod round_trip;
+mod settings_file;
+
+pub use crate::config::overrides::set_reflect_path;
+#[cfg(test)]
+pub use crate::config::round_trip::{
+    assert_settings_round_trip,
+    nudged,
+};
+
+use crate::{
+    config::{
+        overrides::{
+            apply_overrides,
+            collect_overrides,
+            SettingOverride,
+        },
+        settings_file::{
+            apply_section,
+            read_section,
+            read_section_names,
+            write_file,
+            write_section,
+        },
+    },
+    storage::{
+        platform_storage,
+        Storage,
+    },
+};
+use bevy::{
+    prelude::*,
+    reflect::{
+        GetTypeRegistration,
+        Struct,
+        TypeRegistry,
+        Typed,
+    },
+};
+use std::{
+    any::TypeId,
+    collections::{
+        BTreeMap,
+        HashSet,
+    },
+};
+
+// sliders in the inspector change the resource every frame while you're
+// dragging - wait for things to settle down before writing to disk
+const SAVE_DEBOUNCE_SECONDS: f32 = 0.5;
+const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+/// every settings resource lives in one settings.ron (in the user's config
+/// directory, or localStorage on the web), a section each. a setting comes from
+/// (last one wins):
+///
+/// - the resource's Default
+/// - the settings file
+/// - `NATEROIDS_SET="section.field=value;..."` in the environment
+/// - `--set section.field=value` on the command line
+///
+/// only what differs from the defaults is written back, and overrides only
+/// last for the run they were given to. unknown sections and fields are
+/// reported rather than silently dropped
+///
+/// plugins don't add this themselves - `register_settings` does it the first
+/// time it's called
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SettingsFile::load())
+            .add_systems(Startup, report_unknown_settings)
+            .add_systems(Last, save_settings_file.after(CollectSections));
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct CollectSections;
+
+/// what a resource needs for `register_settings` - a plain `#[derive(Resource,
+/// Reflect, Default, Clone)]` struct has all of it
+pub trait Settings: Resource + Reflect + Struct + Typed + GetTypeRegistration + Default + Clone {}
+
+impl<T: Resource + Reflect + Struct + Typed + GetTypeRegistration + Default + Clone> Settings for T {}
+
+pub trait SettingsAppExt {
+    /// loads `T` from its section while the app is being built - so it's there
+    /// before any system gets a chance to read it - and saves it back out
+    /// whenever it changes
+    fn register_settings<T: Settings>(&mut self, section: &'static str) -> &mut Self;
+}
+
+impl SettingsAppExt for App {
+    fn register_settings<T: Settings>(&mut self, section: &'static str) -> &mut Self {
+        if !self.is_plugin_added::<SettingsPlugin>() {
+            self.add_plugins(SettingsPlugin);
+        }
+        self.register_type::<T>();
+
+        let registry = self.world().resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let mut file = self.world_mut().resource_mut::<SettingsFile>();
+
+        let from_file = file.read::<T>(section, &registry);
+        let mut settings = from_file.clone();
+        let overridden = apply_overrides(section, &mut settings, &file.overrides, &registry);
+
+        file.registered.insert(section, TypeId::of::<T>());
+        file.sections.insert(
+            section,
+            write_section(&settings, &from_file, &overridden, &registry),
+        );
+
+        self.insert_resource(settings)
+            .insert_resource(SettingsSection::<T> {
+                name: section,
+                from_file,
+                overridden,
+            })
+            .add_systems(Last, collect_section::<T>.in_set(CollectSections))
+    }
+}
+
+/// the settings file as it's going to be written
+#[derive(Resource, Debug)]
+pub struct SettingsFile {
+    storage:       Box<dyn Storage>,
+    contents:      String,
+    file_sections: Vec<String>,
+    overrides:     Vec<SettingOverride>,
+    registered:    BTreeMap<&'static str, TypeId>,
+    // the body of each section as it will be written
+    sections:      BTreeMap<&'static str, String>,
+    pending:       Option<f32>,
+}
+
+impl SettingsFile {
+    fn load() -> Self {
+        let storage = platform_storage();
+        let contents = storage.load(SETTINGS_FILE_NAME).unwrap_or_default();
+
+        let file_sections = if contents.trim().is_empty() {
+            Vec::new()
+        } else {
+            read_section_names(&contents).unwrap_or_else(|e| {
+                warn!("ignoring unreadable settings: {e}");
+                Vec::new()
+            })
+        };
+
+        Self {
+            storage,
+            // a file we couldn't make sense of is as good as no file
+            contents: if file_sections.is_empty() {
+                String::new()
+            } else {
+                contents
+            },
+            file_sections,
+            overrides: collect_overrides(),
+            registered: BTreeMap::new(),
+            sections: BTreeMap::new(),
+            pending: None,
+        }
+    }
+
+    // the defaults with whatever the file has for this section on top
+    fn read<T: Settings>(&self, section: &str, registry: &TypeRegistry) -> T {
+        let mut settings = T::default();
+        if !self.file_sections.iter().any(|name| name == section) {
+            return settings;
+        }
+
+        match read_section::<T>(&self.contents, section, registry) {
+            Ok((fields, unknown)) => {
+                for field in unknown {
+                    warn!("unknown setting {section}.{field} in the settings file - it won't be saved again");
+                }
+                if let Some(Err(e)) = fields.map(|fields| apply_section(&mut settings, &fields)) {
+                    warn!("ignoring unreadable settings section {section}: {e}");
+                    settings = T::default();
+                }
+            },
+            Err(e) => warn!("ignoring unreadable settings section {section}: {e}"),
+        }
+
+        settings
+    }
+
+    /// every section as it stands - what a replay keeps to compare against
+    pub fn snapshot(&self) -> String { write_file(&self.sections) }
+
+    fn save(&self) {
+        if let Err(e) = self.storage.save(SETTINGS_FILE_NAME, &write_file(&self.sections)) {
+            warn!("unable to save settings: {e}");
+        }
+    }
+}
+
+/// every settings section and the resource behind it - so anything that
+/// wants to name a setting (the console's `set`) uses the settings file's names
+pub fn settings_sections(world: &World) -> Vec<(&'static str, TypeId)> {
+    world
+        .get_resource::<SettingsFile>()
+        .map(|file| {
+            file.registered
+                .iter()
+                .map(|(name, type_id)| (*name, *type_id))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// what a section was before the overrides, so the overrides stay out of the
+// file
+#[derive(Resource)]
+struct SettingsSection<T: Settings> {
+    name:       &'static str,
+    from_file:  T,
+    overridden: HashSet<String>,
+}
+
+// everything is registered by the time Startup runs
+fn report_unknown_settings(file: Res<SettingsFile>) {
+    for section in file.file_sections.iter() {
+        if !file.registered.contains_key(section.as_str()) {
+            warn!("unknown settings section {section} in the settings file - it won't be saved again");
+        }
+    }
+
+    for setting_override in file.overrides.iter() {
+        if !file.registered.contains_key(setting_override.section.as_str()) {
+            warn!(
+                "ignoring setting override {}.{}: unknown section",
+                setting_override.section, setting_override.path
+            );
+        }
+    }
+}
+
+fn collect_section<T: Settings>(
+    settings: Res<T>,
+    section: Res<SettingsSection<T>>,
+    registry: Res<AppTypeRegistry>,
+    mut file: ResMut<SettingsFile>,
+) {
+    // the insert in build counts as a change - no need to write back what we
+    // just read
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+
+    // plenty of resources are touched every frame without anything that's
+    // saved actually changing
+    let body = write_section(
+        settings.as_ref(),
+        &section.from_file,
+        &section.overridden,
+        &registry.read(),
+    );
+    if file.sections.get(section.name) != Some(&body) {
+        file.sections.insert(section.name, body);
+        file.pending = Some(SAVE_DEBOUNCE_SECONDS);
+    }
+}
+
+/// `T`'s section written and the settings file saved straight away, rather
+/// than waiting for the change to settle - for something the player has
+/// just asked to be kept. it's a command, so it's run with `commands.queue`
+pub fn save_settings<T: Settings>(world: &mut World) {
+    let (Some(settings), Some(section)) = (world.get_resource::<T>(), world.get_resource::<SettingsSection<T>>())
+    else {
+        return;
+    };
+
+    let body = write_section(
+        settings,
+        &section.from_file,
+        &section.overridden,
+        &world.resource::<AppTypeRegistry>().read(),
+    );
+    let name = section.name;
+    let Some(mut file) = world.get_resource_mut::<SettingsFile>() else {
+        return;
+    };
+    file.sections.insert(name, body);
+    // this writes every section, so anything waiting goes with it
+    file.save();
+    file.pending = None;
+}
+
+fn save_settings_file(mut file: ResMut<SettingsFile>, time: Res<Time<Real>>) {
+    let Some(remaining) = file.pending.as_mut() else {
+        return;
+    };
+
+    *remaining -= time.delta_secs();
+    if *remaining <= 0.0 {
+        file.save();
+        file.pending = None;
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::TypedReflectDeserializer,
+        GetPath,
+        TypeRegistry,
+    },
+};
+use serde::de::DeserializeSeed;
+use std::collections::HashSet;
+
+// NATEROIDS_SET="boundary.scalar=200;audio.master_volume=50" does the same as
+// passing each of them with --set
+const OVERRIDES_VAR: &str = "NATEROIDS_SET";
+const OVERRIDE_ARG: &str = "--set";
+
+/// a `section.path=value` from the command line or the environment. the path
+/// can reach into nested fields (`boundary.transform.translation.x=10`) and the
+/// value is written the same way it would be in the settings file
+#[derive(Debug, Clone)]
+pub struct SettingOverride {
+    pub section: String,
+    pub path:    String,
+    pub value:   String,
+}
+
+impl SettingOverride {
+    fn parse(text: &str) -> Option<Self> {
+        let (key, value) = text.split_once('=')?;
+        let (section, path) = key.trim().split_once('.')?;
+
+        Some(Self {
+            section: section.to_string(),
+            path:    path.to_string(),
+            value:   value.trim().to_string(),
+        })
+    }
+
+    /// the field directly on the settings resource that this changes
+    pub fn field(&self) -> &str { self.path.split('.').next().unwrap_or_default() }
+}
+
+/// environment first so that the command line wins when both set something
+pub fn collect_overrides() -> Vec<SettingOverride> {
+    let from_env = std::env::var(OVERRIDES_VAR).unwrap_or_default();
+    let from_env = from_env
+        .split(';')
+        .filter(|text| !text.trim().is_empty())
+        .map(String::from);
+
+    let mut from_args = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == OVERRIDE_ARG {
+            match args.next() {
+                Some(text) => from_args.push(text),
+                None => warn!("{OVERRIDE_ARG} needs a section.field=value"),
+            }
+        }
+    }
+
+    from_env
+        .chain(from_args)
+        .filter_map(|text| {
+            let parsed = SettingOverride::parse(&text);
+            if parsed.is_none() {
+                warn!("ignoring setting override {text:?} - expected section.field=value");
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// applies whichever overrides belong to `section` and returns the fields they
+/// changed - those are left alone when the section is saved
+pub fn apply_overrides<T: Reflect>(
+    section: &str,
+    settings: &mut T,
+    overrides: &[SettingOverride],
+    registry: &TypeRegistry,
+) -> HashSet<String> {
+    let mut overridden = HashSet::new();
+
+    for setting_override in overrides.iter().filter(|o| o.section == section) {
+        match set_reflect_path(
+            settings,
+            &setting_override.path,
+            &setting_override.value,
+            registry,
+        ) {
+            Ok(()) => {
+                overridden.insert(setting_override.field().to_string());
+            },
+            Err(e) => warn!(
+                "ignoring setting override {}.{}: {}",
+                setting_override.section, setting_override.path, e
+            ),
+        }
+    }
+
+    overridden
+}
+
+/// sets whatever `path` reaches inside `target` to `value`, written the same
+/// way it would be in the settings file
+pub fn set_reflect_path(
+    target: &mut dyn Reflect,
+    path: &str,
+    value: &str,
+    registry: &TypeRegistry,
+) -> Result<(), String> {
+    let field = target
+        .reflect_path_mut(path)
+        .map_err(|_| "unknown setting".to_string())?;
+
+    let registration = field
+        .get_represented_type_info()
+        .and_then(|info| registry.get(info.type_id()))
+        .ok_or_else(|| "this setting can't be set from outside".to_string())?;
+
+    let mut deserializer = ron::de::Deserializer::from_str(value).map_err(|e| e.to_string())?;
+    let value = TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())?;
+
+    field.try_apply(value.as_ref()).map_err(|e| e.to_string())
+}
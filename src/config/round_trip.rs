@@ -0,0 +1,157 @@
+use crate::config::{
+    settings_file::{
+        apply_section,
+        read_section,
+        write_file,
+        write_section,
+    },
+    Settings,
+};
+use bevy::reflect::{
+    serde::{
+        SerializationData,
+        TypedReflectSerializer,
+    },
+    PartialReflect,
+    ReflectMut,
+    TypeRegistry,
+};
+use std::{
+    any::TypeId,
+    collections::{
+        BTreeMap,
+        HashSet,
+    },
+};
+
+const SECTION: &str = "round_trip";
+
+// every settings type's tests use these - a file written from `settings` has
+// to read back as `settings`, field for field
+
+/// T's default with every number and flag in it changed, so every one of them
+/// has to be written and read back
+pub fn nudged<T: Settings>() -> T {
+    let mut settings = T::default();
+    assert!(
+        nudge(settings.as_partial_reflect_mut()),
+        "nothing in {} could be changed",
+        std::any::type_name::<T>()
+    );
+    settings
+}
+
+pub fn assert_settings_round_trip<T: Settings>(settings: T) {
+    let mut registry = TypeRegistry::default();
+    registry.register::<T>();
+
+    let body = write_section(&settings, &settings, &HashSet::new(), &registry);
+    assert!(
+        !body.is_empty(),
+        "nothing was written - it's all still the default"
+    );
+    let contents = write_file(&BTreeMap::from([(SECTION, body)]));
+
+    let (fields, unknown) = read_section::<T>(&contents, SECTION, &registry)
+        .unwrap_or_else(|e| panic!("{e} reading\n{contents}"));
+    assert!(
+        unknown.is_empty(),
+        "unknown fields {unknown:?} reading\n{contents}"
+    );
+
+    let mut read = T::default();
+    if let Some(fields) = fields {
+        apply_section(&mut read, &fields).unwrap_or_else(|e| panic!("{e} applying\n{contents}"));
+    }
+
+    // skipped fields don't go in the file at all. ron's the comparison
+    // because not everything in a settings type has a reflected PartialEq
+    let skipped = registry
+        .get(TypeId::of::<T>())
+        .and_then(|registration| registration.data::<SerializationData>());
+    for index in 0..settings.field_len() {
+        if skipped.is_some_and(|skipped| skipped.is_field_skipped(index)) {
+            continue;
+        }
+        let name = settings.name_at(index).unwrap();
+        let text = |settings: &T| {
+            ron::ser::to_string(&TypedReflectSerializer::new(
+                settings.field_at(index).unwrap(),
+                &registry,
+            ))
+            .unwrap()
+        };
+        assert_eq!(
+            text(&read),
+            text(&settings),
+            "{name} changed on the way through\n{contents}"
+        );
+    }
+}
+
+// numbers go up and flags flip, anywhere they turn up. whether anything
+// changed comes back so a type with nothing to change is noticed - counted
+// rather than any() so that every field gets nudged, not just the first
+fn nudge(value: &mut dyn PartialReflect) -> bool {
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            (0..value.field_len())
+                .filter(|&index| value.field_at_mut(index).is_some_and(nudge))
+                .count()
+                > 0
+        },
+        ReflectMut::TupleStruct(value) => {
+            (0..value.field_len())
+                .filter(|&index| value.field_mut(index).is_some_and(nudge))
+                .count()
+                > 0
+        },
+        ReflectMut::Tuple(value) => {
+            (0..value.field_len())
+                .filter(|&index| value.field_mut(index).is_some_and(nudge))
+                .count()
+                > 0
+        },
+        ReflectMut::List(value) => {
+            (0..value.len())
+                .filter(|&index| value.get_mut(index).is_some_and(nudge))
+                .count()
+                > 0
+        },
+        ReflectMut::Array(value) => {
+            (0..value.len())
+                .filter(|&index| value.get_mut(index).is_some_and(nudge))
+                .count()
+                > 0
+        },
+        ReflectMut::Opaque(value) => nudge_number(value),
+        // a map's keys can't change in place and its values are usually
+        // keyed on something that matters - leave them be, along with enums
+        _ => false,
+    }
+}
+
+fn nudge_number(value: &mut dyn PartialReflect) -> bool {
+    if let Some(value) = value.try_downcast_mut::<f32>() {
+        *value += 1.5;
+    } else if let Some(value) = value.try_downcast_mut::<f64>() {
+        *value += 1.5;
+    } else if let Some(value) = value.try_downcast_mut::<bool>() {
+        *value = !*value;
+    } else if let Some(value) = value.try_downcast_mut::<u8>() {
+        *value = value.wrapping_add(1);
+    } else if let Some(value) = value.try_downcast_mut::<u32>() {
+        *value += 1;
+    } else if let Some(value) = value.try_downcast_mut::<u64>() {
+        *value += 1;
+    } else if let Some(value) = value.try_downcast_mut::<usize>() {
+        *value += 1;
+    } else if let Some(value) = value.try_downcast_mut::<i32>() {
+        *value += 1;
+    } else if let Some(value) = value.try_downcast_mut::<String>() {
+        value.push('!');
+    } else {
+        return false;
+    }
+    true
+}
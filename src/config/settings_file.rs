@@ -0,0 +1,315 @@
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{
+            SerializationData,
+            TypedReflectDeserializer,
+            TypedReflectSerializer,
+        },
+        ApplyError,
+        DynamicStruct,
+        PartialReflect,
+        ReflectMut,
+        ReflectRef,
+        Struct,
+        StructInfo,
+        TypeInfo,
+        TypeRegistry,
+        Typed,
+    },
+};
+use serde::de::{
+    DeserializeSeed,
+    Deserializer,
+    IgnoredAny,
+    MapAccess,
+    Visitor,
+};
+use std::{
+    any::TypeId,
+    collections::{
+        BTreeMap,
+        HashSet,
+    },
+    fmt::{
+        self,
+        Write,
+    },
+};
+
+// the file is one ron struct with a struct per section, and only the fields
+// that aren't at their default are in it:
+//
+// (
+//     audio: (
+//         master_volume: 80.0,
+//     ),
+//     boundary: (
+//         scalar: 200.0,
+//     ),
+// )
+//
+// everything goes through reflection rather than serde so that a single field
+// can be read, compared with its default, or overridden on its own
+
+/// the names of the sections in the file, in the order they appear
+pub fn read_section_names(contents: &str) -> Result<Vec<String>, String> {
+    let mut deserializer = ron::de::Deserializer::from_str(contents).map_err(|e| e.to_string())?;
+    let names = SectionNames
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())?;
+    deserializer.end().map_err(|e| e.to_string())?;
+    Ok(names)
+}
+
+/// the fields set in `section`, along with any keys in it that `T` doesn't have
+pub fn read_section<T: Struct + Typed>(
+    contents: &str,
+    section: &str,
+    registry: &TypeRegistry,
+) -> Result<(Option<DynamicStruct>, Vec<String>), String> {
+    let TypeInfo::Struct(info) = T::type_info() else {
+        return Err(format!("{} isn't a struct", T::type_info().type_path()));
+    };
+
+    let mut unknown = Vec::new();
+    let mut deserializer = ron::de::Deserializer::from_str(contents).map_err(|e| e.to_string())?;
+    let fields = FindSection {
+        section,
+        fields: SectionFields {
+            info,
+            registry,
+            unknown: &mut unknown,
+        },
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|e| e.to_string())?;
+
+    Ok((fields, unknown))
+}
+
+/// puts what `read_section` found on top of `settings`. try_apply on its own
+/// only ever grows a list - one that's shorter in the file than the default
+/// keeps the default's extra items, so a binding taken off an action would
+/// be back after every restart. lists come out as long as they were written
+pub fn apply_section(settings: &mut dyn PartialReflect, fields: &DynamicStruct) -> Result<(), ApplyError> {
+    settings.try_apply(fields)?;
+    trim_lists(settings, fields);
+    Ok(())
+}
+
+fn trim_lists(target: &mut dyn PartialReflect, value: &dyn PartialReflect) {
+    match (target.reflect_mut(), value.reflect_ref()) {
+        (ReflectMut::Struct(target), ReflectRef::Struct(value)) => {
+            for index in 0..value.field_len() {
+                if let (Some(name), Some(value)) = (value.name_at(index), value.field_at(index)) {
+                    if let Some(target) = target.field_mut(name) {
+                        trim_lists(target, value);
+                    }
+                }
+            }
+        },
+        (ReflectMut::TupleStruct(target), ReflectRef::TupleStruct(value)) => {
+            for index in 0..value.field_len() {
+                if let (Some(target), Some(value)) = (target.field_mut(index), value.field(index)) {
+                    trim_lists(target, value);
+                }
+            }
+        },
+        (ReflectMut::Tuple(target), ReflectRef::Tuple(value)) => {
+            for index in 0..value.field_len() {
+                if let (Some(target), Some(value)) = (target.field_mut(index), value.field(index)) {
+                    trim_lists(target, value);
+                }
+            }
+        },
+        (ReflectMut::List(target), ReflectRef::List(value)) => {
+            while target.len() > value.len() {
+                target.pop();
+            }
+            for index in 0..value.len() {
+                if let (Some(target), Some(value)) = (target.get_mut(index), value.get(index)) {
+                    trim_lists(target, value);
+                }
+            }
+        },
+        _ => (),
+    }
+}
+
+/// the body of a section - one line per field that isn't at its default. a
+/// field that's overridden for this run is written as it was in the file
+pub fn write_section<T: Struct + Default>(
+    value: &T,
+    from_file: &T,
+    overridden: &HashSet<String>,
+    registry: &TypeRegistry,
+) -> String {
+    let default = T::default();
+    let skipped = registry
+        .get(TypeId::of::<T>())
+        .and_then(|registration| registration.data::<SerializationData>());
+    let mut text = String::new();
+
+    for index in 0..value.field_len() {
+        let Some(name) = value.name_at(index) else {
+            continue;
+        };
+        if skipped.is_some_and(|skipped| skipped.is_field_skipped(index)) {
+            continue;
+        }
+
+        let source = if overridden.contains(name) {
+            from_file
+        } else {
+            value
+        };
+        let (Some(field), Some(default_field)) = (source.field_at(index), default.field_at(index)) else {
+            continue;
+        };
+
+        // anything that can't be compared is written out to be safe
+        if field.reflect_partial_eq(default_field) == Some(true) {
+            continue;
+        }
+
+        match ron::ser::to_string(&TypedReflectSerializer::new(field, registry)) {
+            Ok(field_text) => {
+                let _ = writeln!(text, "        {name}: {field_text},");
+            },
+            Err(e) => warn!("unable to save setting {name}: {e}"),
+        }
+    }
+
+    text
+}
+
+/// the whole file from each section's body - empty sections are left out
+pub fn write_file(sections: &BTreeMap<&'static str, String>) -> String {
+    let mut contents = String::from("(\n");
+    for (section, body) in sections.iter().filter(|(_, body)| !body.is_empty()) {
+        let _ = write!(contents, "    {section}: (\n{body}    ),\n");
+    }
+    contents.push_str(")\n");
+    contents
+}
+
+struct SectionNames;
+
+impl<'de> DeserializeSeed<'de> for SectionNames {
+    type Value = Vec<String>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SectionNames {
+    type Value = Vec<String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("settings sections")
+    }
+
+    // a file with nothing in it is just ()
+    fn visit_unit<E>(self) -> Result<Self::Value, E> { Ok(Vec::new()) }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut names = Vec::new();
+        // ron only hands out a struct's field names as identifiers, which a
+        // String won't deserialize from - a &str will
+        while let Some(name) = map.next_key::<&str>()? {
+            map.next_value::<IgnoredAny>()?;
+            names.push(name.to_string());
+        }
+        Ok(names)
+    }
+}
+
+// walks the top level of the file looking for one section and skips the rest
+struct FindSection<'a> {
+    section: &'a str,
+    fields:  SectionFields<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for FindSection<'_> {
+    type Value = Option<DynamicStruct>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FindSection<'_> {
+    type Value = Option<DynamicStruct>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("settings sections")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> { Ok(None) }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = Some(self.fields);
+        let mut found = None;
+
+        while let Some(name) = map.next_key::<&str>()? {
+            match (name == self.section).then(|| fields.take()).flatten() {
+                Some(fields) => found = Some(map.next_value_seed(fields)?),
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                },
+            }
+        }
+        Ok(found)
+    }
+}
+
+// the fields of one section. keys the settings type doesn't have are skipped
+// and handed back so they can be reported
+struct SectionFields<'a> {
+    info:     &'static StructInfo,
+    registry: &'a TypeRegistry,
+    unknown:  &'a mut Vec<String>,
+}
+
+impl<'de> DeserializeSeed<'de> for SectionFields<'_> {
+    type Value = DynamicStruct;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SectionFields<'_> {
+    type Value = DynamicStruct;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a settings section")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> { Ok(DynamicStruct::default()) }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = DynamicStruct::default();
+
+        while let Some(name) = map.next_key::<&str>()? {
+            let Some(field) = self.info.field(name) else {
+                map.next_value::<IgnoredAny>()?;
+                self.unknown.push(name.to_string());
+                continue;
+            };
+            // every field type is registered along with the settings type, so
+            // this is only here to not panic
+            let Some(registration) = self.registry.get(field.type_id()) else {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            };
+
+            let value = map.next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?;
+            fields.insert_boxed(name, value);
+        }
+
+        Ok(fields)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    config::{
+        set_reflect_path,
+        settings_sections,
+    },
+    console::{
+        Args,
+        CommandResult,
+        ConsoleAppExt,
+        ConsoleArgs,
+    },
+};
+use bevy::{
+    prelude::*,
+    reflect::{
+        ReflectRef,
+        TypeRegistry,
+    },
+};
+use std::any::TypeId;
+
+// past this the physics can't keep up and everything tunnels through
+// everything else
+const MAX_TIMESCALE: f32 = 10.;
+
+// the commands that belong to no plugin in particular - spawn and kill live
+// with the actors they make and destroy
+pub(super) fn register_commands(app: &mut App) {
+    app.add_console_command(
+        "set",
+        "change a setting or any reflected resource - the value is written like it is in settings.ron",
+        set_from_console,
+    )
+    .add_console_command(
+        "timescale",
+        "speed up or slow down the game - 1 is normal",
+        set_timescale,
+    );
+}
+
+struct SetArgs {
+    resource: String,
+    path:     String,
+    value:    String,
+}
+
+impl ConsoleArgs for SetArgs {
+    const USAGE: &'static str = "<resource.field> <value>";
+
+    fn parse(args: &mut Args) -> Result<Self, String> {
+        let target: String = args.required("resource.field")?;
+        let (resource, path) = target
+            .split_once('.')
+            .ok_or_else(|| format!("{target} - expected resource.field"))?;
+
+        Ok(Self {
+            resource: resource.to_string(),
+            path:     path.to_string(),
+            value:    args.rest("value")?,
+        })
+    }
+
+    // resource names until there's a dot, then the fields of that resource
+    fn complete(index: usize, partial: &str, world: &World) -> Vec<String> {
+        if index != 0 {
+            return Vec::new();
+        }
+
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let Some((resource, _)) = partial.split_once('.') else {
+            return settings_sections(world)
+                .into_iter()
+                .map(|(name, _)| format!("{name}."))
+                .collect();
+        };
+
+        let Some(type_id) = find_resource(world, &registry, resource) else {
+            return Vec::new();
+        };
+        let Some(ReflectRef::Struct(fields)) = registry
+            .get_type_data::<ReflectResource>(type_id)
+            .and_then(|reflect_resource| reflect_resource.reflect(world))
+            .map(|reflected| reflected.reflect_ref())
+        else {
+            return Vec::new();
+        };
+
+        (0..fields.field_len())
+            .filter_map(|index| fields.name_at(index))
+            .map(|field| format!("{resource}.{field}"))
+            .collect()
+    }
+}
+
+// a settings section by the name it has in settings.ron, otherwise any
+// reflected resource by its type name - `Boundary` or `boundary`
+fn find_resource(world: &World, registry: &TypeRegistry, name: &str) -> Option<TypeId> {
+    if let Some((_, type_id)) = settings_sections(world)
+        .into_iter()
+        .find(|(section, _)| *section == name)
+    {
+        return Some(type_id);
+    }
+
+    registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectResource>().is_some())
+        .find(|registration| {
+            registration
+                .type_info()
+                .type_path_table()
+                .short_path()
+                .eq_ignore_ascii_case(name)
+        })
+        .map(|registration| registration.type_id())
+}
+
+// goes through reflection rather than the settings file, so a settings change
+// made here is saved the same as one made in the settings menu - the file
+// watches the resources, not who changed them
+fn set_from_console(In(args): In<SetArgs>, world: &mut World) -> CommandResult {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let type_id = find_resource(world, &registry, &args.resource)
+        .ok_or_else(|| format!("unknown resource {}", args.resource))?;
+    let reflect_resource = registry
+        .get_type_data::<ReflectResource>(type_id)
+        .ok_or_else(|| format!("{} can't be changed from here", args.resource))?;
+    let mut resource = reflect_resource
+        .reflect_mut(world)
+        .ok_or_else(|| format!("there's no {} right now", args.resource))?;
+
+    set_reflect_path(&mut *resource, &args.path, &args.value, &registry)
+        .map_err(|e| format!("{}.{}: {e}", args.resource, args.path))?;
+
+    Ok(format!("{}.{} = {}", args.resource, args.path, args.value))
+}
+
+struct TimescaleArgs(f32);
+
+impl ConsoleArgs for TimescaleArgs {
+    const USAGE: &'static str = "<scale>";
+
+    fn parse(args: &mut Args) -> Result<Self, String> { args.required("scale").map(Self) }
+}
+
+// virtual time is what the fixed timestep and everything else in game runs on
+fn set_timescale(
+    In(TimescaleArgs(scale)): In<TimescaleArgs>,
+    mut time: ResMut<Time<Virtual>>,
+) -> CommandResult {
+    if !(scale > 0. && scale <= MAX_TIMESCALE) {
+        return Err(format!("scale has to be more than 0 and at most {MAX_TIMESCALE}"));
+    }
+
+    time.set_relative_speed(scale);
+    Ok(format!("timescale {scale}"))
+}
@@ -0,0 +1,445 @@
+mod commands;
+
+use crate::{
+    global_input::GlobalAction,
+    schedule::FrameSet,
+    state::GameState,
+};
+use bevy::{
+    prelude::*,
+    utils::HashMap,
+};
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use leafwing_input_manager::prelude::ActionState;
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    str::FromStr,
+};
+
+const MAX_LOG_LINES: usize = 200;
+const MAX_HISTORY: usize = 50;
+const CONSOLE_HEIGHT: f32 = 260.;
+
+/// a quake style console on ` for poking at a running game - `help` lists the
+/// commands. plugins add their own with `add_console_command`, each one a
+/// system that takes its parsed arguments as `In` and goes through the same
+/// resources, spawners and destruction queue as the game does
+///
+/// the key is only bound in dev tool builds, see build.rs
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleCommands>()
+            .init_resource::<Console>()
+            .add_systems(Update, toggle_console.in_set(FrameSet::Input))
+            // exclusive - commands get the whole world - so it goes after
+            // everything else rather than alongside the rest of the ui
+            .add_systems(Update, draw_console.after(FrameSet::Ui).run_if(console_open));
+
+        commands::register_commands(app);
+    }
+}
+
+/// what a command hands back to be printed - an error is shown in red
+pub type CommandResult = Result<String, String>;
+
+/// a command's arguments, parsed from the words after its name
+pub trait ConsoleArgs: Sized + Send + Sync + 'static {
+    /// shown by help after the command name - `<required> [optional]`
+    const USAGE: &'static str;
+
+    fn parse(args: &mut Args) -> Result<Self, String>;
+
+    /// what tab can complete the argument at `index` to - `partial` is what's
+    /// been typed of it so far
+    fn complete(_index: usize, _partial: &str, _world: &World) -> Vec<String> { Vec::new() }
+}
+
+impl ConsoleArgs for () {
+    const USAGE: &'static str = "";
+
+    fn parse(_args: &mut Args) -> Result<Self, String> { Ok(()) }
+}
+
+/// the words after a command's name, taken from the front in order
+#[derive(Debug)]
+pub struct Args {
+    words: VecDeque<String>,
+}
+
+impl Args {
+    pub fn required<T: FromStr>(&mut self, name: &str) -> Result<T, String>
+    where
+        T::Err: Display,
+    {
+        let word = self.words.pop_front().ok_or_else(|| format!("missing {name}"))?;
+        word.parse().map_err(|e| format!("{name} {word:?}: {e}"))
+    }
+
+    pub fn optional<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String>
+    where
+        T::Err: Display,
+    {
+        if self.words.is_empty() {
+            return Ok(None);
+        }
+        self.required(name).map(Some)
+    }
+
+    /// everything that's left as one string - for values with spaces in them
+    pub fn rest(&mut self, name: &str) -> Result<String, String> {
+        if self.words.is_empty() {
+            return Err(format!("missing {name}"));
+        }
+        Ok(self.words.drain(..).collect::<Vec<_>>().join(" "))
+    }
+}
+
+pub trait ConsoleAppExt {
+    /// `name` runs `system` with whatever its arguments parse to
+    fn add_console_command<A: ConsoleArgs, M>(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        system: impl IntoSystem<In<A>, CommandResult, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl ConsoleAppExt for App {
+    fn add_console_command<A: ConsoleArgs, M>(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        system: impl IntoSystem<In<A>, CommandResult, M> + 'static,
+    ) -> &mut Self {
+        let id = self.register_system(system);
+
+        let run = move |world: &mut World, args: &mut Args| {
+            let input = A::parse(args)?;
+            if !args.words.is_empty() {
+                return Err(format!(
+                    "unexpected {}",
+                    args.words.iter().cloned().collect::<Vec<_>>().join(" ")
+                ));
+            }
+            world
+                .run_system_with_input(id, input)
+                .map_err(|e| e.to_string())?
+        };
+
+        self.world_mut()
+            .get_resource_or_init::<ConsoleCommands>()
+            .0
+            .insert(
+                name,
+                ConsoleCommand {
+                    usage: A::USAGE,
+                    help,
+                    run: Box::new(run),
+                    complete: A::complete,
+                },
+            );
+        self
+    }
+}
+
+type RunCommand = Box<dyn Fn(&mut World, &mut Args) -> CommandResult + Send + Sync>;
+
+struct ConsoleCommand {
+    usage:    &'static str,
+    help:     &'static str,
+    run:      RunCommand,
+    complete: fn(usize, &str, &World) -> Vec<String>,
+}
+
+#[derive(Resource, Default)]
+struct ConsoleCommands(HashMap<&'static str, ConsoleCommand>);
+
+impl ConsoleCommands {
+    // help and clear are the console's own - they need the console itself
+    const BUILT_IN: [&'static str; 2] = ["clear", "help"];
+
+    fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.0.keys().copied().chain(Self::BUILT_IN).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Input,
+    Output,
+    Error,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct Console {
+    open:          bool,
+    input:         String,
+    log:           VecDeque<(LineKind, String)>,
+    history:       Vec<String>,
+    // where up / down has got to in the history - None is the line being typed
+    history_index: Option<usize>,
+    // if opening the console paused the game, closing it unpauses
+    paused_game:   bool,
+}
+
+impl Console {
+    pub fn is_open(&self) -> bool { self.open }
+
+    fn print(&mut self, kind: LineKind, text: impl Into<String>) {
+        for line in text.into().lines() {
+            self.log.push_back((kind, line.to_string()));
+        }
+        while self.log.len() > MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+    }
+
+    fn remember(&mut self, line: &str) {
+        if self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+        }
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history_index = None;
+    }
+
+    fn recall(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+
+        let last = self.history.len() - 1;
+        self.history_index = match (self.history_index, older) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index < last => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        self.input = self
+            .history_index
+            .map(|index| self.history[index].clone())
+            .unwrap_or_default();
+    }
+}
+
+fn console_open(console: Res<Console>) -> bool { console.open }
+
+// pauses the game while it's open, the same as the settings menu does -
+// otherwise typing would fly the spaceship around
+fn toggle_console(
+    user_input: Res<ActionState<GlobalAction>>,
+    mut console: ResMut<Console>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !user_input.just_pressed(&GlobalAction::Console) {
+        return;
+    }
+
+    console.open = !console.open;
+
+    if console.open {
+        if let Some(paused) = state.get().with_paused(true) {
+            next_state.set(paused);
+            console.paused_game = true;
+        }
+        return;
+    }
+
+    // only put back what opening it did - and not if the game was unpaused
+    // or ended in between
+    if std::mem::take(&mut console.paused_game) {
+        if let Some(resumed) = state.get().with_paused(false) {
+            next_state.set(resumed);
+        }
+    }
+}
+
+fn draw_console(world: &mut World) {
+    let Ok(mut context) = world.query::<&mut EguiContext>().get_single_mut(world) else {
+        return;
+    };
+    // egui contexts are cheap handles - keeping one means the world is free for
+    // the commands
+    let context = context.get_mut().clone();
+
+    world.resource_scope(|world, mut console: Mut<Console>| {
+        let mut submitted = None;
+        let mut complete = false;
+
+        egui::TopBottomPanel::top("console")
+            .exact_height(CONSOLE_HEIGHT)
+            .show(&context, |ui| {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .max_height(CONSOLE_HEIGHT - 40.)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for (kind, text) in console.log.iter() {
+                            let text = egui::RichText::new(text).monospace();
+                            ui.label(match kind {
+                                LineKind::Input => text.weak(),
+                                LineKind::Output => text,
+                                LineKind::Error => text.color(egui::Color32::LIGHT_RED),
+                            });
+                        }
+                    });
+
+                // taken before the text box sees it, or tab would move focus away
+                complete = ui.input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::Tab));
+                if ui.input(|input| input.key_pressed(egui::Key::ArrowUp)) {
+                    console.recall(true);
+                } else if ui.input(|input| input.key_pressed(egui::Key::ArrowDown)) {
+                    console.recall(false);
+                }
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut console.input)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("help"),
+                );
+                // the key that opened the console comes through as text too
+                console.input.retain(|c| c != '`');
+
+                if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                    submitted = Some(std::mem::take(&mut console.input));
+                }
+                response.request_focus();
+            });
+
+        if complete {
+            complete_input(world, &mut console);
+        }
+        if let Some(line) = submitted {
+            run_line(world, &mut console, line.trim());
+        }
+    });
+}
+
+fn run_line(world: &mut World, console: &mut Console, line: &str) {
+    if line.is_empty() {
+        return;
+    }
+    console.print(LineKind::Input, format!("> {line}"));
+    console.remember(line);
+
+    let mut words: VecDeque<String> = line.split_whitespace().map(String::from).collect();
+    let Some(name) = words.pop_front() else {
+        return;
+    };
+
+    let result = match name.as_str() {
+        "clear" => {
+            console.log.clear();
+            return;
+        },
+        "help" => help(
+            world.resource::<ConsoleCommands>(),
+            words.front().map(String::as_str),
+        ),
+        _ => world.resource_scope(|world, commands: Mut<ConsoleCommands>| {
+            match commands.0.get(name.as_str()) {
+                Some(command) => (command.run)(world, &mut Args { words }),
+                None => Err(format!("unknown command {name} - try help")),
+            }
+        }),
+    };
+
+    match result {
+        Ok(text) if text.is_empty() => (),
+        Ok(text) => console.print(LineKind::Output, text),
+        Err(e) => console.print(LineKind::Error, e),
+    }
+}
+
+fn help(commands: &ConsoleCommands, name: Option<&str>) -> CommandResult {
+    let describe = |name: &str| match name {
+        "clear" => Ok("clear - empty the console".to_string()),
+        "help" => Ok("help [command] - list the commands, or describe one".to_string()),
+        _ => commands
+            .0
+            .get(name)
+            .map(|command| format!("{name} {} - {}", command.usage, command.help))
+            .ok_or_else(|| format!("unknown command {name}")),
+    };
+
+    match name {
+        Some(name) => describe(name),
+        None => commands
+            .names()
+            .into_iter()
+            .map(describe)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|lines| lines.join("\n")),
+    }
+}
+
+// completes the word being typed - all the way if there's only one way to go,
+// otherwise as far as the candidates agree and they're listed
+fn complete_input(world: &World, console: &mut Console) {
+    let commands = world.resource::<ConsoleCommands>();
+
+    let mut words: Vec<&str> = console.input.split_whitespace().collect();
+    if console.input.is_empty() || console.input.ends_with(' ') {
+        words.push("");
+    }
+    let Some((&partial, previous)) = words.split_last() else {
+        return;
+    };
+
+    let candidates: Vec<String> = match previous.split_first() {
+        None => commands.names().into_iter().map(String::from).collect(),
+        Some((name, arguments)) => commands
+            .0
+            .get(name)
+            .map(|command| (command.complete)(arguments.len(), partial, world))
+            .unwrap_or_default(),
+    };
+    let candidates: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(partial))
+        .collect();
+
+    let completed = match candidates.as_slice() {
+        [] => return,
+        // a resource name waiting for its field doesn't want a space yet
+        [only] if only.ends_with('.') => only.clone(),
+        [only] => format!("{only} "),
+        _ => common_prefix(&candidates),
+    };
+    let input = previous
+        .iter()
+        .copied()
+        .chain([completed.as_str()])
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if candidates.len() > 1 {
+        console.print(LineKind::Output, candidates.join("  "));
+    }
+    console.input = input;
+}
+
+fn common_prefix(candidates: &[String]) -> String {
+    let first = &candidates[0];
+    let length = candidates.iter().skip(1).fold(first.len(), |length, candidate| {
+        first
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(length)
+    });
+    first.chars().take(length).collect()
+}
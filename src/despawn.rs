@@ -1,7 +1,16 @@
 use crate::{
     actor::{
         missile::Missile,
+        Aabb,
+        ActorKind,
         Health,
+        Teleporter,
+    },
+    console::{
+        Args,
+        CommandResult,
+        ConsoleAppExt,
+        ConsoleArgs,
     },
     schedule::InGameSet,
     state::GameState,
@@ -12,40 +21,181 @@ pub struct DespawnPlugin;
 
 impl Plugin for DespawnPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (despawn_dead_entities, despawn_missiles).in_set(InGameSet::DespawnEntities),
-        )
-        .add_systems(OnEnter(GameState::GameOver), despawn_all_entities)
-        .add_systems(OnExit(GameState::Splash), despawn_splash);
+        app.add_event::<ActorDestroyed>()
+            .init_resource::<DestructionQueue>()
+            .add_systems(
+                Update,
+                (mark_dead_entities, mark_expired_missiles).in_set(InGameSet::DespawnEntities),
+            )
+            // the nateroids carry on drifting behind the game over screen and
+            // only go once it's left, whichever way
+            .add_systems(OnEnter(GameState::GameOver), despawn_missiles)
+            .add_systems(OnExit(GameState::GameOver), despawn_all_entities)
+            .add_systems(Last, process_destruction_queue)
+            .add_systems(OnExit(GameState::Splash), despawn_splash)
+            .add_console_command(
+                "kill",
+                "destroy actors as if they'd been shot - all leaves the spaceship alone",
+                kill_from_console,
+            );
+    }
+}
+
+/// sent when an actor runs out of health - size is the world space extent of
+/// its aabb so listeners can scale their reaction to whatever blew up
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ActorDestroyed {
+    pub kind:     ActorKind,
+    pub position: Vec3,
+    pub size:     f32,
+}
+
+/// why an actor is going away. only Killed counts as it being destroyed -
+/// that's the one that scores and explodes - the others just tidy up.
+/// ordered by precedence: when an entity is marked for more than one reason
+/// the greatest wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DestructionReason {
+    Expired,
+    LeftBoundary,
+    Killed,
+    GameOver,
+}
+
+/// everything that's going away this frame. systems mark actors here rather
+/// than despawning them and process_destruction_queue removes them all at
+/// the very end of the frame - so nothing can read an actor that some other
+/// system already removed, and an actor marked twice on the same frame (a
+/// missile and the spaceship both finishing off a nateroid) is only destroyed
+/// once
+///
+/// they go in the order they were marked rather than by entity - a replay
+/// hands out different entities, and the physics has to see the same
+/// despawns in the same order for it to play out the same
+#[derive(Resource, Debug, Default)]
+pub struct DestructionQueue(Vec<(Entity, DestructionReason)>);
+
+impl DestructionQueue {
+    pub fn mark(&mut self, entity: Entity, reason: DestructionReason) {
+        match self.0.iter_mut().find(|(queued, _)| *queued == entity) {
+            Some((_, queued)) => *queued = (*queued).max(reason),
+            None => self.0.push((entity, reason)),
+        }
+    }
+
+    pub fn is_marked(&self, entity: Entity) -> bool { self.0.iter().any(|(queued, _)| *queued == entity) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KillTarget {
+    All,
+    Kind(ActorKind),
+}
+
+impl ConsoleArgs for KillTarget {
+    const USAGE: &'static str = "<all|nateroids|missiles|spaceship>";
+
+    fn parse(args: &mut Args) -> Result<Self, String> {
+        let target: String = args.required("what to kill")?;
+        match target.as_str() {
+            "all" => Ok(KillTarget::All),
+            "nateroids" => Ok(KillTarget::Kind(ActorKind::Nateroid)),
+            "missiles" => Ok(KillTarget::Kind(ActorKind::Missile)),
+            "spaceship" => Ok(KillTarget::Kind(ActorKind::Spaceship)),
+            _ => Err(format!("can't kill {target}")),
+        }
+    }
+
+    fn complete(index: usize, _partial: &str, _world: &World) -> Vec<String> {
+        match index {
+            0 => ["all", "nateroids", "missiles", "spaceship"]
+                .map(String::from)
+                .to_vec(),
+            _ => Vec::new(),
+        }
     }
 }
 
-fn despawn_missiles(mut commands: Commands, query: Query<(Entity, &Missile)>) {
+// marked Killed, so they score, explode and - for the spaceship - end the game
+// the same way a collision would
+fn kill_from_console(
+    In(target): In<KillTarget>,
+    mut queue: ResMut<DestructionQueue>,
+    query: Query<(Entity, &ActorKind)>,
+) -> CommandResult {
+    let mut killed = 0;
+    for (entity, &kind) in query.iter() {
+        let hit = match target {
+            KillTarget::All => kind != ActorKind::Spaceship,
+            KillTarget::Kind(target) => kind == target,
+        };
+        if hit {
+            queue.mark(entity, DestructionReason::Killed);
+            killed += 1;
+        }
+    }
+
+    Ok(format!("killed {killed}"))
+}
+
+fn mark_expired_missiles(mut queue: ResMut<DestructionQueue>, query: Query<(Entity, &Missile)>) {
     for (entity, &missile) in query.iter() {
         if missile.traveled_distance >= missile.total_distance {
-            despawn(&mut commands, entity);
+            queue.mark(entity, DestructionReason::Expired);
         }
     }
 }
 
 pub fn despawn(commands: &mut Commands, entity: Entity) { commands.entity(entity).despawn_recursive(); }
 
-fn despawn_dead_entities(mut commands: Commands, query: Query<(Entity, &Health, &Name)>) {
-    for (entity, health, _name) in query.iter() {
+fn mark_dead_entities(mut queue: ResMut<DestructionQueue>, query: Query<(Entity, &Health)>) {
+    for (entity, health) in query.iter() {
         if health.0 <= 0.0 {
-            // if !name.contains("Missile") {
-            //     println!("{:?} died from poor health: {:?}\n", _name, health);
-            // }
-            despawn(&mut commands, entity);
+            queue.mark(entity, DestructionReason::Killed);
         }
     }
 }
 
-fn despawn_all_entities(mut commands: Commands, query: Query<Entity, With<Health>>) {
-    println!("GameOver");
+fn despawn_missiles(mut queue: ResMut<DestructionQueue>, query: Query<Entity, With<Missile>>) {
     for entity in query.iter() {
-        despawn(&mut commands, entity);
+        queue.mark(entity, DestructionReason::GameOver);
+    }
+}
+
+// every actor wraps or bounces at the boundary, so Teleporter is the one
+// thing they all have
+fn despawn_all_entities(mut queue: ResMut<DestructionQueue>, query: Query<Entity, With<Teleporter>>) {
+    debug!("leaving game over - despawning everything");
+    for entity in query.iter() {
+        queue.mark(entity, DestructionReason::GameOver);
+    }
+}
+
+// the one place actors are despawned - ActorDestroyed goes out here, exactly
+// once per actor, and is picked up at the start of the next frame
+fn process_destruction_queue(
+    mut commands: Commands,
+    mut queue: ResMut<DestructionQueue>,
+    mut destroyed: EventWriter<ActorDestroyed>,
+    query: Query<(&ActorKind, &Transform, &Aabb)>,
+) {
+    for (entity, reason) in queue.0.drain(..) {
+        // something outside the queue got to it first
+        let Some(entity_commands) = commands.get_entity(entity) else {
+            continue;
+        };
+
+        if reason == DestructionReason::Killed {
+            if let Ok((&kind, transform, aabb)) = query.get(entity) {
+                destroyed.send(ActorDestroyed {
+                    kind,
+                    position: transform.translation,
+                    size: aabb.max_dimension() * transform.scale.max_element(),
+                });
+            }
+        }
+
+        entity_commands.despawn_recursive();
     }
 }
 
@@ -54,3 +204,122 @@ fn despawn_splash(mut commands: Commands, query: Query<Entity, With<crate::splas
         despawn(&mut commands, entity);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn world_with_nateroid() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<DestructionQueue>();
+        world.init_resource::<Events<ActorDestroyed>>();
+
+        let nateroid = world
+            .spawn((
+                ActorKind::Nateroid,
+                Transform::from_xyz(10., 0., 0.),
+                Aabb {
+                    min: Vec3::splat(-2.),
+                    max: Vec3::splat(2.),
+                },
+            ))
+            .id();
+
+        (world, nateroid)
+    }
+
+    fn destroyed(world: &World) -> Vec<ActorDestroyed> {
+        let events = world.resource::<Events<ActorDestroyed>>();
+        events.get_cursor().read(events).copied().collect()
+    }
+
+    fn destroyed_count(world: &World) -> usize { destroyed(world).len() }
+
+    // the missile's hit and the spaceship running into it both land on the
+    // same frame - one explosion, one score, one despawn
+    #[test]
+    fn missile_and_spaceship_destroy_the_same_nateroid_once() {
+        let (mut world, nateroid) = world_with_nateroid();
+
+        let mut queue = world.resource_mut::<DestructionQueue>();
+        queue.mark(nateroid, DestructionReason::Killed);
+        queue.mark(nateroid, DestructionReason::Killed);
+
+        world.run_system_once(process_destruction_queue).unwrap();
+
+        assert!(world.get_entity(nateroid).is_err());
+        let destroyed = destroyed(&world);
+        assert_eq!(destroyed.len(), 1);
+        assert_eq!(destroyed[0].kind, ActorKind::Nateroid);
+        assert_eq!(destroyed[0].position, Vec3::new(10., 0., 0.));
+        assert!(!world.resource::<DestructionQueue>().is_marked(nateroid));
+
+        // and nothing left over for the next frame
+        world.run_system_once(process_destruction_queue).unwrap();
+        assert_eq!(destroyed_count(&world), 1);
+    }
+
+    // tidying up doesn't cancel out a kill, whichever order they come in
+    #[test]
+    fn killed_wins_over_tidying_up() {
+        let (mut world, nateroid) = world_with_nateroid();
+
+        let mut queue = world.resource_mut::<DestructionQueue>();
+        queue.mark(nateroid, DestructionReason::Killed);
+        queue.mark(nateroid, DestructionReason::LeftBoundary);
+        queue.mark(nateroid, DestructionReason::Expired);
+
+        world.run_system_once(process_destruction_queue).unwrap();
+
+        assert!(world.get_entity(nateroid).is_err());
+        assert_eq!(destroyed_count(&world), 1);
+    }
+
+    #[test]
+    fn tidying_up_is_not_destroying() {
+        let (mut world, nateroid) = world_with_nateroid();
+
+        world
+            .resource_mut::<DestructionQueue>()
+            .mark(nateroid, DestructionReason::LeftBoundary);
+
+        world.run_system_once(process_destruction_queue).unwrap();
+
+        assert!(world.get_entity(nateroid).is_err());
+        assert_eq!(destroyed_count(&world), 0);
+    }
+
+    // something outside the queue despawned it before the end of the frame
+    #[test]
+    fn already_despawned_is_skipped() {
+        let (mut world, nateroid) = world_with_nateroid();
+
+        world
+            .resource_mut::<DestructionQueue>()
+            .mark(nateroid, DestructionReason::Killed);
+        world.despawn(nateroid);
+
+        world.run_system_once(process_destruction_queue).unwrap();
+
+        assert_eq!(destroyed_count(&world), 0);
+        assert!(!world.resource::<DestructionQueue>().is_marked(nateroid));
+    }
+
+    // leaving game over takes every actor with it - one with no health left
+    // to its name as well - and only actors
+    #[test]
+    fn leaving_game_over_clears_every_teleporter() {
+        let (mut world, nateroid) = world_with_nateroid();
+        world.entity_mut(nateroid).insert(Teleporter::default());
+        let no_health = world.spawn(Teleporter::default()).id();
+        let not_an_actor = world.spawn(Transform::default()).id();
+
+        world.run_system_once(despawn_all_entities).unwrap();
+
+        let queue = world.resource::<DestructionQueue>();
+        assert!(queue.is_marked(nateroid));
+        assert!(queue.is_marked(no_health));
+        assert!(!queue.is_marked(not_an_actor));
+    }
+}
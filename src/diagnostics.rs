@@ -0,0 +1,251 @@
+#[cfg(dev_tools)]
+use crate::frame_timing::{
+    draw_timing_bar,
+    FrameTimingBudgets,
+    FrameTimings,
+};
+use crate::{
+    budget::BudgetUsage,
+    global_input::GlobalAction,
+    schedule::FrameSet,
+};
+use bevy::{
+    diagnostic::{
+        DiagnosticPath,
+        DiagnosticsStore,
+        EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+    render::diagnostic::RenderDiagnosticsPlugin,
+};
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use leafwing_input_manager::prelude::ActionState;
+use std::collections::VecDeque;
+
+// the gameplay plugins register and measure these themselves - they live here
+// so the overlay knows what to ask for
+pub const MISSILE_COUNT: DiagnosticPath = DiagnosticPath::const_new("nateroids/missiles");
+pub const NATEROID_COUNT: DiagnosticPath = DiagnosticPath::const_new("nateroids/nateroids");
+pub const PORTAL_COUNT: DiagnosticPath = DiagnosticPath::const_new("nateroids/portals");
+pub const RIGID_BODY_COUNT: DiagnosticPath = DiagnosticPath::const_new("nateroids/rigid_bodies");
+
+// a few seconds worth of frames at a high refresh rate - enough for the graph
+// and for the 1% low to mean something
+const FRAME_HISTORY: usize = 600;
+const GRAPH_SIZE: [f32; 2] = [240., 48.];
+// the graph's ceiling - anything slower than 30fps is pinned to the top
+const GRAPH_MAX_MS: f32 = 33.3;
+const RENDER_PASSES_SHOWN: usize = 6;
+const BUDGETS_SHOWN: usize = 4;
+
+pub struct DiagnosticsOverlayPlugin;
+
+impl Plugin for DiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            FrameTimeDiagnosticsPlugin,
+            EntityCountDiagnosticsPlugin,
+            RenderDiagnosticsPlugin,
+        ))
+        .init_resource::<DiagnosticsOverlay>()
+        .init_resource::<FrameTimes>()
+        .add_systems(Update, cycle_overlay_mode.in_set(FrameSet::Input))
+        .add_systems(Update, record_frame_time.in_set(FrameSet::Ui))
+        .add_systems(
+            Update,
+            draw_overlay
+                .in_set(FrameSet::Ui)
+                .run_if(|overlay: Res<DiagnosticsOverlay>| overlay.mode != OverlayMode::Off),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlayMode {
+    #[default]
+    Off,
+    Full,
+    // one line, for streaming
+    Compact,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct DiagnosticsOverlay {
+    pub mode: OverlayMode,
+}
+
+// FrameTimeDiagnosticsPlugin only keeps a couple of seconds of history so we
+// keep our own for the graph and the 1% low
+#[derive(Resource, Debug, Default)]
+struct FrameTimes(VecDeque<f32>);
+
+fn cycle_overlay_mode(user_input: Res<ActionState<GlobalAction>>, mut overlay: ResMut<DiagnosticsOverlay>) {
+    if user_input.just_pressed(&GlobalAction::Diagnostics) {
+        overlay.mode = match overlay.mode {
+            OverlayMode::Off => OverlayMode::Full,
+            OverlayMode::Full => OverlayMode::Compact,
+            OverlayMode::Compact => OverlayMode::Off,
+        };
+    }
+}
+
+fn record_frame_time(time: Res<Time<Real>>, mut frame_times: ResMut<FrameTimes>) {
+    if frame_times.0.len() == FRAME_HISTORY {
+        frame_times.0.pop_front();
+    }
+    frame_times.0.push_back(time.delta_secs() * 1000.);
+}
+
+// the average of the slowest 1% of frames, as fps
+fn one_percent_low(frame_times: &VecDeque<f32>) -> Option<f32> {
+    if frame_times.is_empty() {
+        return None;
+    }
+
+    let mut sorted: Vec<f32> = frame_times.iter().copied().collect();
+    sorted.sort_unstable_by(|a, b| b.total_cmp(a));
+
+    let worst = (sorted.len() / 100).max(1);
+    let average_ms = sorted[..worst].iter().sum::<f32>() / worst as f32;
+
+    (average_ms > 0.0).then(|| 1000. / average_ms)
+}
+
+fn draw_overlay(
+    mut contexts: Query<&mut EguiContext>,
+    overlay: Res<DiagnosticsOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    frame_times: Res<FrameTimes>,
+    budget_usage: Res<BudgetUsage>,
+    #[cfg(dev_tools)] frame_timings: Option<Res<FrameTimings>>,
+    #[cfg(dev_tools)] frame_timing_budgets: Option<Res<FrameTimingBudgets>>,
+) {
+    let Ok(mut context) = contexts.get_single_mut() else {
+        return;
+    };
+
+    let value = |path: &DiagnosticPath| diagnostics.get(path).and_then(|d| d.smoothed()).unwrap_or(0.0);
+    let count = |path: &DiagnosticPath| diagnostics.get(path).and_then(|d| d.value()).unwrap_or(0.0);
+
+    let fps = value(&FrameTimeDiagnosticsPlugin::FPS);
+    let low = one_percent_low(&frame_times.0).unwrap_or(0.0);
+
+    // not interactable so hovering it doesn't count as inspecting
+    egui::Area::new(egui::Id::new("diagnostics_overlay"))
+        .anchor(egui::Align2::RIGHT_TOP, [-8., 8.])
+        .interactable(false)
+        .show(context.get_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                if overlay.mode == OverlayMode::Compact {
+                    ui.monospace(format!(
+                        "{fps:.0} fps  1% {low:.0}  {:.0} entities  {:.0} nateroids  {:.0} missiles",
+                        count(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+                        count(&NATEROID_COUNT),
+                        count(&MISSILE_COUNT),
+                    ));
+                    return;
+                }
+
+                // which part of the frame is eating it - dev tool builds only
+                #[cfg(dev_tools)]
+                if let (Some(timings), Some(budgets)) = (&frame_timings, &frame_timing_budgets) {
+                    draw_timing_bar(
+                        ui,
+                        timings,
+                        budgets,
+                        value(&FrameTimeDiagnosticsPlugin::FRAME_TIME) as f32,
+                    );
+                    ui.separator();
+                }
+
+                ui.monospace(format!(
+                    "fps       {fps:>6.0}\n1% low    {low:>6.0}\nframe ms  {:>6.2}",
+                    value(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+                ));
+
+                draw_frame_graph(ui, &frame_times.0);
+
+                ui.monospace(format!(
+                    "entities  {:>6.0}\nbodies    {:>6.0}\nnateroids {:>6.0}\nmissiles  {:>6.0}\nportals   {:>6.0}",
+                    count(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+                    count(&RIGID_BODY_COUNT),
+                    count(&NATEROID_COUNT),
+                    count(&MISSILE_COUNT),
+                    count(&PORTAL_COUNT),
+                ));
+
+                // RenderDiagnosticsPlugin makes one diagnostic per render pass -
+                // show the most expensive ones on the cpu side
+                let mut passes: Vec<(&str, f64)> = diagnostics
+                    .iter()
+                    .filter(|d| d.path().as_str().starts_with("render/"))
+                    .filter(|d| d.path().as_str().ends_with("/elapsed_cpu"))
+                    .filter_map(|d| d.smoothed().map(|v| (d.path().as_str(), v)))
+                    .collect();
+                passes.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+                for (path, ms) in passes.into_iter().take(RENDER_PASSES_SHOWN) {
+                    let name = path.trim_start_matches("render/").trim_end_matches("/elapsed_cpu");
+                    ui.monospace(format!("{name:<24.24} {ms:>5.2}ms"));
+                }
+
+                // whatever is closest to its budget - anything over is what's leaking
+                ui.separator();
+                for entry in budget_usage.top_offenders(BUDGETS_SHOWN) {
+                    let line = egui::RichText::new(format!(
+                        "{:<12} {:>8.0} / {:<8.0}",
+                        entry.category.label(),
+                        entry.current,
+                        entry.limit
+                    ))
+                    .monospace();
+                    if entry.fraction() > 1. {
+                        ui.label(line.color(egui::Color32::LIGHT_RED));
+                    } else {
+                        ui.label(line);
+                    }
+                }
+            });
+        });
+}
+
+fn draw_frame_graph(ui: &mut egui::Ui, frame_times: &VecDeque<f32>) {
+    let (rect, _) = ui.allocate_exact_size(GRAPH_SIZE.into(), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    painter.rect_filled(rect, 0., egui::Color32::from_black_alpha(96));
+
+    // a line at 60fps for reference
+    let target_y = rect.bottom() - rect.height() * (16.7 / GRAPH_MAX_MS);
+    painter.hline(
+        rect.x_range(),
+        target_y,
+        egui::Stroke::new(1., egui::Color32::DARK_GREEN),
+    );
+
+    if frame_times.len() < 2 {
+        return;
+    }
+
+    let step = rect.width() / (FRAME_HISTORY - 1) as f32;
+    let start = rect.right() - step * (frame_times.len() - 1) as f32;
+    let points = frame_times
+        .iter()
+        .enumerate()
+        .map(|(i, ms)| {
+            egui::pos2(
+                start + step * i as f32,
+                rect.bottom() - rect.height() * (ms / GRAPH_MAX_MS).min(1.),
+            )
+        })
+        .collect();
+
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1., egui::Color32::LIGHT_GRAY),
+    ));
+}
@@ -0,0 +1,466 @@
+use crate::{
+    config::settings_sections,
+    console::{
+        Args,
+        CommandResult,
+        ConsoleAppExt,
+        ConsoleArgs,
+    },
+    global_input::GlobalAction,
+    schedule::FrameSet,
+    state::{
+        GameState,
+        IsPaused,
+    },
+    storage::platform_storage,
+};
+use bevy::{
+    core::FrameCount,
+    prelude::*,
+    reflect::{
+        serde::{
+            ReflectDeserializer,
+            ReflectSerializer,
+        },
+        TypeRegistry,
+    },
+};
+use leafwing_input_manager::prelude::{
+    ActionState,
+    Actionlike,
+};
+use serde::de::{
+    DeserializeSeed,
+    Deserializer,
+    Error,
+    IgnoredAny,
+    MapAccess,
+    SeqAccess,
+    Visitor,
+};
+use std::{
+    any::TypeId,
+    collections::{
+        BTreeMap,
+        VecDeque,
+    },
+    fmt::{
+        self,
+        Debug,
+        Write,
+    },
+};
+
+/// how far back the action and wrap logs in a dump go
+pub const DUMP_FRAMES: u32 = 300;
+// bumped whenever the layout changes in a way load-dump can't read
+const DUMP_VERSION: u32 = 2;
+
+/// everything someone needs to look into a bug report in one file - the
+/// diagnostics dump key or the console's `dump` writes it next to the settings
+/// file, or to the clipboard in the browser. it's ron:
+///
+/// (
+///     version: 2,
+///     frame: 1234,
+///     resources: [
+///         {"nateroids::playfield::boundary::Boundary": (...)},
+///     ],
+///     sections: (
+///         states: (...),
+///         entities: {...},
+///     ),
+/// )
+///
+/// resources are every settings resource plus any a plugin adds with
+/// `add_dump_resource` - `load-dump` puts those back for a local repro. the
+/// sections are for reading and come from `add_dump_section`, so each plugin
+/// writes its own
+pub struct DiagnosticsDumpPlugin;
+
+impl Plugin for DiagnosticsDumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_dump_section("states", dump_states)
+            .add_dump_section("entities", dump_entity_counts)
+            .add_systems(Update, dump_on_key.in_set(FrameSet::Input))
+            .add_console_command(
+                "dump",
+                "write everything a bug report needs to a file - the clipboard in the browser",
+                dump_from_console,
+            )
+            .add_console_command(
+                "load-dump",
+                "put the settings and resources from a dump back - settings are saved like any other change",
+                load_dump_from_console,
+            );
+    }
+}
+
+pub trait DumpAppExt {
+    /// `write` is handed the world when a dump is made and returns the
+    /// section as ron
+    fn add_dump_section(&mut self, name: &'static str, write: fn(&World) -> String) -> &mut Self;
+
+    /// a resource that goes in a dump as it is and that load-dump can put
+    /// back - settings resources are already in
+    fn add_dump_resource<T: Resource + Reflect>(&mut self) -> &mut Self;
+
+    /// which of `A`'s actions were pressed over the last few hundred frames
+    fn add_dump_action_log<A: Actionlike + Debug>(&mut self, name: &'static str) -> &mut Self;
+}
+
+impl DumpAppExt for App {
+    fn add_dump_section(&mut self, name: &'static str, write: fn(&World) -> String) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<DumpSections>()
+            .sections
+            .insert(name, write);
+        self
+    }
+
+    fn add_dump_resource<T: Resource + Reflect>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<DumpSections>()
+            .resources
+            .push(TypeId::of::<T>());
+        self
+    }
+
+    fn add_dump_action_log<A: Actionlike + Debug>(&mut self, name: &'static str) -> &mut Self {
+        self.init_resource::<ActionLog<A>>()
+            .add_systems(Update, record_actions::<A>.in_set(FrameSet::Input))
+            .add_dump_section(name, dump_action_log::<A>)
+    }
+}
+
+#[derive(Resource, Default)]
+struct DumpSections {
+    sections:  BTreeMap<&'static str, fn(&World) -> String>,
+    resources: Vec<TypeId>,
+}
+
+// a ron list with one entry per line, lined up with the section it's in
+pub fn ron_list(entries: impl IntoIterator<Item = String>) -> String {
+    let mut text = String::from("[\n");
+    for entry in entries {
+        let _ = writeln!(text, "            {entry},");
+    }
+    text.push_str("        ]");
+    text
+}
+
+fn dump_on_key(user_input: Res<ActionState<GlobalAction>>, mut commands: Commands) {
+    if user_input.just_pressed(&GlobalAction::DiagnosticsDump) {
+        commands.queue(|world: &mut World| match save_dump(write_dump(world)) {
+            Ok(message) => info!("{message}"),
+            Err(e) => warn!("unable to write a diagnostics dump: {e}"),
+        });
+    }
+}
+
+fn dump_from_console(In(()): In<()>, world: &mut World) -> CommandResult { save_dump(write_dump(world)) }
+
+// the settings first so a dump of someone's settings looks like their file
+fn dumped_resources(world: &World) -> Vec<TypeId> {
+    let mut resources: Vec<_> = settings_sections(world)
+        .into_iter()
+        .map(|(_, type_id)| type_id)
+        .collect();
+    if let Some(sections) = world.get_resource::<DumpSections>() {
+        resources.extend(sections.resources.iter().copied());
+    }
+    resources
+}
+
+fn write_dump(world: &World) -> String {
+    let mut text = String::from("(\n");
+    let _ = writeln!(text, "    version: {DUMP_VERSION},");
+    let _ = writeln!(text, "    frame: {},", world.resource::<FrameCount>().0);
+
+    write_resources(world, &mut text);
+
+    text.push_str("    sections: (\n");
+    if let Some(sections) = world.get_resource::<DumpSections>() {
+        for (name, write) in sections.sections.iter() {
+            let _ = writeln!(text, "        {name}: {},", write(world));
+        }
+    }
+    text.push_str("    ),\n)\n");
+
+    text
+}
+
+// written the way bevy's reflection writes any value - the type path and then
+// the value, so load-dump doesn't need to be told what's what
+fn write_resources(world: &World, text: &mut String) {
+    let registry = world.resource::<AppTypeRegistry>().read();
+
+    text.push_str("    resources: [\n");
+    for type_id in dumped_resources(world) {
+        let Some(resource) = registry
+            .get_type_data::<ReflectResource>(type_id)
+            .and_then(|reflect_resource| reflect_resource.reflect(world))
+        else {
+            continue;
+        };
+        match ron::ser::to_string(&ReflectSerializer::new(resource.as_partial_reflect(), &registry)) {
+            Ok(resource_text) => {
+                let _ = writeln!(text, "        {resource_text},");
+            },
+            Err(e) => warn!(
+                "leaving {} out of the dump: {e}",
+                resource.reflect_short_type_path()
+            ),
+        }
+    }
+    text.push_str("    ],\n");
+}
+
+// natively it goes next to settings.ron. there's nowhere to save a file to in
+// the browser, so there it's copied to be pasted into the bug report instead
+#[cfg(not(target_arch = "wasm32"))]
+fn save_dump(text: String) -> CommandResult {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default();
+    let name = format!("dump-{seconds}.ron");
+
+    platform_storage().save(&name, &text)?;
+    Ok(format!("wrote {name} to the nateroids config folder"))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_dump(text: String) -> CommandResult {
+    let clipboard = web_sys::window()
+        .ok_or("no browser window")?
+        .navigator()
+        .clipboard();
+    // the promise only fails if the page doesn't have focus
+    let _ = clipboard.write_text(&text);
+    Ok("copied a diagnostics dump to the clipboard".to_string())
+}
+
+fn dump_states(world: &World) -> String {
+    let game = world.get_resource::<State<GameState>>().map(State::get);
+    let paused = world.get_resource::<State<IsPaused>>().map(State::get);
+    format!(
+        "(game: {:?}, paused: {:?})",
+        format!("{game:?}"),
+        format!("{paused:?}")
+    )
+}
+
+// the component names are what make an archetype recognisable - reflected
+// ones get their short names, anything else its whole path
+fn dump_entity_counts(world: &World) -> String {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut counts = BTreeMap::<String, usize>::new();
+
+    for archetype in world
+        .archetypes()
+        .iter()
+        .filter(|archetype| !archetype.is_empty())
+    {
+        let mut names: Vec<_> = archetype
+            .components()
+            .filter_map(|id| world.components().get_info(id))
+            .map(|info| {
+                info.type_id()
+                    .and_then(|type_id| registry.get(type_id))
+                    .map(|registration| {
+                        registration
+                            .type_info()
+                            .type_path_table()
+                            .short_path()
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| info.name().to_string())
+            })
+            .collect();
+        names.sort();
+        *counts.entry(names.join(", ")).or_default() += archetype.len();
+    }
+
+    let mut text = String::from("{\n");
+    for (components, count) in counts {
+        let _ = writeln!(text, "            {components:?}: {count},");
+    }
+    text.push_str("        }");
+    text
+}
+
+// only the frames something was pressed in are kept
+#[derive(Resource)]
+struct ActionLog<A: Actionlike> {
+    frames: VecDeque<(u32, Vec<A>)>,
+}
+
+impl<A: Actionlike> Default for ActionLog<A> {
+    fn default() -> Self {
+        Self {
+            frames: VecDeque::new(),
+        }
+    }
+}
+
+// the spaceship's actions are on the spaceship, the global ones are a resource
+fn record_actions<A: Actionlike>(
+    frame: Res<FrameCount>,
+    mut log: ResMut<ActionLog<A>>,
+    resource: Option<Res<ActionState<A>>>,
+    q_actions: Query<&ActionState<A>>,
+) {
+    let mut pressed = Vec::new();
+    for action in resource
+        .iter()
+        .flat_map(|state| state.get_pressed())
+        .chain(q_actions.iter().flat_map(ActionState::get_pressed))
+    {
+        if !pressed.contains(&action) {
+            pressed.push(action);
+        }
+    }
+
+    if !pressed.is_empty() {
+        log.frames.push_back((frame.0, pressed));
+    }
+    while log
+        .frames
+        .front()
+        .is_some_and(|(logged, _)| frame.0.wrapping_sub(*logged) >= DUMP_FRAMES)
+    {
+        log.frames.pop_front();
+    }
+}
+
+fn dump_action_log<A: Actionlike + Debug>(world: &World) -> String {
+    let Some(log) = world.get_resource::<ActionLog<A>>() else {
+        return "[]".to_string();
+    };
+
+    ron_list(log.frames.iter().map(|(frame, pressed)| {
+        let pressed: Vec<_> = pressed.iter().map(|action| format!("{action:?}")).collect();
+        format!("(frame: {frame}, pressed: {pressed:?})")
+    }))
+}
+
+struct LoadDumpArgs(String);
+
+impl ConsoleArgs for LoadDumpArgs {
+    const USAGE: &'static str = "<file>";
+
+    fn parse(args: &mut Args) -> Result<Self, String> { args.rest("file").map(Self) }
+}
+
+// a path, or the name of a dump in the config folder
+fn load_dump_from_console(In(LoadDumpArgs(file)): In<LoadDumpArgs>, world: &mut World) -> CommandResult {
+    let text = std::fs::read_to_string(&file)
+        .ok()
+        .or_else(|| platform_storage().load(&file))
+        .ok_or_else(|| format!("can't read {file}"))?;
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let mut deserializer = ron::de::Deserializer::from_str(&text).map_err(|e| e.to_string())?;
+    let resources = DumpResources { registry: &registry }
+        .deserialize(&mut deserializer)
+        .map_err(|e| e.to_string())?;
+
+    // only what a dump would have written - a hand edited dump doesn't get to
+    // replace anything else
+    let allowed = dumped_resources(world);
+    let mut restored = 0;
+    for resource in resources {
+        let Some(type_id) = resource
+            .get_represented_type_info()
+            .map(|info| info.type_id())
+            .filter(|type_id| allowed.contains(type_id))
+        else {
+            continue;
+        };
+        if let Some(reflect_resource) = registry.get_type_data::<ReflectResource>(type_id) {
+            reflect_resource.apply(world, &*resource);
+            restored += 1;
+        }
+    }
+
+    Ok(format!("restored {restored} resources from {file}"))
+}
+
+// the resources list out of a dump, skipping everything else in it
+struct DumpResources<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for DumpResources<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DumpResources<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a diagnostics dump")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut resources = Vec::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "version" => {
+                    let version = map.next_value::<u32>()?;
+                    if version != DUMP_VERSION {
+                        return Err(A::Error::custom(format!(
+                            "this is a version {version} dump - only version {DUMP_VERSION} can be loaded"
+                        )));
+                    }
+                },
+                "resources" => {
+                    resources = map.next_value_seed(ResourceList {
+                        registry: self.registry,
+                    })?;
+                },
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                },
+            }
+        }
+
+        Ok(resources)
+    }
+}
+
+struct ResourceList<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for ResourceList<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ResourceList<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of resources")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut resources = Vec::new();
+        while let Some(resource) = seq.next_element_seed(ReflectDeserializer::new(self.registry))? {
+            resources.push(resource);
+        }
+        Ok(resources)
+    }
+}
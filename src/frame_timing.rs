@@ -0,0 +1,227 @@
+use crate::schedule::FrameSet;
+use bevy::{
+    app::RunFixedMainLoopSystem,
+    prelude::*,
+    utils::Instant,
+};
+use bevy_inspector_egui::bevy_egui::egui;
+
+// how much of each new frame goes into the rolling average - about a second
+// of frames to settle
+const SMOOTHING: f32 = 0.05;
+const BAR_SIZE: [f32; 2] = [240., 8.];
+// not worth a line in the overlay below this
+const SHOWN_MS: f32 = 0.05;
+
+/// how long each part of the frame takes, without attaching a profiler. a
+/// timestamp goes down between each pair of frame sets - they're chained, so
+/// everything in one set has finished before the next starts - and around the
+/// fixed update loop, which is the whole simulation, physics included. the
+/// diagnostics overlay shows it as a stacked bar
+///
+/// dev tool builds only, so none of it is there in a release build
+pub struct FrameTimingPlugin;
+
+impl Plugin for FrameTimingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameTimings>()
+            .init_resource::<FrameTimingBudgets>()
+            .register_type::<FrameTimingBudgets>()
+            .add_systems(
+                RunFixedMainLoop,
+                (
+                    start_timing.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                    finish_section(TimedSection::Simulation)
+                        .in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+                ),
+            )
+            .add_systems(Update, start_timing.before(FrameSet::Input));
+
+        let sets = [
+            (FrameSet::Input, TimedSection::Input),
+            (FrameSet::Intent, TimedSection::Intent),
+            (FrameSet::PhysicsSync, TimedSection::PhysicsSync),
+            (FrameSet::Boundary, TimedSection::Boundary),
+            (FrameSet::Visual, TimedSection::Visual),
+            (FrameSet::Camera, TimedSection::Camera),
+            (FrameSet::Ui, TimedSection::Ui),
+        ];
+        for (index, (set, section)) in sets.into_iter().enumerate() {
+            let finish = finish_section(section).after(set);
+            match sets.get(index + 1) {
+                Some((next, _)) => app.add_systems(Update, finish.before(*next)),
+                None => app.add_systems(Update, finish),
+            };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedSection {
+    Simulation,
+    Input,
+    Intent,
+    PhysicsSync,
+    Boundary,
+    Visual,
+    Camera,
+    Ui,
+}
+
+impl TimedSection {
+    const ALL: [TimedSection; 8] = [
+        TimedSection::Simulation,
+        TimedSection::Input,
+        TimedSection::Intent,
+        TimedSection::PhysicsSync,
+        TimedSection::Boundary,
+        TimedSection::Visual,
+        TimedSection::Camera,
+        TimedSection::Ui,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            TimedSection::Simulation => "simulation",
+            TimedSection::Input => "input",
+            TimedSection::Intent => "intent",
+            TimedSection::PhysicsSync => "physics sync",
+            TimedSection::Boundary => "boundary",
+            TimedSection::Visual => "visual",
+            TimedSection::Camera => "camera",
+            TimedSection::Ui => "ui",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            TimedSection::Simulation => egui::Color32::from_rgb(96, 165, 250),
+            TimedSection::Input => egui::Color32::from_rgb(148, 163, 184),
+            TimedSection::Intent => egui::Color32::from_rgb(167, 139, 250),
+            TimedSection::PhysicsSync => egui::Color32::from_rgb(45, 212, 191),
+            TimedSection::Boundary => egui::Color32::from_rgb(250, 204, 21),
+            TimedSection::Visual => egui::Color32::from_rgb(74, 222, 128),
+            TimedSection::Camera => egui::Color32::from_rgb(251, 146, 60),
+            TimedSection::Ui => egui::Color32::from_rgb(244, 114, 182),
+        }
+    }
+}
+
+/// how many milliseconds each part of the frame gets before the overlay turns
+/// it red - change them in the console with `set FrameTimingBudgets.visual 2`
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct FrameTimingBudgets {
+    pub simulation:   f32,
+    pub input:        f32,
+    pub intent:       f32,
+    pub physics_sync: f32,
+    pub boundary:     f32,
+    pub visual:       f32,
+    pub camera:       f32,
+    pub ui:           f32,
+}
+
+impl Default for FrameTimingBudgets {
+    fn default() -> Self {
+        Self {
+            simulation:   4.,
+            input:        0.5,
+            intent:       1.,
+            physics_sync: 1.,
+            boundary:     0.5,
+            visual:       2.,
+            camera:       0.5,
+            ui:           2.,
+        }
+    }
+}
+
+impl FrameTimingBudgets {
+    fn limit(&self, section: TimedSection) -> f32 {
+        match section {
+            TimedSection::Simulation => self.simulation,
+            TimedSection::Input => self.input,
+            TimedSection::Intent => self.intent,
+            TimedSection::PhysicsSync => self.physics_sync,
+            TimedSection::Boundary => self.boundary,
+            TimedSection::Visual => self.visual,
+            TimedSection::Camera => self.camera,
+            TimedSection::Ui => self.ui,
+        }
+    }
+}
+
+/// the rolling average of each section in milliseconds, in TimedSection order
+#[derive(Resource, Debug, Default)]
+pub struct FrameTimings {
+    started:  Option<Instant>,
+    averages: [f32; TimedSection::ALL.len()],
+}
+
+fn start_timing(mut timings: ResMut<FrameTimings>) { timings.started = Some(Instant::now()); }
+
+// each section starts where the one before it finished
+fn finish_section(section: TimedSection) -> impl FnMut(ResMut<FrameTimings>) {
+    move |mut timings: ResMut<FrameTimings>| {
+        let now = Instant::now();
+        let Some(started) = timings.started.replace(now) else {
+            return;
+        };
+
+        let ms = now.duration_since(started).as_secs_f32() * 1000.;
+        let average = &mut timings.averages[section as usize];
+        *average += (ms - *average) * SMOOTHING;
+    }
+}
+
+/// a bar with each section's share of the frame - whatever's left over is
+/// rendering and everything outside the frame sets. a section over its budget
+/// is red, in the bar and in the list under it
+pub fn draw_timing_bar(
+    ui: &mut egui::Ui,
+    timings: &FrameTimings,
+    budgets: &FrameTimingBudgets,
+    frame_ms: f32,
+) {
+    let (rect, _) = ui.allocate_exact_size(BAR_SIZE.into(), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0., egui::Color32::from_black_alpha(96));
+
+    let over = |section: TimedSection| timings.averages[section as usize] > budgets.limit(section);
+    let color = |section: TimedSection| {
+        if over(section) {
+            egui::Color32::LIGHT_RED
+        } else {
+            section.color()
+        }
+    };
+
+    let total = frame_ms.max(timings.averages.iter().sum());
+    if total <= 0. {
+        return;
+    }
+
+    let mut left = rect.left();
+    for section in TimedSection::ALL {
+        let width = rect.width() * timings.averages[section as usize] / total;
+        let segment = egui::Rect::from_min_max(
+            egui::pos2(left, rect.top()),
+            egui::pos2(left + width, rect.bottom()),
+        );
+        painter.rect_filled(segment, 0., color(section));
+        left += width;
+    }
+
+    for section in TimedSection::ALL {
+        let ms = timings.averages[section as usize];
+        if ms < SHOWN_MS && !over(section) {
+            continue;
+        }
+        ui.label(
+            egui::RichText::new(format!("{:<12} {ms:>5.2}ms", section.label()))
+                .monospace()
+                .color(color(section)),
+        );
+    }
+}
@@ -1,9 +1,67 @@
-use bevy::prelude::*;
-use leafwing_input_manager::prelude::*;
-use strum::{
-    EnumIter,
-    IntoEnumIterator,
+use crate::{
+    actor::SpaceshipControl,
+    audio::UiSoundEvent,
+    config::SettingsAppExt,
+    console::{
+        Console,
+        ConsoleAppExt,
+    },
+    dump::DumpAppExt,
+    input_buffer::{
+        buffer_presses,
+        clear_buffered_presses,
+        clear_double_taps,
+        detect_double_taps,
+        BufferedAction,
+        DoubleTapDetector,
+    },
+    key_bindings::{
+        apply_key_bindings,
+        suppress_chorded_ship_actions,
+        Binding,
+        KeyBindings,
+        RebuildInputMaps,
+    },
+    replay::{
+        activate_pending_replay,
+        checksum_replay_state,
+        count_replay_ticks,
+        is_playing_back,
+        is_recording,
+        is_replay_active,
+        is_replay_pending,
+        play_back_ship_input,
+        record_ship_input,
+        replay_from_console,
+        start_pending_replay,
+        stop_replay_at_game_over,
+        stop_replay_at_recorded_end,
+        toggle_recording,
+        InputRecorder,
+        ReplayConfig,
+    },
+    schedule::FrameSet,
+    scoring::reset_score,
+    state::{
+        is_paused,
+        GameState,
+        InputContext,
+        IsPaused,
+        PlayingGame,
+    },
+    ui::SettingsMenu,
 };
+use bevy::{
+    prelude::*,
+    utils::HashSet,
+};
+#[cfg(dev_tools)]
+use bevy_inspector_egui::quick::ResourceInspectorPlugin;
+use leafwing_input_manager::{
+    plugin::InputManagerSystem,
+    prelude::*,
+};
+use strum::EnumIter;
 
 pub struct InputPlugin;
 
@@ -11,42 +69,154 @@ impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<GlobalAction>::default())
             .init_resource::<ActionState<GlobalAction>>()
-            .insert_resource(GlobalAction::global_input_map());
+            .add_plugins(InputManagerPlugin::<MenuAction>::default())
+            .init_resource::<ActionState<MenuAction>>()
+            .insert_resource(MenuAction::input_map())
+            .init_resource::<OpenPanels>()
+            .init_resource::<InputRecorder>()
+            .init_resource::<BufferedAction<SpaceshipControl>>()
+            .init_resource::<DoubleTapDetector<SpaceshipControl>>()
+            .add_event::<RebuildInputMaps>()
+            .register_settings::<KeyBindings>("key_bindings")
+            .register_settings::<ReplayConfig>("replay");
+
+        // the settings are read while the app is built, so the maps can be
+        // there from the start rather than only once the first frame has run
+        let key_bindings = app.world().resource::<KeyBindings>();
+        let global_map = key_bindings.input_map::<GlobalAction>();
+        let ship_map = key_bindings.input_map::<SpaceshipControl>();
+
+        app.insert_resource(global_map)
+            .insert_resource(ship_map)
+            .add_dump_action_log::<GlobalAction>("global_actions")
+            // after leafwing has updated the action states this frame, before
+            // anything reads them
+            .add_systems(
+                PreUpdate,
+                (
+                    suppress_chorded_ship_actions,
+                    record_ship_input.run_if(is_recording),
+                    play_back_ship_input.run_if(is_playing_back),
+                    // once the action states are what the ship will see
+                    (
+                        buffer_presses::<SpaceshipControl>,
+                        detect_double_taps::<SpaceshipControl>,
+                    ),
+                )
+                    .chain()
+                    .in_set(InputManagerSystem::ManualControl),
+            )
+            .add_systems(OnExit(PlayingGame), clear_buffered_presses::<SpaceshipControl>)
+            // a tap before a menu opened and one after it closed aren't a pair
+            .add_systems(
+                OnExit(InputContext::Gameplay),
+                clear_double_taps::<SpaceshipControl>,
+            )
+            .add_systems(
+                Update,
+                (
+                    apply_key_bindings,
+                    toggle_sounds,
+                    (track_open_panels, update_input_context).chain(),
+                    disable_new_ship_actions.run_if(not(in_state(InputContext::Gameplay))),
+                    toggle_recording.run_if(in_state(PlayingGame)),
+                )
+                    .in_set(FrameSet::Input),
+            )
+            // recording and playback both start from a fresh run - see
+            // replay/mod.rs
+            .add_systems(
+                OnEnter(GameState::RunSetup),
+                (start_pending_replay, reset_score).run_if(is_replay_pending),
+            )
+            .add_systems(OnExit(GameState::RunSetup), activate_pending_replay)
+            .add_systems(OnEnter(GameState::GameOver), stop_replay_at_game_over)
+            .add_systems(
+                FixedFirst,
+                (count_replay_ticks, stop_replay_at_recorded_end)
+                    .chain()
+                    .run_if(is_replay_active)
+                    .run_if(in_state(IsPaused::NotPaused)),
+            )
+            .add_systems(
+                FixedLast,
+                checksum_replay_state
+                    .run_if(is_replay_active)
+                    .run_if(in_state(IsPaused::NotPaused)),
+            )
+            .add_console_command(
+                "replay",
+                "play back the recorded run from the start of a new one",
+                replay_from_console,
+            )
+            .add_systems(OnExit(InputContext::Gameplay), disable_ship_actions)
+            .add_systems(OnEnter(InputContext::Gameplay), enable_ship_actions)
+            // the menus' keys are escape and the arrows, which mean other
+            // things the rest of the time
+            .add_systems(Startup, disable_menu_actions)
+            .add_systems(OnEnter(IsPaused::Paused), enable_menu_actions)
+            .add_systems(OnExit(IsPaused::Paused), disable_menu_actions)
+            .add_systems(OnEnter(GameState::MainMenu), enable_menu_actions)
+            .add_systems(OnExit(GameState::MainMenu), disable_menu_actions)
+            .add_systems(OnEnter(GameState::GameOver), enable_menu_actions)
+            .add_systems(OnExit(GameState::GameOver), disable_menu_actions);
     }
 }
 
 // inspector windows don't open full size
 #[derive(Actionlike, EnumIter, Reflect, PartialEq, Eq, Clone, Copy, Hash, Debug)]
 pub enum GlobalAction {
-    AABBs,
+    AudioInspector,
     BoundaryInspector,
     CameraConfigInspector,
+    Console,
+    CycleBoundaryPreset,
+    CycleBoundaryRenderMode,
+    CycleNateroidVariant,
     Debug,
+    Diagnostics,
+    DiagnosticsDump,
+    GizmoLines,
+    InputHelp,
     LightsInspector,
     MissileInspector,
     NateroidInspector,
-    PhysicsAABB,
+    PhysicsDebug,
     PlanesInspector,
     PortalInspector,
     Pause,
+    RebindControls,
+    Screenshot,
+    Settings,
     SpaceshipInspector,
     SpaceshipControlInspector,
     Stars,
+    StepSimulation,
+    StressTest,
     SuppressNateroids,
+    TeleportStatsInspector,
+    TogglePip,
+    ToggleProjection,
+    ToggleRecording,
+    ToggleSteeringMode,
+    TimeScaleTenth,
+    TimeScaleHalf,
+    TimeScaleNormal,
+    TimeScaleDouble,
 }
 
 /// GlobalActions assign keys to do a lot of obvious stuff. Debug is less
 /// obvious.
 ///
 /// Use Debug like this - invoke it with a system as follows:
-/// ```rust
+/// ```ignore
 /// app.add_systems(Update, my_debug_system.run_if(toggle_active(false, GlobalAction::Debug))
 /// ```
 /// useful when you want to limit the amount of info that is being emitted
 ///
 /// similarly you can also ask for the GlobalAction and use it in your code
 /// directly
-/// ```rust
+/// ```ignore
 /// fn my_system(user_input: Res<ActionState<GlobalAction>>) {
 ///    if user_input.pressed(&GlobalAction::Debug) {
 ///       // whatever debug statements you're using will only happen while you
@@ -54,41 +224,251 @@ pub enum GlobalAction {
 /// }
 /// ```
 impl GlobalAction {
-    pub fn global_input_map() -> InputMap<Self> {
-        fn insert_shift_input(
-            input_map: InputMap<GlobalAction>,
-            action: GlobalAction,
-            key: KeyCode,
-        ) -> InputMap<GlobalAction> {
-            input_map.with_one_to_many(
-                action,
-                [
-                    ButtonlikeChord::new([KeyCode::ShiftLeft]).with(key),
-                    ButtonlikeChord::new([KeyCode::ShiftRight]).with(key),
-                ],
-            )
+    /// what each action is bound to until KeyBindings says otherwise
+    pub fn default_bindings(self) -> Vec<Binding> {
+        // the inspectors and the stress test aren't built into release builds
+        // (see build.rs) so there's nothing for these keys to do
+        let dev_tool = |key: KeyCode| {
+            if cfg!(dev_tools) {
+                vec![Binding::Shift(key)]
+            } else {
+                Vec::new()
+            }
+        };
+
+        match self {
+            Self::AudioInspector => dev_tool(KeyCode::Digit5),
+            Self::BoundaryInspector => dev_tool(KeyCode::KeyB),
+            Self::CameraConfigInspector => dev_tool(KeyCode::KeyC),
+            // the debug console, quake style - also only in dev tool builds
+            Self::Console if cfg!(dev_tools) => vec![Binding::Key(KeyCode::Backquote)],
+            Self::Console => Vec::new(),
+            Self::CycleBoundaryPreset => dev_tool(KeyCode::KeyN),
+            Self::CycleBoundaryRenderMode => vec![Binding::Shift(KeyCode::KeyV)],
+            Self::CycleNateroidVariant if cfg!(dev_tools) => vec![Binding::Ctrl(KeyCode::KeyV)],
+            Self::CycleNateroidVariant => Vec::new(),
+            Self::Debug => vec![Binding::Shift(KeyCode::KeyD)],
+            Self::Diagnostics => vec![Binding::Key(KeyCode::F5)],
+            Self::DiagnosticsDump => vec![Binding::Shift(KeyCode::F12)],
+            Self::GizmoLines => vec![Binding::Key(KeyCode::F6)],
+            Self::InputHelp => vec![Binding::Shift(KeyCode::KeyH)],
+            Self::LightsInspector => dev_tool(KeyCode::KeyL),
+            Self::MissileInspector => dev_tool(KeyCode::Digit1),
+            Self::NateroidInspector => dev_tool(KeyCode::Digit2),
+            Self::Pause => vec![Binding::Key(KeyCode::Escape)],
+            Self::PhysicsDebug => vec![Binding::Key(KeyCode::F2)],
+            Self::PlanesInspector => dev_tool(KeyCode::KeyP),
+            Self::PortalInspector => dev_tool(KeyCode::KeyG),
+            Self::RebindControls => vec![Binding::Key(KeyCode::F1)],
+            // f10 on its own is the settings menu
+            Self::Screenshot => vec![Binding::Shift(KeyCode::F10)],
+            Self::Settings => vec![Binding::Key(KeyCode::F10)],
+            Self::SpaceshipInspector => dev_tool(KeyCode::Digit3),
+            Self::SpaceshipControlInspector => dev_tool(KeyCode::Digit4),
+            Self::Stars => vec![Binding::Key(KeyCode::F3)],
+            Self::StepSimulation => dev_tool(KeyCode::Period),
+            Self::StressTest => dev_tool(KeyCode::KeyT),
+            Self::SuppressNateroids => vec![Binding::Key(KeyCode::F4)],
+            Self::TeleportStatsInspector => dev_tool(KeyCode::Digit6),
+            Self::TogglePip => vec![Binding::Key(KeyCode::KeyP)],
+            Self::ToggleProjection => vec![Binding::Key(KeyCode::KeyO)],
+            // the newer dev keys go on ctrl - shift is getting crowded
+            Self::ToggleRecording if cfg!(dev_tools) => vec![Binding::Ctrl(KeyCode::KeyR)],
+            Self::ToggleRecording => Vec::new(),
+            Self::ToggleSteeringMode => vec![Binding::Key(KeyCode::KeyM)],
+            Self::TimeScaleTenth => dev_tool(KeyCode::F7),
+            Self::TimeScaleHalf => dev_tool(KeyCode::F8),
+            Self::TimeScaleNormal => dev_tool(KeyCode::F9),
+            Self::TimeScaleDouble => dev_tool(KeyCode::F11),
         }
+    }
+}
 
-        // while fold accumulates each pass - we just do an insert each time as a
-        // statement and then return the map at the end of each iteration so the
-        // accumulation works
-        Self::iter().fold(InputMap::default(), |input_map, action| match action {
-            Self::AABBs => input_map.with(action, KeyCode::F1),
-            Self::BoundaryInspector => insert_shift_input(input_map, action, KeyCode::KeyB),
-            Self::CameraConfigInspector => insert_shift_input(input_map, action, KeyCode::KeyC),
-            Self::Debug => insert_shift_input(input_map, action, KeyCode::KeyD),
-            Self::LightsInspector => insert_shift_input(input_map, action, KeyCode::KeyL),
-            Self::MissileInspector => insert_shift_input(input_map, action, KeyCode::Digit1),
-            Self::NateroidInspector => insert_shift_input(input_map, action, KeyCode::Digit2),
-            Self::Pause => input_map.with(action, KeyCode::Escape),
-            Self::PhysicsAABB => input_map.with(action, KeyCode::F2),
-            Self::PlanesInspector => insert_shift_input(input_map, action, KeyCode::KeyP),
-            Self::PortalInspector => insert_shift_input(input_map, action, KeyCode::KeyG),
-            Self::SpaceshipInspector => insert_shift_input(input_map, action, KeyCode::Digit3),
-            Self::SpaceshipControlInspector => insert_shift_input(input_map, action, KeyCode::Digit4),
-            Self::Stars => input_map.with(action, KeyCode::F3),
-            Self::SuppressNateroids => input_map.with(action, KeyCode::F4),
-        })
+/// getting around the menus - only live while one of them is up. left and
+/// right nudge a setting, the tab actions flip through the settings tabs
+#[derive(Actionlike, EnumIter, Reflect, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    PreviousTab,
+    NextTab,
+    Select,
+    Back,
+}
+
+impl MenuAction {
+    // not rebindable - escape, enter and the arrows are what everyone tries
+    // first in a menu
+    fn input_map() -> InputMap<Self> {
+        InputMap::default()
+            .with_one_to_many(Self::Up, [KeyCode::ArrowUp, KeyCode::KeyW])
+            .with(Self::Up, GamepadButton::DPadUp)
+            .with_one_to_many(Self::Down, [KeyCode::ArrowDown, KeyCode::KeyS])
+            .with(Self::Down, GamepadButton::DPadDown)
+            .with_one_to_many(Self::Left, [KeyCode::ArrowLeft, KeyCode::KeyA])
+            .with(Self::Left, GamepadButton::DPadLeft)
+            .with_one_to_many(Self::Right, [KeyCode::ArrowRight, KeyCode::KeyD])
+            .with(Self::Right, GamepadButton::DPadRight)
+            .with(Self::PreviousTab, KeyCode::KeyQ)
+            .with(Self::PreviousTab, GamepadButton::LeftTrigger)
+            .with(Self::NextTab, KeyCode::KeyE)
+            .with(Self::NextTab, GamepadButton::RightTrigger)
+            .with_one_to_many(Self::Select, [KeyCode::Enter, KeyCode::Space])
+            .with(Self::Select, GamepadButton::South)
+            .with(Self::Back, KeyCode::Escape)
+            .with(Self::Back, GamepadButton::East)
+    }
+}
+
+fn enable_menu_actions(mut menu_state: ResMut<ActionState<MenuAction>>) { menu_state.enable(); }
+
+fn disable_menu_actions(mut menu_state: ResMut<ActionState<MenuAction>>) { menu_state.disable(); }
+
+impl GlobalAction {
+    // everything that flips something on or off via toggle_active - pause has its
+    // own sounds, debug is held rather than toggled, settings and the console
+    // are menus, the time controls set rather than flip, recording restarts
+    // the run, steering is a resource with its own announcement and a dump, a
+    // preset change, the boundary's render mode or a nateroid's look goes
+    // round rather than flipping, the projection is a camera setting and a
+    // screenshot is a one-off
+    fn is_toggle(self) -> bool {
+        !matches!(
+            self,
+            Self::Console
+                | Self::CycleBoundaryPreset
+                | Self::CycleBoundaryRenderMode
+                | Self::CycleNateroidVariant
+                | Self::Debug
+                | Self::DiagnosticsDump
+                | Self::Pause
+                | Self::Screenshot
+                | Self::Settings
+                | Self::StepSimulation
+                | Self::TimeScaleTenth
+                | Self::TimeScaleHalf
+                | Self::TimeScaleNormal
+                | Self::TimeScaleDouble
+                | Self::ToggleProjection
+                | Self::ToggleRecording
+                | Self::ToggleSteeringMode
+        )
+    }
+}
+
+impl GlobalAction {
+    // every action that add_inspector opens a window with
+    fn is_inspector(self) -> bool {
+        matches!(
+            self,
+            Self::AudioInspector
+                | Self::BoundaryInspector
+                | Self::CameraConfigInspector
+                | Self::LightsInspector
+                | Self::MissileInspector
+                | Self::NateroidInspector
+                | Self::PlanesInspector
+                | Self::PortalInspector
+                | Self::SpaceshipInspector
+                | Self::SpaceshipControlInspector
+                | Self::TeleportStatsInspector
+        )
+    }
+}
+
+// the inspectors and the rebind panel that are open right now. each one's
+// toggle_active keeps its own state where nothing else can see it, so this
+// flips along with them on the same presses
+#[derive(Resource, Debug, Default)]
+struct OpenPanels(HashSet<GlobalAction>);
+
+fn track_open_panels(user_input: Res<ActionState<GlobalAction>>, mut open: ResMut<OpenPanels>) {
+    let toggled = user_input
+        .get_just_pressed()
+        .into_iter()
+        .filter(|action| action.is_inspector() || *action == GlobalAction::RebindControls);
+
+    for action in toggled {
+        if !open.0.remove(&action) {
+            open.0.insert(action);
+        }
+    }
+}
+
+// a menu wins over an inspector - closing the last of everything puts the
+// ship back in your hands. being paused is the pause menu being up, and game
+// over is a menu too
+fn update_input_context(
+    open: Res<OpenPanels>,
+    settings_menu: Res<SettingsMenu>,
+    console: Res<Console>,
+    paused: Option<Res<State<IsPaused>>>,
+    game_state: Res<State<GameState>>,
+    context: Res<State<InputContext>>,
+    mut next_context: ResMut<NextState<InputContext>>,
+) {
+    let paused = is_paused(paused);
+    let wanted = if settings_menu.is_open()
+        || console.is_open()
+        || paused
+        || *game_state.get() == GameState::GameOver
+        || open.0.contains(&GlobalAction::RebindControls)
+    {
+        InputContext::Menu
+    } else if open.0.iter().any(|action| action.is_inspector()) {
+        InputContext::Inspecting
+    } else {
+        InputContext::Gameplay
+    };
+
+    if *context.get() != wanted {
+        next_context.set(wanted);
+    }
+}
+
+// the ship's keys are for the ship only while you're playing - typing into an
+// inspector or a menu shouldn't fly it around. the global actions stay live
+// everywhere, as they're what closes things again
+fn disable_ship_actions(
+    mut ship_state: ResMut<ActionState<SpaceshipControl>>,
+    mut q_ship_states: Query<&mut ActionState<SpaceshipControl>>,
+) {
+    ship_state.disable();
+    for mut action_state in q_ship_states.iter_mut() {
+        action_state.disable();
+    }
+}
+
+fn enable_ship_actions(
+    mut ship_state: ResMut<ActionState<SpaceshipControl>>,
+    mut q_ship_states: Query<&mut ActionState<SpaceshipControl>>,
+) {
+    ship_state.enable();
+    for mut action_state in q_ship_states.iter_mut() {
+        action_state.enable();
+    }
+}
+
+// a spaceship spawned with an inspector open starts out with its input off too
+fn disable_new_ship_actions(
+    mut q_ship_states: Query<&mut ActionState<SpaceshipControl>, Added<ActionState<SpaceshipControl>>>,
+) {
+    for mut action_state in q_ship_states.iter_mut() {
+        action_state.disable();
+    }
+}
+
+// toggle_active is a run condition so it can't send anything itself - listen
+// for the same presses here instead
+fn toggle_sounds(user_input: Res<ActionState<GlobalAction>>, mut ui_sounds: EventWriter<UiSoundEvent>) {
+    if user_input
+        .get_just_pressed()
+        .into_iter()
+        .any(GlobalAction::is_toggle)
+    {
+        ui_sounds.send(UiSoundEvent::Toggle);
     }
 }
 
@@ -101,8 +481,8 @@ impl GlobalAction {
 /// entries so we can have simple syntax for toggling systems as a run condition
 /// as follows:
 ///
-/// ```
-/// .add_systems(Update, my_system.run_if(toggle_active(false, GlobalAction::AABBs)))
+/// ```ignore
+/// .add_systems(Update, my_system.run_if(toggle_active(false, GlobalAction::GizmoLines)))
 /// ```
 /// cool, huh? the fact that the closure works with Bevy's dependency injection
 /// is rocket science to me- i don't know how it knows to do this but it does
@@ -123,6 +503,23 @@ pub fn toggle_active(
     }
 }
 
+/// adds a resource inspector that the given action toggles open - only in dev
+/// builds, release builds get the resource without the inspector (see
+/// build.rs)
+pub trait InspectorAppExt {
+    fn add_inspector<T: Resource + Reflect>(&mut self, action: GlobalAction) -> &mut Self;
+}
+
+impl InspectorAppExt for App {
+    #[cfg(dev_tools)]
+    fn add_inspector<T: Resource + Reflect>(&mut self, action: GlobalAction) -> &mut Self {
+        self.add_plugins(ResourceInspectorPlugin::<T>::default().run_if(toggle_active(false, action)))
+    }
+
+    #[cfg(not(dev_tools))]
+    fn add_inspector<T: Resource + Reflect>(&mut self, _action: GlobalAction) -> &mut Self { self }
+}
+
 #[derive(Default)]
 pub struct ToggleState {
     pub state: bool,
@@ -0,0 +1,570 @@
+use crate::{
+    actor::{
+        screen_side,
+        ActorKind,
+        MissileFired,
+        Spaceship,
+        SpaceshipControl,
+    },
+    budget::BudgetUsage,
+    camera::PrimaryCamera,
+    despawn::ActorDestroyed,
+    mutators::{
+        Mutator,
+        MutatorSet,
+        Mutators,
+    },
+    playfield::SpatialIndex,
+    replay::{
+        record_ship_input,
+        stop_recording_here,
+        InputRecorder,
+        ReplayLog,
+    },
+    rng::GameRng,
+    schedule::FrameSet,
+    scoring::Score,
+    state::{
+        GameState,
+        PlayingGame,
+        PLAY_AGAIN,
+    },
+};
+use bevy::{
+    app::{
+        PluginGroupBuilder,
+        ScheduleRunnerPlugin,
+    },
+    prelude::*,
+    render::{
+        settings::WgpuSettings,
+        RenderPlugin,
+    },
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use leafwing_input_manager::{
+    plugin::InputManagerSystem,
+    prelude::*,
+};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+// every update moves the clock on by exactly one frame at 60fps, however long
+// the update actually took - so a run is as fast as the cpu allows
+const SIMULATED_FRAME: Duration = Duration::from_nanos(16_666_667);
+const DEFAULT_RUN_SECONDS: f32 = 300.;
+// how far off the nose the autopilot lets a nateroid get before it turns, and
+// twice that before it turns again - so it isn't forever letting go of a turn
+// and pressing it again
+const AUTOPILOT_AIM: f32 = 0.08;
+const AUTOPILOT_RANGE: f32 = 150.;
+// a press fires once, see should_fire - five a second
+const AUTOPILOT_FIRE_FRAMES: u32 = 12;
+// longer than DOUBLE_TAP_GAP, so a turn that's let go of and pressed again
+// isn't taken for a dash
+const AUTOPILOT_TURN_GAP: u32 = 20;
+
+/// the whole game with no window, no gpu and no sound:
+///
+/// `cargo run --release --features headless -- --seconds 600`
+///
+/// it plays until the spaceship is destroyed or the time runs out and then
+/// prints a line of json with how the run went. an autopilot flies the
+/// spaceship - it turns towards the closest nateroid and shoots at it, and
+/// doesn't thrust or dodge. it's there to be the same every run, not to be
+/// good, so the numbers move when the game does. `--no-autopilot` leaves the
+/// spaceship a sitting duck
+///
+/// there's nobody to pick mutators so the main menu, the run setup screen and
+/// the game over screen are skipped - each game starts with whatever Mutators
+/// already holds, none unless something else set them. `--mutator "tiny ship"`
+/// turns one on, by the name the run setup screen gives it - it can be given
+/// more than once
+///
+/// `--soak` keeps starting new games until the time runs out and fails the run
+/// (a non-zero exit) if anything went over its memory budget along the way.
+/// an hour of game time:
+///
+/// `cargo run --release --features headless -- --seconds 3600 --soak`
+///
+/// tests/soak.rs is the same run with the entity, portal and wrap counts
+/// checked as it goes - it's ignored unless it's asked for
+///
+/// for balancing, `--waves` plays that many games back to back with no time
+/// limit unless `--seconds` sets one, and prints a line of json at the end of
+/// each. there are no waves in the game yet, so a wave is a whole game - from
+/// a fresh spaceship to the last one going. `--seed` makes the whole thing
+/// repeatable, see GameRng. the balance_sim example is the same thing without
+/// the feature:
+///
+/// `cargo run --release --example balance_sim -- --waves 50 --seed 42`
+///
+/// `--record run.ntr` saves the run, autopilot and all, as a replay when the
+/// spaceship's gone - there's no time limit unless `--seconds` sets one, and
+/// a run the time limit ends is saved as far as it got. `--replay run.ntr`
+/// plays one back, prints how it compared and fails if it went differently -
+/// see src/replay and tests/replays.rs
+pub struct HeadlessPlugin {
+    // the command line, or what a test asked for instead
+    args: Vec<String>,
+}
+
+impl Default for HeadlessPlugin {
+    fn default() -> Self { Self::with_args(std::env::args().skip(1)) }
+}
+
+impl HeadlessPlugin {
+    pub fn with_args(args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(SIMULATED_FRAME))
+            .insert_resource(HeadlessRun::parse(self.args.iter().cloned()))
+            .add_systems(Startup, start_replay_or_recording)
+            .add_systems(
+                OnEnter(GameState::RunSetup),
+                hand_over_to_autopilot.run_if(autopilot_flying),
+            )
+            // where a player's presses would be, so a recording sees them
+            .add_systems(
+                PreUpdate,
+                fly_autopilot
+                    .run_if(autopilot_flying)
+                    .run_if(in_state(PlayingGame))
+                    .in_set(InputManagerSystem::ManualControl)
+                    .before(record_ship_input),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), skip_main_menu)
+            .add_systems(OnEnter(GameState::RunSetup), skip_run_setup)
+            .add_systems(OnEnter(GameState::GameOver), finish_game)
+            .add_systems(OnEnter(GameState::LoadFailed), exit_on_load_failure)
+            .add_systems(
+                Update,
+                (
+                    record_run.run_if(in_state(PlayingGame)),
+                    finish_replay,
+                    stop_recording_here.run_if(out_of_time),
+                    finish_run,
+                    skip_game_over.run_if(in_state(GameState::GameOver)),
+                )
+                    .chain()
+                    .in_set(FrameSet::Ui),
+            );
+    }
+}
+
+/// DefaultPlugins with the window, the event loop and the gpu taken out -
+/// everything else stays so the assets still load and the scenes still give
+/// the colliders their sizes
+pub fn default_plugins() -> PluginGroupBuilder {
+    DefaultPlugins
+        .set(WindowPlugin {
+            primary_window:       None,
+            exit_condition:       ExitCondition::DontExit,
+            close_when_requested: false,
+        })
+        .set(RenderPlugin {
+            render_creation: WgpuSettings {
+                backends: None,
+                ..default()
+            }
+            .into(),
+            ..default()
+        })
+        .disable::<WinitPlugin>()
+}
+
+// there's no screen to say so, and the run would never start - the paths
+// that failed are already in the log
+fn exit_on_load_failure(mut exit: EventWriter<AppExit>) { exit.send(AppExit::error()); }
+
+#[derive(Resource, Debug, Default)]
+struct HeadlessRun {
+    seconds:             f32,
+    soak:                bool,
+    autopilot:           bool,
+    waves:               Option<u32>,
+    replay:              Option<String>,
+    record:              Option<String>,
+    mutators:            MutatorSet,
+    played:              f32,
+    games:               u32,
+    missiles_fired:      u32,
+    nateroids_destroyed: u32,
+    // the totals when the game that's going now started
+    game_start:          GameTotals,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct GameTotals {
+    played:              f32,
+    missiles_fired:      u32,
+    nateroids_destroyed: u32,
+}
+
+impl HeadlessRun {
+    // logging isn't set up yet while plugins are being built
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let mut seconds = None;
+        let mut soak = false;
+        let mut autopilot = true;
+        let mut waves = None;
+        let mut replay = None;
+        let mut record = None;
+        let mut mutators = MutatorSet::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seconds" => match args.next().and_then(|value| value.parse().ok()) {
+                    Some(value) => seconds = Some(value),
+                    None => eprintln!("--seconds needs a number, using the default"),
+                },
+                "--soak" => soak = true,
+                "--no-autopilot" => autopilot = false,
+                "--waves" => match args.next().and_then(|value| value.parse().ok()) {
+                    Some(value) => waves = Some(value),
+                    None => eprintln!("--waves needs a whole number, playing one game"),
+                },
+                "--replay" => match args.next() {
+                    Some(path) => replay = Some(path),
+                    None => eprintln!("--replay needs a file to play"),
+                },
+                "--record" => match args.next() {
+                    Some(path) => record = Some(path),
+                    None => eprintln!("--record needs a file to save to"),
+                },
+                "--mutator" => match args
+                    .next()
+                    .and_then(|name| Mutator::iter().find(|mutator| mutator.label() == name))
+                {
+                    Some(mutator) => mutators.set(mutator, true),
+                    None => eprintln!("--mutator needs one of the run setup screen's names"),
+                },
+                // settings overrides, see config - and the seed, see GameRng
+                "--set" | "--seed" => {
+                    args.next();
+                },
+                other => eprintln!("ignoring unknown argument {other}"),
+            }
+        }
+
+        // a balancing run plays all its waves unless it's told otherwise, and
+        // a replay goes as long as it goes
+        let unlimited = waves.is_some() || replay.is_some() || record.is_some();
+        let seconds = seconds.unwrap_or(if unlimited {
+            f32::INFINITY
+        } else {
+            DEFAULT_RUN_SECONDS
+        });
+
+        Self {
+            seconds,
+            soak,
+            // a replay has its own presses to play
+            autopilot: autopilot && replay.is_none(),
+            waves,
+            replay,
+            record,
+            mutators,
+            ..default()
+        }
+    }
+
+    fn totals(&self) -> GameTotals {
+        GameTotals {
+            played:              self.played,
+            missiles_fired:      self.missiles_fired,
+            nateroids_destroyed: self.nateroids_destroyed,
+        }
+    }
+
+    // another game after this one - soak keeps going until the time runs out
+    fn wants_another_game(&self) -> bool {
+        match self.waves {
+            Some(waves) => self.games < waves,
+            None => self.soak,
+        }
+    }
+}
+
+// before the main menu is skipped, so the first run is the one that's played
+// back or recorded
+fn start_replay_or_recording(
+    run: Res<HeadlessRun>,
+    mut recorder: ResMut<InputRecorder>,
+    mut mutators: ResMut<Mutators>,
+    mut exit: EventWriter<AppExit>,
+) {
+    // a replay puts back the ones it was recorded with
+    if !run.mutators.is_empty() {
+        mutators.active = run.mutators;
+    }
+
+    if let Some(path) = &run.record {
+        recorder.record_to(path.clone());
+    }
+
+    let Some(path) = &run.replay else {
+        return;
+    };
+    let loaded = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| ReplayLog::from_text(&text));
+    match loaded {
+        Ok(log) => recorder.play(log),
+        Err(e) => {
+            eprintln!("{path}: {e}");
+            exit.send(AppExit::error());
+        },
+    }
+}
+
+fn record_run(
+    time: Res<Time>,
+    mut run: ResMut<HeadlessRun>,
+    mut missiles_fired: EventReader<MissileFired>,
+    mut destroyed: EventReader<ActorDestroyed>,
+) {
+    run.played += time.delta_secs();
+    run.missiles_fired += missiles_fired.read().count() as u32;
+    run.nateroids_destroyed += destroyed
+        .read()
+        .filter(|event| event.kind == ActorKind::Nateroid)
+        .count() as u32;
+}
+
+fn autopilot_flying(run: Res<HeadlessRun>) -> bool { run.autopilot }
+
+fn out_of_time(run: Res<HeadlessRun>) -> bool { run.played >= run.seconds }
+
+// the same as a replay - with nothing bound leafwing leaves the spaceship's
+// actions alone, so they stay whatever the autopilot set them to. playing
+// again doesn't come back through here, and the maps stay empty until then
+fn hand_over_to_autopilot(
+    mut ship_map: ResMut<InputMap<SpaceshipControl>>,
+    mut q_ship_maps: Query<&mut InputMap<SpaceshipControl>>,
+) {
+    *ship_map = InputMap::default();
+    for mut input_map in q_ship_maps.iter_mut() {
+        *input_map = InputMap::default();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Autopilot {
+    frame:       u32,
+    turning:     Option<SpaceshipControl>,
+    let_go_at:   u32,
+    last_turned: Option<SpaceshipControl>,
+}
+
+// the closest nateroid the short way round through the walls, from the
+// spatial index the frame before
+fn fly_autopilot(
+    mut autopilot: Local<Autopilot>,
+    spatial_index: Res<SpatialIndex>,
+    mutators: Res<Mutators>,
+    q_camera: Query<&Transform, With<PrimaryCamera>>,
+    mut q_spaceship: Query<(&Transform, &mut ActionState<SpaceshipControl>), With<Spaceship>>,
+) {
+    let (Ok(camera_transform), Ok((transform, mut controls))) =
+        (q_camera.get_single(), q_spaceship.get_single_mut())
+    else {
+        return;
+    };
+    autopilot.frame += 1;
+
+    let off_nose = spatial_index
+        .within_of_kind(transform.translation, AUTOPILOT_RANGE, ActorKind::Nateroid)
+        .min_by(|a, b| a.distance_squared.total_cmp(&b.distance_squared))
+        .map(|target| {
+            let heading = -transform.forward().as_vec3();
+            heading.truncate().angle_to(target.delta.truncate())
+        });
+
+    let mut turn = None;
+    let mut fire = false;
+    if let Some(off_nose) = off_nose {
+        let slack = if autopilot.turning.is_some() {
+            AUTOPILOT_AIM
+        } else {
+            AUTOPILOT_AIM * 2.
+        };
+        if off_nose.abs() > slack {
+            // off_nose is the way round z the target is
+            let right = -screen_side(camera_transform, &mutators);
+            turn = Some(if off_nose.signum() == right {
+                SpaceshipControl::TurnRight
+            } else {
+                SpaceshipControl::TurnLeft
+            });
+        }
+        fire = off_nose.abs() < AUTOPILOT_AIM * 2. && autopilot.frame.is_multiple_of(AUTOPILOT_FIRE_FRAMES);
+    }
+
+    // a turn the same way as the one just let go of waits out the double tap
+    if turn.is_some()
+        && turn != autopilot.turning
+        && turn == autopilot.last_turned
+        && autopilot.frame - autopilot.let_go_at < AUTOPILOT_TURN_GAP
+    {
+        turn = None;
+    }
+    if autopilot.turning.is_some() && turn != autopilot.turning {
+        autopilot.last_turned = autopilot.turning;
+        autopilot.let_go_at = autopilot.frame;
+    }
+    autopilot.turning = turn;
+
+    for action in [
+        SpaceshipControl::TurnLeft,
+        SpaceshipControl::TurnRight,
+        SpaceshipControl::Fire,
+    ] {
+        let wanted = turn == Some(action) || (action == SpaceshipControl::Fire && fire);
+        if wanted && !controls.pressed(&action) {
+            controls.press(&action);
+        } else if !wanted && controls.pressed(&action) {
+            controls.release(&action);
+        }
+    }
+}
+
+// a line per wave for a balancing run - the game's own numbers rather than the
+// running totals
+fn finish_game(mut run: ResMut<HeadlessRun>, score: Res<Score>) {
+    run.games += 1;
+    let totals = run.totals();
+
+    if run.waves.is_some() {
+        let start = run.game_start;
+        println!(
+            "{{\"wave\":{},\"seconds\":{:.2},\"score\":{},\"missiles_fired\":{},\"nateroids_destroyed\":{}}}",
+            run.games,
+            totals.played - start.played,
+            score.points,
+            totals.missiles_fired - start.missiles_fired,
+            totals.nateroids_destroyed - start.nateroids_destroyed,
+        );
+    }
+
+    run.game_start = totals;
+}
+
+// nobody's there to press enter, so it plays again the way enter would
+fn skip_game_over(mut next_state: ResMut<NextState<GameState>>) { next_state.set(PLAY_AGAIN.entered); }
+
+fn skip_main_menu(mut next_state: ResMut<NextState<GameState>>) { next_state.set(GameState::RunSetup); }
+
+fn skip_run_setup(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::InGame {
+        paused:     false,
+        inspecting: false,
+    });
+}
+
+// one line for the replay rather than the run's - and a replay that didn't go
+// the way the recording did fails, which is what ci is looking for
+fn finish_replay(run: Res<HeadlessRun>, recorder: Res<InputRecorder>, mut exit: EventWriter<AppExit>) {
+    let (Some(path), Some(report)) = (&run.replay, recorder.report()) else {
+        return;
+    };
+
+    let diverged_at = report
+        .diverged_at
+        .map_or("null".to_string(), |tick| tick.to_string());
+    println!(
+        "{{\"replay\":\"{path}\",\"ticks\":{},\"score\":{},\"recorded_score\":{},\"diverged_at\":{diverged_at}}}",
+        report.ticks, report.score, report.recorded_score,
+    );
+
+    exit.send(if report.matches() {
+        AppExit::Success
+    } else {
+        AppExit::error()
+    });
+}
+
+// a balancing script reads stdout so this is println rather than info - and
+// it's formatted by hand since there's nothing else in here that needs json
+fn finish_run(
+    run: Res<HeadlessRun>,
+    score: Res<Score>,
+    state: Res<State<GameState>>,
+    budget_usage: Res<BudgetUsage>,
+    mutators: Res<Mutators>,
+    rng: Res<GameRng>,
+    mut exit: EventWriter<AppExit>,
+) {
+    // finish_replay has that covered
+    if run.replay.is_some() {
+        return;
+    }
+
+    // a soak or balancing run goes straight on to the next game
+    let destroyed = *state.get() == GameState::GameOver;
+    if (run.wants_another_game() || !destroyed) && run.played < run.seconds {
+        return;
+    }
+
+    let over_budget: Vec<String> = budget_usage
+        .entries
+        .iter()
+        .filter(|entry| entry.peaked_over())
+        .map(|entry| format!("\"{}\"", entry.category.label()))
+        .collect();
+    let mutators: Vec<String> = mutators
+        .active
+        .iter()
+        .map(|mutator| format!("\"{}\"", mutator.label()))
+        .collect();
+
+    println!(
+        "{{\"seed\":{},\"seconds\":{:.2},\"games\":{},\"score\":{},\"missiles_fired\":{},\"nateroids_destroyed\":{},\"spaceship_destroyed\":{},\"mutators\":[{}],\"over_budget\":[{}]}}",
+        rng.seed(),
+        run.played,
+        run.games,
+        score.points,
+        run.missiles_fired,
+        run.nateroids_destroyed,
+        destroyed,
+        mutators.join(","),
+        over_budget.join(",")
+    );
+
+    exit.send(if run.soak && !over_budget.is_empty() {
+        AppExit::error()
+    } else {
+        AppExit::Success
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autopilot_flies_unless_told_not_to_or_replaying() {
+        assert!(HeadlessRun::parse(["--waves", "3"].map(String::from).into_iter()).autopilot);
+        assert!(!HeadlessRun::parse(["--no-autopilot"].map(String::from).into_iter()).autopilot);
+        assert!(!HeadlessRun::parse(["--replay", "run.ntr"].map(String::from).into_iter()).autopilot);
+    }
+
+    #[test]
+    fn mutators_are_picked_by_name() {
+        let run = HeadlessRun::parse(
+            ["--mutator", "tiny ship", "--mutator", "giant rocks", "--mutator", "nonsense"]
+                .map(String::from)
+                .into_iter(),
+        );
+        assert_eq!(run.mutators.summary(), "tiny ship, giant rocks");
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::{
+    prelude::*,
+    utils::HashMap,
+};
+use leafwing_input_manager::prelude::*;
+use std::time::Duration;
+
+/// when each of `A`'s actions was last pressed, for anything that can't act
+/// on a press the moment it happens - a fire cooldown that's a frame from
+/// done shouldn't eat the shot. a press is good for one use, and only within
+/// the window the reader asks for
+///
+/// the times are virtual time, so pausing doesn't run a press out
+#[derive(Resource, Debug)]
+pub struct BufferedAction<A: Actionlike> {
+    presses: HashMap<A, Duration>,
+}
+
+impl<A: Actionlike> Default for BufferedAction<A> {
+    fn default() -> Self {
+        Self {
+            presses: HashMap::default(),
+        }
+    }
+}
+
+impl<A: Actionlike> BufferedAction<A> {
+    /// true, once, if `action` was pressed no longer than `window` before
+    /// `now`
+    pub fn consume_if_buffered(&mut self, action: &A, now: Duration, window: Duration) -> bool {
+        match self.presses.remove(action) {
+            Some(pressed_at) => now.saturating_sub(pressed_at) <= window,
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) { self.presses.clear(); }
+}
+
+// a press on the spaceship's own action state or on the resource - either
+// one counts, the same as the dump's action log
+pub fn buffer_presses<A: Actionlike>(
+    time: Res<Time<Virtual>>,
+    mut buffer: ResMut<BufferedAction<A>>,
+    resource: Option<Res<ActionState<A>>>,
+    q_actions: Query<&ActionState<A>>,
+) {
+    let now = time.elapsed();
+    for action in resource
+        .iter()
+        .flat_map(|state| state.get_just_pressed())
+        .chain(q_actions.iter().flat_map(ActionState::get_just_pressed))
+    {
+        buffer.presses.insert(action, now);
+    }
+}
+
+pub fn clear_buffered_presses<A: Actionlike>(mut buffer: ResMut<BufferedAction<A>>) { buffer.clear(); }
+
+/// the last two presses of each of `A`'s actions, so a quick second press can
+/// mean something different from the first. only a press counts - holding
+/// the key down never makes a second one
+#[derive(Resource, Debug)]
+pub struct DoubleTapDetector<A: Actionlike> {
+    taps: HashMap<A, Taps>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Taps {
+    // none once a double tap has been used up, so a third tap starts over
+    previous: Option<Duration>,
+    latest:   Duration,
+}
+
+impl<A: Actionlike> Default for DoubleTapDetector<A> {
+    fn default() -> Self {
+        Self {
+            taps: HashMap::default(),
+        }
+    }
+}
+
+impl<A: Actionlike> DoubleTapDetector<A> {
+    /// true, once, if the last two presses of `action` were no more than
+    /// `max_gap` apart
+    pub fn double_tapped(&mut self, action: &A, max_gap: Duration) -> bool {
+        let Some(taps) = self.taps.get_mut(action) else {
+            return false;
+        };
+        let Some(previous) = taps.previous else {
+            return false;
+        };
+        if taps.latest.saturating_sub(previous) > max_gap {
+            return false;
+        }
+
+        taps.previous = None;
+        true
+    }
+
+    pub fn clear(&mut self) { self.taps.clear(); }
+}
+
+// the same presses buffer_presses sees, on the same clock
+pub fn detect_double_taps<A: Actionlike>(
+    time: Res<Time<Virtual>>,
+    mut detector: ResMut<DoubleTapDetector<A>>,
+    resource: Option<Res<ActionState<A>>>,
+    q_actions: Query<&ActionState<A>>,
+) {
+    let now = time.elapsed();
+    let mut pressed: Vec<A> = Vec::new();
+    for action in resource
+        .iter()
+        .flat_map(|state| state.get_just_pressed())
+        .chain(q_actions.iter().flat_map(ActionState::get_just_pressed))
+    {
+        // the resource and the spaceship both have it - that's one press
+        if !pressed.contains(&action) {
+            pressed.push(action);
+        }
+    }
+
+    for action in pressed {
+        let previous = detector.taps.get(&action).map(|taps| taps.latest);
+        detector.taps.insert(
+            action,
+            Taps {
+                previous,
+                latest: now,
+            },
+        );
+    }
+}
+
+pub fn clear_double_taps<A: Actionlike>(mut detector: ResMut<DoubleTapDetector<A>>) { detector.clear(); }
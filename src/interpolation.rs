@@ -0,0 +1,88 @@
+use crate::actor::Teleporter;
+use bevy::{
+    app::RunFixedMainLoopSystem,
+    prelude::*,
+};
+
+// the simulation ticks at a fixed rate in FixedUpdate and the screen refreshes
+// at whatever rate it likes. anything with an InterpolatedTransform is drawn
+// part way between its last two ticks so it moves smoothly at 144hz without
+// the simulation having to run at 144hz
+//
+// the trick is that the Transform only holds the in-between pose while Update
+// is running - it's put back to the real one before the fixed loop so that
+// physics and gameplay never see it
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            RunFixedMainLoop,
+            restore_simulated_transforms.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+        )
+        .add_systems(FixedLast, record_simulated_transforms)
+        .add_systems(
+            RunFixedMainLoop,
+            interpolate_transforms.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+        );
+    }
+}
+
+/// the pose at the previous and the latest fixed tick
+#[derive(Component, Debug, Clone, Copy)]
+pub struct InterpolatedTransform {
+    previous: Transform,
+    current:  Transform,
+}
+
+impl InterpolatedTransform {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current:  transform,
+        }
+    }
+}
+
+// the GlobalTransform goes back too - it was propagated from the in-between
+// pose, and rapier would take that as someone moving the body. actors have no
+// parent so the two are the same thing
+fn restore_simulated_transforms(
+    mut q_interpolated: Query<(&mut Transform, &mut GlobalTransform, &InterpolatedTransform)>,
+) {
+    for (mut transform, mut global_transform, interpolated) in q_interpolated.iter_mut() {
+        *transform = interpolated.current;
+        *global_transform = GlobalTransform::from(interpolated.current);
+    }
+}
+
+// an actor that just wrapped would otherwise be drawn sliding all the way
+// across the playfield for a frame, so it jumps straight to its new pose
+fn record_simulated_transforms(
+    mut q_interpolated: Query<(&Transform, &mut InterpolatedTransform, Option<&Teleporter>)>,
+) {
+    for (transform, mut interpolated, teleporter) in q_interpolated.iter_mut() {
+        interpolated.previous = if teleporter.is_some_and(|teleporter| teleporter.just_teleported) {
+            *transform
+        } else {
+            interpolated.current
+        };
+        interpolated.current = *transform;
+    }
+}
+
+fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut q_interpolated: Query<(&mut Transform, &InterpolatedTransform)>,
+) {
+    let t = fixed_time.overstep_fraction();
+
+    for (mut transform, interpolated) in q_interpolated.iter_mut() {
+        let (previous, current) = (interpolated.previous, interpolated.current);
+        *transform = Transform {
+            translation: previous.translation.lerp(current.translation, t),
+            rotation:    previous.rotation.slerp(current.rotation, t),
+            scale:       previous.scale.lerp(current.scale, t),
+        };
+    }
+}
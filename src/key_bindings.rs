@@ -0,0 +1,495 @@
+use crate::{
+    actor::SpaceshipControl,
+    config::save_settings,
+    global_input::GlobalAction,
+};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use std::any::Any;
+use strum::IntoEnumIterator;
+
+// a stick at rest never quite reads zero
+const STICK_DEAD_ZONE: f32 = 0.1;
+
+/// one input an action can be bound to - everything the defaults use and
+/// everything the rebind panel can capture. the axes are for the throttle and
+/// only ever come from the defaults or the settings file
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    // either shift with the key - the inspectors and other dev keys live here
+    // so they stay out of the way of the ship's bare letters
+    Shift(KeyCode),
+    // for when there's nothing left on shift
+    Ctrl(KeyCode),
+    Alt(KeyCode),
+    Mouse(MouseButton),
+    // the key that pulls the axis negative, then the one that pushes it
+    // positive
+    KeyAxis(KeyCode, KeyCode),
+    GamepadAxis(GamepadAxis),
+}
+
+impl Binding {
+    // the modifier held for a chord and the key pressed with it
+    fn chord(self) -> Option<(ModifierKey, KeyCode)> {
+        match self {
+            Binding::Shift(key) => Some((ModifierKey::Shift, key)),
+            Binding::Ctrl(key) => Some((ModifierKey::Control, key)),
+            Binding::Alt(key) => Some((ModifierKey::Alt, key)),
+            Binding::Key(_) | Binding::Mouse(_) | Binding::KeyAxis(..) | Binding::GamepadAxis(_) => None,
+        }
+    }
+
+    fn insert_into<A: Actionlike>(self, input_map: &mut InputMap<A>, action: A) {
+        match self {
+            Binding::Key(key) => {
+                input_map.insert(action, key);
+            },
+            Binding::Mouse(button) => {
+                input_map.insert(action, button);
+            },
+            Binding::KeyAxis(negative, positive) => {
+                input_map.insert_axis(action, VirtualAxis::new(negative, positive));
+            },
+            Binding::GamepadAxis(axis) => {
+                input_map.insert_axis(
+                    action,
+                    GamepadControlAxis::new(axis).with_deadzone_symmetric(STICK_DEAD_ZONE),
+                );
+            },
+            chord => {
+                if let Some((modifier, key)) = chord.chord() {
+                    input_map.insert_one_to_many(action, chorded(&[modifier], key));
+                }
+            },
+        }
+    }
+}
+
+/// `key` with all of `modifiers` held - each on either side of the keyboard,
+/// so it's one chord for every way of holding them
+pub fn chorded(modifiers: &[ModifierKey], key: KeyCode) -> Vec<ButtonlikeChord> {
+    let mut held: Vec<Vec<KeyCode>> = vec![Vec::new()];
+    for modifier in modifiers {
+        held = held
+            .into_iter()
+            .flat_map(|keys| {
+                [modifier.left(), modifier.right()].map(|side| {
+                    let mut keys = keys.clone();
+                    keys.push(side);
+                    keys
+                })
+            })
+            .collect();
+    }
+
+    held.into_iter()
+        .map(|keys| ButtonlikeChord::new(keys).with(key))
+        .collect()
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct ActionBindings<A> {
+    pub action: A,
+    pub inputs: Vec<Binding>,
+}
+
+/// the actions KeyBindings has a list for
+pub trait BoundAction: Actionlike + Copy + IntoEnumIterator {
+    fn default_bindings(self) -> Vec<Binding>;
+
+    fn bindings(key_bindings: &KeyBindings) -> &[ActionBindings<Self>];
+
+    fn bindings_mut(key_bindings: &mut KeyBindings) -> &mut Vec<ActionBindings<Self>>;
+}
+
+impl BoundAction for SpaceshipControl {
+    fn default_bindings(self) -> Vec<Binding> { SpaceshipControl::default_bindings(self) }
+
+    fn bindings(key_bindings: &KeyBindings) -> &[ActionBindings<Self>] { &key_bindings.ship }
+
+    fn bindings_mut(key_bindings: &mut KeyBindings) -> &mut Vec<ActionBindings<Self>> {
+        &mut key_bindings.ship
+    }
+}
+
+impl BoundAction for GlobalAction {
+    fn default_bindings(self) -> Vec<Binding> { GlobalAction::default_bindings(self) }
+
+    fn bindings(key_bindings: &KeyBindings) -> &[ActionBindings<Self>] { &key_bindings.global }
+
+    fn bindings_mut(key_bindings: &mut KeyBindings) -> &mut Vec<ActionBindings<Self>> {
+        &mut key_bindings.global
+    }
+}
+
+/// what every ship and global action is bound to. it's a settings section
+/// like any other, so it's loaded from the settings file before anything
+/// reads it and saved back whenever a rebind changes it - and a section that
+/// can't be read leaves the defaults in place. InputPlugin builds the input
+/// maps from it, and builds them again after every change
+#[derive(Resource, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct KeyBindings {
+    pub ship:   Vec<ActionBindings<SpaceshipControl>>,
+    pub global: Vec<ActionBindings<GlobalAction>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            ship:   defaults(),
+            global: defaults(),
+        }
+    }
+}
+
+fn defaults<A: BoundAction>() -> Vec<ActionBindings<A>> {
+    A::iter()
+        .map(|action| ActionBindings {
+            action,
+            inputs: action.default_bindings(),
+        })
+        .collect()
+}
+
+impl KeyBindings {
+    /// `action` answers to `input` and nothing else. anything else already
+    /// on it keeps it too - that's allowed, it's just worth a warning
+    pub fn rebind<A: BoundAction>(&mut self, action: A, input: Binding) {
+        // ship and global actions both, as the input maps are read side by side
+        warn_if_shared(&self.ship, action, input);
+        warn_if_shared(&self.global, action, input);
+
+        let bindings = A::bindings_mut(self);
+        match bindings.iter_mut().find(|bindings| bindings.action == action) {
+            Some(bindings) => bindings.inputs = vec![input],
+            None => bindings.push(ActionBindings {
+                action,
+                inputs: vec![input],
+            }),
+        }
+    }
+
+    /// writes the bindings to the settings file now, rather than once a change
+    /// has had the usual moment to settle
+    pub fn save(commands: &mut Commands) { commands.queue(save_settings::<KeyBindings>); }
+
+    // an action that's newer than the settings file isn't in it - it gets its
+    // defaults rather than nothing at all
+    pub fn input_map<A: BoundAction>(&self) -> InputMap<A> {
+        let mut input_map = InputMap::default();
+        for action in A::iter() {
+            let inputs = match A::bindings(self)
+                .iter()
+                .find(|bindings| bindings.action == action)
+            {
+                Some(bindings) => bindings.inputs.clone(),
+                None => action.default_bindings(),
+            };
+            for input in inputs {
+                input.insert_into(&mut input_map, action);
+            }
+        }
+        input_map
+    }
+}
+
+// anything in `bindings` other than `action` itself that's already on `input`
+fn warn_if_shared<A: BoundAction, B: BoundAction>(bindings: &[ActionBindings<B>], action: A, input: Binding) {
+    let same = (&action as &dyn Any).downcast_ref::<B>();
+    for other in bindings
+        .iter()
+        .filter(|bindings| bindings.inputs.contains(&input) && Some(&bindings.action) != same)
+    {
+        warn!("{input:?} is bound to {:?} as well as {action:?}", other.action);
+    }
+}
+
+/// the input maps are built again from KeyBindings as they stand, without
+/// KeyBindings itself changing - for whatever took them over for a while
+/// (playing back a replay, the touch controls) to hand them back. changing
+/// KeyBindings would save them as well
+#[derive(Event, Debug, Default, Clone, Copy)]
+pub struct RebuildInputMaps;
+
+/// a global action's chord is a ship action's bare key as well - shift D is
+/// debug and D on its own turns right. leafwing only sorts out clashes within
+/// one input map (https://github.com/Leafwing-Studios/leafwing-input-manager/issues/617)
+/// so nothing stops both going off. while a global chord is held the ship
+/// actions on its keys are switched off instead - bound to its key or its
+/// modifier, or to one end of a key axis, as W is for the throttle - and they
+/// stay off until those keys are let go
+pub fn suppress_chorded_ship_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut suppressed_keys: Local<Vec<KeyCode>>,
+    mut ship_state: ResMut<ActionState<SpaceshipControl>>,
+    mut q_ship_states: Query<&mut ActionState<SpaceshipControl>>,
+) {
+    let was_suppressing = !suppressed_keys.is_empty();
+    suppressed_keys.retain(|key| keys.pressed(*key));
+
+    let held = |modifier: ModifierKey| keys.any_pressed([modifier.left(), modifier.right()]);
+    let chords = key_bindings
+        .global
+        .iter()
+        .flat_map(|bindings| &bindings.inputs)
+        .filter_map(|input| input.chord())
+        .filter(|&(modifier, key)| held(modifier) && keys.pressed(key));
+    // the modifier too - dash is on bare alt
+    for (modifier, key) in chords {
+        for key in [key, modifier.left(), modifier.right()] {
+            if keys.pressed(key) && !suppressed_keys.contains(&key) {
+                suppressed_keys.push(key);
+            }
+        }
+    }
+
+    // nothing held now and nothing switched off from before
+    if !was_suppressing && suppressed_keys.is_empty() {
+        return;
+    }
+
+    for bindings in &key_bindings.ship {
+        let clashes = bindings.inputs.iter().any(|input| match *input {
+            Binding::Key(key) => suppressed_keys.contains(&key),
+            Binding::KeyAxis(negative, positive) => {
+                suppressed_keys.contains(&negative) || suppressed_keys.contains(&positive)
+            },
+            _ => false,
+        });
+
+        suppress_ship_action(&mut ship_state, &bindings.action, clashes);
+        for mut action_state in q_ship_states.iter_mut() {
+            suppress_ship_action(&mut action_state, &bindings.action, clashes);
+        }
+    }
+}
+
+// action by action, which is separate from switching the whole state off for
+// the menus and inspectors - neither undoes the other. a disabled action is
+// still updated underneath, it just reads as released
+fn suppress_ship_action(
+    action_state: &mut ActionState<SpaceshipControl>,
+    action: &SpaceshipControl,
+    suppress: bool,
+) {
+    let disabled = action_state
+        .action_data(action)
+        .is_some_and(|action_data| action_data.disabled);
+    if suppress && !disabled {
+        action_state.disable_action(action);
+    } else if !suppress && disabled {
+        action_state.enable_action(action);
+    }
+}
+
+/// the input maps as the bindings have them now - the spaceship has its own
+/// copy of the ship's so that's brought up to date as well
+pub fn apply_key_bindings(
+    key_bindings: Res<KeyBindings>,
+    mut rebuilds: EventReader<RebuildInputMaps>,
+    mut global_map: ResMut<InputMap<GlobalAction>>,
+    mut ship_map: ResMut<InputMap<SpaceshipControl>>,
+    mut q_ship_maps: Query<&mut InputMap<SpaceshipControl>>,
+) {
+    // counted rather than any() so every event gets read
+    if rebuilds.read().count() == 0 && !key_bindings.is_changed() {
+        return;
+    }
+
+    *global_map = key_bindings.input_map();
+    *ship_map = key_bindings.input_map();
+    for mut input_map in q_ship_maps.iter_mut() {
+        *input_map = ship_map.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::assert_settings_round_trip;
+    use leafwing_input_manager::plugin::InputManagerSystem;
+
+    // nothing in here is a number or a flag to nudge - every kind of binding
+    // instead, each somewhere it isn't by default
+    #[test]
+    fn key_bindings_round_trip() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.rebind(SpaceshipControl::Fire, Binding::Key(KeyCode::KeyF));
+        key_bindings.rebind(SpaceshipControl::TurnLeft, Binding::Mouse(MouseButton::Left));
+        key_bindings.rebind(
+            SpaceshipControl::Thrust,
+            Binding::KeyAxis(KeyCode::KeyK, KeyCode::KeyI),
+        );
+        key_bindings.rebind(
+            SpaceshipControl::StrafeLeft,
+            Binding::GamepadAxis(GamepadAxis::RightStickX),
+        );
+        key_bindings.rebind(GlobalAction::Console, Binding::Shift(KeyCode::KeyC));
+        key_bindings.rebind(GlobalAction::Screenshot, Binding::Ctrl(KeyCode::KeyP));
+        key_bindings.rebind(GlobalAction::AudioInspector, Binding::Alt(KeyCode::KeyA));
+
+        assert_settings_round_trip(key_bindings);
+    }
+
+    // the input maps KeyBindings builds and the suppression after leafwing has
+    // updated the action states, as InputPlugin has them
+    fn chord_app(key_bindings: KeyBindings) -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            bevy::input::InputPlugin,
+            InputManagerPlugin::<GlobalAction>::default(),
+            InputManagerPlugin::<SpaceshipControl>::default(),
+        ))
+        .insert_resource(key_bindings.input_map::<GlobalAction>())
+        .insert_resource(key_bindings.input_map::<SpaceshipControl>())
+        .init_resource::<ActionState<GlobalAction>>()
+        .init_resource::<ActionState<SpaceshipControl>>()
+        .insert_resource(key_bindings)
+        .add_systems(
+            PreUpdate,
+            suppress_chorded_ship_actions.in_set(InputManagerSystem::ManualControl),
+        );
+        app
+    }
+
+    fn press(app: &mut App, keys: &[KeyCode]) {
+        for key in keys {
+            key.press(app.world_mut());
+        }
+        app.update();
+    }
+
+    fn release(app: &mut App, keys: &[KeyCode]) {
+        for key in keys {
+            key.release(app.world_mut());
+        }
+        app.update();
+    }
+
+    fn global_pressed(app: &App, action: GlobalAction) -> bool {
+        app.world()
+            .resource::<ActionState<GlobalAction>>()
+            .pressed(&action)
+    }
+
+    fn ship_pressed(app: &App, action: SpaceshipControl) -> bool {
+        app.world()
+            .resource::<ActionState<SpaceshipControl>>()
+            .pressed(&action)
+    }
+
+    // the clash this is all for - shift C is a global chord and C on its own
+    // is continuous fire. only the global action goes, and C stays quiet until
+    // it's let go even once shift is
+    #[test]
+    fn shift_c_does_not_fire_continuous_fire() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.rebind(SpaceshipControl::ContinuousFire, Binding::Key(KeyCode::KeyC));
+        key_bindings.rebind(GlobalAction::CameraConfigInspector, Binding::Shift(KeyCode::KeyC));
+        let mut app = chord_app(key_bindings);
+
+        press(&mut app, &[KeyCode::ShiftLeft, KeyCode::KeyC]);
+        assert!(global_pressed(&app, GlobalAction::CameraConfigInspector));
+        assert!(!ship_pressed(&app, SpaceshipControl::ContinuousFire));
+
+        release(&mut app, &[KeyCode::ShiftLeft]);
+        assert!(!ship_pressed(&app, SpaceshipControl::ContinuousFire));
+
+        release(&mut app, &[KeyCode::KeyC]);
+        press(&mut app, &[KeyCode::KeyC]);
+        assert!(ship_pressed(&app, SpaceshipControl::ContinuousFire));
+    }
+
+    // a key that's one end of the throttle's axis rather than a button
+    #[test]
+    fn shift_w_does_not_thrust() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.rebind(GlobalAction::Screenshot, Binding::Shift(KeyCode::KeyW));
+        let mut app = chord_app(key_bindings);
+        let thrust = |app: &App| {
+            app.world()
+                .resource::<ActionState<SpaceshipControl>>()
+                .value(&SpaceshipControl::Thrust)
+        };
+
+        press(&mut app, &[KeyCode::ShiftLeft, KeyCode::KeyW]);
+        assert!(global_pressed(&app, GlobalAction::Screenshot));
+        assert_eq!(thrust(&app), 0.);
+
+        release(&mut app, &[KeyCode::ShiftLeft, KeyCode::KeyW]);
+        press(&mut app, &[KeyCode::KeyW]);
+        assert_eq!(thrust(&app), 1.);
+    }
+
+    // shift B opens the boundary inspector and B on its own doesn't - and
+    // whatever's on bare B doesn't go off with the chord
+    #[test]
+    fn shift_b_is_not_b() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.rebind(GlobalAction::BoundaryInspector, Binding::Shift(KeyCode::KeyB));
+        key_bindings.rebind(SpaceshipControl::Fire, Binding::Key(KeyCode::KeyB));
+        let mut app = chord_app(key_bindings);
+
+        press(&mut app, &[KeyCode::KeyB]);
+        assert!(!global_pressed(&app, GlobalAction::BoundaryInspector));
+        assert!(ship_pressed(&app, SpaceshipControl::Fire));
+        release(&mut app, &[KeyCode::KeyB]);
+
+        press(&mut app, &[KeyCode::ShiftRight, KeyCode::KeyB]);
+        assert!(global_pressed(&app, GlobalAction::BoundaryInspector));
+        assert!(!ship_pressed(&app, SpaceshipControl::Fire));
+    }
+
+    // dash is on bare left alt, so an alt chord would hold it down as well -
+    // it stays off until alt is let go, even once the chord's key is
+    #[test]
+    fn alt_chord_does_not_dash() {
+        let mut key_bindings = KeyBindings::default();
+        key_bindings.rebind(GlobalAction::AudioInspector, Binding::Alt(KeyCode::KeyA));
+        let mut app = chord_app(key_bindings);
+
+        press(&mut app, &[KeyCode::AltLeft, KeyCode::KeyA]);
+        assert!(global_pressed(&app, GlobalAction::AudioInspector));
+        assert!(!ship_pressed(&app, SpaceshipControl::Dash));
+        assert!(!ship_pressed(&app, SpaceshipControl::TurnLeft));
+
+        release(&mut app, &[KeyCode::KeyA]);
+        assert!(!ship_pressed(&app, SpaceshipControl::Dash));
+
+        release(&mut app, &[KeyCode::AltLeft]);
+        press(&mut app, &[KeyCode::AltLeft]);
+        assert!(ship_pressed(&app, SpaceshipControl::Dash));
+    }
+
+    // a replay or the touch controls handing the ship back - the maps come
+    // back without KeyBindings changing, so the settings file isn't written
+    #[test]
+    fn rebuilding_the_maps_leaves_key_bindings_alone() {
+        let mut app = App::new();
+        app.add_event::<RebuildInputMaps>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<InputMap<GlobalAction>>()
+            .init_resource::<InputMap<SpaceshipControl>>()
+            .add_systems(Update, apply_key_bindings);
+        app.update();
+        let built = app.world().resource::<InputMap<SpaceshipControl>>().clone();
+        let changed = app.world().resource_ref::<KeyBindings>().last_changed();
+
+        *app.world_mut().resource_mut::<InputMap<SpaceshipControl>>() = InputMap::default();
+        app.update();
+        assert_eq!(
+            *app.world().resource::<InputMap<SpaceshipControl>>(),
+            InputMap::default()
+        );
+
+        app.world_mut().send_event(RebuildInputMaps);
+        app.update();
+        assert_eq!(*app.world().resource::<InputMap<SpaceshipControl>>(), built);
+        assert_eq!(app.world().resource_ref::<KeyBindings>().last_changed(), changed);
+    }
+}
@@ -0,0 +1,172 @@
+// exclude when targeting wasm - this breaks in the browser right now
+mod actor;
+mod asset_loader;
+mod audio;
+mod budget;
+mod camera;
+mod config;
+mod console;
+mod despawn;
+mod diagnostics;
+mod dump;
+#[cfg(dev_tools)]
+mod frame_timing;
+mod global_input;
+mod headless;
+mod input_buffer;
+mod interpolation;
+mod key_bindings;
+mod manifest;
+mod mutators;
+mod orientation;
+mod physics;
+mod playfield;
+mod replay;
+mod rng;
+mod schedule;
+mod score_reporting;
+mod scoring;
+mod screenshot;
+mod splash;
+mod state;
+mod storage;
+mod time_controls;
+mod ui;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+use crate::{
+    actor::ActorPlugin,
+    asset_loader::AssetLoaderPlugin,
+    audio::AudioPlugin,
+    budget::BudgetPlugin,
+    camera::CameraPlugin,
+    console::ConsolePlugin,
+    despawn::DespawnPlugin,
+    diagnostics::DiagnosticsOverlayPlugin,
+    dump::DiagnosticsDumpPlugin,
+    global_input::InputPlugin,
+    headless::HeadlessPlugin,
+    interpolation::InterpolationPlugin,
+    mutators::MutatorsPlugin,
+    orientation::OrientationPlugin,
+    physics::PhysicsPlugin,
+    playfield::PlayfieldPlugin,
+    schedule::SchedulePlugin,
+    score_reporting::ScoreReportingPlugin,
+    scoring::ScoringPlugin,
+    screenshot::ScreenshotPlugin,
+    splash::SplashPlugin,
+    state::StatePlugin,
+    time_controls::TimeControlsPlugin,
+    ui::UiPlugin,
+};
+use bevy::prelude::*;
+
+#[cfg(dev_tools)]
+use crate::frame_timing::FrameTimingPlugin;
+#[cfg(target_arch = "wasm32")]
+use crate::web::WebPlugin;
+use bevy_remote::{
+    http::RemoteHttpPlugin,
+    RemotePlugin,
+};
+
+// what benches/ measures and tests/ checks
+pub use crate::{
+    actor::{
+        stress_schedule,
+        stress_world,
+        ActorKind,
+        EntityTeleported,
+        STRESS_CUBE_COUNT,
+    },
+    orientation::CameraOrientation,
+    playfield::{
+        boundary_grid_mesh,
+        ActorPortals,
+        Boundary,
+        BoundaryFace,
+        LineListBuilder,
+        Neighbor,
+        Portal,
+        SpatialIndex,
+    },
+    schedule::EntityBatching,
+};
+
+// for putting an actor together by hand rather than from its kind's config
+pub use crate::actor::ActorBundle;
+
+/// the whole game, ready to run. headless swaps the window, gpu and sound for
+/// a loop that plays as fast as it can and prints how it went - see
+/// HeadlessPlugin. the game binary picks it with the headless feature and the
+/// balance_sim example always does
+pub fn app(headless: bool) -> App { game(headless.then(HeadlessPlugin::default)) }
+
+/// the headless game with `args` in place of the command line - for tests that
+/// play the whole thing, like tests/replays.rs
+pub fn headless_app(args: &[&str]) -> App { game(Some(HeadlessPlugin::with_args(args.iter().copied()))) }
+
+fn game(headless: Option<HeadlessPlugin>) -> App {
+    let mut app = App::new();
+    let is_headless = headless.is_some();
+
+    if let Some(plugin) = headless {
+        app.add_plugins((headless::default_plugins(), plugin));
+    } else {
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(DefaultPlugins);
+
+        #[cfg(target_arch = "wasm32")]
+        app.add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(WindowPlugin {
+                    primary_window: Some(web::primary_window()),
+                    ..default()
+                }),
+        )
+        .add_plugins(WebPlugin);
+    }
+
+    app.add_plugins((
+        ActorPlugin,
+        AssetLoaderPlugin,
+        AudioPlugin,
+        PlayfieldPlugin,
+        CameraPlugin,
+        DespawnPlugin,
+        DiagnosticsOverlayPlugin,
+        InputPlugin,
+        OrientationPlugin,
+        PhysicsPlugin,
+        SchedulePlugin,
+        ScoringPlugin,
+        SplashPlugin,
+        StatePlugin,
+        UiPlugin,
+    ))
+    .add_plugins((
+        BudgetPlugin,
+        ConsolePlugin,
+        DiagnosticsDumpPlugin,
+        InterpolationPlugin,
+        MutatorsPlugin,
+        ScoreReportingPlugin,
+        ScreenshotPlugin,
+        TimeControlsPlugin,
+    ));
+
+    // timing each part of the frame is a dev tool - see build.rs
+    #[cfg(dev_tools)]
+    app.add_plugins(FrameTimingPlugin);
+
+    // nothing to connect to in a headless run
+    if !is_headless {
+        app.add_plugins(RemotePlugin::default())
+            .add_plugins(RemoteHttpPlugin::default());
+    }
+
+    app
+}
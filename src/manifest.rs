@@ -0,0 +1,127 @@
+use crate::actor::{
+    ActorKind,
+    ColliderType,
+};
+use bevy::{
+    asset::{
+        io::Reader,
+        AssetLoader,
+        LoadContext,
+    },
+    prelude::*,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// where the manifest lives, under assets/
+pub const MANIFEST_PATH: &str = "manifest.ron";
+
+/// which model each actor uses, and how it's set up - read from
+/// assets/manifest.ron so a model can be swapped without a rebuild. entries
+/// are keyed by the actor's name - Spaceship, Nateroid, Missile:
+///
+/// ```ron
+/// #![enable(implicit_some)]
+/// (
+///     actors: {
+///         "Nateroid": (model: "models/Planet.glb", name: "planet", scale: 1.5, collider: ConvexHull),
+///     },
+/// )
+/// ```
+///
+/// only model has to be there - anything else left out keeps the actor's own
+/// default. nateroids can have variants as well, other looks they spawn with
+/// now and then:
+///
+/// ```ron
+/// "Nateroid": (
+///     model: "models/donut.glb",
+///     variants: [
+///         (model: "models/Planet.glb", name: "planet", weights: (small: 0.0, large: 3.0)),
+///     ],
+/// ),
+/// ```
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct Manifest {
+    #[serde(default)]
+    pub actors: HashMap<String, ManifestEntry>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ManifestEntry {
+    /// the gltf file - its first scene is the one that's used
+    pub model:    String,
+    /// what to call it when it goes wrong
+    pub name:     Option<String>,
+    pub scale:    Option<f32>,
+    pub collider: Option<ColliderType>,
+    #[serde(default)]
+    pub variants: Vec<ManifestVariant>,
+}
+
+/// another model for the same actor. it's fitted with the entry's scale and
+/// collider
+#[derive(Deserialize, Debug, Clone)]
+pub struct ManifestVariant {
+    pub model:   String,
+    pub name:    Option<String>,
+    #[serde(default)]
+    pub weights: SizeWeights,
+}
+
+/// how likely a look is for each size of nateroid, next to the others - the
+/// entry's own model is 1 for all three, and so is anything left out
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct SizeWeights {
+    pub small:  f32,
+    pub medium: f32,
+    pub large:  f32,
+}
+
+impl Default for SizeWeights {
+    fn default() -> Self {
+        Self {
+            small:  1.,
+            medium: 1.,
+            large:  1.,
+        }
+    }
+}
+
+impl Manifest {
+    /// the entry for an actor, if there is one
+    pub fn entry(&self, kind: ActorKind) -> Option<&ManifestEntry> { self.actors.get(&kind.to_string()) }
+
+    /// entries that aren't for any actor - probably a typo
+    pub fn unknown_entries(&self) -> impl Iterator<Item = &str> {
+        self.actors
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !ActorKind::ALL.iter().any(|kind| kind.to_string() == *name))
+    }
+}
+
+// only claims manifest.ron so any other ron under assets is left for
+// whatever else wants it - it's loaded by type anyway
+#[derive(Default)]
+pub struct ManifestLoader;
+
+impl AssetLoader for ManifestLoader {
+    type Asset = Manifest;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Settings = ();
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Manifest, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] { &["manifest.ron"] }
+}
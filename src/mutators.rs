@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use strum::{
+    EnumIter,
+    IntoEnumIterator,
+};
+
+/// the run's mutators are picked on the run setup screen and stay put until
+/// the next one - everything that cares reads Mutators when it spawns or
+/// steers something, nothing is changed behind its back mid-run
+pub struct MutatorsPlugin;
+
+impl Plugin for MutatorsPlugin {
+    fn build(&self, app: &mut App) { app.init_resource::<Mutators>(); }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Mutator {
+    GravityWell,
+    FastMissiles,
+    TinyShip,
+    GiantRocks,
+    NoWrap,
+    MirrorControls,
+}
+
+impl Mutator {
+    fn bit(self) -> u8 { 1 << self as u8 }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mutator::GravityWell => "gravity well",
+            Mutator::FastMissiles => "fast missiles",
+            Mutator::TinyShip => "tiny ship",
+            Mutator::GiantRocks => "giant rocks",
+            Mutator::NoWrap => "no wrap",
+            Mutator::MirrorControls => "mirror controls",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Mutator::GravityWell => "everything is pulled toward the middle of the playfield",
+            Mutator::FastMissiles => "missiles fly faster",
+            Mutator::TinyShip => "a smaller spaceship - harder to hit, harder to see",
+            Mutator::GiantRocks => "bigger nateroids",
+            Mutator::NoWrap => "the walls are solid - everything bounces off them",
+            Mutator::MirrorControls => "left turns right and right turns left",
+        }
+    }
+}
+
+/// which mutators are on - small enough to copy around, so a high score can
+/// keep the set it was made with
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MutatorSet(u8);
+
+impl MutatorSet {
+    pub fn contains(self, mutator: Mutator) -> bool { self.0 & mutator.bit() != 0 }
+
+    pub fn set(&mut self, mutator: Mutator, on: bool) {
+        if on {
+            self.0 |= mutator.bit();
+        } else {
+            self.0 &= !mutator.bit();
+        }
+    }
+
+    pub fn is_empty(self) -> bool { self.0 == 0 }
+
+    pub fn iter(self) -> impl Iterator<Item = Mutator> {
+        Mutator::iter().filter(move |mutator| self.contains(*mutator))
+    }
+
+    /// "tiny ship, giant rocks" - empty when nothing is on
+    pub fn summary(self) -> String { self.iter().map(Mutator::label).collect::<Vec<_>>().join(", ") }
+}
+
+/// the mutators that are on plus how strong each one is - the strengths only
+/// matter while their mutator is on. a replay keeps the ones it was recorded
+/// with
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Mutators {
+    pub active:                MutatorSet,
+    pub gravity_well_strength: f32,
+    pub missile_speed:         f32,
+    pub ship_scale:            f32,
+    pub rock_scale:            f32,
+}
+
+impl Default for Mutators {
+    fn default() -> Self {
+        Self {
+            active:                MutatorSet::default(),
+            gravity_well_strength: 15.,
+            missile_speed:         2.,
+            ship_scale:            0.5,
+            rock_scale:            2.5,
+        }
+    }
+}
+
+// each of these is what its system multiplies by - 1 when the mutator is off
+impl Mutators {
+    pub fn is_active(&self, mutator: Mutator) -> bool { self.active.contains(mutator) }
+
+    pub fn missile_speed_multiplier(&self) -> f32 { self.when(Mutator::FastMissiles, self.missile_speed) }
+
+    pub fn ship_scale_multiplier(&self) -> f32 { self.when(Mutator::TinyShip, self.ship_scale) }
+
+    pub fn rock_scale_multiplier(&self) -> f32 { self.when(Mutator::GiantRocks, self.rock_scale) }
+
+    fn when(&self, mutator: Mutator, value: f32) -> f32 {
+        if self.is_active(mutator) {
+            value
+        } else {
+            1.
+        }
+    }
+}
+
+/// run condition for systems that only exist for one mutator
+pub fn mutator_active(mutator: Mutator) -> impl Fn(Res<Mutators>) -> bool {
+    move |mutators: Res<Mutators>| mutators.is_active(mutator)
+}
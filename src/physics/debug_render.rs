@@ -0,0 +1,192 @@
+use crate::{
+    camera::RenderLayer,
+    global_input::GlobalAction,
+    schedule::FrameSet,
+};
+use bevy::{
+    color::palettes::tailwind,
+    prelude::*,
+    render::view::RenderLayers,
+};
+#[cfg(dev_tools)]
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use bevy_rapier3d::prelude::{
+    ColliderDebugColor,
+    DebugRenderContext,
+    DebugRenderMode,
+    RapierDebugRenderPlugin,
+    ReadDefaultRapierContext,
+    Sensor,
+    Sleeping,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+const CONTACT_SIZE: f32 = 1.5;
+
+/// rapier's debug lines plus the contacts and aabbs we draw ourselves, all on
+/// the debug render layer so only the game camera ever sees them. the physics
+/// debug key turns it on and off and, in dev tool builds, a small window picks
+/// what's shown
+pub struct PhysicsDebugRenderPlugin;
+
+impl Plugin for PhysicsDebugRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RapierDebugRenderPlugin::default())
+            .init_resource::<PhysicsDebug>()
+            .add_systems(Startup, draw_gizmos_on_debug_layer)
+            .add_systems(Update, toggle_physics_debug.in_set(FrameSet::Input))
+            .add_systems(
+                Update,
+                (
+                    sync_rapier_debug_render.run_if(resource_changed::<PhysicsDebug>),
+                    color_sensor_colliders,
+                    draw_contacts.run_if(showing_contacts),
+                )
+                    .in_set(FrameSet::Visual),
+            );
+
+        #[cfg(dev_tools)]
+        app.add_systems(
+            Update,
+            draw_physics_debug_panel
+                .in_set(FrameSet::Ui)
+                .run_if(physics_debug_enabled),
+        );
+    }
+}
+
+/// what the physics debug drawing shows while it's on
+#[derive(Resource, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+pub struct PhysicsDebug {
+    pub enabled:   bool,
+    pub colliders: bool,
+    pub contacts:  bool,
+    pub aabbs:     bool,
+}
+
+impl Default for PhysicsDebug {
+    fn default() -> Self {
+        Self {
+            enabled:   false,
+            colliders: true,
+            contacts:  false,
+            aabbs:     false,
+        }
+    }
+}
+
+#[cfg(dev_tools)]
+fn physics_debug_enabled(debug: Res<PhysicsDebug>) -> bool { debug.enabled }
+
+fn showing_contacts(debug: Res<PhysicsDebug>) -> bool { debug.enabled && debug.contacts }
+
+/// run condition for the actor aabbs - they're drawn alongside the colliders
+/// so it's easy to see where the two disagree
+pub fn showing_aabbs(debug: Res<PhysicsDebug>) -> bool { debug.enabled && debug.aabbs }
+
+// rapier draws with the default gizmo group - anything that isn't debug drawing
+// uses a group of its own
+fn draw_gizmos_on_debug_layer(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
+    config.render_layers = RenderLayers::from_layers(RenderLayer::Debug.layers());
+}
+
+fn toggle_physics_debug(user_input: Res<ActionState<GlobalAction>>, mut debug: ResMut<PhysicsDebug>) {
+    if user_input.just_pressed(&GlobalAction::PhysicsDebug) {
+        debug.enabled = !debug.enabled;
+    }
+}
+
+// rapier only draws the collider shapes - contacts are ours so they can be
+// colored by whether the bodies are asleep
+fn sync_rapier_debug_render(debug: Res<PhysicsDebug>, mut rapier_debug: ResMut<DebugRenderContext>) {
+    rapier_debug.enabled = debug.enabled && debug.colliders;
+    rapier_debug.pipeline.mode = DebugRenderMode::COLLIDER_SHAPES;
+}
+
+// sleeping colliders are already dimmed by rapier - sensors would otherwise
+// look like anything else
+fn color_sensor_colliders(mut commands: Commands, q_sensors: Query<Entity, Added<Sensor>>) {
+    for entity in q_sensors.iter() {
+        commands
+            .entity(entity)
+            .insert(ColliderDebugColor(Hsla::from(tailwind::PURPLE_400)));
+    }
+}
+
+// a cross at each contact point - orange while either body is awake, grey once
+// both have gone to sleep. sensors don't make contacts so their overlaps get
+// a line between the two instead
+fn draw_contacts(
+    mut gizmos: Gizmos,
+    rapier_context: ReadDefaultRapierContext,
+    q_colliders: Query<(&Transform, Option<&Sleeping>)>,
+) {
+    let rapier_context = rapier_context.single();
+    let asleep = |entity: Entity| {
+        q_colliders
+            .get(entity)
+            .ok()
+            .and_then(|(_, sleeping)| sleeping)
+            .is_some_and(|sleeping| sleeping.sleeping)
+    };
+
+    for pair in rapier_context.contact_pairs() {
+        if !pair.has_any_active_contact() {
+            continue;
+        }
+        let Ok((transform, _)) = q_colliders.get(pair.collider1()) else {
+            continue;
+        };
+
+        let color = if asleep(pair.collider1()) && asleep(pair.collider2()) {
+            tailwind::SLATE_500
+        } else {
+            tailwind::ORANGE_400
+        };
+
+        for manifold in pair.manifolds() {
+            for point in manifold.points() {
+                // rapier's collider space has no scale in it
+                let position = transform.translation + transform.rotation * point.local_p1();
+                gizmos.cross(Isometry3d::from_translation(position), CONTACT_SIZE, color);
+            }
+        }
+    }
+
+    for (collider1, collider2, intersecting) in rapier_context.intersection_pairs() {
+        if !intersecting {
+            continue;
+        }
+        if let (Ok((from, _)), Ok((to, _))) = (q_colliders.get(collider1), q_colliders.get(collider2)) {
+            gizmos.line(from.translation, to.translation, tailwind::PURPLE_400);
+        }
+    }
+}
+
+#[cfg(dev_tools)]
+fn draw_physics_debug_panel(mut contexts: Query<&mut EguiContext>, mut debug: ResMut<PhysicsDebug>) {
+    let Ok(mut context) = contexts.get_single_mut() else {
+        return;
+    };
+
+    // edit a copy so the rapier settings are only touched on a real change
+    let mut draft = debug.clone();
+
+    egui::Window::new("physics debug")
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_TOP, [-10., 10.])
+        .show(context.get_mut(), |ui| {
+            ui.checkbox(&mut draft.colliders, "colliders")
+                .on_hover_text("sleeping bodies are dimmed, sensors are purple");
+            ui.checkbox(&mut draft.contacts, "contacts")
+                .on_hover_text("orange while awake, grey once both bodies are asleep");
+            ui.checkbox(&mut draft.aabbs, "aabbs");
+        });
+
+    debug.set_if_neq(draft);
+}
@@ -0,0 +1,38 @@
+mod debug_render;
+
+use crate::{
+    diagnostics::RIGID_BODY_COUNT,
+    physics::debug_render::PhysicsDebugRenderPlugin,
+    schedule::FrameSet,
+};
+use bevy::{
+    diagnostic::{
+        Diagnostic,
+        Diagnostics,
+        RegisterDiagnostic,
+    },
+    prelude::*,
+};
+use bevy_rapier3d::prelude::{
+    NoUserData,
+    RapierPhysicsPlugin,
+    RigidBody,
+};
+
+pub use debug_render::showing_aabbs;
+
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        // stepped once per simulation tick - see SIMULATION_HZ
+        app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule())
+            .add_plugins(PhysicsDebugRenderPlugin)
+            .register_diagnostic(Diagnostic::new(RIGID_BODY_COUNT))
+            .add_systems(Update, measure_rigid_bodies.in_set(FrameSet::Ui));
+    }
+}
+
+fn measure_rigid_bodies(mut diagnostics: Diagnostics, q_bodies: Query<(), With<RigidBody>>) {
+    diagnostics.add_measurement(&RIGID_BODY_COUNT, || q_bodies.iter().len() as f64);
+}
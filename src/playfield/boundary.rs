@@ -1,71 +1,260 @@
 use crate::{
+    actor::{
+        EntityLeftBoundary,
+        EntityTeleported,
+    },
+    camera::PrimaryCamera,
+    config::SettingsAppExt,
     global_input::{
         toggle_active,
         GlobalAction,
+        InspectorAppExt,
     },
+    schedule::FrameSet,
     state::PlayingGame,
+    time_controls::GameTime,
+};
+use bevy::{
+    prelude::*,
+    utils::HashMap,
 };
-use bevy::prelude::*;
 use bevy_inspector_egui::{
     inspector_options::std_options::NumberDisplay,
     prelude::*,
-    quick::ResourceInspectorPlugin,
 };
+use leafwing_input_manager::prelude::ActionState;
+use rand::Rng;
 
 use crate::playfield::{
     boundary_face::BoundaryFace,
-    portals::{
-        Portal,
-        PortalGizmo,
+    line_meshes::{
+        line_mesh_bundle,
+        show_line_mesh,
+        LineListBuilder,
+        LineMaterial,
+        LineSink,
     },
+    portals::Portal,
 };
 
 use crate::orientation::CameraOrientation;
 use bevy::color::palettes::tailwind;
+use std::f32::consts::{
+    FRAC_PI_2,
+    PI,
+    TAU,
+};
 
 pub struct BoundaryPlugin;
 
 impl Plugin for BoundaryPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Boundary>()
+        app.register_settings::<Boundary>("boundary")
+            .add_event::<BoundaryResized>()
             .init_gizmo_group::<BoundaryGizmo>()
-            .register_type::<Boundary>()
-            .add_plugins(
-                ResourceInspectorPlugin::<Boundary>::default()
-                    .run_if(toggle_active(false, GlobalAction::BoundaryInspector)),
+            .init_resource::<BoundaryPulses>()
+            .init_resource::<BoundaryFade>()
+            .add_inspector::<Boundary>(GlobalAction::BoundaryInspector)
+            .add_systems(Update, cycle_render_mode.in_set(FrameSet::Input))
+            .add_systems(Update, update_boundary_transform.in_set(FrameSet::Boundary))
+            .add_systems(
+                Update,
+                update_gizmos_config
+                    .before(draw_boundary)
+                    .before(update_boundary_lines)
+                    .in_set(FrameSet::Visual),
             )
-            .add_systems(Update, update_gizmos_config)
-            .add_systems(Update, draw_boundary.run_if(in_state(PlayingGame)));
+            .add_systems(
+                Update,
+                (
+                    draw_boundary.run_if(toggle_active(false, GlobalAction::GizmoLines)),
+                    update_boundary_lines.run_if(toggle_active(true, GlobalAction::GizmoLines)),
+                    (start_pulses, draw_pulses).chain(),
+                )
+                    .in_set(FrameSet::Visual)
+                    .run_if(in_state(PlayingGame)),
+            );
     }
 }
 
 #[derive(Default, Reflect, GizmoConfigGroup)]
 struct BoundaryGizmo {}
 
-fn update_gizmos_config(mut config_store: ResMut<GizmoConfigStore>, boundary: Res<Boundary>) {
+// how smooth the sphere's circles are and how many of them there are
+const SPHERE_RESOLUTION: u32 = 64;
+const SPHERE_LATITUDES: u32 = 6;
+const SPHERE_LONGITUDES: u32 = 8;
+
+const DEFAULT_CORNER_LENGTH: f32 = 0.1;
+
+// how big a wrap's pulse gets, as a share of a cell
+const PULSE_SIZE: f32 = 0.25;
+const PULSE_RESOLUTION: u32 = 48;
+
+// smaller than anyone could drag a slider by, bigger than float noise
+const RESIZE_EPSILON: f32 = 1e-3;
+
+/// sent when the boundary's size changes - from the inspector or the settings
+/// menu, say - so anything that depends on it can catch up straight away
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BoundaryResized {
+    pub old_scale: Vec3,
+    pub new_scale: Vec3,
+}
+
+// how thin the lines get with the camera right up against a wall
+const FADE_NEAR_WIDTH: f32 = 0.35;
+
+// how far through the fade the grid is - 0 with the camera at
+// `fade_near_distance` from a wall or closer, 1 at `fade_far_distance`. the
+// gizmos and the line mesh both fade, only the gizmos get thinner
+#[derive(Resource, Debug)]
+struct BoundaryFade(f32);
+
+impl Default for BoundaryFade {
+    fn default() -> Self { Self(1.) }
+}
+
+impl BoundaryFade {
+    fn alpha(&self, boundary: &Boundary) -> f32 {
+        boundary.fade_near_alpha.lerp(boundary.fade_far_alpha, self.0)
+    }
+}
+
+// the camera is usually outside the box, so it's the distance either way. it
+// moves after this, in FrameSet::Camera - a frame behind doesn't show
+fn update_gizmos_config(
+    mut config_store: ResMut<GizmoConfigStore>,
+    mut fade: ResMut<BoundaryFade>,
+    boundary: Res<Boundary>,
+    q_camera: Query<&GlobalTransform, With<PrimaryCamera>>,
+) {
+    let progress = q_camera.get_single().map_or(1., |camera| {
+        let distance = boundary.signed_distance(camera.translation()).abs();
+        let range = boundary.fade_far_distance - boundary.fade_near_distance;
+        if range <= 0. {
+            1.
+        } else {
+            ((distance - boundary.fade_near_distance) / range).clamp(0., 1.)
+        }
+    });
+    fade.0 = progress;
+
+    // only the gizmo group - a line mesh is always a pixel wide
     let (config, _) = config_store.config_mut::<BoundaryGizmo>();
-    config.line_width = boundary.line_width;
+    config.line_width = boundary.line_width * FADE_NEAR_WIDTH.lerp(1., progress);
+}
+
+/// where a ray hits the boundary - see `Boundary::raycast`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundaryHit {
+    pub point:    Vec3,
+    pub normal:   Dir3,
+    pub face:     BoundaryFace,
+    pub distance: f32,
+}
+
+/// how a portal gets bent over one edge of the box - what the boundary debug
+/// overlay draws
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PortalFold {
+    pub face:          BoundaryFace,
+    /// the point on the edge closest to the portal's center
+    pub anchor:        Vec3,
+    /// the edge the fold turns around
+    pub axis:          Vec3,
+    /// where the portal's center ends up once it's folded onto `face`
+    pub folded_center: Vec3,
+    /// how much of the circle spills over the edge, in radians
+    pub angle:         f32,
+}
+
+/// the shape of the playfield. a sphere sits where the middle of the cuboid
+/// would be and ignores the cell count and scalar - its radius is in world
+/// units
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq)]
+pub enum BoundaryShape {
+    #[default]
+    Cuboid,
+    Sphere {
+        radius: f32,
+    },
+}
+
+/// overrides for one face of the cuboid's grid - hide the front face, say, so
+/// it doesn't get in the way with the camera pulled back. a face without an
+/// entry in `face_config` is drawn with the default
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct FaceConfig {
+    // the boundary's color if there isn't one
+    pub color:   Option<Color>,
+    pub visible: bool,
+    // grid lines per cell on this face
+    pub density: u32,
+}
+
+impl Default for FaceConfig {
+    fn default() -> Self {
+        Self {
+            color:   None,
+            visible: true,
+            density: 1,
+        }
+    }
+}
+
+/// how much of the cuboid gets drawn - the sphere is its globe unless it's off
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq)]
+pub enum BoundaryRenderMode {
+    #[default]
+    FullGrid,
+    OuterEdgesOnly,
+    /// ticks out of each corner, `corner_length` of the way along each edge
+    CornersOnly {
+        corner_length: f32,
+    },
+    Off,
 }
 
-// circle_direction_change_factor:
-// if we're within a certain radians of the wall we continue to draw on it but
-// after that we consider that we're looking to be at a new wall boundary point
-// adjust this if it makes sense to
-//
-// circle_smoothing_factor:
-// keep it small so that if you change directions the circle doesn't fly
-// away fast - looks terrible
-//
 #[derive(Resource, Reflect, InspectorOptions, Clone, Debug)]
 #[reflect(Resource, InspectorOptions)]
 pub struct Boundary {
-    pub cell_count: UVec3,
-    pub color:      Color,
+    pub shape:              BoundaryShape,
+    pub cell_count:         UVec3,
+    // how many squares the grid is drawn with along each axis - 0 is one per
+    // cell, so the size and the grid can be changed separately
+    pub grid_subdivisions:  UVec3,
+    pub render_mode:        BoundaryRenderMode,
+    pub color:              Color,
     #[inspector(min = 0.1, max = 40.0, display = NumberDisplay::Slider)]
-    pub line_width: f32,
+    pub line_width:         f32,
     #[inspector(min = 50., max = 300., display = NumberDisplay::Slider)]
-    pub scalar:     f32,
-    pub transform:  Transform,
+    pub scalar:             f32,
+    // an axis that doesn't wrap is a wall - flat play locks z so it can't
+    // wander off through the front and back faces
+    pub wrap_axes:          BVec3,
+    pub face_config:        HashMap<BoundaryFace, FaceConfig>,
+    // how long the ring where something wrapped takes to fade, in seconds
+    #[inspector(min = 0., max = 3., display = NumberDisplay::Slider)]
+    pub pulse_duration:     f32,
+    // how far inside something that's just wrapped has to get before it can
+    // wrap again - 0 turns it off
+    #[inspector(min = 0., max = 10., display = NumberDisplay::Slider)]
+    pub reentry_margin:     f32,
+    // the grid lines fade and thin out as the camera gets close to a wall, so
+    // zoomed in they don't take over the screen
+    #[inspector(min = 0., max = 1., display = NumberDisplay::Slider)]
+    pub fade_near_alpha:    f32,
+    #[inspector(min = 0., max = 1., display = NumberDisplay::Slider)]
+    pub fade_far_alpha:     f32,
+    pub fade_near_distance: f32,
+    pub fade_far_distance:  f32,
+    // worked out from the cell count and scalar so there's no point saving it.
+    // only the inspector ever turns it - anything to do with the walls works
+    // in the box's own frame through to_local, to_world and face_normal, so
+    // it all turns with it
+    #[reflect(skip_serializing)]
+    pub transform:          Transform,
 }
 
 impl Default for Boundary {
@@ -74,319 +263,558 @@ impl Default for Boundary {
         let scalar = 110.;
 
         Self {
+            shape: BoundaryShape::Cuboid,
             cell_count,
+            grid_subdivisions: UVec3::ZERO,
+            render_mode: BoundaryRenderMode::FullGrid,
             color: Color::from(tailwind::BLUE_300),
             line_width: 4.,
             scalar,
+            wrap_axes: BVec3::TRUE,
+            face_config: HashMap::default(),
+            pulse_duration: 0.6,
+            reentry_margin: 2.,
+            fade_near_alpha: 0.25,
+            fade_far_alpha: 1.,
+            fade_near_distance: 20.,
+            fade_far_distance: 200.,
             transform: Transform::from_scale(scalar * cell_count.as_vec3()),
         }
     }
 }
 
 impl Boundary {
-    fn get_overextended_intersection_points(
-        &self,
-        portal: &Portal,
-        overextended_faces: Vec<BoundaryFace>,
-    ) -> Vec<(BoundaryFace, Vec<Vec3>)> {
-        let mut intersections = Vec::new();
-        let half_size = self.transform.scale / 2.0;
-        let min = self.transform.translation - half_size;
-        let max = self.transform.translation + half_size;
+    /// where something at `position` ends up once it's crossed the boundary,
+    /// and whether that was a wrap. an axis in `wrap_axes` sends it to the
+    /// opposite face, any other axis holds it on the face it reached - a
+    /// clamp on its own isn't a wrap. something a whole width or more past a
+    /// face went round more than once in one step, so it's wrapped by the
+    /// remainder instead and lands where it would have if it had been caught
+    /// every time
+    ///
+    /// a sphere always wraps - straight through the middle to the point on
+    /// the other side
+    pub fn calculate_teleport_position(&self, position: Vec3) -> (Vec3, bool) {
+        self.calculate_teleport_position_on(position, BVec3::TRUE)
+    }
+
+    /// calculate_teleport_position for only some of the axes - the rest are
+    /// left alone for whoever's doing something else with them. an axis the
+    /// boundary doesn't wrap is still held on its face either way
+    pub fn calculate_teleport_position_on(&self, position: Vec3, axes: BVec3) -> (Vec3, bool) {
+        let (teleport_position, wrapped) = self.wrap_on(position, axes);
+        (teleport_position, wrapped.any())
+    }
 
-        for face in overextended_faces {
-            let face_points = face.get_face_points(&min, &max);
-            let face_intersections = intersect_circle_with_rectangle(portal, &face_points);
+    // calculate_teleport_position_on, but with which axes wrapped. on a sphere
+    // it's all of them or none
+    fn wrap_on(&self, position: Vec3, axes: BVec3) -> (Vec3, BVec3) {
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            let offset = position - self.transform.translation;
+            if offset.length() < radius {
+                return (position, BVec3::FALSE);
+            }
+            return (
+                self.transform.translation - offset.normalize_or_zero() * radius,
+                BVec3::TRUE,
+            );
+        }
 
-            if !face_intersections.is_empty() {
-                intersections.push((face, face_intersections));
+        // wrapped in the boundary's own frame, so a turned box wraps to the
+        // face opposite in the box and not in the world
+        let local = self.to_local(position);
+        let size = self.transform.scale;
+        let boundary_max = size / 2.0;
+        let boundary_min = -boundary_max;
+        let wrap_axes: [bool; 3] = self.wrap_axes.into();
+        let axes: [bool; 3] = axes.into();
+
+        let mut teleport_position = local;
+        let mut wrapped = BVec3::FALSE;
+
+        for (axis, wraps) in wrap_axes.into_iter().enumerate() {
+            if !wraps {
+                teleport_position[axis] = local[axis].clamp(boundary_min[axis], boundary_max[axis]);
+            } else if !axes[axis] {
+                continue;
+            } else if (local[axis] - boundary_max[axis]).max(boundary_min[axis] - local[axis]) >= size[axis] {
+                teleport_position[axis] =
+                    (local[axis] - boundary_min[axis]).rem_euclid(size[axis]) + boundary_min[axis];
+                wrapped.set(axis, true);
+            } else if local[axis] >= boundary_max[axis] {
+                teleport_position[axis] = boundary_min[axis];
+                wrapped.set(axis, true);
+            } else if local[axis] <= boundary_min[axis] {
+                teleport_position[axis] = boundary_max[axis];
+                wrapped.set(axis, true);
             }
         }
 
-        intersections
+        // nothing moved - hand back the position untouched rather than one
+        // that's been there and back through the rotation
+        if teleport_position == local {
+            return (position, BVec3::FALSE);
+        }
+        (self.to_world(teleport_position), wrapped)
     }
 
-    /// Finds the intersection point of a ray (defined by an origin and
-    /// direction) with the edges of a viewable area.
-    ///
-    /// # Parameters
-    /// - `origin`: The starting point of the ray.
-    /// - `direction`: The direction vector of the ray.
-    /// - `dimensions`: The dimensions of the viewable area.
-    ///
-    /// # Returns
-    /// An `Option<Vec3>` containing the intersection point if found, or `None`
-    /// if no valid intersection exists.
-    ///
-    /// # Method
-    /// - The function calculates the intersection points of the ray with the
-    ///   positive and negative boundaries of the viewable area along all axes.
-    ///   todo: is this true? you'll have to test in 3d mode
-    /// - It iterates over these axes, updating the minimum intersection
-    ///   distance (`t_min`) if a valid intersection is found.
-    /// - Finally, it returns the intersection point corresponding to the
-    ///   minimum distance, or `None` if no valid intersection is found.
-    pub fn calculate_teleport_position(&self, position: Vec3) -> Vec3 {
-        let boundary_min = self.transform.translation - self.transform.scale / 2.0;
-        let boundary_max = self.transform.translation + self.transform.scale / 2.0;
+    // the boundary's own frame - centered on it and turned with it. the wrap,
+    // its normals, bounce_on, the raycast, the distance field and the clamp
+    // work in it - see `transform` for what doesn't
+    fn to_local(&self, position: Vec3) -> Vec3 {
+        self.transform.rotation.inverse() * (position - self.transform.translation)
+    }
+
+    fn to_world(&self, local: Vec3) -> Vec3 { self.transform.translation + self.transform.rotation * local }
+
+    /// `face`'s outward normal, turned with the box
+    pub fn face_normal(&self, face: BoundaryFace) -> Dir3 {
+        Dir3::new_unchecked(self.transform.rotation * face.get_normal())
+    }
+
+    /// the box's own face whose normal points most the same way as `normal`
+    pub fn closest_face(&self, normal: Dir3) -> BoundaryFace {
+        BoundaryFace::closest_to(self.transform.rotation.inverse() * normal)
+    }
+
+    /// calculate_teleport_position with some hysteresis - an axis something
+    /// has just wrapped on has to get `reentry_margin` in from that axis's
+    /// faces before it can wrap on it again, and until then it's held on the
+    /// wall instead. without it anything sitting on a face (or pushed back
+    /// onto it) wraps back and forth every frame. it's measured along the axis
+    /// that wrapped, so something sliding along another face still wraps
+    /// through the rest as usual - on a sphere it's from the surface. only
+    /// `axes` wrap - see calculate_teleport_position_on
+    pub fn teleport_with_reentry(
+        &self,
+        position: Vec3,
+        axes: BVec3,
+        awaiting_reentry: &mut BVec3,
+    ) -> (Vec3, bool) {
+        if let BoundaryShape::Sphere { .. } = self.shape {
+            if awaiting_reentry.any() {
+                if self.signed_distance(position) > -self.reentry_margin {
+                    return (self.hold_inside(position), false);
+                }
+                *awaiting_reentry = BVec3::FALSE;
+            }
+            let (teleport_position, wrapped) = self.wrap_on(position, axes);
+            *awaiting_reentry = wrapped;
+            return (teleport_position, wrapped.any());
+        }
+
+        let local = self.to_local(position);
+        // on an axis too thin for the margin, the middle is as far in as it gets
+        let reach = (self.transform.scale / 2. - self.reentry_margin).max(Vec3::ZERO);
+        for axis in 0..3 {
+            if awaiting_reentry.test(axis) && local[axis].abs() <= reach[axis] {
+                awaiting_reentry.set(axis, false);
+            }
+        }
+
+        let held = *awaiting_reentry;
+        let (teleport_position, wrapped) = self.wrap_on(position, axes & !held);
+        *awaiting_reentry = held | wrapped;
+        if !held.any() {
+            return (teleport_position, wrapped.any());
+        }
+
+        // the axes still waiting are held on their walls
+        let half_size = self.transform.scale / 2.;
+        let mut local = self.to_local(teleport_position);
+        for axis in (0..3).filter(|&axis| held.test(axis)) {
+            local[axis] = local[axis].clamp(-half_size[axis], half_size[axis]);
+        }
+        (self.to_world(local), wrapped.any())
+    }
+
+    /// for something within `distance` of a face on an axis that wraps, that
+    /// axis and how far it would move wrapping through the face - what a copy
+    /// on the other side needs. nothing for the sphere
+    pub fn wrap_offsets_within(&self, position: Vec3, distance: f32) -> impl Iterator<Item = (usize, Vec3)> {
+        let is_sphere = matches!(self.shape, BoundaryShape::Sphere { .. });
+        let wrap_axes: [bool; 3] = self.wrap_axes.into();
+        let local = self.to_local(position);
+        let size = self.transform.scale;
+        let rotation = self.transform.rotation;
+
+        [0, 1, 2]
+            .map(|axis| {
+                let mut offset = Vec3::ZERO;
+                if local[axis] >= size[axis] / 2. - distance {
+                    offset[axis] = -size[axis];
+                } else if local[axis] <= distance - size[axis] / 2. {
+                    offset[axis] = size[axis];
+                }
+                (axis, rotation * offset)
+            })
+            .into_iter()
+            .filter(move |&(axis, offset)| !is_sphere && wrap_axes[axis] && offset != Vec3::ZERO)
+    }
+
+    fn hold_inside(&self, position: Vec3) -> Vec3 {
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            let center = self.transform.translation;
+            return center + (position - center).clamp_length_max(radius);
+        }
+        self.clamp_to_bounds(position)
+    }
 
-        let mut teleport_position = position;
+    /// `position` moved in to at least `margin` from every wall, in the
+    /// boundary's own frame - so a turned box holds it inside its own faces
+    /// and a sphere inside its surface. on an axis (or a sphere) too small
+    /// for the margin it ends up in the middle
+    pub fn pull_inside(&self, position: Vec3, margin: f32) -> Vec3 {
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            let center = self.transform.translation;
+            return center + (position - center).clamp_length_max((radius - margin).max(0.));
+        }
+
+        let reach = (self.transform.scale / 2. - margin).max(Vec3::ZERO);
+        self.to_world(self.to_local(position).clamp(-reach, reach))
+    }
+
+    /// somewhere inside picked at random, at least `margin` from every face -
+    /// or as far from them as it gets on an axis too thin for that. even over
+    /// the whole box, or the whole sphere
+    pub fn random_interior_point(&self, rng: &mut impl Rng, margin: f32) -> Vec3 {
+        let mut within = |reach: f32| {
+            if reach <= 0. {
+                0.
+            } else {
+                rng.random_range(-reach..=reach)
+            }
+        };
 
-        if position.x >= boundary_max.x {
-            teleport_position.x = boundary_min.x;
-        } else if position.x <= boundary_min.x {
-            teleport_position.x = boundary_max.x;
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            let reach = (radius - margin).max(0.);
+            // picked from the cube around it until one lands in the ball -
+            // about half of them do
+            loop {
+                let point = Vec3::new(within(1.), within(1.), within(1.));
+                if point.length_squared() <= 1. {
+                    return self.transform.translation + point * reach;
+                }
+            }
         }
 
-        if position.y >= boundary_max.y {
-            teleport_position.y = boundary_min.y;
-        } else if position.y <= boundary_min.y {
-            teleport_position.y = boundary_max.y;
+        let reach = (self.transform.scale / 2. - margin).max(Vec3::ZERO);
+        self.to_world(Vec3::new(within(reach.x), within(reach.y), within(reach.z)))
+    }
+
+    /// the face on `axis` that something at `position` is on or past, if
+    /// either - in the boundary's own frame. never one for the sphere
+    pub fn face_reached_on(&self, position: Vec3, axis: usize) -> Option<BoundaryFace> {
+        if matches!(self.shape, BoundaryShape::Sphere { .. }) {
+            return None;
+        }
+        let local = self.to_local(position);
+        let half_size = self.transform.scale[axis] / 2.0;
+        if local[axis] >= half_size {
+            Some(BoundaryFace::from_axis(axis, true))
+        } else if local[axis] <= -half_size {
+            Some(BoundaryFace::from_axis(axis, false))
+        } else {
+            None
         }
+    }
+
+    /// one axis's faces as a wall - something on or past one is put back on
+    /// it and, if it's still heading out, its velocity is reflected off the
+    /// face with `restitution` of the speed into it kept. returns the new
+    /// position and velocity
+    ///
+    /// the sphere's surface is the one wall whichever axis is asked about -
+    /// once it's back on the surface the other axes leave it alone
+    pub fn bounce_on(&self, position: Vec3, velocity: Vec3, axis: usize, restitution: f32) -> (Vec3, Vec3) {
+        let reflect = |normal: Vec3| {
+            let outward = velocity.dot(normal);
+            if outward > 0. {
+                velocity - normal * outward * (1. + restitution)
+            } else {
+                velocity
+            }
+        };
 
-        if position.z >= boundary_max.z {
-            teleport_position.z = boundary_min.z;
-        } else if position.z <= boundary_min.z {
-            teleport_position.z = boundary_max.z;
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            let offset = position - self.transform.translation;
+            if offset.length() < radius {
+                return (position, velocity);
+            }
+            let normal = offset.normalize_or_zero();
+            return (self.transform.translation + normal * radius, reflect(normal));
         }
 
-        teleport_position
+        let Some(face) = self.face_reached_on(position, axis) else {
+            return (position, velocity);
+        };
+
+        let mut local = self.to_local(position);
+        let half_size = self.transform.scale[axis] / 2.0;
+        local[axis] = local[axis].clamp(-half_size, half_size);
+
+        (self.to_world(local), reflect(*self.face_normal(face)))
     }
 
     pub fn draw_portal(
         &self,
-        gizmos: &mut Gizmos<PortalGizmo>,
+        lines: &mut impl LineSink,
         portal: &Portal,
         color: Color,
         resolution: u32,
         orientation: &CameraOrientation,
     ) {
-        let overextended_faces = self.get_overextended_faces_for(portal);
-
-        let over_extended_intersection_points =
-            self.get_overextended_intersection_points(portal, overextended_faces);
+        let (u, v) = portal.normal.any_orthonormal_pair();
+
+        // a portal can only spill onto the four faces around its own - fixed
+        // arrays as this runs for every portal every frame.
+        // a sphere has no edges to bend around - portals there are just circles
+        let is_sphere = matches!(self.shape, BoundaryShape::Sphere { .. });
+        let mut faces = [BoundaryFace::default(); 4];
+        let mut angles = [0.; 8];
+        let mut face_count = 0;
+        for (face, depth) in self
+            .get_overextended_faces_for(portal)
+            .filter(|_| !is_sphere)
+            .take(faces.len())
+        {
+            if let Some(crossings) = self.edge_crossings(portal, face, depth, u, v) {
+                faces[face_count] = face;
+                angles[face_count * 2..face_count * 2 + 2].copy_from_slice(&crossings);
+                face_count += 1;
+            }
+        }
 
-        if over_extended_intersection_points.is_empty() {
+        if face_count == 0 {
             let rotation =
                 Quat::from_rotation_arc(orientation.config.axis_profundus, portal.normal.as_vec3());
             let isometry = Isometry3d::new(portal.position, rotation);
-            gizmos
-                .circle(isometry, portal.radius, color)
-                .resolution(resolution);
+            lines.add_circle(isometry, portal.radius, color, resolution);
 
             return;
         }
 
-        // todo #handle3d - with all likelihood this doesn't exactly make sense
-        // when there's a corner so you may need a match to output both sets of points
-        // for the extensions and only output the draw_portal_arc once..
-        for (face, points) in over_extended_intersection_points {
-            if points.len() >= 2 {
-                let rotated_position =
-                    self.rotate_portal_center_to_target_face(portal.position, portal.normal, face);
-
-                // keep this around if you need to debug 3d later on
-                // gizmos.sphere(portal.position, Quat::IDENTITY,1.,
-                // Color::from(tailwind::PURPLE_500)).resolution(resolution);
-                // gizmos.sphere(rotated_position, Quat::IDENTITY,1.,
-                // Color::from(tailwind::PURPLE_500)).resolution(resolution);
-                //
-                // let rotation_point = self.find_closest_point_on_edge(portal.position,
-                // portal.normal.as_vec3(), face.get_normal());
-                // let rotation_axis =
-                // portal.normal.as_vec3().cross(face.get_normal()).normalize();
-                // gizmos.line(rotation_point, rotation_point + rotation_axis * 30.0,
-                // Color::from(tailwind::YELLOW_500));
-                // gizmos.line(rotation_point, portal.position, Color::from(tailwind::RED_500));
-                // gizmos.line(rotation_point, rotated_position,
-                // Color::from(tailwind::GREEN_500));
-
-                gizmos
-                    .short_arc_3d_between(
-                        rotated_position,
-                        points[0],
-                        points[1],
-                        color, // Color::from(tailwind::GREEN_800),
-                    )
-                    .resolution(resolution);
-                self.draw_primary_arc(gizmos, portal, color, resolution, points[0], points[1]);
+        let faces = &faces[..face_count];
+        let angles = &mut angles[..face_count * 2];
+        angles.sort_by(f32::total_cmp);
+        let ends = angles
+            .iter()
+            .skip(1)
+            .copied()
+            .chain(std::iter::once(angles[0] + TAU));
+
+        let point_at = |angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            portal.position + (u * cos + v * sin) * portal.radius
+        };
+
+        // between two neighbouring crossings the circle is on the same side of
+        // every edge it spills over. on the portal's own face it's drawn where
+        // it is, past one edge it's folded over onto that face, and past two
+        // it's hanging off the corner of the box - there's no face for that
+        // part so it isn't drawn
+        for (start, end) in angles.iter().copied().zip(ends) {
+            if end < start + f32::EPSILON {
+                continue;
+            }
+
+            let middle = point_at((start + end) / 2.);
+            let mut beyond = faces.iter().filter(|face| self.is_beyond_face(middle, **face));
+
+            match (beyond.next(), beyond.next()) {
+                (None, _) => {
+                    draw_arc_between_angles(
+                        lines,
+                        portal.position,
+                        &point_at,
+                        (start, end),
+                        |point| point,
+                        color,
+                        resolution,
+                    );
+                },
+                (Some(face), None) => {
+                    let fold = self.fold_onto_face(portal.position, portal.normal, *face);
+                    draw_arc_between_angles(
+                        lines,
+                        portal.position,
+                        &point_at,
+                        (start, end),
+                        fold,
+                        color,
+                        resolution,
+                    );
+                },
+                _ => (),
             }
         }
     }
 
-    // when we rotate this to the target face we get a new center
-    // for the arc that is drawn outside the boundary
-    // wrapped to a point that provide a center that gives
-    // the illusion of having the circle wrap around the edge
-    fn rotate_portal_center_to_target_face(
+    // the two angles around the portal where its circle crosses the line
+    // along `face`'s edge, measured from `u` towards `v`. the whole line and
+    // not just the edge - a circle over a corner crosses it past the end of
+    // the edge, and that's where the part folded onto the face has to stop.
+    // `depth` is how far the circle reaches past the face, from
+    // get_overextended_faces_for
+    fn edge_crossings(
+        &self,
+        portal: &Portal,
+        face: BoundaryFace,
+        depth: f32,
+        u: Vec3,
+        v: Vec3,
+    ) -> Option<[f32; 2]> {
+        let face_normal = self.face_normal(face);
+        let (along_u, along_v) = (face_normal.dot(u), face_normal.dot(v));
+        let length = along_u.hypot(along_v);
+
+        // the face opposite the portal's own never shares an edge with it
+        if length < 1e-3 {
+            return None;
+        }
+
+        let to_face = portal.radius - depth;
+        let ratio = to_face / (portal.radius * length);
+        if ratio.abs() >= 1.0 {
+            return None;
+        }
+
+        let direction = along_v.atan2(along_u);
+        let spread = ratio.acos();
+        Some([
+            (direction - spread).rem_euclid(TAU),
+            (direction + spread).rem_euclid(TAU),
+        ])
+    }
+
+    /// each edge the portal spills over and how it's folded there - empty for a
+    /// portal that fits on its face, and always for the sphere
+    pub fn portal_folds<'a>(&'a self, portal: &'a Portal) -> impl Iterator<Item = PortalFold> + 'a {
+        let (u, v) = portal.normal.any_orthonormal_pair();
+        let is_sphere = matches!(self.shape, BoundaryShape::Sphere { .. });
+
+        self.get_overextended_faces_for(portal)
+            .filter(move |_| !is_sphere)
+            .filter_map(move |(face, depth)| {
+                let [start, end] = self.edge_crossings(portal, face, depth, u, v)?;
+                let own_face = self.closest_face(portal.normal);
+                let fold = self.fold_onto_face(portal.position, portal.normal, face);
+
+                Some(PortalFold {
+                    face,
+                    anchor: self.find_closest_point_on_edge(portal.position, own_face, face),
+                    axis: portal.normal.cross(*self.face_normal(face)).normalize(),
+                    folded_center: fold(portal.position),
+                    angle: (end - start).rem_euclid(TAU),
+                })
+            })
+    }
+
+    fn is_beyond_face(&self, position: Vec3, face: BoundaryFace) -> bool {
+        let (face_point, normal) = self.face_plane(face);
+        normal.dot(position - face_point) > 0.
+    }
+
+    // the part of a portal past an edge gets folded a quarter turn around that
+    // edge so it lies flat on the face next door - the same circle, wrapped
+    // around the edge like it had been drawn on paper
+    fn fold_onto_face(
         &self,
         position: Vec3,
         normal: Dir3,
         target_face: BoundaryFace,
-    ) -> Vec3 {
+    ) -> impl Fn(Vec3) -> Vec3 {
         let current_normal = normal.as_vec3();
 
-        let target_normal = target_face.get_normal();
+        let target_normal = self.face_normal(target_face);
 
         // The rotation axis is the cross product of the current and target normals
-        let rotation_axis = current_normal.cross(target_normal).normalize();
+        let rotation_axis = current_normal.cross(*target_normal).normalize();
 
         // Find the closest point on the rotation axis to the current position
-        let rotation_point = self.find_closest_point_on_edge(position, current_normal, target_normal);
+        let rotation_point =
+            self.find_closest_point_on_edge(position, self.closest_face(normal), target_face);
 
         // Create a rotation quaternion (90 degrees around the rotation axis)
-        let rotation = Quat::from_axis_angle(rotation_axis, std::f32::consts::FRAC_PI_2);
+        let rotation = Quat::from_axis_angle(rotation_axis, FRAC_PI_2);
 
-        // Apply the rotation to the position relative to the rotation point
-        let relative_pos = position - rotation_point;
-        let rotated_pos = rotation * relative_pos;
-
-        // Return the rotated position in world space
-        rotation_point + rotated_pos
+        // Apply the rotation relative to the rotation point
+        move |point| rotation_point + rotation * (point - rotation_point)
     }
 
-    fn find_closest_point_on_edge(&self, position: Vec3, normal1: Vec3, normal2: Vec3) -> Vec3 {
-        // Calculate the direction of the intersection line (edge)
-        let edge_direction = normal1.cross(normal2).normalize();
-
-        // Compute the half extents and center of the cuboid
-        let half_extents = self.transform.scale / 2.0;
-        let center = self.transform.translation;
-
-        // Determine the correct anchor point on the edge formed by the two normals
-        let mut anchor_point = center;
-
-        // Adjust the anchor point based on which planes are intersecting
-        if normal1.x != 0.0 {
-            anchor_point.x += normal1.x * half_extents.x;
-        }
-        if normal1.y != 0.0 {
-            anchor_point.y += normal1.y * half_extents.y;
-        }
-        if normal1.z != 0.0 {
-            anchor_point.z += normal1.z * half_extents.z;
-        }
+    // the edge between two neighbouring faces runs through the middle of both
+    // of them put together
+    fn find_closest_point_on_edge(&self, position: Vec3, face: BoundaryFace, other: BoundaryFace) -> Vec3 {
+        let edge_direction = self.face_normal(face).cross(*self.face_normal(other)).normalize();
+        let anchor_point = self.face_center(face) + self.face_center(other) - self.transform.translation;
 
-        if normal2.x != 0.0 {
-            anchor_point.x += normal2.x * half_extents.x;
-        }
-        if normal2.y != 0.0 {
-            anchor_point.y += normal2.y * half_extents.y;
-        }
-        if normal2.z != 0.0 {
-            anchor_point.z += normal2.z * half_extents.z;
-        }
-
-        // Calculate vector from anchor point to position
-        let to_position = position - anchor_point;
-
-        // Project this onto the edge direction
-        let projection_length = to_position.dot(edge_direction);
-        let point_on_edge = anchor_point + projection_length * edge_direction;
-
-        point_on_edge
+        anchor_point + (position - anchor_point).dot(edge_direction) * edge_direction
     }
 
-    // arc_3d has these assumptions:
-    // rotation: defines orientation of the arc, by default we assume the arc is
-    // contained in a plane parallel to the XZ plane and the default starting
-    // point is (position + Vec3::X)
-    //
-    // so we have to rotate the arc to match up with the actual place it should be
-    // drawn
-    fn draw_primary_arc(
-        &self,
-        gizmos: &mut Gizmos<PortalGizmo>,
-        portal: &Portal,
-        color: Color,
-        resolution: u32,
-        from: Vec3,
-        to: Vec3,
-    ) {
-        let center = portal.position;
-        let radius = portal.radius;
-        let normal = portal.normal.as_vec3();
-
-        // Calculate vectors from center to intersection points
-        let vec_from = (from - center).normalize();
-        let vec_to = (to - center).normalize();
-
-        // Calculate the angle and determine direction
-        let mut angle = vec_from.angle_between(vec_to);
-        let cross_product = vec_from.cross(vec_to);
-        let is_clockwise = cross_product.dot(normal) < 0.0;
-
-        angle = std::f32::consts::TAU - angle;
-
-        // Calculate the rotation to align the arc with the boundary face
-        let face_rotation = Quat::from_rotation_arc(Vec3::Y, normal);
-
-        // Determine the start vector based on clockwise/counterclockwise
-        let start_vec = if is_clockwise { vec_from } else { vec_to };
-        let start_rotation = Quat::from_rotation_arc(face_rotation * Vec3::X, start_vec);
+    // each face the portal's circle reaches past and how far past it goes. an
+    // iterator over a fixed array rather than a vec - this runs for every
+    // portal every frame
+    fn get_overextended_faces_for(&self, portal: &Portal) -> impl Iterator<Item = (BoundaryFace, f32)> {
+        // the face the portal is on doesn't count as overextended. the closest
+        // one rather than an exact match - a normal that's a hair off an axis
+        // would otherwise leave the portal overextended past its own face
+        let own_face = self.closest_face(portal.normal);
+
+        BoundaryFace::ALL
+            .map(|face| {
+                let (face_point, normal) = self.face_plane(face);
+                (face, normal.dot(portal.position - face_point) + portal.radius)
+            })
+            .into_iter()
+            .filter(move |&(face, depth)| depth > 0. && face != own_face)
+    }
 
-        // Combine rotations
-        let final_rotation = start_rotation * face_rotation;
+    /// the middle of `face`
+    pub fn face_center(&self, face: BoundaryFace) -> Vec3 {
+        let (axis, _) = face.axis();
+        self.transform.translation + self.face_normal(face) * self.transform.scale[axis] / 2.
+    }
 
-        // Draw the arc
-        gizmos
-            .arc_3d(angle, radius, Isometry3d::new(center, final_rotation), color)
-            .resolution(resolution);
+    /// half the face's size along the two axes it spans - the next axis round
+    /// from its normal first, so y then z for the left and right faces
+    pub fn face_half_extents(&self, face: BoundaryFace) -> Vec2 {
+        let (axis, _) = face.axis();
+        let half_size = self.transform.scale / 2.;
+        Vec2::new(half_size[(axis + 1) % 3], half_size[(axis + 2) % 3])
+    }
 
-        // Debug visualization
-        // gizmos.line(center, from, Color::from(tailwind::GREEN_500));
-        // gizmos.line(center, to, Color::from(tailwind::BLUE_500));
+    /// the face's corners, going round it in order
+    pub fn face_corners(&self, face: BoundaryFace) -> [Vec3; 4] {
+        let (axis, _) = face.axis();
+        let center = self.face_center(face);
+        let half_extents = self.face_half_extents(face);
+        let mut u = Vec3::ZERO;
+        let mut v = Vec3::ZERO;
+        u[(axis + 1) % 3] = half_extents.x;
+        v[(axis + 2) % 3] = half_extents.y;
+        let (u, v) = (self.transform.rotation * u, self.transform.rotation * v);
+
+        [center - u - v, center + u - v, center + u + v, center - u + v]
     }
 
-    fn get_overextended_faces_for(&self, portal: &Portal) -> Vec<BoundaryFace> {
-        let mut overextended_faces = Vec::new();
-        let half_size = self.transform.scale / 2.0;
-        let min = self.transform.translation - half_size;
-        let max = self.transform.translation + half_size;
-        let radius = portal.radius;
+    /// a point on the face and its outward normal
+    pub fn face_plane(&self, face: BoundaryFace) -> (Vec3, Dir3) {
+        (self.face_center(face), self.face_normal(face))
+    }
 
-        // Check all faces regardless of the portal's normal
-        if portal.position.x - radius < min.x {
-            overextended_faces.push(BoundaryFace::Left);
-        }
-        if portal.position.x + radius > max.x {
-            overextended_faces.push(BoundaryFace::Right);
-        }
-        if portal.position.y - radius < min.y {
-            overextended_faces.push(BoundaryFace::Bottom);
-        }
-        if portal.position.y + radius > max.y {
-            overextended_faces.push(BoundaryFace::Top);
-        }
-        if portal.position.z - radius < min.z {
-            overextended_faces.push(BoundaryFace::Back);
-        }
-        if portal.position.z + radius > max.z {
-            overextended_faces.push(BoundaryFace::Front);
+    pub fn get_normal_for_position(&self, position: Vec3) -> Dir3 {
+        if let BoundaryShape::Sphere { .. } = self.shape {
+            return Dir3::new(position - self.transform.translation).unwrap_or(Dir3::Y);
         }
 
-        // Remove the face the portal is on from the overextended faces
-        let face_to_remove = match portal.normal {
-            Dir3::NEG_X => BoundaryFace::Left,
-            Dir3::X => BoundaryFace::Right,
-            Dir3::NEG_Y => BoundaryFace::Bottom,
-            Dir3::Y => BoundaryFace::Top,
-            Dir3::NEG_Z => BoundaryFace::Back,
-            Dir3::Z => BoundaryFace::Front,
-            _ => return overextended_faces, // Handle any other case without removing a face
-        };
-
-        overextended_faces.retain(|&face| face != face_to_remove);
-        overextended_faces
-    }
-    pub fn get_normal_for_position(&self, position: Vec3) -> Dir3 {
-        let half_size = self.transform.scale / 2.0;
-        let boundary_min = self.transform.translation - half_size;
-        let boundary_max = self.transform.translation + half_size;
+        // found in the boundary's frame and turned back out into the world's
+        let position = self.to_local(position);
+        let boundary_max = self.transform.scale / 2.0;
+        let boundary_min = -boundary_max;
 
         let epsilon = 0.001; // Small value to account for floating-point imprecision
 
-        if (position.x - boundary_min.x).abs() < epsilon {
+        let normal = if (position.x - boundary_min.x).abs() < epsilon {
             Dir3::NEG_X
         } else if (position.x - boundary_max.x).abs() < epsilon {
             Dir3::X
@@ -401,43 +829,149 @@ impl Boundary {
         } else {
             // Default to Y if not on a boundary face
             Dir3::Y
+        };
+        self.transform.rotation * normal
+    }
+
+    /// where a ray - the cursor's, say - meets the plane the spaceship flies
+    /// in, the one through the middle of the boundary facing the z axis
+    pub fn play_plane_point(&self, ray: Ray3d) -> Option<Vec3> {
+        let distance = ray.intersect_plane(self.transform.translation, InfinitePlane3d::new(Vec3::Z))?;
+        Some(ray.get_point(distance))
+    }
+
+    /// where a ray from `origin` along `direction` leaves the boundary - or
+    /// comes into it, from outside. a ray along a face or starting on one
+    /// heading out doesn't hit anything. the face is the box's own, and the
+    /// normal is turned with it
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<BoundaryHit> {
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            let t = ray_sphere_exit(origin - self.transform.translation, direction, radius)?;
+            let point = origin + direction * t;
+            let normal = self.get_normal_for_position(point);
+            return Some(BoundaryHit {
+                point,
+                normal,
+                face: self.closest_face(normal),
+                distance: origin.distance(point),
+            });
+        }
+
+        // in the boundary's own frame, where its walls are slabs
+        let origin = self.to_local(origin);
+        let direction = self.transform.rotation.inverse() * direction;
+        let boundary_max = self.transform.scale / 2.0;
+        let boundary_min = -boundary_max;
+
+        // the slab test - the ray is inside the box between the last slab it
+        // goes into and the first one it comes out of
+        let mut near = (f32::NEG_INFINITY, None);
+        let mut far = (f32::INFINITY, None);
+
+        for axis in 0..3 {
+            let (start, dir) = (origin[axis], direction[axis]);
+
+            // dividing by this would be a nan waiting to happen - parallel to the
+            // slab the ray is either always between its walls or never is
+            if dir.abs() < f32::EPSILON {
+                if start < boundary_min[axis] || start > boundary_max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            // heading towards max it comes in through min and goes out through max
+            let (enter_wall, exit_wall) = if dir > 0.0 {
+                (boundary_min[axis], boundary_max[axis])
+            } else {
+                (boundary_max[axis], boundary_min[axis])
+            };
+            let enter = (enter_wall - start) / dir;
+            let exit = (exit_wall - start) / dir;
+
+            if enter > near.0 {
+                near = (
+                    enter,
+                    Some((axis, enter_wall, BoundaryFace::from_axis(axis, dir < 0.0))),
+                );
+            }
+            if exit < far.0 {
+                far = (
+                    exit,
+                    Some((axis, exit_wall, BoundaryFace::from_axis(axis, dir > 0.0))),
+                );
+            }
+        }
+
+        if near.0 > far.0 {
+            return None;
         }
+
+        // from outside it's the way in that's hit first
+        let (t, wall) = if near.0 > 0.0 { near } else { far };
+        let (axis, wall, face) = wall.filter(|_| t > 0.0)?;
+
+        // on the wall exactly, rather than a float's width either side of it
+        let mut point = origin + direction * t;
+        point[axis] = wall;
+
+        Some(BoundaryHit {
+            point: self.to_world(point),
+            normal: self.face_normal(face),
+            face,
+            distance: origin.distance(point),
+        })
     }
 
+    /// just the point `raycast` hits
     pub fn find_edge_point(&self, origin: Vec3, direction: Vec3) -> Option<Vec3> {
-        let boundary_min = self.transform.translation - self.transform.scale / 2.0;
-        let boundary_max = self.transform.translation + self.transform.scale / 2.0;
+        self.raycast(origin, direction).map(|hit| hit.point)
+    }
 
-        let mut t_min = f32::MAX;
+    /// how far `point` is from the nearest wall - negative inside the
+    /// boundary, positive outside
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        let offset = self.to_local(point);
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            return offset.length() - radius;
+        }
 
-        for (start, dir, pos_bound, neg_bound) in [
-            (origin.x, direction.x, boundary_max.x, boundary_min.x),
-            (origin.y, direction.y, boundary_max.y, boundary_min.y),
-            (origin.z, direction.z, boundary_max.z, boundary_min.z),
-        ] {
-            if dir != 0.0 {
-                let mut update_t_min = |boundary: f32| {
-                    let t = (boundary - start) / dir;
-                    let point = origin + direction * t;
-                    if t > 0.0 && t < t_min && is_in_bounds(point, start, origin, boundary_min, boundary_max)
-                    {
-                        t_min = t;
-                    }
-                };
-
-                update_t_min(pos_bound);
-                update_t_min(neg_bound);
-            }
+        let past_walls = offset.abs() - self.transform.scale / 2.;
+        past_walls.max(Vec3::ZERO).length() + past_walls.max_element().min(0.)
+    }
+
+    /// the wall nearest `point` and the signed distance to the boundary. from
+    /// outside it's the face it's furthest past - the one it would have to come
+    /// back through
+    pub fn nearest_face(&self, point: Vec3) -> (BoundaryFace, f32) {
+        let offset = self.to_local(point);
+        let distance = self.signed_distance(point);
+        if let BoundaryShape::Sphere { .. } = self.shape {
+            let face = Dir3::new(offset).map_or(BoundaryFace::default(), BoundaryFace::closest_to);
+            return (face, distance);
         }
 
-        if t_min != f32::MAX {
-            let edge_point = origin + direction * t_min;
-            return Some(edge_point);
+        let past_walls = offset.abs() - self.transform.scale / 2.;
+        let axis = (0..3)
+            .max_by(|a, b| past_walls[*a].total_cmp(&past_walls[*b]))
+            .unwrap_or_default();
+        (BoundaryFace::from_axis(axis, offset[axis] >= 0.), distance)
+    }
+
+    /// 0 at `approach` or further from every wall, rising to 1 at the nearest
+    /// one - and 1 past it
+    pub fn normalized_wall_proximity(&self, point: Vec3, approach: f32) -> f32 {
+        if approach <= 0. {
+            return 0.;
         }
-        None
+        (1. + self.signed_distance(point) / approach).clamp(0., 1.)
     }
 
     pub fn longest_diagonal(&self) -> f32 {
+        if let BoundaryShape::Sphere { radius } = self.shape {
+            return radius * 2.;
+        }
+
         let boundary_scale = self.scale();
         (boundary_scale.x.powi(2) + boundary_scale.y.powi(2) + boundary_scale.z.powi(2)).sqrt()
     }
@@ -447,83 +981,803 @@ impl Boundary {
         boundary_scale.x.max(boundary_scale.y).max(boundary_scale.z)
     }
 
-    pub fn scale(&self) -> Vec3 { self.scalar * self.cell_count.as_vec3() }
+    /// the size of the box the playfield fits in - the cuboid itself, or the
+    /// cube around the sphere
+    pub fn scale(&self) -> Vec3 {
+        match self.shape {
+            BoundaryShape::Cuboid => self.scalar * self.cell_count.as_vec3(),
+            BoundaryShape::Sphere { radius } => Vec3::splat(radius * 2.),
+        }
+    }
+
+    /// the face a portal at this normal belongs to - on a sphere that's just
+    /// the closest one, portals there are plain circles. on a turned box the
+    /// normal's come through the rotation, so a hair off is still a match
+    pub fn face_for_normal(&self, normal: Dir3) -> Option<BoundaryFace> {
+        let face = self.closest_face(normal);
+        match self.shape {
+            BoundaryShape::Cuboid => (self.face_normal(face).dot(*normal) > 0.999).then_some(face),
+            BoundaryShape::Sphere { .. } => Some(face),
+        }
+    }
+
+    /// the grid's squares along each axis - `grid_subdivisions`, or the cell
+    /// count where that's 0
+    pub fn grid_divisions(&self) -> UVec3 {
+        UVec3::select(
+            self.grid_subdivisions.cmpeq(UVec3::ZERO),
+            self.cell_count,
+            self.grid_subdivisions,
+        )
+    }
+
+    pub fn face_config(&self, face: BoundaryFace) -> FaceConfig {
+        self.face_config.get(&face).copied().unwrap_or_default()
+    }
+
+    /// the shortest displacement from `from` to `to` when you're allowed to go
+    /// through the walls - something just across the seam to your right is
+    /// a short hop to the right, not all the way across the arena to the left.
+    /// only on the axes that wrap, in the boundary's own frame. the sphere
+    /// wraps through the middle to the far side rather than across to a copy
+    /// of itself, so there's no short way round there and it's the straight
+    /// line
+    pub fn wrapped_delta(&self, from: Vec3, to: Vec3) -> Vec3 {
+        let BoundaryShape::Cuboid = self.shape else {
+            return to - from;
+        };
+        let rotation = self.transform.rotation;
+        let local = rotation.inverse() * (to - from);
+        rotation * wrap_delta_on(local, self.transform.scale, self.wrap_axes)
+    }
+
+    pub fn clamp_to_bounds(&self, position: Vec3) -> Vec3 {
+        let half_size = self.transform.scale / 2.0;
+        let local = self.to_local(position);
+        let clamped = local.clamp(-half_size, half_size);
+        if clamped == local {
+            return position;
+        }
+        self.to_world(clamped)
+    }
 }
 
-fn is_in_bounds(point: Vec3, start: f32, origin: Vec3, boundary_min: Vec3, boundary_max: Vec3) -> bool {
-    if start == origin.x {
-        point.y >= boundary_min.y
-            && point.y <= boundary_max.y
-            && point.z >= boundary_min.z
-            && point.z <= boundary_max.z
-    } else if start == origin.y {
-        point.x >= boundary_min.x
-            && point.x <= boundary_max.x
-            && point.z >= boundary_min.z
-            && point.z <= boundary_max.z
-    } else {
-        point.x >= boundary_min.x
-            && point.x <= boundary_max.x
-            && point.y >= boundary_min.y
-            && point.y <= boundary_max.y
+// how far along `direction` something at `offset` from the middle of a sphere
+// gets before it leaves - the far root, so it works from inside
+fn ray_sphere_exit(offset: Vec3, direction: Vec3, radius: f32) -> Option<f32> {
+    let a = direction.length_squared();
+    if a == 0.0 {
+        return None;
+    }
+    let b = offset.dot(direction);
+    let c = offset.length_squared() - radius * radius;
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
     }
+
+    let t = (-b + discriminant.sqrt()) / a;
+    (t > 0.0).then_some(t)
 }
 
-fn draw_boundary(mut boundary: ResMut<Boundary>, mut gizmos: Gizmos<BoundaryGizmo>) {
-    // updating the boundary resource transform from its configuration so it can be
-    // dynamically changed with the inspector while the game is running
-    // the boundary transform is used both for position but also
-    // so the fixed camera can be positioned based on the boundary scale
-    boundary.transform.scale = boundary.scale();
-
-    gizmos
-        .grid_3d(
-            Isometry3d::new(boundary.transform.translation, Quat::IDENTITY),
-            boundary.cell_count,
-            Vec3::splat(boundary.scalar),
-            boundary.color,
-        )
-        .outer_edges();
+// folds a displacement in the boundary's frame back into the nearest copy of
+// the boundary, on each of `axes`
+pub(super) fn wrap_delta_on(mut delta: Vec3, size: Vec3, axes: BVec3) -> Vec3 {
+    for axis in 0..3 {
+        if axes.test(axis) && size[axis] > 0.0 {
+            delta[axis] -= size[axis] * (delta[axis] / size[axis]).round();
+        }
+    }
+
+    delta
 }
 
-pub fn intersect_circle_with_rectangle(portal: &Portal, rectangle_points: &[Vec3; 4]) -> Vec<Vec3> {
-    let mut intersections = Vec::new();
+// updating the boundary resource transform from its configuration so it can be
+// dynamically changed with the inspector while the game is running
+// the boundary transform is used both for position but also
+// so the fixed camera can be positioned based on the boundary scale
+//
+// only written when it actually differs - the line mesh is rebuilt whenever
+// Boundary changes. the event is measured from the last one sent so a run of
+// tiny changes still adds up to one eventually
+pub(super) fn update_boundary_transform(
+    mut boundary: ResMut<Boundary>,
+    mut resized: EventWriter<BoundaryResized>,
+    mut announced_scale: Local<Option<Vec3>>,
+) {
+    let scale = boundary.scale();
+    if boundary.transform.scale != scale {
+        boundary.transform.scale = scale;
+    }
 
-    for i in 0..4 {
-        let start = rectangle_points[i];
-        let end = rectangle_points[(i + 1) % 4];
+    let old_scale = *announced_scale.get_or_insert(scale);
+    if !old_scale.abs_diff_eq(scale, RESIZE_EPSILON) {
+        resized.send(BoundaryResized {
+            old_scale,
+            new_scale: scale,
+        });
+        *announced_scale = Some(scale);
+    }
+}
+
+fn draw_boundary(boundary: Res<Boundary>, fade: Res<BoundaryFade>, mut gizmos: Gizmos<BoundaryGizmo>) {
+    boundary_lines(&mut gizmos, &boundary, fade.alpha(&boundary));
+}
 
-        let edge_intersections = intersect_circle_with_line_segment(portal, start, end);
-        intersections.extend(edge_intersections);
+// the render mode goes round in this order - the corners start out a tenth of
+// the way along each edge
+fn cycle_render_mode(user_input: Res<ActionState<GlobalAction>>, mut boundary: ResMut<Boundary>) {
+    if !user_input.just_pressed(&GlobalAction::CycleBoundaryRenderMode) {
+        return;
     }
 
-    intersections
+    boundary.render_mode = match boundary.render_mode {
+        BoundaryRenderMode::FullGrid => BoundaryRenderMode::OuterEdgesOnly,
+        BoundaryRenderMode::OuterEdgesOnly => BoundaryRenderMode::CornersOnly {
+            corner_length: DEFAULT_CORNER_LENGTH,
+        },
+        BoundaryRenderMode::CornersOnly { .. } => BoundaryRenderMode::Off,
+        BoundaryRenderMode::Off => BoundaryRenderMode::FullGrid,
+    };
 }
 
-fn intersect_circle_with_line_segment(portal: &Portal, start: Vec3, end: Vec3) -> Vec<Vec3> {
-    let edge = end - start;
-    let center_to_start = start - portal.position;
+// a ring on each side of a wrap, so the way out and the way back in look like
+// they go together - and just the one where something left for good
+#[derive(Resource, Debug, Default)]
+struct BoundaryPulses(Vec<Pulse>);
 
-    let a = edge.dot(edge);
-    let b = 2.0 * center_to_start.dot(edge);
-    let c = center_to_start.dot(center_to_start) - portal.radius * portal.radius;
+#[derive(Debug)]
+struct Pulse {
+    position: Vec3,
+    normal:   Dir3,
+    started:  f32,
+}
 
-    let discriminant = b * b - 4.0 * a * c;
+// the exit is just past the wall - pulled back onto it so its normal can be
+// found
+fn start_pulses(
+    boundary: Res<Boundary>,
+    time: Res<GameTime>,
+    mut teleported: EventReader<EntityTeleported>,
+    mut left_boundary: EventReader<EntityLeftBoundary>,
+    mut pulses: ResMut<BoundaryPulses>,
+) {
+    let started = time.elapsed_secs();
+    for event in teleported.read() {
+        let exit = boundary.clamp_to_bounds(event.from);
+        pulses.0.extend([
+            Pulse {
+                position: exit,
+                normal: boundary.get_normal_for_position(exit),
+                started,
+            },
+            Pulse {
+                position: event.to,
+                normal: boundary.get_normal_for_position(event.to),
+                started,
+            },
+        ]);
+    }
 
-    if discriminant < 0.0 {
-        return vec![];
+    for event in left_boundary.read() {
+        pulses.0.push(Pulse {
+            position: boundary.clamp_to_bounds(event.position),
+            normal: boundary.face_normal(event.face),
+            started,
+        });
+    }
+}
+
+fn draw_pulses(
+    boundary: Res<Boundary>,
+    time: Res<GameTime>,
+    mut pulses: ResMut<BoundaryPulses>,
+    mut gizmos: Gizmos<BoundaryGizmo>,
+) {
+    let now = time.elapsed_secs();
+    let duration = boundary.pulse_duration;
+    pulses.0.retain(|pulse| now - pulse.started < duration);
+
+    for pulse in &pulses.0 {
+        let progress = (now - pulse.started) / duration;
+        // gizmo circles lie in the xy plane before they're turned to face the
+        // normal
+        let rotation = Quat::from_rotation_arc(Vec3::Z, pulse.normal.as_vec3());
+        gizmos
+            .circle(
+                Isometry3d::new(pulse.position, rotation),
+                boundary.scalar * PULSE_SIZE * progress,
+                boundary.color.with_alpha(1. - progress),
+            )
+            .resolution(PULSE_RESOLUTION);
     }
+}
 
-    let mut intersections = Vec::new();
-    let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
-    let t2 = (-b - discriminant.sqrt()) / (2.0 * a);
+#[derive(Component, Debug)]
+struct BoundaryLines;
+
+// the grid has a material of its own rather than the shared LineMaterial, so
+// that the fade can go on its base color without fading the portals as well
+fn update_boundary_lines(
+    mut commands: Commands,
+    boundary: Res<Boundary>,
+    fade: Res<BoundaryFade>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q_lines: Query<(&Mesh3d, &MeshMaterial3d<StandardMaterial>, &mut Visibility), With<BoundaryLines>>,
+) {
+    let faded = Color::WHITE.with_alpha(fade.alpha(&boundary));
+
+    let Ok((mesh, material, mut visibility)) = q_lines.get_single_mut() else {
+        let mesh = meshes.add(boundary_grid_mesh(&boundary));
+        let material = LineMaterial::blended(&mut materials);
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color = faded;
+        }
+        commands.spawn((line_mesh_bundle(mesh, &material, "BoundaryLines"), BoundaryLines));
+        return;
+    };
 
-    if (0.0..=1.0).contains(&t1) {
-        intersections.push(start + t1 * edge);
+    show_line_mesh(&mut visibility);
+    if boundary.is_changed() {
+        meshes.insert(&mesh.0, boundary_grid_mesh(&boundary));
     }
-    if (0.0..=1.0).contains(&t2) && (t1 - t2).abs() > 1e-6 {
-        intersections.push(start + t2 * edge);
+
+    // the fade is worked out every frame but mostly doesn't move - only
+    // touch the material when it has, or it goes back to the gpu every frame
+    if materials
+        .get(&material.0)
+        .is_some_and(|material| material.base_color != faded)
+    {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color = faded;
+        }
     }
+}
+
+/// the grid as one line mesh, the one update_boundary_lines shows -
+/// tests/golden.rs renders it too
+pub fn boundary_grid_mesh(boundary: &Boundary) -> Mesh {
+    let mut lines = LineListBuilder::default();
+    boundary_lines(&mut lines, boundary, 1.);
+    lines.build()
+}
 
-    intersections
+// the same lines for the gizmos and the line mesh. the sphere has no edges or
+// corners - anything but off is its globe
+fn boundary_lines(lines: &mut impl LineSink, boundary: &Boundary, alpha: f32) {
+    match (boundary.shape, boundary.render_mode) {
+        (_, BoundaryRenderMode::Off) => (),
+        (BoundaryShape::Sphere { radius }, _) => {
+            let color = boundary.color.with_alpha(boundary.color.alpha() * alpha);
+            sphere_grid(lines, boundary.transform.translation, radius, color);
+        },
+        (BoundaryShape::Cuboid, BoundaryRenderMode::CornersOnly { corner_length }) => {
+            let color = boundary.color.with_alpha(boundary.color.alpha() * alpha);
+            corner_ticks(lines, boundary, corner_length, color);
+        },
+        (BoundaryShape::Cuboid, _) => cuboid_grid(lines, boundary, alpha),
+    }
+}
+
+// like a camera viewfinder - three short lines out of each corner along its
+// edges, each the same share of its own edge so a long box gets long ticks
+fn corner_ticks(lines: &mut impl LineSink, boundary: &Boundary, corner_length: f32, color: Color) {
+    let half = boundary.transform.scale / 2.;
+    let tick = boundary.transform.scale * corner_length.clamp(0., 0.5);
+
+    for corner in 0..8 {
+        let sign = Vec3::new(
+            if corner & 1 == 0 { -1. } else { 1. },
+            if corner & 2 == 0 { -1. } else { 1. },
+            if corner & 4 == 0 { -1. } else { 1. },
+        );
+        let position = sign * half;
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            lines.add_line(
+                boundary.to_world(position),
+                boundary.to_world(position - sign * axis * tick),
+                color,
+            );
+        }
+    }
+}
+
+// each face is a grid of its own so it can have its own color and density, or
+// not be drawn at all. the lines through the inside of the box aren't on any
+// face - they're always there, in the boundary's color. with only the outer
+// edges each face is a single square. `alpha` scales every color's own
+fn cuboid_grid(lines: &mut impl LineSink, boundary: &Boundary, alpha: f32) {
+    let outer_edges_only = matches!(boundary.render_mode, BoundaryRenderMode::OuterEdgesOnly);
+    let faded = |color: Color| color.with_alpha(color.alpha() * alpha);
+    let size = boundary.transform.scale;
+    // worked out in the box's own frame, and each line turned out into the world
+    let min = -size / 2.;
+    let mut line = |start: Vec3, end: Vec3, color: Color| {
+        lines.add_line(boundary.to_world(start), boundary.to_world(end), color);
+    };
+
+    // `step` of `count` of the way along `axis`
+    let along = |axis: usize, step: u32, count: u32| {
+        let mut offset = Vec3::ZERO;
+        offset[axis] = size[axis] * step as f32 / count.max(1) as f32;
+        offset
+    };
+
+    for face in BoundaryFace::ALL {
+        let config = boundary.face_config(face);
+        if !config.visible {
+            continue;
+        }
+
+        let color = faded(config.color.unwrap_or(boundary.color));
+        let steps = if outer_edges_only {
+            UVec3::ONE
+        } else {
+            boundary.grid_divisions() * config.density.max(1)
+        };
+        let (axis, positive) = face.axis();
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        let origin = if positive { min + along(axis, 1, 1) } else { min };
+
+        for step in 0..=steps[b] {
+            let start = origin + along(b, step, steps[b]);
+            line(start, start + along(a, 1, 1), color);
+        }
+        for step in 0..=steps[a] {
+            let start = origin + along(a, step, steps[a]);
+            line(start, start + along(b, 1, 1), color);
+        }
+    }
+
+    if outer_edges_only {
+        return;
+    }
+
+    let cells = boundary.grid_divisions();
+    for axis in 0..3 {
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        for i in 1..cells[a] {
+            for j in 1..cells[b] {
+                let start = min + along(a, i, cells[a]) + along(b, j, cells[b]);
+                line(start, start + along(axis, 1, 1), faded(boundary.color));
+            }
+        }
+    }
+}
+
+// lines of latitude and longitude, like a globe with y as its axis
+fn sphere_grid(lines: &mut impl LineSink, center: Vec3, radius: f32, color: Color) {
+    // circles are drawn in the xy plane, so the latitudes are tipped over
+    let flat = Quat::from_rotation_x(FRAC_PI_2);
+    for latitude in 1..SPHERE_LATITUDES {
+        let polar = PI * latitude as f32 / SPHERE_LATITUDES as f32;
+        lines.add_circle(
+            Isometry3d::new(center + Vec3::Y * radius * polar.cos(), flat),
+            radius * polar.sin(),
+            color,
+            SPHERE_RESOLUTION,
+        );
+    }
+
+    // each longitude is a great circle, so half as many go all the way round
+    for longitude in 0..SPHERE_LONGITUDES / 2 {
+        let azimuth = TAU * longitude as f32 / SPHERE_LONGITUDES as f32;
+        lines.add_circle(
+            Isometry3d::new(center, Quat::from_rotation_y(azimuth)),
+            radius,
+            color,
+            SPHERE_RESOLUTION,
+        );
+    }
+}
+
+// short arcs can't go more than halfway round or they'd pick the wrong way
+// - quarters are comfortably short. `fold` moves the whole arc somewhere else
+// rigidly, so folding the center and ends is the same as folding every point
+fn draw_arc_between_angles(
+    lines: &mut impl LineSink,
+    center: Vec3,
+    point_at: &impl Fn(f32) -> Vec3,
+    (start, end): (f32, f32),
+    fold: impl Fn(Vec3) -> Vec3,
+    color: Color,
+    resolution: u32,
+) {
+    let sweep = end - start;
+    let pieces = (sweep / FRAC_PI_2).ceil().max(1.);
+    let piece_resolution = (resolution as f32 * sweep / TAU / pieces).ceil().max(1.) as u32;
+
+    for piece in 0..pieces as u32 {
+        let from = start + sweep * piece as f32 / pieces;
+        let to = start + sweep * (piece + 1) as f32 / pieces;
+        lines.add_short_arc_between(
+            fold(center),
+            fold(point_at(from)),
+            fold(point_at(to)),
+            color,
+            piece_resolution,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+    use bevy::render::mesh::VertexAttributeValues;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn boundary_round_trips() { assert_settings_round_trip(nudged::<Boundary>()); }
+
+    // the boundary is a couple of hundred units across so this is float noise
+    const EPSILON: f32 = 0.01;
+
+    // off center and a different size along every axis, where mixing up an
+    // axis or a sign shows
+    fn offset_boundary() -> Boundary {
+        Boundary {
+            transform: Transform::from_translation(Vec3::new(40., -25., 10.))
+                .with_scale(Vec3::new(300., 80., 150.)),
+            ..default()
+        }
+    }
+
+    // every corner is a corner of the box on the face's side of it, and going
+    // round them each step moves along just one axis
+    #[test]
+    fn face_corners_on_an_offset_boundary() {
+        let boundary = offset_boundary();
+        let half = boundary.transform.scale / 2.;
+        let min = boundary.transform.translation - half;
+        let max = boundary.transform.translation + half;
+        let close = |a: f32, b: f32| (a - b).abs() < EPSILON;
+
+        for face in BoundaryFace::ALL {
+            let (axis, positive) = face.axis();
+            let wall = if positive { max[axis] } else { min[axis] };
+            let (point, normal) = boundary.face_plane(face);
+            assert!(
+                close(point[axis], wall),
+                "{face:?} plane point {point} is off its wall"
+            );
+            assert_eq!(normal.as_vec3(), face.get_normal(), "{face:?} plane normal");
+
+            let corners = boundary.face_corners(face);
+            for (index, corner) in corners.iter().enumerate() {
+                let on_box = (0..3).all(|i| close(corner[i], min[i]) || close(corner[i], max[i]));
+                assert!(
+                    on_box && close(corner[axis], wall),
+                    "{face:?} corner {corner} isn't a corner of the box"
+                );
+
+                let next = corners[(index + 1) % corners.len()];
+                let moved = (0..3).filter(|&i| !close(corner[i], next[i])).count();
+                assert_eq!(moved, 1, "{face:?} corners {corner} and {next} aren't neighbours");
+            }
+
+            let extents = boundary.face_half_extents(face);
+            let spans = (corners[2] - corners[0]).abs() / 2.;
+            assert!(
+                close(extents.x + extents.y, spans.element_sum()),
+                "{face:?} half extents {extents} don't match its corners"
+            );
+        }
+    }
+
+    // z is a wall - out past x and z at once it wraps on x and is held on the
+    // z face, and out past z alone it's only held there, which isn't a wrap
+    #[test]
+    fn clamped_axis_alongside_a_wrapped_one() {
+        let boundary = Boundary {
+            wrap_axes: BVec3::new(true, true, false),
+            ..default()
+        };
+        let half = boundary.transform.scale / 2.;
+
+        let (position, wrapped) =
+            boundary.calculate_teleport_position(Vec3::new(half.x + 1., 0., half.z + 5.));
+        assert!(wrapped);
+        assert!(
+            position.distance(Vec3::new(-half.x, 0., half.z)) < EPSILON,
+            "came in at {position}"
+        );
+
+        let (position, wrapped) = boundary.calculate_teleport_position(Vec3::new(0., 0., half.z + 5.));
+        assert!(!wrapped);
+        assert!(
+            position.distance(Vec3::new(0., 0., half.z)) < EPSILON,
+            "held at {position}"
+        );
+    }
+
+    // both ends of every line in the portal, built the same way
+    // update_portal_lines builds it
+    fn portal_line_ends(boundary: &Boundary, portal: &Portal) -> Vec<Vec3> {
+        let mut lines = LineListBuilder::default();
+        boundary.draw_portal(
+            &mut lines,
+            portal,
+            Color::WHITE,
+            128,
+            &CameraOrientation::default(),
+        );
+        match lines.build().attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => {
+                positions.iter().copied().map(Vec3::from).collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    // the arcs of a portal on its face, over an edge, into a corner and
+    // hanging off one, and on a boundary that's been moved - whatever gets
+    // folded or cut, every line has to end on the box's surface
+    #[test]
+    fn portal_arcs_end_on_the_boundary() {
+        let moved = Boundary {
+            transform: Transform::from_translation(Vec3::new(40., -25., 10.))
+                .with_scale(Boundary::default().scale()),
+            ..default()
+        };
+        let cases = [
+            (Boundary::default(), BoundaryFace::Right, Vec3::new(110., 0., 0.)),
+            (Boundary::default(), BoundaryFace::Right, Vec3::new(110., 45., 0.)),
+            (
+                Boundary::default(),
+                BoundaryFace::Right,
+                Vec3::new(110., 45., 45.),
+            ),
+            (
+                Boundary::default(),
+                BoundaryFace::Right,
+                Vec3::new(110., 55., 55.),
+            ),
+            (
+                moved.clone(),
+                BoundaryFace::Top,
+                moved.transform.translation + Vec3::new(60., 55., 45.),
+            ),
+        ];
+
+        for (boundary, face, position) in cases {
+            let half = boundary.transform.scale / 2.;
+            let min = boundary.transform.translation - half;
+            let max = boundary.transform.translation + half;
+            let portal = Portal::on_face(face, position, 20.);
+
+            let ends = portal_line_ends(&boundary, &portal);
+            assert!(!ends.is_empty(), "nothing drawn for the portal at {position}");
+            for end in ends {
+                let inside = end.cmpge(min - EPSILON).all() && end.cmple(max + EPSILON).all();
+                let on_face =
+                    (end - min).abs().min_element() < EPSILON || (end - max).abs().min_element() < EPSILON;
+                assert!(
+                    inside && on_face,
+                    "the portal at {position} has a line ending off the boundary, at {end}"
+                );
+            }
+        }
+    }
+
+    fn turned_boundary(rotation: Quat) -> Boundary {
+        Boundary {
+            transform: Transform::from_translation(Vec3::new(40., -25., 10.))
+                .with_rotation(rotation)
+                .with_scale(Boundary::default().scale()),
+            ..default()
+        }
+    }
+
+    // a box turned 45 degrees around y - going out through each of its four
+    // side faces has to come back in through the face opposite in the box,
+    // with that face's normal turned the same way
+    #[test]
+    fn rotated_box_wraps_to_its_own_opposite_face() {
+        let rotation = Quat::from_rotation_y(FRAC_PI_4);
+        let boundary = turned_boundary(rotation);
+        let half = boundary.transform.scale / 2.;
+        let to_world = |local: Vec3| boundary.transform.translation + rotation * local;
+
+        for local_normal in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z] {
+            let past_face = to_world(local_normal * (half + 1.));
+            let (entry, wrapped) = boundary.calculate_teleport_position_on(past_face, BVec3::TRUE);
+            let expected = to_world(-local_normal * half);
+            assert!(wrapped, "out through {local_normal} didn't wrap");
+            assert!(
+                entry.distance(expected) < EPSILON,
+                "out through {local_normal} came in at {entry}, not {expected}"
+            );
+
+            let normal = boundary.get_normal_for_position(entry);
+            assert!(
+                normal.distance(rotation * -local_normal) < EPSILON,
+                "came in through {} but its normal is {normal:?}",
+                -local_normal
+            );
+        }
+    }
+
+    // from the middle straight out along each of the turned box's own axes
+    // it hits the middle of that face, not wherever the world's box would be
+    #[test]
+    fn rotated_box_raycast_hits_its_own_faces() {
+        let rotation = Quat::from_rotation_y(FRAC_PI_4);
+        let boundary = turned_boundary(rotation);
+        let half = boundary.transform.scale / 2.;
+
+        for face in BoundaryFace::ALL {
+            let (axis, _) = face.axis();
+            let direction = rotation * face.get_normal();
+            let hit = boundary
+                .raycast(boundary.transform.translation, direction)
+                .expect("a ray from the middle always leaves");
+
+            assert_eq!(hit.face, face);
+            assert!(
+                hit.normal.distance(direction) < EPSILON,
+                "{face:?} normal {:?}",
+                hit.normal
+            );
+            assert!(
+                (hit.distance - half[axis]).abs() < EPSILON,
+                "{face:?} hit {} away",
+                hit.distance
+            );
+        }
+    }
+
+    // the face helpers on a box turned off every axis - each face's plane is
+    // where the ray out along its normal hits, its corners are on that plane
+    // and on the box, and its normal finds its way back to it
+    #[test]
+    fn rotated_box_face_helpers() {
+        let rotation = Quat::from_euler(EulerRot::XYZ, 0.3, FRAC_PI_4, -0.6);
+        let boundary = turned_boundary(rotation);
+
+        for face in BoundaryFace::ALL {
+            let (point, normal) = boundary.face_plane(face);
+            assert!(normal.distance(rotation * face.get_normal()) < EPSILON);
+            let hit = boundary
+                .raycast(boundary.transform.translation, *normal)
+                .expect("a ray from the middle always leaves");
+            assert!(
+                hit.point.distance(point) < EPSILON,
+                "{face:?} center {point} isn't where the ray out hits, {}",
+                hit.point
+            );
+
+            for corner in boundary.face_corners(face) {
+                assert!(
+                    normal.dot(corner - point).abs() < EPSILON && boundary.signed_distance(corner).abs() < EPSILON,
+                    "{face:?} corner {corner} is off the face"
+                );
+            }
+
+            assert_eq!(boundary.face_for_normal(normal), Some(face));
+            assert_eq!(boundary.closest_face(normal), face);
+        }
+    }
+
+    // the portal over an edge and into a corner, and every grid and corner tick
+    // line, on a turned box - all of them have to end on its surface
+    #[test]
+    fn rotated_box_lines_end_on_the_boundary() {
+        let rotation = Quat::from_euler(EulerRot::XYZ, 0.3, FRAC_PI_4, -0.6);
+        let mut boundary = turned_boundary(rotation);
+        let on_surface = |boundary: &Boundary, end: Vec3| boundary.signed_distance(end).abs() < EPSILON;
+
+        let half = boundary.transform.scale / 2.;
+        for local in [Vec3::new(half.x, 45., 0.), Vec3::new(half.x, 45., 45.)] {
+            let mut portal = Portal::on_face(BoundaryFace::Right, boundary.to_world(local), 20.);
+            portal.normal = boundary.face_normal(BoundaryFace::Right);
+            let ends = portal_line_ends(&boundary, &portal);
+            assert!(!ends.is_empty(), "nothing drawn for the portal at {local}");
+            assert_eq!(boundary.portal_folds(&portal).count(), if local.z == 0. { 1 } else { 2 });
+            for end in ends {
+                assert!(
+                    on_surface(&boundary, end),
+                    "the portal at {local} has a line ending off the boundary, at {end}"
+                );
+            }
+        }
+
+        for render_mode in [
+            BoundaryRenderMode::FullGrid,
+            BoundaryRenderMode::CornersOnly {
+                corner_length: DEFAULT_CORNER_LENGTH,
+            },
+        ] {
+            boundary.render_mode = render_mode;
+            let grid = boundary_grid_mesh(&boundary);
+            let Some(VertexAttributeValues::Float32x3(positions)) = grid.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                panic!("the grid has no positions");
+            };
+            for end in positions.iter().copied().map(Vec3::from) {
+                assert!(
+                    on_surface(&boundary, end),
+                    "{render_mode:?} has a line ending off the boundary, at {end}"
+                );
+            }
+        }
+    }
+
+    // along a face, just inside it - parallel to the walls on that axis it
+    // goes on out through the face ahead, and just outside it never comes in
+    #[test]
+    fn raycast_parallel_to_a_face() {
+        let boundary = offset_boundary();
+        let half = boundary.transform.scale / 2.;
+        let center = boundary.transform.translation;
+
+        let inside = center + Vec3::new(0., half.y - 1., 0.);
+        let hit = boundary.raycast(inside, Vec3::X).expect("it leaves through the right");
+        assert_eq!(hit.face, BoundaryFace::Right);
+        assert!(
+            hit.point.distance(inside + Vec3::X * half.x) < EPSILON,
+            "left at {}",
+            hit.point
+        );
+        assert_eq!(boundary.find_edge_point(inside, Vec3::X), Some(hit.point));
+
+        let outside = center + Vec3::new(-half.x - 50., half.y + 1., 0.);
+        assert!(boundary.raycast(outside, Vec3::X).is_none());
+        assert!(boundary.find_edge_point(outside, Vec3::X).is_none());
+    }
+
+    // starting on a face, heading in goes right across to the opposite one
+    // and heading out doesn't hit anything
+    #[test]
+    fn raycast_from_on_a_face() {
+        let boundary = offset_boundary();
+        let half = boundary.transform.scale / 2.;
+        let on_right = boundary.transform.translation + Vec3::new(half.x, 10., -5.);
+
+        let hit = boundary.raycast(on_right, Vec3::NEG_X).expect("it crosses to the left");
+        assert_eq!(hit.face, BoundaryFace::Left);
+        assert!(
+            (hit.distance - boundary.transform.scale.x).abs() < EPSILON,
+            "crossed {}",
+            hit.distance
+        );
+
+        assert!(boundary.raycast(on_right, Vec3::X).is_none());
+    }
+
+    // the line mesh is the default renderer, so the fade has to get to it and
+    // not only to the gizmos
+    #[test]
+    fn line_mesh_grid_fades() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        world.insert_resource(Boundary::default());
+        world.insert_resource(BoundaryFade(1.));
+        let material_alpha = |world: &mut World| {
+            let mut q_lines =
+                world.query_filtered::<&MeshMaterial3d<StandardMaterial>, With<BoundaryLines>>();
+            let handle = q_lines.single(world).0.clone();
+            world
+                .resource::<Assets<StandardMaterial>>()
+                .get(&handle)
+                .unwrap()
+                .base_color
+                .alpha()
+        };
+
+        world.run_system_once(update_boundary_lines).unwrap();
+        let boundary = Boundary::default();
+        assert!((material_alpha(&mut world) - boundary.fade_far_alpha).abs() < EPSILON);
+
+        world.resource_mut::<BoundaryFade>().0 = 0.;
+        world.run_system_once(update_boundary_lines).unwrap();
+        assert!((material_alpha(&mut world) - boundary.fade_near_alpha).abs() < EPSILON);
+    }
 }
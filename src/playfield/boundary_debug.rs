@@ -0,0 +1,146 @@
+#[cfg(dev_tools)]
+use crate::camera::PrimaryCamera;
+use crate::{
+    camera::RenderLayer,
+    global_input::GlobalAction,
+    playfield::{
+        boundary::{
+            Boundary,
+            PortalFold,
+        },
+        portals::{
+            ActorPortals,
+            Portal,
+        },
+    },
+    schedule::FrameSet,
+};
+#[cfg(dev_tools)]
+use bevy::window::PrimaryWindow;
+use bevy::{
+    color::palettes::tailwind,
+    prelude::*,
+    render::view::RenderLayers,
+};
+#[cfg(dev_tools)]
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+    EguiContextSettings,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+// how far the edge axis is drawn either side of the anchor, as a share of the
+// portal's radius
+const AXIS_LENGTH: f32 = 1.5;
+const MARKER_SIZE: f32 = 0.5;
+
+/// what the portal drawing is doing when it bends a portal around an edge -
+/// the edge it turns around, the anchor on that edge, where the portal's
+/// center lands once folded and how much of the circle spills over. only
+/// while the debug key is held, so it costs nothing the rest of the time. the
+/// numbers go out as debug! logs as well - RUST_LOG=nateroids=debug to see
+/// them
+pub struct BoundaryDebugPlugin;
+
+impl Plugin for BoundaryDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<BoundaryDebugGizmo>()
+            .add_systems(Startup, draw_boundary_debug_on_debug_layer)
+            .add_systems(
+                Update,
+                draw_portal_folds.in_set(FrameSet::Visual).run_if(debug_held),
+            );
+
+        #[cfg(dev_tools)]
+        app.add_systems(Update, label_portal_folds.in_set(FrameSet::Ui).run_if(debug_held));
+    }
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct BoundaryDebugGizmo {}
+
+fn draw_boundary_debug_on_debug_layer(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<BoundaryDebugGizmo>();
+    config.render_layers = RenderLayers::from_layers(RenderLayer::Debug.layers());
+}
+
+fn debug_held(user_input: Res<ActionState<GlobalAction>>) -> bool { user_input.pressed(&GlobalAction::Debug) }
+
+fn folds<'a>(
+    boundary: &'a Boundary,
+    portals: impl Iterator<Item = &'a ActorPortals> + 'a,
+) -> impl Iterator<Item = (&'a Portal, PortalFold)> + 'a {
+    portals
+        .flat_map(|portals| [portals.approaching.as_ref(), portals.emerging.as_ref()])
+        .flatten()
+        .flat_map(|portal| boundary.portal_folds(portal).map(move |fold| (portal, fold)))
+}
+
+fn draw_portal_folds(
+    boundary: Res<Boundary>,
+    mut gizmos: Gizmos<BoundaryDebugGizmo>,
+    q_portals: Query<&ActorPortals>,
+) {
+    let _span = debug_span!("portal folds").entered();
+
+    for (portal, fold) in folds(&boundary, q_portals.iter()) {
+        debug!(
+            face = ?fold.face,
+            anchor = ?fold.anchor,
+            axis = ?fold.axis,
+            folded_center = ?fold.folded_center,
+            angle = fold.angle.to_degrees(),
+            "portal fold"
+        );
+
+        let reach = fold.axis * portal.radius * AXIS_LENGTH;
+        gizmos.line(fold.anchor - reach, fold.anchor + reach, tailwind::YELLOW_400);
+        gizmos.line(portal.position, fold.anchor, tailwind::RED_400);
+        gizmos.line(fold.anchor, fold.folded_center, tailwind::GREEN_400);
+        gizmos.sphere(
+            Isometry3d::from_translation(fold.anchor),
+            MARKER_SIZE,
+            tailwind::ORANGE_400,
+        );
+        gizmos.cross(
+            Isometry3d::from_translation(fold.folded_center),
+            MARKER_SIZE * 2.,
+            tailwind::GREEN_400,
+        );
+    }
+}
+
+// the angle sits next to the folded center - egui, as gizmos can't draw text.
+// dev tool builds only, the lines are enough to see a fold going wrong
+#[cfg(dev_tools)]
+fn label_portal_folds(
+    boundary: Res<Boundary>,
+    mut q_egui: Query<(&mut EguiContext, &EguiContextSettings), With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    q_portals: Query<&ActorPortals>,
+) {
+    let (Ok((mut context, egui_settings)), Ok((camera, camera_transform))) =
+        (q_egui.get_single_mut(), q_camera.get_single())
+    else {
+        return;
+    };
+
+    for (index, (_, fold)) in folds(&boundary, q_portals.iter()).enumerate() {
+        let Ok(viewport_position) = camera.world_to_viewport(camera_transform, fold.folded_center) else {
+            continue;
+        };
+        let position = viewport_position / egui_settings.scale_factor;
+
+        egui::Area::new(egui::Id::new(("portal fold", index)))
+            .fixed_pos(egui::pos2(position.x + 8., position.y + 8.))
+            .interactable(false)
+            .show(context.get_mut(), |ui| {
+                ui.label(
+                    egui::RichText::new(format!("{:?} {:.0}°", fold.face, fold.angle.to_degrees()))
+                        .monospace()
+                        .small(),
+                );
+            });
+    }
+}
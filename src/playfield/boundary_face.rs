@@ -6,7 +6,7 @@ use bevy::{
     prelude::Reflect,
 };
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
 pub enum BoundaryFace {
     #[default]
     Left,
@@ -18,6 +18,15 @@ pub enum BoundaryFace {
 }
 
 impl BoundaryFace {
+    pub const ALL: [BoundaryFace; 6] = [
+        BoundaryFace::Left,
+        BoundaryFace::Right,
+        BoundaryFace::Top,
+        BoundaryFace::Bottom,
+        BoundaryFace::Front,
+        BoundaryFace::Back,
+    ];
+
     pub fn get_normal(&self) -> Vec3 {
         match self {
             BoundaryFace::Left => Vec3::NEG_X,
@@ -41,44 +50,40 @@ impl BoundaryFace {
         }
     }
 
-    pub fn get_face_points(&self, min: &Vec3, max: &Vec3) -> [Vec3; 4] {
+    /// which axis the face is across, and whether it's at the positive end
+    pub fn axis(&self) -> (usize, bool) {
         match self {
-            BoundaryFace::Left => [
-                Vec3::new(min.x, min.y, min.z),
-                Vec3::new(min.x, max.y, min.z),
-                Vec3::new(min.x, max.y, max.z),
-                Vec3::new(min.x, min.y, max.z),
-            ],
-            BoundaryFace::Right => [
-                Vec3::new(max.x, min.y, min.z),
-                Vec3::new(max.x, max.y, min.z),
-                Vec3::new(max.x, max.y, max.z),
-                Vec3::new(max.x, min.y, max.z),
-            ],
-            BoundaryFace::Bottom => [
-                Vec3::new(min.x, min.y, min.z),
-                Vec3::new(max.x, min.y, min.z),
-                Vec3::new(max.x, min.y, max.z),
-                Vec3::new(min.x, min.y, max.z),
-            ],
-            BoundaryFace::Top => [
-                Vec3::new(min.x, max.y, min.z),
-                Vec3::new(max.x, max.y, min.z),
-                Vec3::new(max.x, max.y, max.z),
-                Vec3::new(min.x, max.y, max.z),
-            ],
-            BoundaryFace::Back => [
-                Vec3::new(min.x, min.y, min.z),
-                Vec3::new(max.x, min.y, min.z),
-                Vec3::new(max.x, max.y, min.z),
-                Vec3::new(min.x, max.y, min.z),
-            ],
-            BoundaryFace::Front => [
-                Vec3::new(min.x, min.y, max.z),
-                Vec3::new(max.x, min.y, max.z),
-                Vec3::new(max.x, max.y, max.z),
-                Vec3::new(min.x, max.y, max.z),
-            ],
+            BoundaryFace::Left => (0, false),
+            BoundaryFace::Right => (0, true),
+            BoundaryFace::Bottom => (1, false),
+            BoundaryFace::Top => (1, true),
+            BoundaryFace::Back => (2, false),
+            BoundaryFace::Front => (2, true),
+        }
+    }
+
+    /// the face at the positive or negative end of an axis - 0 is x
+    pub fn from_axis(axis: usize, positive: bool) -> Self {
+        match (axis, positive) {
+            (0, true) => BoundaryFace::Right,
+            (0, false) => BoundaryFace::Left,
+            (1, true) => BoundaryFace::Top,
+            (1, false) => BoundaryFace::Bottom,
+            (_, true) => BoundaryFace::Front,
+            (_, false) => BoundaryFace::Back,
         }
     }
+
+    /// the face whose normal points most the same way - for normals that
+    /// aren't on an axis, like the ones off a sphere
+    pub fn closest_to(normal: Dir3) -> Self {
+        Self::ALL
+            .into_iter()
+            .max_by(|a, b| {
+                a.get_normal()
+                    .dot(*normal)
+                    .total_cmp(&b.get_normal().dot(*normal))
+            })
+            .unwrap_or_default()
+    }
 }
@@ -0,0 +1,159 @@
+use crate::{
+    config::SettingsAppExt,
+    global_input::GlobalAction,
+    playfield::Boundary,
+    schedule::FrameSet,
+};
+use bevy::{
+    color::palettes::tailwind,
+    prelude::*,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+// long enough to see the box change shape, short enough not to wait for it
+const TRANSITION_SECONDS: f32 = 0.75;
+
+/// a few named boundaries to flip between with the preset key, so tuning in
+/// the inspector isn't lost the moment something else gets tried. the presets
+/// are settings like any other - edit them in the settings file or with `set`
+/// in the console and they're still there next time
+///
+/// the size and color ease into the new preset rather than snapping. the cell
+/// count can't ease, so the box jumps to the new shape and the scalar takes it
+/// the rest of the way. every frame of that is a resize, so anything left
+/// outside is pulled back in along the way
+pub struct BoundaryPresetsPlugin;
+
+impl Plugin for BoundaryPresetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings::<BoundaryPresets>("boundary_presets")
+            .init_resource::<PresetTransition>()
+            .add_systems(
+                Update,
+                (cycle_boundary_preset, ease_into_preset)
+                    .chain()
+                    .in_set(FrameSet::Input),
+            );
+    }
+}
+
+#[derive(Reflect, Clone, Debug)]
+pub struct BoundaryPreset {
+    pub name:     String,
+    pub boundary: Boundary,
+}
+
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct BoundaryPresets {
+    pub presets: Vec<BoundaryPreset>,
+    pub current: usize,
+}
+
+impl Default for BoundaryPresets {
+    fn default() -> Self {
+        let preset = |name: &str, boundary: Boundary| BoundaryPreset {
+            name: name.to_string(),
+            boundary,
+        };
+
+        Self {
+            presets: vec![
+                preset("classic", Boundary::default()),
+                preset(
+                    "tall",
+                    Boundary {
+                        cell_count: UVec3::new(1, 2, 1),
+                        color: Color::from(tailwind::EMERALD_300),
+                        ..default()
+                    },
+                ),
+                preset(
+                    "cube",
+                    Boundary {
+                        cell_count: UVec3::ONE,
+                        scalar: 160.,
+                        color: Color::from(tailwind::VIOLET_300),
+                        ..default()
+                    },
+                ),
+            ],
+            current: 0,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct PresetTransition {
+    from_scalar: f32,
+    from_color:  Color,
+    target:      Option<Boundary>,
+    elapsed:     f32,
+}
+
+// everything but the scalar and color switches straight away - the transform
+// is the boundary's own, worked out from the rest
+fn cycle_boundary_preset(
+    user_input: Res<ActionState<GlobalAction>>,
+    mut presets: ResMut<BoundaryPresets>,
+    mut transition: ResMut<PresetTransition>,
+    mut boundary: ResMut<Boundary>,
+) {
+    if !user_input.just_pressed(&GlobalAction::CycleBoundaryPreset) || presets.presets.is_empty() {
+        return;
+    }
+
+    presets.current = (presets.current + 1) % presets.presets.len();
+    let preset = &presets.presets[presets.current];
+    info!("boundary preset {}", preset.name);
+
+    *transition = PresetTransition {
+        from_scalar: boundary.scalar,
+        from_color:  boundary.color,
+        target:      Some(preset.boundary.clone()),
+        elapsed:     0.,
+    };
+    *boundary = Boundary {
+        scalar: boundary.scalar,
+        color: boundary.color,
+        transform: boundary.transform,
+        ..preset.boundary.clone()
+    };
+}
+
+// real time, so it finishes even while paused
+fn ease_into_preset(
+    time: Res<Time<Real>>,
+    mut transition: ResMut<PresetTransition>,
+    mut boundary: ResMut<Boundary>,
+) {
+    let Some(target) = &transition.target else {
+        return;
+    };
+
+    let elapsed = transition.elapsed + time.delta_secs();
+    let progress = (elapsed / TRANSITION_SECONDS).clamp(0., 1.);
+    // eased so it doesn't start or stop with a jolt
+    let eased = progress * progress * (3. - 2. * progress);
+
+    boundary.scalar = transition.from_scalar.lerp(target.scalar, eased);
+    boundary.color = transition.from_color.mix(&target.color, eased);
+
+    if progress >= 1. {
+        transition.target = None;
+    } else {
+        transition.elapsed = elapsed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn boundary_presets_round_trips() { assert_settings_round_trip(nudged::<BoundaryPresets>()); }
+}
@@ -0,0 +1,223 @@
+use crate::{
+    global_input::{
+        toggle_active,
+        GlobalAction,
+    },
+    schedule::FrameSet,
+};
+use bevy::{
+    pbr::NotShadowCaster,
+    prelude::*,
+    render::{
+        mesh::{
+            PrimitiveTopology,
+            VertexAttributeValues,
+        },
+        render_asset::RenderAssetUsages,
+        view::NoFrustumCulling,
+    },
+};
+use std::f32::consts::TAU;
+
+// the boundary and portals used to be drawn with gizmos, which are submitted
+// all over again every frame - that was most of the cpu time on wasm. now
+// they're plain line meshes that are only rebuilt when something moves. F6
+// switches back to the gizmos so the two can be compared
+pub struct LineMeshesPlugin;
+
+impl Plugin for LineMeshesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LineMaterial>().add_systems(
+            Update,
+            hide_line_meshes
+                .in_set(FrameSet::Visual)
+                .run_if(toggle_active(false, GlobalAction::GizmoLines)),
+        );
+    }
+}
+
+/// one unlit material shared by every line mesh - the color comes from the
+/// vertices
+#[derive(Resource, Debug)]
+pub struct LineMaterial(pub Handle<StandardMaterial>);
+
+impl FromWorld for LineMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        }))
+    }
+}
+
+impl LineMaterial {
+    /// a material of its own, for lines that fade all together - the vertex
+    /// colors are multiplied by its base color, alpha and all
+    pub fn blended(materials: &mut Assets<StandardMaterial>) -> Self {
+        Self(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }))
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct LineMesh;
+
+/// what to spawn for a line mesh - the vertices are in world space so it sits
+/// at the origin
+pub fn line_mesh_bundle(mesh: Handle<Mesh>, material: &LineMaterial, name: &'static str) -> impl Bundle {
+    (
+        Mesh3d(mesh),
+        MeshMaterial3d(material.0.clone()),
+        Transform::default(),
+        // bevy doesn't recompute the bounds when we swap the mesh out
+        NoFrustumCulling,
+        NotShadowCaster,
+        LineMesh,
+        Name::new(name),
+    )
+}
+
+// whichever one is drawing, the other one stays out of the way
+fn hide_line_meshes(mut q_lines: Query<&mut Visibility, With<LineMesh>>) {
+    for mut visibility in q_lines.iter_mut() {
+        visibility.set_if_neq(Visibility::Hidden);
+    }
+}
+
+pub fn show_line_mesh(visibility: &mut Mut<Visibility>) { visibility.set_if_neq(Visibility::Inherited); }
+
+/// circles and arcs the same way gizmos draw them, so the shapes can go to
+/// either gizmos or a line mesh
+pub trait LineSink {
+    fn add_line(&mut self, start: Vec3, end: Vec3, color: Color);
+
+    fn add_circle(&mut self, isometry: Isometry3d, radius: f32, color: Color, resolution: u32);
+
+    fn add_short_arc_between(&mut self, center: Vec3, from: Vec3, to: Vec3, color: Color, resolution: u32);
+}
+
+impl<Config: GizmoConfigGroup> LineSink for Gizmos<'_, '_, Config> {
+    fn add_line(&mut self, start: Vec3, end: Vec3, color: Color) { self.line(start, end, color); }
+
+    fn add_circle(&mut self, isometry: Isometry3d, radius: f32, color: Color, resolution: u32) {
+        self.circle(isometry, radius, color).resolution(resolution);
+    }
+
+    fn add_short_arc_between(&mut self, center: Vec3, from: Vec3, to: Vec3, color: Color, resolution: u32) {
+        self.short_arc_3d_between(center, from, to, color)
+            .resolution(resolution);
+    }
+}
+
+/// collects line segments for a LineList mesh
+#[derive(Debug, Default)]
+pub struct LineListBuilder {
+    positions: Vec<[f32; 3]>,
+    colors:    Vec<[f32; 4]>,
+}
+
+impl LineListBuilder {
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        let color = color.to_linear().to_f32_array();
+        self.positions.extend([start.to_array(), end.to_array()]);
+        self.colors.extend([color, color]);
+    }
+
+    pub fn line_strip(&mut self, points: impl IntoIterator<Item = Vec3>, color: Color) {
+        let mut points = points.into_iter();
+        let Some(mut previous) = points.next() else {
+            return;
+        };
+        for point in points {
+            self.line(previous, point, color);
+            previous = point;
+        }
+    }
+
+    pub fn build(self) -> Mesh {
+        Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, self.colors)
+    }
+
+    /// copies the lines into a mesh that was built by this, reusing the
+    /// mesh's buffers, and empties the builder so it can be kept around
+    /// for the next one - between the two nothing is allocated once the
+    /// buffers have grown big enough
+    pub fn write_to(&mut self, mesh: &mut Mesh) {
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            positions.clear();
+            positions.extend_from_slice(&self.positions);
+        }
+        if let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR) {
+            colors.clear();
+            colors.extend_from_slice(&self.colors);
+        }
+
+        self.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.colors.clear();
+    }
+}
+
+impl LineSink for LineListBuilder {
+    fn add_line(&mut self, start: Vec3, end: Vec3, color: Color) { self.line(start, end, color); }
+
+    // gizmo circles lie in the xy plane before the isometry is applied
+    fn add_circle(&mut self, isometry: Isometry3d, radius: f32, color: Color, resolution: u32) {
+        let resolution = resolution.max(1);
+        let points = (0..=resolution).map(|step| {
+            let (sin, cos) = (TAU * step as f32 / resolution as f32).sin_cos();
+            isometry * Vec3::new(cos * radius, sin * radius, 0.)
+        });
+        self.line_strip(points, color);
+    }
+
+    fn add_short_arc_between(&mut self, center: Vec3, from: Vec3, to: Vec3, color: Color, resolution: u32) {
+        let from_axis = (from - center).normalize_or_zero();
+        let to_axis = (to - center).normalize_or_zero();
+        let (up, angle) = Quat::from_rotation_arc(from_axis, to_axis).to_axis_angle();
+        let rotation = Quat::from_rotation_arc(Vec3::Y, up);
+
+        self.arc_from(
+            rotation.inverse() * from_axis,
+            angle,
+            center.distance(from),
+            Isometry3d::new(center, rotation),
+            color,
+            resolution,
+        );
+    }
+}
+
+impl LineListBuilder {
+    // gizmo arcs sweep around y, here starting from `start` rather than x
+    fn arc_from(
+        &mut self,
+        start: Vec3,
+        angle: f32,
+        radius: f32,
+        isometry: Isometry3d,
+        color: Color,
+        resolution: u32,
+    ) {
+        let resolution = resolution.max(1);
+        let angle = angle.clamp(-TAU, TAU);
+        let points = (0..=resolution).map(|step| {
+            let step_angle = angle * step as f32 / resolution as f32;
+            isometry * (Quat::from_axis_angle(Vec3::Y, step_angle) * start * radius)
+        });
+        self.line_strip(points, color);
+    }
+}
@@ -1,17 +1,49 @@
 mod boundary;
+mod boundary_debug;
 mod boundary_face;
+mod boundary_presets;
+mod line_meshes;
 mod planes;
 mod portals;
+mod spatial_index;
 
 pub use crate::playfield::{
-    boundary::Boundary,
-    portals::ActorPortals,
+    boundary::{
+        boundary_grid_mesh,
+        Boundary,
+        BoundaryResized,
+        BoundaryShape,
+    },
+    boundary_face::BoundaryFace,
+    line_meshes::{
+        LineListBuilder,
+        LineMesh,
+    },
+    portals::{
+        ActorPortals,
+        Portal,
+    },
+    spatial_index::{
+        Neighbor,
+        SpatialIndex,
+    },
+};
+
+pub(crate) use crate::playfield::portals::{
+    init_portals,
+    update_approaching_portals,
+    update_emerging_portals,
+    PortalConfig,
 };
 
 use crate::playfield::{
     boundary::BoundaryPlugin,
+    boundary_debug::BoundaryDebugPlugin,
+    boundary_presets::BoundaryPresetsPlugin,
+    line_meshes::LineMeshesPlugin,
     planes::PlanesPlugin,
     portals::PortalPlugin,
+    spatial_index::SpatialIndexPlugin,
 };
 use bevy::prelude::*;
 
@@ -20,7 +52,11 @@ pub struct PlayfieldPlugin;
 impl Plugin for PlayfieldPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(BoundaryPlugin)
+            .add_plugins(BoundaryDebugPlugin)
+            .add_plugins(BoundaryPresetsPlugin)
+            .add_plugins(LineMeshesPlugin)
             .add_plugins(PlanesPlugin)
-            .add_plugins(PortalPlugin);
+            .add_plugins(PortalPlugin)
+            .add_plugins(SpatialIndexPlugin);
     }
 }
@@ -1,13 +1,14 @@
 use crate::{
     global_input::{
-        toggle_active,
         GlobalAction,
+        InspectorAppExt,
     },
     orientation::{
         CameraOrientation,
         OrientationConfig,
     },
     playfield::Boundary,
+    schedule::FrameSet,
 };
 use bevy::{
     prelude::*,
@@ -16,20 +17,16 @@ use bevy::{
 use bevy_inspector_egui::{
     inspector_options::std_options::NumberDisplay,
     prelude::*,
-    quick::ResourceInspectorPlugin,
 };
 
 pub struct PlanesPlugin;
 
 impl Plugin for PlanesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, manage_box_planes)
+        app.add_systems(Update, manage_box_planes.in_set(FrameSet::Boundary))
             .register_type::<PlaneConfig>()
             .init_resource::<PlaneConfig>()
-            .add_plugins(
-                ResourceInspectorPlugin::<PlaneConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::PlanesInspector)),
-            );
+            .add_inspector::<PlaneConfig>(GlobalAction::PlanesInspector);
     }
 }
 
@@ -109,6 +106,7 @@ enum PlaneType {
     Right,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_or_update_plane(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
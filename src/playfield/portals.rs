@@ -3,16 +3,30 @@ use crate::{
         Aabb,
         Teleporter,
     },
+    diagnostics::PORTAL_COUNT,
+    dump::DumpAppExt,
     global_input::{
         toggle_active,
         GlobalAction,
+        InspectorAppExt,
     },
     orientation::CameraOrientation,
     playfield::{
         boundary_face::BoundaryFace,
+        line_meshes::{
+            line_mesh_bundle,
+            show_line_mesh,
+            LineListBuilder,
+            LineMaterial,
+        },
         Boundary,
     },
+    schedule::{
+        EntityBatching,
+        FrameSet,
+    },
     state::PlayingGame,
+    time_controls::GameTime,
 };
 use bevy::{
     app::{
@@ -23,16 +37,24 @@ use bevy::{
         palettes::tailwind,
         Color,
     },
+    diagnostic::{
+        Diagnostic,
+        Diagnostics,
+        RegisterDiagnostic,
+    },
     math::{
         Dir3,
         Vec3,
     },
     prelude::*,
+    utils::{
+        HashMap,
+        HashSet,
+    },
 };
 use bevy_inspector_egui::{
     inspector_options::std_options::NumberDisplay,
     prelude::*,
-    quick::ResourceInspectorPlugin,
 };
 use bevy_rapier3d::dynamics::Velocity;
 
@@ -41,25 +63,40 @@ pub struct PortalPlugin;
 impl Plugin for PortalPlugin {
     fn build(&self, app: &mut App) {
         app.init_gizmo_group::<PortalGizmo>()
+            .register_diagnostic(Diagnostic::new(PORTAL_COUNT))
             .init_resource::<PortalConfig>()
+            .init_resource::<PortalLines>()
             .register_type::<PortalConfig>()
-            .add_plugins(
-                ResourceInspectorPlugin::<PortalConfig>::default()
-                    .run_if(toggle_active(false, GlobalAction::PortalInspector)),
-            )
+            .add_inspector::<PortalConfig>(GlobalAction::PortalInspector)
+            // tuned in the inspector rather than saved, so a dump has to carry it
+            .add_dump_resource::<PortalConfig>()
             .add_systems(
                 Update,
                 (
                     update_portal_config,
                     init_portals,
-                    draw_approaching_portals,
-                    draw_emerging_portals,
+                    update_approaching_portals,
+                    update_emerging_portals,
+                    draw_portal_gizmos.run_if(toggle_active(false, GlobalAction::GizmoLines)),
+                    update_portal_lines.run_if(toggle_active(true, GlobalAction::GizmoLines)),
                 )
+                    .chain()
+                    .in_set(FrameSet::Visual)
                     .run_if(in_state(PlayingGame)),
-            );
+            )
+            .add_systems(Update, measure_portals.in_set(FrameSet::Ui));
     }
 }
 
+fn measure_portals(mut diagnostics: Diagnostics, q_portals: Query<&ActorPortals>) {
+    diagnostics.add_measurement(&PORTAL_COUNT, || {
+        q_portals
+            .iter()
+            .map(|portals| portals.approaching.is_some() as usize + portals.emerging.is_some() as usize)
+            .sum::<usize>() as f64
+    });
+}
+
 #[derive(Debug, Default, Reflect, GizmoConfigGroup)]
 pub struct PortalGizmo {}
 
@@ -69,9 +106,18 @@ fn update_portal_config(mut config_store: ResMut<GizmoConfigStore>, portal_confi
     config.line_joints = GizmoLineJoint::Round(portal_config.line_joints);
 }
 
+// direction_change_factor:
+// if we're within a certain radians of the wall we continue to draw on it but
+// after that we consider that we're looking to be at a new wall boundary point
+// adjust this if it makes sense to
+//
+// movement_smoothing_factor:
+// keep it small so that if you change directions the circle doesn't fly
+// away fast - looks terrible
+//
 #[derive(Resource, Reflect, InspectorOptions, Clone, Debug)]
 #[reflect(Resource, InspectorOptions)]
-struct PortalConfig {
+pub(crate) struct PortalConfig {
     color_approaching:             Color,
     color_emerging:                Color,
     #[inspector(min = 0.0, max = std::f32::consts::PI, display = NumberDisplay::Slider)]
@@ -94,6 +140,8 @@ struct PortalConfig {
     pub portal_scalar:             f32,
     #[inspector(min = 1., max = 10., display = NumberDisplay::Slider)]
     pub portal_smallest:           f32,
+    #[inspector(min = 0.0, max = 2.0, display = NumberDisplay::Slider)]
+    rebuild_threshold:             f32,
     #[inspector(min = 3, max = 256, display = NumberDisplay::Slider)]
     resolution:                    u32,
 }
@@ -113,6 +161,7 @@ impl Default for PortalConfig {
             movement_smoothing_factor: 0.08,
             portal_scalar:             2.,
             portal_smallest:           5.,
+            rebuild_threshold:         0.1,
             resolution:                128,
         }
     }
@@ -127,7 +176,8 @@ pub struct ActorPortals {
 #[derive(Resource, Clone, Debug)]
 pub struct Portal {
     pub actor_direction:            Vec3,
-    pub actor_distance_to_wall:     f32,
+    // 0 until the actor is in the shrink zone, 1 once it's at the wall
+    pub wall_proximity:             f32,
     pub boundary_distance_approach: f32,
     pub boundary_distance_shrink:   f32,
     pub face:                       BoundaryFace,
@@ -141,7 +191,7 @@ impl Default for Portal {
     fn default() -> Self {
         Self {
             actor_direction:            Vec3::ZERO,
-            actor_distance_to_wall:     0.,
+            wall_proximity:             0.,
             boundary_distance_approach: 0.,
             boundary_distance_shrink:   0.,
             face:                       BoundaryFace::Right,
@@ -153,11 +203,25 @@ impl Default for Portal {
     }
 }
 
-fn init_portals(
+impl Portal {
+    /// a portal sitting still on a face - for the tests and benches to draw
+    pub fn on_face(face: BoundaryFace, position: Vec3, radius: f32) -> Self {
+        Self {
+            face,
+            normal: Dir3::new_unchecked(face.get_normal()),
+            position,
+            radius,
+            ..default()
+        }
+    }
+}
+
+pub(crate) fn init_portals(
     mut q_actor: Query<(&Aabb, &Transform, &Velocity, &Teleporter, &mut ActorPortals)>,
     boundary: Res<Boundary>,
     portal_config: Res<PortalConfig>,
-    time: Res<Time>,
+    time: Res<GameTime>,
+    batching: Res<EntityBatching>,
 ) {
     // todo #handle3d
     let boundary_size = boundary
@@ -169,39 +233,53 @@ fn init_portals(
     let boundary_distance_approach = boundary_size * portal_config.distance_approach;
     let boundary_distance_shrink = boundary_size * portal_config.distance_shrink;
 
-    for (aabb, transform, velocity, teleporter, mut visual) in q_actor.iter_mut() {
-        let radius = aabb.max_dimension().max(portal_config.portal_smallest) * portal_config.portal_scalar;
-
-        let portal_position = transform.translation;
-        let actor_direction = velocity.linvel.normalize_or_zero();
-
-        let portal = Portal {
-            actor_direction,
-            position: portal_position,
-            boundary_distance_approach,
-            boundary_distance_shrink,
-            radius,
-            ..default()
-        };
-
-        handle_approaching_visual(&boundary, portal.clone(), &portal_config, &time, &mut visual);
-        handle_emerging_visual(portal.clone(), &portal_config, teleporter, &time, &mut visual);
-    }
+    // every actor's portals only depend on that actor, so they're spread across
+    // threads - it adds up with a few hundred actors near the walls
+    q_actor
+        .par_iter_mut()
+        .batching_strategy(batching.0.clone())
+        .for_each(|(aabb, transform, velocity, teleporter, mut visual)| {
+            let radius =
+                aabb.max_dimension().max(portal_config.portal_smallest) * portal_config.portal_scalar;
+
+            let portal_position = transform.translation;
+            let actor_direction = velocity.linvel.normalize_or_zero();
+
+            let portal = Portal {
+                actor_direction,
+                position: portal_position,
+                boundary_distance_approach,
+                boundary_distance_shrink,
+                radius,
+                ..default()
+            };
+
+            handle_approaching_visual(&boundary, portal.clone(), &portal_config, &time, &mut visual);
+            handle_emerging_visual(
+                &boundary,
+                portal.clone(),
+                &portal_config,
+                teleporter,
+                &time,
+                &mut visual,
+            );
+        });
 }
 
 fn handle_emerging_visual(
+    boundary: &Res<Boundary>,
     portal: Portal,
     portal_config: &Res<PortalConfig>,
     teleporter: &Teleporter,
-    time: &Res<Time>,
+    time: &Res<GameTime>,
     visual: &mut Mut<ActorPortals>,
 ) {
     if teleporter.just_teleported {
         if let Some(normal) = teleporter.last_teleported_normal {
             // establish the existence of an emerging
-            if let Some(face) = BoundaryFace::from_normal(normal) {
+            if let Some(face) = boundary.face_for_normal(normal) {
                 visual.emerging = Some(Portal {
-                    actor_distance_to_wall: 0.0,
+                    wall_proximity: 1.0,
                     face,
                     normal,
                     fade_out_started: Some(time.elapsed_secs()),
@@ -223,26 +301,27 @@ fn handle_approaching_visual(
     boundary: &Res<Boundary>,
     portal: Portal,
     portal_config: &Res<PortalConfig>,
-    time: &Res<Time>,
+    time: &Res<GameTime>,
     visual: &mut Mut<ActorPortals>,
 ) {
-    if let Some(collision_point) = boundary.find_edge_point(portal.position, portal.actor_direction) {
-        let actor_distance_to_wall = portal.position.distance(collision_point);
-
-        if actor_distance_to_wall <= portal.boundary_distance_approach {
-            let normal = boundary.get_normal_for_position(collision_point);
-            let position = smooth_circle_position(visual, collision_point, normal, portal_config);
-
-            if let Some(face) = BoundaryFace::from_normal(normal) {
-                visual.approaching = Some(Portal {
-                    actor_distance_to_wall,
-                    face,
-                    normal,
-                    position,
-                    ..portal
-                });
-                return;
-            }
+    if let Some(hit) = boundary.raycast(portal.position, portal.actor_direction) {
+        if hit.distance <= portal.boundary_distance_approach {
+            let position = smooth_circle_position(visual, hit.point, hit.normal, portal_config);
+
+            // the ray says where the portal goes, but how close the actor is
+            // is the straight line to the wall - heading along a wall at a
+            // shallow angle it's already close
+            let wall_proximity =
+                boundary.normalized_wall_proximity(portal.position, portal.boundary_distance_shrink);
+
+            visual.approaching = Some(Portal {
+                wall_proximity,
+                face: hit.face,
+                normal: hit.normal,
+                position,
+                ..portal
+            });
+            return;
         }
     }
 
@@ -291,50 +370,42 @@ fn smooth_circle_position(
     }
 }
 
-fn draw_approaching_portals(
-    time: Res<Time>,
-    boundary: Res<Boundary>,
+pub(crate) fn update_approaching_portals(
+    time: Res<GameTime>,
     config: Res<PortalConfig>,
-    orientation: Res<CameraOrientation>,
+    batching: Res<EntityBatching>,
     mut q_portals: Query<&mut ActorPortals>,
-    mut gizmos: Gizmos<PortalGizmo>,
 ) {
-    for mut portal in q_portals.iter_mut() {
-        if let Some(ref mut approaching) = portal.approaching {
-            let radius = get_approaching_radius(approaching);
-
-            // handle fadeout and get rid of it if we're past duration
-            // otherwise proceed
-            if let Some(fade_out_start) = approaching.fade_out_started {
-                // Calculate the elapsed time since fade-out started
-                let elapsed_time = time.elapsed_secs() - fade_out_start;
-
-                // Fade out over n seconds
-                let fade_out_duration = config.fadeout_duration;
-                if elapsed_time >= fade_out_duration || approaching.radius < config.minimum_radius {
-                    // Remove visual after fade-out is complete
-                    portal.approaching = None;
-                    continue;
+    q_portals
+        .par_iter_mut()
+        .batching_strategy(batching.0.clone())
+        .for_each(|mut portal| {
+            if let Some(ref mut approaching) = portal.approaching {
+                let radius = get_approaching_radius(approaching);
+
+                // handle fadeout and get rid of it if we're past duration
+                // otherwise proceed
+                if let Some(fade_out_start) = approaching.fade_out_started {
+                    // Calculate the elapsed time since fade-out started
+                    let elapsed_time = time.elapsed_secs() - fade_out_start;
+
+                    // Fade out over n seconds
+                    let fade_out_duration = config.fadeout_duration;
+                    if elapsed_time >= fade_out_duration || approaching.radius < config.minimum_radius {
+                        // Remove visual after fade-out is complete
+                        portal.approaching = None;
+                        return;
+                    }
+
+                    // Calculate the current reduction based on elapsed time
+                    let fade_factor = (1.0 - (elapsed_time / fade_out_duration)).clamp(0.0, 1.0);
+                    approaching.radius *= fade_factor;
+                } else {
+                    // Apply the normal proximity-based scaling
+                    approaching.radius = radius;
                 }
-
-                // Calculate the current reduction based on elapsed time
-                let fade_factor = (1.0 - (elapsed_time / fade_out_duration)).clamp(0.0, 1.0);
-                approaching.radius *= fade_factor;
-            } else {
-                // Apply the normal proximity-based scaling
-                approaching.radius = radius;
             }
-
-            // Draw the portal with the updated radius
-            boundary.draw_portal(
-                &mut gizmos,
-                approaching,
-                config.color_approaching,
-                config.resolution,
-                &orientation,
-            );
-        }
-    }
+        });
 }
 
 // extracted for readability
@@ -345,57 +416,183 @@ fn get_approaching_radius(approaching: &mut Portal) -> f32 {
     let max_radius = approaching.radius;
     let min_radius = max_radius * 0.5;
 
-    // Calculate the radius based on proximity to the boundary
-    // as it's approaching we keep it at a fixed size until we enter the shrink zone
-    if approaching.actor_distance_to_wall > approaching.boundary_distance_shrink {
-        max_radius
-    } else {
-        let scale_factor =
-            (approaching.actor_distance_to_wall / approaching.boundary_distance_shrink).clamp(0.0, 1.0);
-        min_radius + (max_radius - min_radius) * scale_factor
+    // as it's approaching we keep it at a fixed size until we enter the shrink
+    // zone, where proximity starts to climb
+    max_radius - (max_radius - min_radius) * approaching.wall_proximity
+}
+
+pub(crate) fn update_emerging_portals(
+    time: Res<GameTime>,
+    config: Res<PortalConfig>,
+    batching: Res<EntityBatching>,
+    mut q_portals: Query<&mut ActorPortals>,
+) {
+    q_portals
+        .par_iter_mut()
+        .batching_strategy(batching.0.clone())
+        .for_each(|mut portal| {
+            if let Some(ref mut emerging) = portal.emerging {
+                if let Some(emerging_start) = emerging.fade_out_started {
+                    // Calculate the elapsed time since the emerging process started
+                    let elapsed_time = time.elapsed_secs() - emerging_start;
+
+                    // Define the total duration for the emerging process
+                    let emerging_duration = config.fadeout_duration;
+
+                    // Calculate the progress based on elapsed time
+                    let progress = (elapsed_time / emerging_duration).clamp(0.0, 1.0);
+
+                    // Interpolate the radius from the full size down to zero
+                    let initial_radius = emerging.radius;
+                    let radius = initial_radius * (1.0 - progress); // Scale down as progress increases
+
+                    if radius > 0.0 {
+                        emerging.radius = radius;
+                    }
+
+                    // Remove visual after the emerging duration is complete
+                    if elapsed_time >= emerging_duration {
+                        portal.emerging = None;
+                    }
+                }
+            }
+        });
+}
+
+impl ActorPortals {
+    // an emerging portal that has shrunk away is left in place until its time is
+    // up but there's nothing to draw
+    fn drawable<'a>(
+        &'a self,
+        config: &'a PortalConfig,
+    ) -> impl Iterator<Item = (PortalKind, &'a Portal, Color)> {
+        let approaching = self
+            .approaching
+            .as_ref()
+            .map(|portal| (PortalKind::Approaching, portal, config.color_approaching));
+        let emerging = self
+            .emerging
+            .as_ref()
+            .filter(|portal| portal.fade_out_started.is_some() && portal.radius > 0.0)
+            .map(|portal| (PortalKind::Emerging, portal, config.color_emerging));
+
+        approaching.into_iter().chain(emerging)
     }
 }
 
-fn draw_emerging_portals(
-    time: Res<Time>,
+fn draw_portal_gizmos(
     boundary: Res<Boundary>,
     config: Res<PortalConfig>,
     orientation: Res<CameraOrientation>,
-    mut q_portals: Query<&mut ActorPortals>,
+    q_portals: Query<&ActorPortals>,
     mut gizmos: Gizmos<PortalGizmo>,
 ) {
-    for mut portal in q_portals.iter_mut() {
-        if let Some(ref mut emerging) = portal.emerging {
-            if let Some(emerging_start) = emerging.fade_out_started {
-                // Calculate the elapsed time since the emerging process started
-                let elapsed_time = time.elapsed_secs() - emerging_start;
-
-                // Define the total duration for the emerging process
-                let emerging_duration = config.fadeout_duration;
-
-                // Calculate the progress based on elapsed time
-                let progress = (elapsed_time / emerging_duration).clamp(0.0, 1.0);
-
-                // Interpolate the radius from the full size down to zero
-                let initial_radius = emerging.radius;
-                let radius = initial_radius * (1.0 - progress); // Scale down as progress increases
-
-                if radius > 0.0 {
-                    emerging.radius = radius;
-                    boundary.draw_portal(
-                        &mut gizmos,
-                        emerging,
-                        config.color_emerging,
-                        config.resolution,
-                        &orientation,
-                    );
-                }
+    for portals in q_portals.iter() {
+        for (_, portal, color) in portals.drawable(&config) {
+            boundary.draw_portal(&mut gizmos, portal, color, config.resolution, &orientation);
+        }
+    }
+}
 
-                // Remove visual after the emerging duration is complete
-                if elapsed_time >= emerging_duration {
-                    portal.emerging = None;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PortalKind {
+    Approaching,
+    Emerging,
+}
+
+// what a portal's line mesh was last built from - it's only rebuilt once the
+// portal has moved or resized by more than the threshold
+#[derive(Debug)]
+struct PortalMesh {
+    entity:   Entity,
+    mesh:     Handle<Mesh>,
+    position: Vec3,
+    radius:   f32,
+    normal:   Dir3,
+}
+
+impl PortalMesh {
+    fn is_stale(&self, portal: &Portal, threshold: f32) -> bool {
+        self.normal != portal.normal
+            || self.position.distance(portal.position) > threshold
+            || (self.radius - portal.radius).abs() > threshold
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct PortalLines(HashMap<(Entity, PortalKind), PortalMesh>);
+
+#[allow(clippy::too_many_arguments)]
+fn update_portal_lines(
+    mut commands: Commands,
+    mut portal_lines: ResMut<PortalLines>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    material: Res<LineMaterial>,
+    boundary: Res<Boundary>,
+    config: Res<PortalConfig>,
+    orientation: Res<CameraOrientation>,
+    q_portals: Query<(Entity, &ActorPortals)>,
+    mut q_visibility: Query<&mut Visibility>,
+    mut drawn: Local<HashSet<(Entity, PortalKind)>>,
+    mut lines: Local<LineListBuilder>,
+) {
+    let rebuild_all = boundary.is_changed() || config.is_changed() || orientation.is_changed();
+    // both locals keep their capacity from frame to frame
+    drawn.clear();
+
+    for (actor, portals) in q_portals.iter() {
+        for (kind, portal, color) in portals.drawable(&config) {
+            let key = (actor, kind);
+            drawn.insert(key);
+
+            if let Some(existing) = portal_lines.0.get(&key) {
+                if let Ok(mut visibility) = q_visibility.get_mut(existing.entity) {
+                    show_line_mesh(&mut visibility);
+                }
+                if !rebuild_all && !existing.is_stale(portal, config.rebuild_threshold) {
+                    continue;
                 }
             }
+
+            lines.clear();
+            boundary.draw_portal(&mut *lines, portal, color, config.resolution, &orientation);
+
+            match portal_lines.0.get_mut(&key) {
+                Some(existing) => {
+                    if let Some(mesh) = meshes.get_mut(&existing.mesh) {
+                        lines.write_to(mesh);
+                    }
+                    existing.position = portal.position;
+                    existing.radius = portal.radius;
+                    existing.normal = portal.normal;
+                },
+                None => {
+                    let mesh = meshes.add(std::mem::take(&mut *lines).build());
+                    let entity = commands
+                        .spawn(line_mesh_bundle(mesh.clone(), &material, "PortalLines"))
+                        .id();
+                    portal_lines.0.insert(
+                        key,
+                        PortalMesh {
+                            entity,
+                            mesh,
+                            position: portal.position,
+                            radius: portal.radius,
+                            normal: portal.normal,
+                        },
+                    );
+                },
+            }
         }
     }
+
+    // portals that have faded out, or whose actor is gone
+    portal_lines.0.retain(|key, portal_mesh| {
+        let keep = drawn.contains(key);
+        if !keep {
+            commands.entity(portal_mesh.entity).despawn();
+            meshes.remove(&portal_mesh.mesh);
+        }
+        keep
+    });
 }
@@ -0,0 +1,313 @@
+use crate::{
+    actor::ActorKind,
+    playfield::{
+        boundary::{
+            update_boundary_transform,
+            wrap_delta_on,
+        },
+        Boundary,
+        BoundaryShape,
+    },
+    schedule::FrameSet,
+};
+use bevy::prelude::*;
+
+// roughly the size of the biggest nateroid - small enough that a query only
+// touches a handful of cells, big enough that the grid stays tiny
+const CELL_SIZE: f32 = 25.;
+
+pub struct SpatialIndexPlugin;
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>().add_systems(
+            Update,
+            rebuild_spatial_index
+                .after(update_boundary_transform)
+                .in_set(FrameSet::Boundary),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    entity:   Entity,
+    kind:     ActorKind,
+    position: Vec3,
+    // in the boundary's frame, which is what the grid is in
+    local:    Vec3,
+}
+
+/// something found by a SpatialIndex query. `delta` has already been
+/// unwrapped - it's the short way round through the walls from the query
+/// point, so `center + delta` is where to aim and its length is the real
+/// distance. `position` is where the actor actually is
+#[derive(Debug, Clone, Copy)]
+pub struct Neighbor {
+    pub entity:           Entity,
+    pub kind:             ActorKind,
+    pub position:         Vec3,
+    pub delta:            Vec3,
+    pub distance_squared: f32,
+}
+
+/// every actor bucketed into a uniform grid that covers the boundary. it's
+/// rebuilt once a frame after the boundary is settled, so anything from
+/// FrameSet::Visual on sees this frame's positions (and this frame's wraps)
+///
+/// the grid is in the boundary's own frame, so it turns with it, and it wraps
+/// the way the boundary does - a query near one face also finds whatever is
+/// just across the opposite face, on the axes that wrap. the sphere's wrap is
+/// through the middle to the far side, which isn't a step across to a copy of
+/// it, so there's nothing to unwrap - the grid covers the cube around it and
+/// its queries go in straight lines
+#[derive(Resource, Debug, Default)]
+pub struct SpatialIndex {
+    cells:    Vec<Vec<IndexEntry>>,
+    dims:     UVec3,
+    size:     Vec3,
+    // the boundary divided into whole cells, so cells are only approximately
+    // CELL_SIZE
+    cell:     Vec3,
+    center:   Vec3,
+    rotation: Quat,
+    wraps:    BVec3,
+}
+
+impl SpatialIndex {
+    fn resize(&mut self, boundary: &Boundary) {
+        let size = boundary.transform.scale;
+        let dims = (size / CELL_SIZE).floor().as_uvec3().max(UVec3::ONE);
+
+        self.size = size;
+        self.cell = size / dims.as_vec3();
+        self.center = boundary.transform.translation;
+        self.rotation = boundary.transform.rotation;
+        self.wraps = match boundary.shape {
+            BoundaryShape::Cuboid => boundary.wrap_axes,
+            BoundaryShape::Sphere { .. } => BVec3::FALSE,
+        };
+
+        if dims != self.dims {
+            self.dims = dims;
+            self.cells = vec![Vec::new(); (dims.x * dims.y * dims.z) as usize];
+        }
+    }
+
+    fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+    }
+
+    fn to_local(&self, position: Vec3) -> Vec3 { self.rotation.inverse() * (position - self.center) }
+
+    // cell coordinates go past the edges during queries - they wrap around on
+    // an axis that wraps. on one that doesn't, anything outside (a missile on
+    // its way out, say) is in the edge cell
+    fn cell_index(&self, cell: IVec3) -> usize {
+        let dims = self.dims.as_ivec3();
+        let wrapped = cell.rem_euclid(dims);
+        let clamped = cell.clamp(IVec3::ZERO, dims - IVec3::ONE);
+        let cell = IVec3::select(self.wraps, wrapped, clamped);
+        (cell.x + dims.x * (cell.y + dims.y * cell.z)) as usize
+    }
+
+    fn cell_of(&self, local: Vec3) -> IVec3 { ((local + self.size / 2.) / self.cell).floor().as_ivec3() }
+
+    fn insert(&mut self, entry: IndexEntry) {
+        let index = self.cell_index(self.cell_of(entry.local));
+        self.cells[index].push(entry);
+    }
+
+    /// starts over with `actors` inside `boundary` - the cell vecs keep their
+    /// capacity, so once things settle this doesn't allocate
+    pub fn rebuild(
+        &mut self,
+        boundary: &Boundary,
+        actors: impl IntoIterator<Item = (Entity, ActorKind, Vec3)>,
+    ) {
+        self.resize(boundary);
+        self.clear();
+
+        for (entity, kind, position) in actors {
+            let local = self.to_local(position);
+            self.insert(IndexEntry {
+                entity,
+                kind,
+                position,
+                local,
+            });
+        }
+    }
+
+    /// everything within `radius` of `center`, nearest or not - sort the
+    /// result if the order matters
+    pub fn within(&self, center: Vec3, radius: f32) -> impl Iterator<Item = Neighbor> + '_ {
+        let radius_squared = radius * radius;
+        let local_center = self.to_local(center);
+        let low = self.cell_of(local_center - Vec3::splat(radius));
+        let high = self.cell_of(local_center + Vec3::splat(radius));
+
+        // a radius bigger than the boundary would visit the same cells more than
+        // once and report everything twice - going round on an axis that wraps,
+        // or piling up in the edge cells on one that doesn't. before the first
+        // rebuild there are no cells at all
+        let dims = self.dims.as_ivec3();
+        let edge = dims - IVec3::ONE;
+        let (low, high) = (
+            IVec3::select(self.wraps, low, low.clamp(IVec3::ZERO, edge)),
+            IVec3::select(self.wraps, high.min(low + edge), high.clamp(IVec3::ZERO, edge)),
+        );
+        let high = if self.cells.is_empty() {
+            low - IVec3::ONE
+        } else {
+            high
+        };
+
+        (low.z..=high.z)
+            .flat_map(move |z| {
+                (low.y..=high.y).flat_map(move |y| (low.x..=high.x).map(move |x| IVec3::new(x, y, z)))
+            })
+            .flat_map(move |cell| self.cells[self.cell_index(cell)].iter())
+            .filter_map(move |entry| {
+                let delta = self.rotation * wrap_delta_on(entry.local - local_center, self.size, self.wraps);
+                let distance_squared = delta.length_squared();
+                (distance_squared <= radius_squared).then_some(Neighbor {
+                    entity: entry.entity,
+                    kind: entry.kind,
+                    position: entry.position,
+                    delta,
+                    distance_squared,
+                })
+            })
+    }
+
+    /// within, narrowed down to one kind of actor
+    pub fn within_of_kind(
+        &self,
+        center: Vec3,
+        radius: f32,
+        kind: ActorKind,
+    ) -> impl Iterator<Item = Neighbor> + '_ {
+        self.within(center, radius)
+            .filter(move |neighbor| neighbor.kind == kind)
+    }
+}
+
+// rebuilt from scratch - with a few hundred actors that's cheaper than
+// tracking which ones changed cells. benches/spatial_index.rs has the numbers
+fn rebuild_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    boundary: Res<Boundary>,
+    q_actors: Query<(Entity, &ActorKind, &Transform)>,
+) {
+    index.rebuild(
+        &boundary,
+        q_actors
+            .iter()
+            .map(|(entity, kind, transform)| (entity, *kind, transform.translation)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    fn nearby(index: &SpatialIndex, center: Vec3, radius: f32) -> Vec<Neighbor> {
+        let mut found: Vec<_> = index.within(center, radius).collect();
+        found.sort_by(|a, b| a.distance_squared.total_cmp(&b.distance_squared));
+        found
+    }
+
+    // a box turned 45 degrees around z - just inside its own right face is
+    // next to just inside its own left face, wherever that is in the world
+    #[test]
+    fn turned_box_wraps_across_its_own_faces() {
+        let boundary = Boundary {
+            transform: Transform::from_translation(Vec3::new(40., -25., 10.))
+                .with_rotation(Quat::from_rotation_z(FRAC_PI_4))
+                .with_scale(Vec3::new(200., 100., 100.)),
+            ..default()
+        };
+        let to_world = |local: Vec3| boundary.transform.translation + boundary.transform.rotation * local;
+        let (right, left) = (Entity::from_raw(1), Entity::from_raw(2));
+        let (right_at, left_at) = (
+            to_world(Vec3::new(98., 0., 0.)),
+            to_world(Vec3::new(-98., 0., 0.)),
+        );
+
+        let mut index = SpatialIndex::default();
+        index.rebuild(
+            &boundary,
+            [
+                (right, ActorKind::Nateroid, right_at),
+                (left, ActorKind::Nateroid, left_at),
+            ],
+        );
+
+        let found = nearby(&index, right_at, 10.);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].entity, left);
+        assert_eq!(found[1].position, left_at);
+        // the way to go is on out through the right face
+        let out = boundary.transform.rotation * Vec3::new(4., 0., 0.);
+        assert!(found[1].delta.distance(out) < 0.01, "{:?}", found[1].delta);
+    }
+
+    // through the middle to the far side isn't next door - the sphere's
+    // neighbours are the ones in a straight line
+    #[test]
+    fn sphere_does_not_wrap() {
+        let boundary = Boundary {
+            shape: BoundaryShape::Sphere { radius: 50. },
+            transform: Transform::from_scale(Vec3::splat(100.)),
+            ..default()
+        };
+        let (near, far) = (Entity::from_raw(1), Entity::from_raw(2));
+
+        let mut index = SpatialIndex::default();
+        index.rebuild(
+            &boundary,
+            [
+                (near, ActorKind::Nateroid, Vec3::new(48., 0., 0.)),
+                (far, ActorKind::Nateroid, Vec3::new(-48., 0., 0.)),
+            ],
+        );
+
+        let found = nearby(&index, Vec3::new(45., 0., 0.), 10.);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].entity, near);
+        assert_eq!(nearby(&index, Vec3::ZERO, 60.).len(), 2);
+    }
+
+    // the flat game doesn't wrap in z, so the front face isn't next to the back
+    #[test]
+    fn only_wrapping_axes_wrap() {
+        let boundary = Boundary {
+            transform: Transform::from_scale(Vec3::new(200., 100., 100.)),
+            wrap_axes: BVec3::new(true, true, false),
+            ..default()
+        };
+
+        let mut index = SpatialIndex::default();
+        index.rebuild(
+            &boundary,
+            [
+                (Entity::from_raw(1), ActorKind::Nateroid, Vec3::new(0., 0., 48.)),
+                (Entity::from_raw(2), ActorKind::Nateroid, Vec3::new(0., 0., -48.)),
+            ],
+        );
+
+        assert_eq!(nearby(&index, Vec3::new(0., 0., 48.), 10.).len(), 1);
+        assert!(
+            (boundary
+                .wrapped_delta(Vec3::new(0., 0., 48.), Vec3::new(0., 0., -48.))
+                .z
+                + 96.)
+                .abs()
+                < 0.01
+        );
+    }
+}
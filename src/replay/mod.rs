@@ -0,0 +1,544 @@
+mod replay_file;
+
+pub use crate::replay::replay_file::ReplayLog;
+
+use crate::{
+    actor::{
+        ActorKind,
+        Health,
+        MissileConfig,
+        NateroidConfig,
+        SpaceshipControl,
+    },
+    config::SettingsFile,
+    console::CommandResult,
+    despawn::{
+        DestructionQueue,
+        DestructionReason,
+    },
+    global_input::GlobalAction,
+    key_bindings::RebuildInputMaps,
+    mutators::Mutators,
+    replay::replay_file::{
+        state_checksum,
+        ReplayChecksum,
+        ReplayEntry,
+        CHECKSUM_TICKS,
+    },
+    rng::GameRng,
+    scoring::Score,
+    state::GameState,
+    storage::platform_storage,
+    ui::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+    },
+};
+use bevy::prelude::*;
+use bevy_rapier3d::dynamics::Velocity;
+use leafwing_input_manager::prelude::*;
+use rand::Rng;
+
+/// where a recording is saved to and played back from - a file in the
+/// nateroids config folder natively, a localStorage item in the browser
+/// (which downloads a copy as well, to share)
+#[derive(Resource, Reflect, Debug, PartialEq, Clone)]
+#[reflect(Resource)]
+pub struct ReplayConfig {
+    pub path: String,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            path: "replay.ntr".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+enum RecorderMode {
+    #[default]
+    Idle,
+    Recording,
+    Playing,
+}
+
+/// how a replay went next to the recording it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub ticks:          u32,
+    pub score:          u32,
+    pub recorded_score: u32,
+    /// the first checksum that didn't match the recording's
+    pub diverged_at:    Option<u32>,
+}
+
+impl ReplayReport {
+    pub fn matches(&self) -> bool { self.diverged_at.is_none() && self.score == self.recorded_score }
+}
+
+/// records the spaceship's input while armed and plays a recording back in
+/// place of the keyboard. either one restarts the run first, from the seed
+/// and mutators the recording started with - a replay only means anything
+/// from the same starting point
+///
+/// every CHECKSUM_TICKS the simulation is checksummed. a recording keeps
+/// them and a replay checks against them, so a replay that stops being the
+/// same run says where it went wrong
+#[derive(Resource, Debug, Default)]
+pub struct InputRecorder {
+    mode:        RecorderMode,
+    // what starts once the restart has got back to the beginning of a run
+    pending:     Option<RecorderMode>,
+    log:         ReplayLog,
+    tick:        u32,
+    cursor:      usize,
+    // a file to record to instead of ReplayConfig's path - see
+    // HeadlessPlugin
+    record_path: Option<String>,
+    diverged_at: Option<u32>,
+    report:      Option<ReplayReport>,
+}
+
+impl InputRecorder {
+    pub fn is_active(&self) -> bool { self.mode != RecorderMode::Idle }
+
+    /// plays `log` back from the start of the next run
+    pub fn play(&mut self, log: ReplayLog) {
+        self.log = log;
+        self.report = None;
+        self.pending = Some(RecorderMode::Playing);
+    }
+
+    /// records the next run into the file at `path`
+    pub fn record_to(&mut self, path: String) {
+        self.record_path = Some(path);
+        self.pending = Some(RecorderMode::Recording);
+    }
+
+    /// how the last replay went, once it's finished
+    pub fn report(&self) -> Option<ReplayReport> { self.report }
+}
+
+pub fn is_recording(recorder: Res<InputRecorder>) -> bool { recorder.mode == RecorderMode::Recording }
+
+pub fn is_playing_back(recorder: Res<InputRecorder>) -> bool { recorder.mode == RecorderMode::Playing }
+
+pub fn is_replay_active(recorder: Res<InputRecorder>) -> bool { recorder.is_active() }
+
+pub fn is_replay_pending(recorder: Res<InputRecorder>) -> bool { recorder.pending.is_some() }
+
+/// the replay saved at `path` in platform storage
+pub fn load_replay(path: &str) -> Result<ReplayLog, String> {
+    let text = platform_storage()
+        .load(path)
+        .ok_or_else(|| format!("there's no replay at {path}"))?;
+    ReplayLog::from_text(&text).map_err(|e| format!("{path}: {e}"))
+}
+
+// the hook that gets a run back to its start - everything goes the way it
+// does at game over, then it's run setup, which hands straight over to a
+// new run while something is pending (see start_pending_replay)
+fn restart_run(
+    queue: &mut DestructionQueue,
+    actors: impl Iterator<Item = Entity>,
+    next_state: &mut NextState<GameState>,
+) {
+    for entity in actors {
+        queue.mark(entity, DestructionReason::GameOver);
+    }
+    next_state.set(GameState::RunSetup);
+}
+
+/// start recording from a fresh run, or stop and save what's been recorded
+pub fn toggle_recording(
+    user_input: Res<ActionState<GlobalAction>>,
+    config: Res<ReplayConfig>,
+    score: Res<Score>,
+    mut recorder: ResMut<InputRecorder>,
+    mut queue: ResMut<DestructionQueue>,
+    q_actors: Query<Entity, With<Health>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !user_input.just_pressed(&GlobalAction::ToggleRecording) {
+        return;
+    }
+
+    match recorder.mode {
+        RecorderMode::Idle => {
+            info!("recording from the start of a new run");
+            recorder.pending = Some(RecorderMode::Recording);
+            restart_run(&mut queue, q_actors.iter(), &mut next_state);
+        },
+        RecorderMode::Recording => stop_recording(&mut recorder, &config, score.points),
+        RecorderMode::Playing => info!("can't record while a replay is playing"),
+    }
+}
+
+fn stop_recording(recorder: &mut InputRecorder, config: &ReplayConfig, score: u32) {
+    recorder.mode = RecorderMode::Idle;
+    recorder.log.ticks = recorder.tick;
+    recorder.log.score = score;
+
+    let text = match recorder.log.to_text() {
+        Ok(text) => text,
+        Err(e) => {
+            warn!("unable to write the replay: {e}");
+            return;
+        },
+    };
+
+    let (path, saved) = match recorder.record_path.take() {
+        Some(path) => {
+            let saved = std::fs::write(&path, &text).map_err(|e| format!("{path}: {e}"));
+            (path, saved)
+        },
+        None => (config.path.clone(), save_replay(&config.path, &text)),
+    };
+    match saved {
+        Ok(()) => info!("saved {} ticks of input to {path}", recorder.tick),
+        Err(e) => warn!("unable to save the replay: {e}"),
+    }
+}
+
+// kept for the replay command and the main menu - in the browser it's
+// downloaded as well, since localStorage can't be shared
+fn save_replay(path: &str, text: &str) -> Result<(), String> {
+    platform_storage().save(path, text)?;
+
+    #[cfg(target_arch = "wasm32")]
+    crate::web::download(path.rsplit('/').next().unwrap_or(path), text)?;
+
+    Ok(())
+}
+
+/// `replay` in the console - loads the recording and plays it from a fresh
+/// run
+pub fn replay_from_console(
+    In(()): In<()>,
+    config: Res<ReplayConfig>,
+    mut recorder: ResMut<InputRecorder>,
+    mut queue: ResMut<DestructionQueue>,
+    q_actors: Query<Entity, With<Health>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) -> CommandResult {
+    if recorder.is_active() {
+        return Err("stop the recording or replay that's going first".to_string());
+    }
+    if !matches!(state.get(), GameState::InGame { .. } | GameState::GameOver) {
+        return Err("a replay starts from a run - start one first".to_string());
+    }
+
+    let log = load_replay(&config.path)?;
+    let ticks = log.ticks;
+    recorder.play(log);
+    restart_run(&mut queue, q_actors.iter(), &mut next_state);
+
+    Ok(format!("playing back {ticks} ticks from {}", config.path))
+}
+
+/// run setup hands straight over to the run that's being recorded or played
+/// back, started from the same seed, mutators and spawn timers as the
+/// recording. the spaceship's input maps are emptied for a replay - with
+/// nothing bound leafwing leaves its actions alone, so they're whatever the
+/// replay last set them to rather than what the keyboard says
+#[allow(clippy::too_many_arguments)]
+pub fn start_pending_replay(
+    mut recorder: ResMut<InputRecorder>,
+    mut rng: ResMut<GameRng>,
+    mut mutators: ResMut<Mutators>,
+    mut missile_config: ResMut<MissileConfig>,
+    mut nateroid_config: ResMut<NateroidConfig>,
+    settings: Res<SettingsFile>,
+    mut announcements: EventWriter<Announcement>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ship_map: ResMut<InputMap<SpaceshipControl>>,
+    mut q_ship_maps: Query<&mut InputMap<SpaceshipControl>>,
+) {
+    // otherwise the first nateroid comes whenever the last run's timer says
+    for timer in [
+        &mut missile_config.0.spawn_timer,
+        &mut nateroid_config.0.spawn_timer,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        timer.reset();
+    }
+
+    match recorder.pending {
+        Some(RecorderMode::Playing) => {
+            *rng = GameRng::seeded(recorder.log.seed);
+            *mutators = recorder.log.mutators;
+            if recorder.log.settings != settings.snapshot() {
+                warn!("the replay was recorded with different settings - it may not play out the same");
+                announcements.send(Announcement::new(
+                    "recorded with different settings",
+                    AnnouncementStyle::Warning,
+                    AnnouncementPriority::Low,
+                ));
+            }
+
+            *ship_map = InputMap::default();
+            for mut input_map in q_ship_maps.iter_mut() {
+                *input_map = InputMap::default();
+            }
+        },
+        // from the game's own rng, so a `--seed` run records the same run
+        // every time
+        Some(RecorderMode::Recording) => {
+            let seed = rng.rng.random();
+            *rng = GameRng::seeded(seed);
+            recorder.log = ReplayLog {
+                seed,
+                mutators: *mutators,
+                settings: settings.snapshot(),
+                ..default()
+            };
+        },
+        Some(RecorderMode::Idle) | None => (),
+    }
+
+    next_state.set(GameState::InGame {
+        paused:     false,
+        inspecting: false,
+    });
+}
+
+/// the run has started - counting starts from the first tick of it
+pub fn activate_pending_replay(mut recorder: ResMut<InputRecorder>) {
+    let Some(mode) = recorder.pending.take() else {
+        return;
+    };
+
+    recorder.mode = mode;
+    recorder.tick = 0;
+    recorder.cursor = 0;
+    recorder.diverged_at = None;
+}
+
+/// only ticks the simulation actually takes count - not the ones while paused
+pub fn count_replay_ticks(mut recorder: ResMut<InputRecorder>) { recorder.tick += 1; }
+
+/// a recording keeps a checksum every CHECKSUM_TICKS and a replay compares
+/// its own against them - after physics has moved everything for the tick
+pub fn checksum_replay_state(
+    mut recorder: ResMut<InputRecorder>,
+    mut announcements: EventWriter<Announcement>,
+    q_actors: Query<(&ActorKind, &Transform, &Velocity)>,
+) {
+    let tick = recorder.tick;
+    if !tick.is_multiple_of(CHECKSUM_TICKS) {
+        return;
+    }
+
+    let checksum = state_checksum(q_actors.iter());
+    match recorder.mode {
+        RecorderMode::Recording => recorder.log.checksums.push(ReplayChecksum { tick, checksum }),
+        RecorderMode::Playing => {
+            let diverged = recorder
+                .log
+                .checksum_at(tick)
+                .is_some_and(|recorded| recorded != checksum);
+            if diverged && recorder.diverged_at.is_none() {
+                recorder.diverged_at = Some(tick);
+                warn!("the replay stopped matching the recording at tick {tick}");
+                announcements.send(Announcement::new(
+                    format!("replay diverged at tick {tick}"),
+                    AnnouncementStyle::Warning,
+                    AnnouncementPriority::Normal,
+                ));
+            }
+        },
+        RecorderMode::Idle => (),
+    }
+}
+
+/// the run ending ends a recording or a replay along with it
+pub fn stop_replay_at_game_over(
+    config: Res<ReplayConfig>,
+    score: Res<Score>,
+    mut recorder: ResMut<InputRecorder>,
+    mut rebuilds: EventWriter<RebuildInputMaps>,
+    mut announcements: EventWriter<Announcement>,
+) {
+    match recorder.mode {
+        RecorderMode::Recording => stop_recording(&mut recorder, &config, score.points),
+        RecorderMode::Playing => {
+            stop_playback(&mut recorder, &mut rebuilds, score.points, &mut announcements)
+        },
+        RecorderMode::Idle => (),
+    }
+}
+
+/// a recording that stopped before game over - see HeadlessPlugin's
+/// `--seconds` - plays back as far as it went and stops there, on the score
+/// it had by the end of that tick
+pub fn stop_replay_at_recorded_end(
+    score: Res<Score>,
+    mut recorder: ResMut<InputRecorder>,
+    mut rebuilds: EventWriter<RebuildInputMaps>,
+    mut announcements: EventWriter<Announcement>,
+) {
+    if recorder.mode == RecorderMode::Playing && recorder.tick > recorder.log.ticks {
+        stop_playback(&mut recorder, &mut rebuilds, score.points, &mut announcements);
+    }
+}
+
+/// ends a recording where it's got to and saves it, game over or not
+pub fn stop_recording_here(config: Res<ReplayConfig>, score: Res<Score>, mut recorder: ResMut<InputRecorder>) {
+    if recorder.mode == RecorderMode::Recording {
+        stop_recording(&mut recorder, &config, score.points);
+    }
+}
+
+// the keyboard gets the ship back - apply_key_bindings rebuilds its maps
+fn stop_playback(
+    recorder: &mut InputRecorder,
+    rebuilds: &mut EventWriter<RebuildInputMaps>,
+    score: u32,
+    announcements: &mut EventWriter<Announcement>,
+) {
+    recorder.mode = RecorderMode::Idle;
+    rebuilds.send(RebuildInputMaps);
+
+    let report = ReplayReport {
+        ticks: recorder.tick,
+        score,
+        recorded_score: recorder.log.score,
+        diverged_at: recorder.diverged_at,
+    };
+    recorder.report = Some(report);
+
+    let (text, style) = match report.diverged_at {
+        _ if report.matches() => (
+            "replay matched the recording".to_string(),
+            AnnouncementStyle::Success,
+        ),
+        Some(tick) => (
+            format!("replay finished - it diverged at tick {tick}"),
+            AnnouncementStyle::Warning,
+        ),
+        None => (
+            format!(
+                "replay finished on {score} - the recording scored {}",
+                report.recorded_score
+            ),
+            AnnouncementStyle::Warning,
+        ),
+    };
+    info!("{text}");
+    announcements.send(Announcement::new(text, style, AnnouncementPriority::Normal));
+}
+
+// the spaceship's own action state if there's a spaceship, the resource if
+// there isn't
+pub fn record_ship_input(
+    mut recorder: ResMut<InputRecorder>,
+    ship_state: Res<ActionState<SpaceshipControl>>,
+    q_ship_states: Query<&ActionState<SpaceshipControl>>,
+) {
+    let action_state = q_ship_states.iter().next().unwrap_or(&*ship_state);
+    let pressed: Vec<_> = SpaceshipControl::buttons()
+        .filter(|action| action_state.pressed(action))
+        .collect();
+    let just_pressed: Vec<_> = SpaceshipControl::buttons()
+        .filter(|action| action_state.just_pressed(action))
+        .collect();
+    let throttle = action_state.value(&SpaceshipControl::Thrust);
+
+    let unchanged = recorder
+        .log
+        .entries
+        .last()
+        .map_or(pressed.is_empty() && throttle == 0.0, |last| {
+            last.pressed == pressed && last.throttle == throttle
+        });
+    if unchanged && just_pressed.is_empty() {
+        return;
+    }
+
+    let tick = recorder.tick;
+    recorder.log.entries.push(ReplayEntry {
+        tick,
+        pressed,
+        just_pressed,
+        throttle,
+    });
+}
+
+// every entry the simulation has caught up with is applied - the last one
+// says what's held, and anything pressed in any of them is pressed again.
+// it's over once it's as long as the recording was
+pub fn play_back_ship_input(
+    mut recorder: ResMut<InputRecorder>,
+    mut rebuilds: EventWriter<RebuildInputMaps>,
+    score: Res<Score>,
+    mut announcements: EventWriter<Announcement>,
+    mut ship_state: ResMut<ActionState<SpaceshipControl>>,
+    mut q_ship_states: Query<&mut ActionState<SpaceshipControl>>,
+) {
+    let start = recorder.cursor;
+    while recorder
+        .log
+        .entries
+        .get(recorder.cursor)
+        .is_some_and(|entry| entry.tick <= recorder.tick)
+    {
+        recorder.cursor += 1;
+    }
+
+    let finished = recorder.tick >= recorder.log.ticks;
+    if recorder.cursor == start && !finished {
+        return;
+    }
+
+    let applied = &recorder.log.entries[start..recorder.cursor];
+    let (held, throttle): (&[SpaceshipControl], f32) = match applied.last() {
+        // everything is let go of once the replay is over
+        Some(entry) if !finished => (&entry.pressed, entry.throttle),
+        _ => (&[], 0.0),
+    };
+    let again: Vec<SpaceshipControl> = applied
+        .iter()
+        .flat_map(|entry| entry.just_pressed.iter().copied())
+        .collect();
+
+    let apply = |action_state: &mut ActionState<SpaceshipControl>| {
+        action_state.set_value(&SpaceshipControl::Thrust, throttle);
+        for action in SpaceshipControl::buttons() {
+            let wanted = held.contains(&action) || again.contains(&action);
+            if again.contains(&action) && action_state.pressed(&action) {
+                action_state.release(&action);
+            }
+            if wanted && !action_state.pressed(&action) {
+                action_state.press(&action);
+            } else if !wanted && action_state.pressed(&action) {
+                action_state.release(&action);
+            }
+        }
+    };
+    apply(&mut ship_state);
+    for mut action_state in q_ship_states.iter_mut() {
+        apply(&mut action_state);
+    }
+
+    if finished {
+        stop_playback(&mut recorder, &mut rebuilds, score.points, &mut announcements);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn replay_config_round_trips() { assert_settings_round_trip(nudged::<ReplayConfig>()); }
+}
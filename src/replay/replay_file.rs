@@ -0,0 +1,234 @@
+use crate::{
+    actor::{
+        ActorKind,
+        SpaceshipControl,
+    },
+    mutators::Mutators,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::dynamics::Velocity;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// a replay is this line and then the log as ron:
+//
+// nateroids replay 3
+// (
+//     seed: 1234,
+//     ...
+// )
+//
+// the version is checked before anything else is read, so a replay from
+// another version says so rather than failing somewhere in the middle
+const HEADER: &str = "nateroids replay";
+// bumped whenever the layout changes in a way an older replay can't be read
+const REPLAY_VERSION: u32 = 3;
+
+/// how often a recording notes down a checksum of the simulation, in ticks -
+/// a second at SIMULATION_HZ
+pub const CHECKSUM_TICKS: u32 = 60;
+
+/// everything it takes to play a run again - what it started from, the
+/// spaceship's input keyed by fixed timestep, and checksums along the way to
+/// tell whether the replay is still the same run
+///
+/// input is keyed by tick rather than by frame - the simulation only moves
+/// on a tick, so that's what lines a replay up with the run it came from
+/// whatever the frame rate is
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReplayLog {
+    /// what GameRng started from
+    pub seed:      u64,
+    pub mutators:  Mutators,
+    /// the settings file as it was - a replay played with different ones is
+    /// likely to go its own way
+    pub settings:  String,
+    pub entries:   Vec<ReplayEntry>,
+    pub checksums: Vec<ReplayChecksum>,
+    /// how far the recording went and what it had scored by then
+    pub ticks:     u32,
+    pub score:     u32,
+}
+
+// only the frames something changed in are kept - a held key is one entry
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayEntry {
+    pub tick:         u32,
+    pub pressed:      Vec<SpaceshipControl>,
+    pub just_pressed: Vec<SpaceshipControl>,
+    pub throttle:     f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayChecksum {
+    pub tick:     u32,
+    pub checksum: u64,
+}
+
+impl ReplayLog {
+    pub fn to_text(&self) -> Result<String, String> {
+        let body =
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|e| e.to_string())?;
+        Ok(format!("{HEADER} {REPLAY_VERSION}\n{body}\n"))
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let (header, body) = text.split_once('\n').unwrap_or((text, ""));
+        let version: u32 = header
+            .strip_prefix(HEADER)
+            .and_then(|version| version.trim().parse().ok())
+            .ok_or("not a nateroids replay")?;
+        if version != REPLAY_VERSION {
+            return Err(format!(
+                "a version {version} replay - this build plays version {REPLAY_VERSION}"
+            ));
+        }
+
+        ron::from_str(body).map_err(|e| e.to_string())
+    }
+
+    /// what the recording's checksum was at `tick`, if it took one then
+    pub fn checksum_at(&self, tick: u32) -> Option<u64> {
+        self.checksums
+            .binary_search_by_key(&tick, |checksum| checksum.tick)
+            .ok()
+            .map(|index| self.checksums[index].checksum)
+    }
+}
+
+/// where everything is and how it's moving, boiled down to a number. the
+/// same run on the same build comes out the same bit for bit, and anything
+/// that isn't the same almost certainly doesn't. it doesn't matter what order
+/// the actors come in
+pub fn state_checksum<'a>(actors: impl Iterator<Item = (&'a ActorKind, &'a Transform, &'a Velocity)>) -> u64 {
+    actors.fold(0, |sum, (kind, transform, velocity)| {
+        let mut hash = Fnv::default();
+        hash.write(&[*kind as u8]);
+        for value in [
+            transform.translation.to_array(),
+            velocity.linvel.to_array(),
+            velocity.angvel.to_array(),
+        ] {
+            hash.write_floats(&value);
+        }
+        hash.write_floats(&transform.rotation.to_array());
+        sum.wrapping_add(hash.0)
+    })
+}
+
+// fnv-1a - std's hasher is allowed to change between releases and a replay
+// outlives a build
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self { Self(0xcbf2_9ce4_8422_2325) }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_floats(&mut self, values: &[f32]) {
+        for value in values {
+            self.write(&value.to_bits().to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> ReplayLog {
+        ReplayLog {
+            seed: 42,
+            settings: "(\n    audio: (\n        master_volume: 0.5,\n    ),\n)\n".to_string(),
+            entries: vec![ReplayEntry {
+                tick:         12,
+                pressed:      vec![SpaceshipControl::Fire],
+                just_pressed: vec![SpaceshipControl::Fire],
+                throttle:     0.5,
+            }],
+            checksums: vec![
+                ReplayChecksum {
+                    tick:     60,
+                    checksum: 7,
+                },
+                ReplayChecksum {
+                    tick:     120,
+                    checksum: u64::MAX,
+                },
+            ],
+            ticks: 130,
+            score: 300,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let text = log().to_text().unwrap();
+        assert!(text.starts_with("nateroids replay 3\n"));
+
+        let read = ReplayLog::from_text(&text).unwrap();
+        assert_eq!(read.seed, 42);
+        assert_eq!(read.settings, log().settings);
+        assert_eq!(read.entries, log().entries);
+        assert_eq!(read.checksums, log().checksums);
+        assert_eq!((read.ticks, read.score), (130, 300));
+        assert_eq!(read.mutators, Mutators::default());
+    }
+
+    #[test]
+    fn says_what_is_wrong_with_the_header() {
+        let body = log().to_text().unwrap();
+        let body = body.split_once('\n').unwrap().1;
+
+        assert_eq!(
+            ReplayLog::from_text(&format!("nateroids replay 2\n{body}")).unwrap_err(),
+            "a version 2 replay - this build plays version 3"
+        );
+        assert_eq!(ReplayLog::from_text(body).unwrap_err(), "not a nateroids replay");
+    }
+
+    #[test]
+    fn checksum_at_a_tick() {
+        let log = log();
+        assert_eq!(log.checksum_at(60), Some(7));
+        assert_eq!(log.checksum_at(120), Some(u64::MAX));
+        assert_eq!(log.checksum_at(90), None);
+    }
+
+    #[test]
+    fn checksum_ignores_order_but_not_position() {
+        let nateroid = (
+            ActorKind::Nateroid,
+            Transform::from_xyz(1., 2., 3.),
+            Velocity::linear(Vec3::X),
+        );
+        let missile = (
+            ActorKind::Missile,
+            Transform::from_xyz(-4., 0., 9.),
+            Velocity::linear(Vec3::Y),
+        );
+        let checksum = |actors: &[&(ActorKind, Transform, Velocity)]| {
+            state_checksum(
+                actors
+                    .iter()
+                    .map(|(kind, transform, velocity)| (kind, transform, velocity)),
+            )
+        };
+
+        assert_eq!(checksum(&[&nateroid, &missile]), checksum(&[&missile, &nateroid]));
+
+        let mut nudged = nateroid;
+        nudged.1.translation.x = f32::from_bits(nudged.1.translation.x.to_bits() + 1);
+        assert_ne!(checksum(&[&nateroid, &missile]), checksum(&[&nudged, &missile]));
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use rand::{
+    RngCore,
+    SeedableRng,
+};
+use rand_chacha::ChaCha12Rng;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+const SEED_ARG: &str = "--seed";
+// mixed into the seed for the sounds' stream so it doesn't start out the same
+// as the simulation's
+const SOUNDS_STREAM: u64 = 0x50_0d5;
+
+/// every random choice the simulation makes - where nateroids spawn, how
+/// they're turned and how fast they go, which look they get - comes out of
+/// this, so the same seed and the same input play out the same run. anything
+/// that's only for show (stars, twinkling) keeps using the thread rng and
+/// doesn't take turns away from the simulation - sounds have a stream of their
+/// own in here
+///
+/// `--seed 42` on the command line fixes it, otherwise it's a different run
+/// every time. a diagnostics dump has it, how far along each stream is and
+/// all, so `load-dump` carries on from the same draw
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct GameRng {
+    pub rng:    RngStream,
+    /// a sound's pitch and which of its takes plays. it's from the same seed
+    /// but apart from `rng`, so how many sounds went off - which depends on
+    /// what's loaded and whether audio is on at all - can't change what the
+    /// simulation draws next
+    pub sounds: RngStream,
+    seed:       u64,
+}
+
+/// one of GameRng's streams - the same generator StdRng is, but written out
+/// as its seed and how far along it is, so it can go in a dump and come back
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(opaque, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(from = "StreamPosition", into = "StreamPosition")]
+pub struct RngStream(ChaCha12Rng);
+
+// how far along is a u128, which ron can't write - so it's split in two
+#[derive(Serialize, Deserialize)]
+struct StreamPosition {
+    seed:     [u8; 32],
+    stream:   u64,
+    word_pos: (u64, u64),
+}
+
+impl From<RngStream> for StreamPosition {
+    fn from(RngStream(rng): RngStream) -> Self {
+        let word_pos = rng.get_word_pos();
+        Self {
+            seed:     rng.get_seed(),
+            stream:   rng.get_stream(),
+            word_pos: ((word_pos >> 64) as u64, word_pos as u64),
+        }
+    }
+}
+
+impl From<StreamPosition> for RngStream {
+    fn from(position: StreamPosition) -> Self {
+        let (high, low) = position.word_pos;
+        let mut rng = ChaCha12Rng::from_seed(position.seed);
+        rng.set_stream(position.stream);
+        rng.set_word_pos(((high as u128) << 64) | low as u128);
+        Self(rng)
+    }
+}
+
+impl RngStream {
+    fn seeded(seed: u64) -> Self { Self(ChaCha12Rng::seed_from_u64(seed)) }
+}
+
+impl RngCore for RngStream {
+    fn next_u32(&mut self) -> u32 { self.0.next_u32() }
+
+    fn next_u64(&mut self) -> u64 { self.0.next_u64() }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) { self.0.fill_bytes(dest) }
+}
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            rng: RngStream::seeded(seed),
+            sounds: RngStream::seeded(seed ^ SOUNDS_STREAM),
+            seed,
+        }
+    }
+
+    /// what the rng was started from - a replay saves it to start from the
+    /// same place again
+    pub fn seed(&self) -> u64 { self.seed }
+
+    // logging isn't set up yet while plugins are being built
+    fn seed_from_args() -> Option<u64> {
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == SEED_ARG {
+                match args.next().and_then(|value| value.parse().ok()) {
+                    Some(seed) => return Some(seed),
+                    None => eprintln!("{SEED_ARG} needs a whole number, picking one at random"),
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self { Self::seeded(Self::seed_from_args().unwrap_or_else(rand::random)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    // a replay plays out the same whether or not anything was heard
+    #[test]
+    fn sounds_leave_the_simulation_alone() {
+        let mut quiet = GameRng::seeded(42);
+        let mut loud = GameRng::seeded(42);
+        for _ in 0..10 {
+            let _: f32 = loud.sounds.random();
+        }
+
+        let quiet_draws: Vec<u32> = (0..10).map(|_| quiet.rng.random()).collect();
+        let loud_draws: Vec<u32> = (0..10).map(|_| loud.rng.random()).collect();
+        assert_eq!(quiet_draws, loud_draws);
+    }
+
+    // what a diagnostics dump does with it - written out part way through and
+    // read back, the copy carries on with the same draws
+    #[test]
+    fn round_trips_mid_stream() {
+        use bevy::reflect::serde::{
+            ReflectDeserializer,
+            ReflectSerializer,
+        };
+        use serde::de::DeserializeSeed;
+
+        let mut registry = bevy::reflect::TypeRegistry::default();
+        registry.register::<GameRng>();
+        let mut original = GameRng::seeded(7);
+        for _ in 0..5 {
+            let _: u32 = original.rng.random();
+            let _: f32 = original.sounds.random();
+        }
+
+        let text = ron::ser::to_string(&ReflectSerializer::new(&original, &registry)).unwrap();
+        let mut deserializer = ron::de::Deserializer::from_str(&text).unwrap();
+        let read = ReflectDeserializer::new(&registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let mut copy = GameRng::seeded(0);
+        copy.apply(&*read);
+
+        assert_eq!(copy.seed(), 7);
+        let draws = |rng: &mut GameRng| -> Vec<u32> { (0..10).map(|_| rng.rng.random()).collect() };
+        assert_eq!(draws(&mut copy), draws(&mut original));
+        assert_eq!(copy.sounds, original.sounds);
+    }
+}
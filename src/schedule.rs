@@ -1,7 +1,44 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::batching::BatchingStrategy,
+    prelude::*,
+};
+use bevy_rapier3d::plugin::PhysicsSet;
 
-use crate::state::IsPaused;
+use crate::time_controls::{
+    simulation_frame,
+    simulation_ticking,
+};
 
+/// the order everything happens in within an Update - every plugin puts its
+/// Update systems in one of these so nothing depends on the order plugins
+/// happened to be added in
+///
+/// Input - reading devices, global toggles, menus opening and state changes
+/// Intent - clearing out whatever died last frame, applying collisions and
+///          turning player input into what the spaceship wants to do
+/// PhysicsSync - per frame entity updates that aren't part of the simulation.
+///               the simulation - physics, movement, spawning and wrapping -
+///               ticks in FixedUpdate, which has already run by the time
+///               Update starts, so everything in Update sees this frame's
+///               moves and wraps
+/// Boundary - keeping the boundary and anything that follows it up to date
+/// Visual - portals, gizmos, lines, lights and stars
+/// Camera - camera movement, after everything it might look at has moved
+/// Ui - hud, menus, diagnostics and sound - whatever reports the frame back to
+///      the player, so it always reflects where things ended up
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub enum FrameSet {
+    Input,
+    Intent,
+    PhysicsSync,
+    Boundary,
+    Visual,
+    Camera,
+    Ui,
+}
+
+/// the parts of gameplay that stop when the game is paused - they live inside
+/// FrameSet::Intent and FrameSet::PhysicsSync
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum InGameSet {
     UserInput,
@@ -10,38 +47,243 @@ pub enum InGameSet {
     DespawnEntities,
 }
 
+/// how the systems that do the same thing for every actor - teleporting and
+/// the portals - split them up across threads. bevy's own choice unless a bench
+/// wants them done in one go to compare against, see `serial`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct EntityBatching(pub BatchingStrategy);
+
+impl EntityBatching {
+    /// each kind of entity in one batch, so one thread does all of them
+    pub fn serial() -> Self { Self(BatchingStrategy::fixed(usize::MAX)) }
+}
+
+/// how often the simulation ticks - physics, movement, spawning and wrapping
+/// all run in FixedUpdate at this rate whatever the frame rate is
+pub const SIMULATION_HZ: f64 = 60.;
+
 pub struct SchedulePlugin;
 
 impl Plugin for SchedulePlugin {
     fn build(&self, app: &mut App) {
-        app.configure_sets(
-            Update,
-            (
-                InGameSet::DespawnEntities,
-                // Flush commands (i.e. `apply_deferred` runs)
-                InGameSet::CollisionDetection,
-                InGameSet::UserInput,
-                InGameSet::EntityUpdates,
+        app.insert_resource(Time::<Fixed>::from_hz(SIMULATION_HZ))
+            .init_resource::<EntityBatching>()
+            .configure_sets(
+                Update,
+                (
+                    FrameSet::Input,
+                    FrameSet::Intent,
+                    FrameSet::PhysicsSync,
+                    FrameSet::Boundary,
+                    FrameSet::Visual,
+                    FrameSet::Camera,
+                    FrameSet::Ui,
+                )
+                    .chain(),
             )
-                .chain()
-                // the following is pretty cool - because we added an InGameSet system set to
-                // all the systems that are "in game" - in order to ensure proper ordering
-                // the following comes along for the ride - i.e., they will only run _if_
-                // in_state evaluates to true - i.e., we are in_game
-                // and we have a system that runs on state to watch for keyboard control
-                // that takes us in or out of InGame - i.e., pausing
-                // 1 line of code right here allows for pausing and starting the game!
-                .run_if(in_state(IsPaused::NotPaused)),
-        )
-        .add_systems(
-            Update,
-            // apply_deferred - think of this as flushing all queued updates
-            // in this case, after a despawn - before moving on to the next SystemSet
-            // this way there isn't any chance that UserInput systems will use despawned entities
-            // for performance reasons this is pretty cool
-            apply_deferred
-                .after(InGameSet::DespawnEntities)
-                .before(InGameSet::UserInput),
-        );
+            .configure_sets(
+                Update,
+                (
+                    InGameSet::DespawnEntities,
+                    InGameSet::CollisionDetection,
+                    InGameSet::UserInput,
+                )
+                    .in_set(FrameSet::Intent),
+            )
+            .configure_sets(Update, InGameSet::EntityUpdates.in_set(FrameSet::PhysicsSync))
+            .configure_sets(
+                Update,
+                (
+                    InGameSet::DespawnEntities,
+                    // Flush commands (i.e. `apply_deferred` runs)
+                    InGameSet::CollisionDetection,
+                    InGameSet::UserInput,
+                    InGameSet::EntityUpdates,
+                )
+                    .chain()
+                    // the following is pretty cool - because we added an InGameSet system set to
+                    // all the systems that are "in game" - in order to ensure proper ordering
+                    // the following comes along for the ride - i.e., they will only run _if_
+                    // in_state evaluates to true - i.e., we are in_game
+                    // and we have a system that runs on state to watch for keyboard control
+                    // that takes us in or out of InGame - i.e., pausing
+                    // 1 line of code right here allows for pausing and starting the game!
+                    // (plus the frame a debug step lands on - see time_controls.rs)
+                    .run_if(simulation_frame),
+            )
+            // the simulation runs in FixedUpdate - it gets the same pause treatment,
+            // collisions are handled before the spaceship is steered and before
+            // anything moves or wraps. all of that is handed to rapier before it
+            // steps, rather than in whichever order the executor picks - a
+            // replay has to go the same way every time
+            .configure_sets(
+                FixedUpdate,
+                (
+                    InGameSet::CollisionDetection,
+                    InGameSet::UserInput,
+                    InGameSet::EntityUpdates,
+                )
+                    .chain()
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(simulation_ticking),
+            )
+            .add_systems(
+                Update,
+                // apply_deferred - think of this as flushing all queued updates
+                // in this case, after a despawn - before moving on to the next SystemSet
+                // this way there isn't any chance that UserInput systems will use despawned entities
+                // for performance reasons this is pretty cool
+                apply_deferred
+                    .after(InGameSet::DespawnEntities)
+                    .before(InGameSet::UserInput)
+                    .in_set(FrameSet::Intent),
+            );
+
+        #[cfg(debug_assertions)]
+        detect_ambiguities(app);
+    }
+}
+
+// debug builds warn about any two systems that touch the same data with no
+// order between them. the resources below are written from all over the place
+// on purpose - events are read a frame later anyway, and assets are only ever
+// added or swapped out - so they're left out of the report
+#[cfg(debug_assertions)]
+fn detect_ambiguities(app: &mut App) {
+    use bevy::ecs::schedule::{
+        LogLevel,
+        ScheduleBuildSettings,
+    };
+
+    let warn = |schedule: &mut Schedule| {
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Warn,
+            ..default()
+        });
+    };
+
+    app.edit_schedule(Update, warn)
+        .edit_schedule(FixedUpdate, warn)
+        .allow_ambiguous_resource::<Assets<Mesh>>()
+        .allow_ambiguous_resource::<Assets<StandardMaterial>>()
+        .allow_ambiguous_resource::<Events<crate::audio::PlaySoundEffect>>()
+        .allow_ambiguous_resource::<Events<crate::audio::UiSoundEvent>>()
+        .allow_ambiguous_resource::<Events<crate::ui::Announcement>>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actor::{
+            teleport_at_boundary,
+            Aabb,
+            EntityLeftBoundary,
+            EntityTeleported,
+            TeleportStats,
+            Teleporter,
+        },
+        camera::{
+            follow_camera_target,
+            CameraTarget,
+            PrimaryCamera,
+        },
+        despawn::DestructionQueue,
+        mutators::Mutators,
+        playfield::{
+            init_portals,
+            ActorPortals,
+            Boundary,
+            PortalConfig,
+        },
+        state::{
+            GameState,
+            IsPaused,
+            PlayingGame,
+        },
+        time_controls::{
+            GameTime,
+            SimulationStep,
+        },
+    };
+    use bevy::{
+        state::app::StatesPlugin,
+        time::TimeUpdateStrategy,
+    };
+    use bevy_rapier3d::dynamics::Velocity;
+    use std::time::Duration;
+
+    // the wrap happens in FixedUpdate, and the emerging portal and the camera
+    // following the wrapped entity have to catch up with it in the same frame -
+    // one frame late and the portal shows up after the jump and the camera
+    // lurches a frame behind it
+    #[test]
+    fn portal_and_camera_follow_a_wrap_in_the_same_frame() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, SchedulePlugin))
+            // a little over one tick a frame, so every update after the first
+            // runs the simulation
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)))
+            .insert_state(GameState::InGame {
+                paused:     false,
+                inspecting: false,
+            })
+            .add_computed_state::<PlayingGame>()
+            .add_computed_state::<IsPaused>()
+            .add_event::<EntityTeleported>()
+            .add_event::<EntityLeftBoundary>()
+            .init_resource::<Boundary>()
+            .init_resource::<Mutators>()
+            .init_resource::<DestructionQueue>()
+            .init_resource::<TeleportStats>()
+            .init_resource::<SimulationStep>()
+            .init_resource::<GameTime>()
+            .init_resource::<PortalConfig>()
+            .init_resource::<CameraTarget>()
+            // where their plugins put them
+            .add_systems(FixedUpdate, teleport_at_boundary.in_set(InGameSet::EntityUpdates))
+            .add_systems(Update, init_portals.in_set(FrameSet::Visual))
+            .add_systems(Update, follow_camera_target.in_set(FrameSet::Camera));
+
+        let half_width = app.world().resource::<Boundary>().transform.scale.x / 2.;
+        let actor = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(half_width - 1., 0., 0.),
+                Velocity::linear(Vec3::X * 60.),
+                Aabb {
+                    min: Vec3::splat(-1.),
+                    max: Vec3::splat(1.),
+                },
+                Teleporter::default(),
+                ActorPortals::default(),
+            ))
+            .id();
+        let camera = app
+            .world_mut()
+            .spawn((Transform::from_xyz(0., 0., 300.), PrimaryCamera))
+            .id();
+        app.world_mut().resource_mut::<CameraTarget>().follow(actor);
+
+        // the first update has no time to tick with - the camera just picks up
+        // where the actor is
+        app.update();
+        assert!(!app.world().get::<Teleporter>(actor).unwrap().just_teleported);
+        let camera_before = app.world().get::<Transform>(camera).unwrap().translation;
+
+        // physics carries it out through the face
+        let outside = Vec3::new(half_width + 1., 0., 0.);
+        app.world_mut().get_mut::<Transform>(actor).unwrap().translation = outside;
+        app.update();
+
+        let wrapped = app.world().get::<Transform>(actor).unwrap().translation;
+        assert!(app.world().get::<Teleporter>(actor).unwrap().just_teleported);
+        assert!(wrapped.x < 0.);
+
+        assert!(app.world().get::<ActorPortals>(actor).unwrap().emerging.is_some());
+
+        let camera_after = app.world().get::<Transform>(camera).unwrap().translation;
+        let jump = wrapped - Vec3::new(half_width - 1., 0., 0.);
+        assert!((camera_after - camera_before - jump).length() < 1e-3);
     }
 }
@@ -0,0 +1,37 @@
+use crate::score_reporting::{
+    RunResult,
+    ScoreReporter,
+};
+use bevy::utils::BoxedFuture;
+
+/// POSTs each run as json - ehttp runs the request on its own thread natively
+/// and with the browser's fetch on wasm, so this is the same everywhere
+#[derive(Debug)]
+pub struct HttpReporter {
+    endpoint: String,
+}
+
+impl HttpReporter {
+    pub fn new(endpoint: String) -> Self { Self { endpoint } }
+}
+
+impl ScoreReporter for HttpReporter {
+    fn submit(&self, result: &RunResult) -> BoxedFuture<'static, Result<(), String>> {
+        let request = ehttp::Request {
+            headers: ehttp::Headers::new(&[
+                ("Accept", "application/json"),
+                ("Content-Type", "application/json"),
+            ]),
+            ..ehttp::Request::post(&self.endpoint, result.to_json().into_bytes())
+        };
+
+        Box::pin(async move {
+            let response = ehttp::fetch_async(request).await?;
+            if response.ok {
+                Ok(())
+            } else {
+                Err(format!("{} {}", response.status, response.status_text))
+            }
+        })
+    }
+}
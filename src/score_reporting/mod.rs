@@ -0,0 +1,268 @@
+#[cfg(feature = "score-reporting")]
+mod http;
+
+use crate::{
+    config::SettingsAppExt,
+    mutators::Mutators,
+    schedule::FrameSet,
+    scoring::Score,
+    state::GameState,
+};
+use bevy::{
+    prelude::*,
+    tasks::{
+        block_on,
+        poll_once,
+        IoTaskPool,
+        Task,
+    },
+    utils::BoxedFuture,
+};
+use std::sync::Arc;
+
+// a failed submission is tried again after 2, 4, 8 and 16 seconds and then
+// given up on
+const MAX_ATTEMPTS: u32 = 5;
+const FIRST_RETRY_SECONDS: f32 = 2.;
+
+/// sends each finished run somewhere else - a leaderboard server, say. nothing
+/// that happens here can reach back into the game: submissions run on the io
+/// task pool, a failure is retried a few times and then only logged
+///
+/// with the `score-reporting` feature and `score_reporting.enabled` set the
+/// runs are POSTed as json to `score_reporting.endpoint`. without either,
+/// they go nowhere
+pub struct ScoreReportingPlugin;
+
+impl Plugin for ScoreReportingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings::<ScoreReportingSettings>("score_reporting")
+            .init_resource::<PendingReports>()
+            .add_systems(
+                Update,
+                choose_reporter.run_if(resource_changed::<ScoreReportingSettings>),
+            )
+            .add_systems(OnEnter(GameState::GameOver), report_run)
+            .add_systems(Update, drive_reports.in_set(FrameSet::Ui));
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Default, PartialEq, Clone)]
+#[reflect(Resource)]
+pub struct ScoreReportingSettings {
+    pub enabled:  bool,
+    pub endpoint: String,
+}
+
+/// what gets sent at game over. there are no waves, difficulty levels or
+/// replays yet so those go out empty - they're in the payload so a server
+/// doesn't have to change when they turn up
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    pub score:           u32,
+    pub wave:            Option<u32>,
+    pub difficulty:      Option<String>,
+    pub mutators:        Vec<&'static str>,
+    pub replay_checksum: Option<String>,
+}
+
+// only the http reporter sends json
+#[cfg(feature = "score-reporting")]
+impl RunResult {
+    // formatted by hand, like the headless summary - nothing else needs json
+    pub fn to_json(&self) -> String {
+        let optional_string = |value: &Option<String>| match value {
+            Some(value) => format!("\"{}\"", escape_json(value)),
+            None => "null".to_string(),
+        };
+        let mutators: Vec<String> = self
+            .mutators
+            .iter()
+            .map(|mutator| format!("\"{}\"", escape_json(mutator)))
+            .collect();
+
+        format!(
+            "{{\"score\":{},\"wave\":{},\"difficulty\":{},\"mutators\":[{}],\"replay_checksum\":{}}}",
+            self.score,
+            self.wave.map_or("null".to_string(), |wave| wave.to_string()),
+            optional_string(&self.difficulty),
+            mutators.join(","),
+            optional_string(&self.replay_checksum),
+        )
+    }
+}
+
+#[cfg(feature = "score-reporting")]
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// somewhere to send runs. the future is driven on the io task pool and only
+/// ever checked for how it went - on wasm it doesn't need to be Send, so a
+/// browser fetch fits behind the same trait
+pub trait ScoreReporter: Send + Sync + 'static {
+    fn submit(&self, result: &RunResult) -> BoxedFuture<'static, Result<(), String>>;
+}
+
+/// the default - every submission succeeds straight away without going
+/// anywhere
+#[derive(Debug, Default)]
+pub struct NoopReporter;
+
+impl ScoreReporter for NoopReporter {
+    fn submit(&self, _result: &RunResult) -> BoxedFuture<'static, Result<(), String>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// whichever reporter is in use. it's rebuilt from the settings whenever they
+/// change - something that wants to send runs somewhere else entirely can
+/// replace it after that
+#[derive(Resource, Clone)]
+pub struct ActiveScoreReporter(pub Arc<dyn ScoreReporter>);
+
+// anything still retrying picks up the new reporter too, so fixing the endpoint
+// rescues the runs that failed against the old one
+fn choose_reporter(mut commands: Commands, settings: Res<ScoreReportingSettings>) {
+    commands.insert_resource(ActiveScoreReporter(reporter_for(&settings)));
+}
+
+fn reporter_for(settings: &ScoreReportingSettings) -> Arc<dyn ScoreReporter> {
+    if !settings.enabled {
+        return Arc::new(NoopReporter);
+    }
+    if settings.endpoint.is_empty() {
+        warn!("score reporting is enabled but there's no endpoint to report to");
+        return Arc::new(NoopReporter);
+    }
+
+    #[cfg(feature = "score-reporting")]
+    {
+        Arc::new(http::HttpReporter::new(settings.endpoint.clone()))
+    }
+    #[cfg(not(feature = "score-reporting"))]
+    {
+        warn!("score reporting is enabled but this build doesn't have the score-reporting feature");
+        Arc::new(NoopReporter)
+    }
+}
+
+struct Submission {
+    result:  RunResult,
+    attempt: u32,
+}
+
+#[derive(Resource, Default)]
+struct PendingReports {
+    in_flight: Vec<(Submission, Task<Result<(), String>>)>,
+    waiting:   Vec<(Submission, Timer)>,
+}
+
+impl PendingReports {
+    fn send(&mut self, reporter: &dyn ScoreReporter, submission: Submission) {
+        let task = IoTaskPool::get().spawn(reporter.submit(&submission.result));
+        self.in_flight.push((submission, task));
+    }
+}
+
+// score isn't reset until we leave GameOver
+fn report_run(
+    score: Res<Score>,
+    mutators: Res<Mutators>,
+    reporter: Option<Res<ActiveScoreReporter>>,
+    mut pending: ResMut<PendingReports>,
+) {
+    let Some(reporter) = reporter else {
+        return;
+    };
+
+    let result = RunResult {
+        score:           score.points,
+        wave:            None,
+        difficulty:      None,
+        mutators:        mutators.active.iter().map(|mutator| mutator.label()).collect(),
+        replay_checksum: None,
+    };
+
+    pending.send(reporter.0.as_ref(), Submission { result, attempt: 1 });
+}
+
+// runs while paused and between games too - real time, not game time
+fn drive_reports(
+    time: Res<Time<Real>>,
+    reporter: Option<Res<ActiveScoreReporter>>,
+    mut pending: ResMut<PendingReports>,
+) {
+    let Some(reporter) = reporter else {
+        return;
+    };
+    if pending.in_flight.is_empty() && pending.waiting.is_empty() {
+        return;
+    }
+
+    let mut still_in_flight = Vec::new();
+    for (submission, mut task) in std::mem::take(&mut pending.in_flight) {
+        match block_on(poll_once(&mut task)) {
+            None => still_in_flight.push((submission, task)),
+            Some(Ok(())) => (),
+            Some(Err(e)) if submission.attempt >= MAX_ATTEMPTS => {
+                warn!(
+                    "giving up on reporting a score of {} after {} attempts: {e}",
+                    submission.result.score, submission.attempt
+                );
+            },
+            Some(Err(e)) => {
+                let wait = FIRST_RETRY_SECONDS * 2f32.powi(submission.attempt as i32 - 1);
+                warn!(
+                    "unable to report a score of {}, trying again in {wait}s: {e}",
+                    submission.result.score
+                );
+                pending
+                    .waiting
+                    .push((submission, Timer::from_seconds(wait, TimerMode::Once)));
+            },
+        }
+    }
+    pending.in_flight = still_in_flight;
+
+    let mut still_waiting = Vec::new();
+    for (submission, mut timer) in std::mem::take(&mut pending.waiting) {
+        if timer.tick(time.delta()).finished() {
+            pending.send(
+                reporter.0.as_ref(),
+                Submission {
+                    attempt: submission.attempt + 1,
+                    ..submission
+                },
+            );
+        } else {
+            still_waiting.push((submission, timer));
+        }
+    }
+    pending.waiting = still_waiting;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn score_reporting_settings_round_trips() {
+        assert_settings_round_trip(nudged::<ScoreReportingSettings>());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    actor::ActorKind,
+    despawn::ActorDestroyed,
+    mutators::{
+        MutatorSet,
+        Mutators,
+    },
+    schedule::InGameSet,
+    state::GameState,
+    time_controls::GameTime,
+    ui::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+    },
+};
+use bevy::prelude::*;
+
+// small nateroids are harder to hit so they're worth more - this is what a
+// default sized one is worth
+const NATEROID_POINTS: f32 = 100.;
+const REFERENCE_NATEROID_SIZE: f32 = 10.;
+
+// each kill inside the window bumps the multiplier and refills the window
+const COMBO_WINDOW_SECONDS: f32 = 3.;
+const MAX_COMBO_MULTIPLIER: u32 = 8;
+
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .init_resource::<Combo>()
+            .add_event::<ComboEvent>()
+            .add_systems(Update, decay_combo.in_set(InGameSet::EntityUpdates))
+            .add_systems(Update, score_destroyed_actors.in_set(InGameSet::DespawnEntities))
+            .add_systems(OnExit(GameState::GameOver), reset_score);
+    }
+}
+
+// best is the high score for this session - it survives a reset. it keeps the
+// mutators it was made with so a modified run can be told apart
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub points:        u32,
+    pub best:          u32,
+    pub best_mutators: MutatorSet,
+    beat_best:         bool,
+}
+
+// remaining counts down to zero, at which point the multiplier drops back to 1
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Combo {
+    pub multiplier: u32,
+    pub remaining:  f32,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self {
+            multiplier: 1,
+            remaining:  0.,
+        }
+    }
+}
+
+impl Combo {
+    /// how much of the window is left, 1.0 right after a kill
+    pub fn fraction(&self) -> f32 { (self.remaining / COMBO_WINDOW_SECONDS).clamp(0.0, 1.0) }
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComboEvent {
+    Increased(u32),
+    Broken,
+}
+
+fn decay_combo(time: Res<GameTime>, mut combo: ResMut<Combo>, mut combo_events: EventWriter<ComboEvent>) {
+    if combo.remaining <= 0. {
+        return;
+    }
+
+    combo.remaining -= time.delta_secs();
+    if combo.remaining <= 0. {
+        if combo.multiplier > 1 {
+            combo_events.send(ComboEvent::Broken);
+        }
+        *combo = Combo::default();
+    }
+}
+
+fn score_destroyed_actors(
+    mut destroyed: EventReader<ActorDestroyed>,
+    mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut combo_events: EventWriter<ComboEvent>,
+    mut announcements: EventWriter<Announcement>,
+    mutators: Res<Mutators>,
+) {
+    for event in destroyed.read() {
+        if event.kind == ActorKind::Nateroid {
+            if combo.remaining > 0. && combo.multiplier < MAX_COMBO_MULTIPLIER {
+                combo.multiplier += 1;
+                combo_events.send(ComboEvent::Increased(combo.multiplier));
+            }
+            combo.remaining = COMBO_WINDOW_SECONDS;
+
+            let size = (event.size / REFERENCE_NATEROID_SIZE).clamp(0.25, 4.0);
+            score.points += (NATEROID_POINTS / size).round() as u32 * combo.multiplier;
+        }
+    }
+
+    if score.points > score.best {
+        // only worth shouting about once per game, and not on the very first
+        if !score.beat_best && score.best > 0 {
+            let text = if mutators.active.is_empty() {
+                "new high score!"
+            } else {
+                "new high score! (mutated)"
+            };
+            announcements.send(Announcement::new(
+                text,
+                AnnouncementStyle::Success,
+                AnnouncementPriority::Normal,
+            ));
+        }
+        score.best = score.points;
+        score.best_mutators = mutators.active;
+        score.beat_best = true;
+    }
+}
+
+pub fn reset_score(mut score: ResMut<Score>, mut combo: ResMut<Combo>) {
+    *combo = Combo::default();
+    *score = Score {
+        best: score.best,
+        best_mutators: score.best_mutators,
+        ..default()
+    };
+}
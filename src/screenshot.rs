@@ -0,0 +1,166 @@
+use crate::{
+    config::SettingsAppExt,
+    global_input::GlobalAction,
+    schedule::FrameSet,
+    ui::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{
+        save_to_disk,
+        Screenshot,
+        ScreenshotCaptured,
+    },
+};
+use leafwing_input_manager::prelude::*;
+use std::path::PathBuf;
+
+/// saves what's on screen as a png - into screenshots/ natively, as a
+/// download in the browser. bevy copies the frame once it's finished
+/// rendering, so the boundary lines and the rest of the gizmos are in it
+///
+/// with burst_frames above 1 a press keeps going for that many frames in a
+/// row, numbered, for stitching into a gif
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings::<ScreenshotConfig>("screenshots")
+            .init_resource::<Burst>()
+            .add_systems(
+                Update,
+                (start_burst, take_screenshots).chain().in_set(FrameSet::Input),
+            );
+    }
+}
+
+#[derive(Resource, Reflect, Debug, PartialEq, Clone)]
+#[reflect(Resource)]
+pub struct ScreenshotConfig {
+    pub directory:    String,
+    pub burst_frames: u32,
+}
+
+impl Default for ScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            directory:    "screenshots".to_string(),
+            burst_frames: 1,
+        }
+    }
+}
+
+// the burst that's going - every frame of it shares the press's timestamp
+#[derive(Resource, Debug, Default)]
+struct Burst {
+    stamp: u128,
+    taken: u32,
+    total: u32,
+}
+
+// milliseconds, so two presses in the same second don't overwrite each other
+fn timestamp() -> u128 {
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|since| since.as_millis())
+        .unwrap_or_default();
+
+    #[cfg(target_arch = "wasm32")]
+    return web_sys::js_sys::Date::now() as u128;
+}
+
+fn start_burst(
+    user_input: Res<ActionState<GlobalAction>>,
+    config: Res<ScreenshotConfig>,
+    mut burst: ResMut<Burst>,
+) {
+    // one at a time - a press in the middle of a burst is ignored
+    if !user_input.just_pressed(&GlobalAction::Screenshot) || burst.taken < burst.total {
+        return;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Err(e) = std::fs::create_dir_all(&config.directory) {
+        warn!(
+            "unable to take a screenshot - can't make {}: {e}",
+            config.directory
+        );
+        return;
+    }
+
+    *burst = Burst {
+        stamp: timestamp(),
+        taken: 0,
+        total: config.burst_frames.max(1),
+    };
+}
+
+fn take_screenshots(mut commands: Commands, config: Res<ScreenshotConfig>, mut burst: ResMut<Burst>) {
+    if burst.taken >= burst.total {
+        return;
+    }
+
+    let name = if burst.total == 1 {
+        format!("screenshot-{}.png", burst.stamp)
+    } else {
+        format!("screenshot-{}-{:03}.png", burst.stamp, burst.taken)
+    };
+    let path = PathBuf::from(&config.directory).join(name);
+    // only the last of a burst says anything
+    let last = burst.taken + 1 == burst.total;
+    burst.taken += 1;
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_screenshot(path, last));
+}
+
+// bevy's save_to_disk writes the file (or starts the download) and logs if
+// it can't - it doesn't say how it went, so natively the file is checked for
+// afterwards
+fn save_screenshot(
+    path: PathBuf,
+    announce: bool,
+) -> impl FnMut(Trigger<ScreenshotCaptured>, EventWriter<Announcement>) {
+    let mut save = save_to_disk(path.clone());
+    move |trigger: Trigger<ScreenshotCaptured>, mut announcements: EventWriter<Announcement>| {
+        save(trigger);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let saved = path.exists();
+        #[cfg(target_arch = "wasm32")]
+        let saved = true;
+
+        if !saved {
+            warn!("unable to save a screenshot to {}", path.display());
+            announcements.send(Announcement::new(
+                "couldn't save the screenshot",
+                AnnouncementStyle::Warning,
+                AnnouncementPriority::Low,
+            ));
+        } else if announce {
+            announcements.send(Announcement::new(
+                format!("saved {}", path.display()),
+                AnnouncementStyle::Success,
+                AnnouncementPriority::Low,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn screenshot_config_round_trips() { assert_settings_round_trip(nudged::<ScreenshotConfig>()); }
+}
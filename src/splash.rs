@@ -1,5 +1,6 @@
 use crate::{
     camera::RenderLayer,
+    schedule::FrameSet,
     state::GameState,
 };
 use bevy::{
@@ -7,9 +8,18 @@ use bevy::{
     render::view::RenderLayers,
 };
 
+/// the title, growing in the middle of the screen. splash comes after
+/// loading, so the models are always there by the time it can be skipped -
+/// space, enter, a click or any gamepad button moves on once it's been up
+/// long enough not to just flash past
 pub(crate) struct SplashPlugin;
 
 const SPLASH_TIME: f32 = 2.;
+// how long before it can be skipped
+const SPLASH_MIN_TIME: f32 = 0.5;
+// the title grows to this over SPLASH_TIME, fast at first and settling in
+const SPLASH_FONT_SIZE: f32 = 140.;
+const SPLASH_START_FONT_SIZE: f32 = 1.;
 
 #[derive(Component)]
 pub(crate) struct SplashText;
@@ -25,16 +35,22 @@ impl Plugin for SplashPlugin {
             timer: Timer::from_seconds(SPLASH_TIME, TimerMode::Once),
         })
         .add_systems(OnEnter(GameState::Splash), splash_screen)
-        .add_systems(Update, run_splash.run_if(in_state(GameState::Splash)));
+        .add_systems(
+            Update,
+            run_splash
+                .in_set(FrameSet::Ui)
+                .run_if(in_state(GameState::Splash)),
+        );
     }
 }
 
-fn splash_screen(mut commands: Commands) {
+fn splash_screen(mut commands: Commands, mut splash_timer: ResMut<SplashTimer>) {
+    splash_timer.timer.reset();
     commands.spawn((
         SplashText,
         Text::new("nateroids"),
         TextFont {
-            font_size: 1.0,
+            font_size: SPLASH_START_FONT_SIZE,
             ..default()
         },
         Node {
@@ -43,24 +59,36 @@ fn splash_screen(mut commands: Commands) {
             position_type: PositionType::Absolute,
             ..default()
         },
-        RenderLayers::from_layers(RenderLayer::Game.layers()),
+        RenderLayers::from_layers(RenderLayer::Ui.layers()),
     ));
 }
 
+// sized from how far through the splash it is rather than added to each
+// frame, so it grows the same at any frame rate and stops where it should
 fn run_splash(
     mut next_state: ResMut<NextState<GameState>>,
-    mut spawn_timer: ResMut<SplashTimer>,
+    mut splash_timer: ResMut<SplashTimer>,
     time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    q_gamepads: Query<&Gamepad>,
     mut q_text: Query<&mut TextFont, With<SplashText>>,
 ) {
-    spawn_timer.timer.tick(time.delta());
+    splash_timer.timer.tick(time.delta());
+
     if let Ok(mut text) = q_text.get_single_mut() {
-        text.font_size += 1.2;
+        text.font_size = EasingCurve::new(SPLASH_START_FONT_SIZE, SPLASH_FONT_SIZE, EaseFunction::CubicOut)
+            .sample_clamped(splash_timer.timer.fraction());
     }
-    if spawn_timer.timer.just_finished() {
-        next_state.set(GameState::InGame {
-            paused:     false,
-            inspecting: false,
-        });
+
+    let skipped = keys.any_just_pressed([KeyCode::Space, KeyCode::Enter])
+        || mouse.get_just_pressed().next().is_some()
+        || q_gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+    let can_skip = splash_timer.timer.elapsed_secs() >= SPLASH_MIN_TIME;
+
+    if splash_timer.timer.finished() || (skipped && can_skip) {
+        next_state.set(GameState::MainMenu);
     }
 }
@@ -1,4 +1,8 @@
-use crate::global_input::GlobalAction;
+use crate::{
+    audio::UiSoundEvent,
+    global_input::GlobalAction,
+    schedule::FrameSet,
+};
 use bevy::{
     dev_tools::states::*,
     prelude::*,
@@ -11,26 +15,32 @@ pub struct StatePlugin;
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
+            .init_state::<InputContext>()
             .add_computed_state::<PlayingGame>()
             .add_computed_state::<IsPaused>()
             .add_computed_state::<IsInspecting>()
             .add_systems(
                 Update,
-                (
-                    toggle_pause.run_if(in_state(PlayingGame)),
-                    transition_to_in_game.run_if(in_state(GameState::GameOver)),
-                ),
+                toggle_pause.run_if(in_state(PlayingGame)).in_set(FrameSet::Input),
             )
             .add_systems(OnEnter(IsPaused::Paused), pause_rapier)
             .add_systems(OnEnter(IsPaused::NotPaused), unpause_rapier)
-            .add_systems(Update, log_transitions::<GameState>);
+            .add_systems(Update, log_transitions::<GameState>.in_set(FrameSet::Input));
     }
 
     fn name(&self) -> &str { "state plugin" }
 }
 
-// splash is the default so bevy will automatically enter this state
-// we catch that in splash.rs to do the splash screen
+// loading is the default so bevy will automatically enter this state - it
+// waits for the models and moves on to splash, or to load failed if any of
+// them can't be loaded, see asset_loader.rs. we catch splash in splash.rs to
+// do the splash screen, and after it comes the main menu, see
+// ui/main_menu.rs
+//
+// a run from the main menu starts on the run setup screen where the mutators
+// are picked, see ui/run_setup.rs. game over waits for the player and then
+// goes straight back in with the same mutators, see PLAY_AGAIN and
+// ui/game_over.rs
 //
 // in state/computed_states bevy example, they have a tutorial state that is
 // active/inactive that is computed and shows tutorial text while in various
@@ -38,7 +48,11 @@ impl Plugin for StatePlugin {
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Reflect, States)]
 pub enum GameState {
     #[default]
+    Loading,
+    LoadFailed,
     Splash,
+    MainMenu,
+    RunSetup,
     InGame {
         paused:     bool,
         inspecting: bool,
@@ -46,6 +60,46 @@ pub enum GameState {
     GameOver,
 }
 
+/// play again from the game over screen - straight back into the game with
+/// the run's mutators, without the run setup screen. what leaving RunSetup
+/// does for a new run hangs off this too: the lives, the spaceship, the
+/// teleport stats and the camera shake
+pub const PLAY_AGAIN: OnTransition<GameState> = OnTransition {
+    exited:  GameState::GameOver,
+    entered: GameState::InGame {
+        paused:     false,
+        inspecting: false,
+    },
+};
+
+// paused and inspecting are separate switches - either one can be flipped
+// without touching the other, so the game can be paused with an inspector
+// under the pointer and it stays paused when the pointer leaves
+impl GameState {
+    /// the game paused or unpaused with inspecting left as it was - none if
+    /// it's that way already, or if it isn't the game
+    pub fn with_paused(self, paused: bool) -> Option<Self> {
+        match self {
+            GameState::InGame {
+                paused: was,
+                inspecting,
+            } if was != paused => Some(GameState::InGame { paused, inspecting }),
+            _ => None,
+        }
+    }
+
+    /// the same for inspecting - paused is left as it was
+    pub fn with_inspecting(self, inspecting: bool) -> Option<Self> {
+        match self {
+            GameState::InGame {
+                paused,
+                inspecting: was,
+            } if was != inspecting => Some(GameState::InGame { paused, inspecting }),
+            _ => None,
+        }
+    }
+}
+
 // as PlayingGame is a computed state that covers paused - we wanted it to have
 // a different name than InGame.  Playing is "true" whether we are paused or not
 // in the future, as in the bevy computed_states example - we might add other
@@ -117,41 +171,60 @@ impl ComputedStates for IsInspecting {
     }
 }
 
+/// in game and paused - works as a run condition too. outside the game it's
+/// neither paused nor running
+pub fn is_paused(paused: Option<Res<State<IsPaused>>>) -> bool {
+    matches!(paused.as_deref().map(State::get), Some(IsPaused::Paused))
+}
+
+/// in game and not paused
+pub fn is_running(paused: Option<Res<State<IsPaused>>>) -> bool {
+    matches!(paused.as_deref().map(State::get), Some(IsPaused::NotPaused))
+}
+
+/// who the keyboard and mouse are for right now - the ship, an inspector
+/// window or a menu. it's worked out from what's open rather than from
+/// GameState, see InputPlugin. only Gameplay flies the ship - GlobalAction
+/// works in all of them
+///
+/// not to be confused with IsInspecting, which is only whether the pointer is
+/// over egui at the moment
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Reflect, States)]
+pub enum InputContext {
+    #[default]
+    Gameplay,
+    Inspecting,
+    Menu,
+}
+
+// only ever pauses - once paused, escape is the pause menu's back, see
+// ui/pause_menu.rs
 fn toggle_pause(
     user_input: Res<ActionState<GlobalAction>>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut ui_sounds: EventWriter<UiSoundEvent>,
     state: Res<State<GameState>>,
 ) {
     if user_input.just_pressed(&GlobalAction::Pause) {
-        if let GameState::InGame { paused, inspecting } = state.get() {
-            next_state.set(GameState::InGame {
-                paused:     !*paused,
-                inspecting: *inspecting,
-            });
+        if let Some(paused) = state.get().with_paused(true) {
+            next_state.set(paused);
+            ui_sounds.send(UiSoundEvent::PauseOpened);
         }
     }
 }
 
-fn transition_to_in_game(mut next_state: ResMut<NextState<GameState>>) {
-    println!("Transitioning to InGame");
-    next_state.set(GameState::InGame {
-        paused:     false,
-        inspecting: false,
-    });
-}
-
-fn pause_rapier(mut rapier_config_query: Query<&mut RapierConfiguration>) {
+pub(crate) fn pause_rapier(mut rapier_config_query: Query<&mut RapierConfiguration>) {
     if let Ok(mut rapier_config) = rapier_config_query.get_single_mut() {
-        println!("pausing game and physics");
+        debug!("pausing game and physics");
         rapier_config.physics_pipeline_active = false;
     } else {
         error!("Error: Unable to find the RapierConfiguration component.");
     }
 }
 
-fn unpause_rapier(mut rapier_config_query: Query<&mut RapierConfiguration>) {
+pub(crate) fn unpause_rapier(mut rapier_config_query: Query<&mut RapierConfiguration>) {
     if let Ok(mut rapier_config) = rapier_config_query.get_single_mut() {
-        println!("unpausing game and physics");
+        debug!("unpausing game and physics");
         rapier_config.physics_pipeline_active = true;
     } else {
         error!("Error: Unable to find the RapierConfiguration component.");
@@ -0,0 +1,86 @@
+use std::fmt::Debug;
+
+/// somewhere to keep small bits of text between runs - a file in the user's
+/// config directory natively, localStorage in the browser where there's no
+/// file system to write to
+pub trait Storage: Debug + Send + Sync + 'static {
+    fn load(&self, key: &str) -> Option<String>;
+
+    fn save(&self, key: &str, contents: &str) -> Result<(), String>;
+}
+
+/// the right Storage for whatever we were built for
+pub fn platform_storage() -> Box<dyn Storage> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let storage = FileStorage::default();
+    #[cfg(target_arch = "wasm32")]
+    let storage = LocalStorage;
+
+    Box::new(storage)
+}
+
+/// each key is a file in the nateroids config directory
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct FileStorage {
+    // not every platform has a config dir - nothing is kept if there isn't one
+    dir: Option<std::path::PathBuf>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for FileStorage {
+    fn default() -> Self {
+        Self {
+            dir: dirs::config_dir().map(|dir| dir.join("nateroids")),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn load(&self, key: &str) -> Option<String> { std::fs::read_to_string(self.dir.as_ref()?.join(key)).ok() }
+
+    fn save(&self, key: &str, contents: &str) -> Result<(), String> {
+        let Some(dir) = self.dir.as_ref() else {
+            return Ok(());
+        };
+
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(dir.join(key), contents))
+            .map_err(|e| format!("{:?}: {}", dir.join(key), e))
+    }
+}
+
+/// each key is a localStorage item, prefixed so we don't trip over anything
+/// else served from the same origin
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    const PREFIX: &'static str = "nateroids/";
+
+    // private browsing and some embedded browsers don't hand one out
+    fn storage() -> Option<web_sys::Storage> { web_sys::window()?.local_storage().ok().flatten() }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        Self::storage()?
+            .get_item(&format!("{}{key}", Self::PREFIX))
+            .ok()
+            .flatten()
+    }
+
+    fn save(&self, key: &str, contents: &str) -> Result<(), String> {
+        let Some(storage) = Self::storage() else {
+            return Ok(());
+        };
+
+        storage
+            .set_item(&format!("{}{key}", Self::PREFIX), contents)
+            .map_err(|e| format!("localStorage {key}: {e:?}"))
+    }
+}
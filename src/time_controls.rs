@@ -0,0 +1,345 @@
+use crate::{
+    global_input::GlobalAction,
+    schedule::FrameSet,
+    state::{
+        is_paused,
+        is_running,
+        GameState,
+        IsPaused,
+    },
+};
+use bevy::prelude::*;
+#[cfg(dev_tools)]
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use bevy_rapier3d::plugin::RapierConfiguration;
+use leafwing_input_manager::prelude::ActionState;
+use std::time::Duration;
+
+/// debug controls for the simulation clock - step one fixed tick at a time
+/// while paused and run the game slower or faster. there's nothing new to
+/// keep in sync: pausing is the pause menu's GameState, and the speed is
+/// Time<Virtual>, the same as the console's timescale
+///
+/// a step runs exactly one fixed tick of everything the pause stops - input,
+/// physics, movement, wrapping - and then lets the frame it landed on run its
+/// own in game systems once, so collisions and portals see it too
+///
+/// GameTime is the clock for anything in Update that times the game itself -
+/// it stops with the simulation, so a portal half way through fading is still
+/// half way through when the game's unpaused
+pub struct TimeControlsPlugin;
+
+impl Plugin for TimeControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationStep>()
+            .init_resource::<SimulationTick>()
+            .init_resource::<GameTime>()
+            .add_systems(Update, time_control_keys.in_set(FrameSet::Input))
+            .add_systems(FixedFirst, begin_step)
+            .add_systems(FixedLast, finish_tick)
+            .add_systems(Last, clear_stepped_frame)
+            .add_systems(
+                RunFixedMainLoop,
+                advance_game_time.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+            );
+
+        // the keys are dev tool only, so release builds never show it
+        #[cfg(dev_tools)]
+        app.add_systems(
+            Update,
+            draw_time_overlay
+                .in_set(FrameSet::Ui)
+                .run_if(time_controls_in_use),
+        );
+    }
+}
+
+/// how many fixed ticks the simulation has actually run - paused ticks don't
+/// count
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct SimulationTick(pub u64);
+
+/// Time<Virtual> while the simulation runs, stopped while it's paused, and
+/// one fixed tick on the frame a step lands on. FixedUpdate doesn't need it -
+/// its Time only moves on when the simulation ticks anyway
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct GameTime {
+    delta:   Duration,
+    elapsed: Duration,
+}
+
+impl GameTime {
+    pub fn delta_secs(&self) -> f32 { self.delta.as_secs_f32() }
+
+    pub fn elapsed_secs(&self) -> f32 { self.elapsed.as_secs_f32() }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct SimulationStep {
+    requested:          bool,
+    stepping:           bool,
+    stepped_this_frame: bool,
+}
+
+/// run condition for the simulation's FixedUpdate sets - not paused, or
+/// paused and in the middle of a step
+pub fn simulation_ticking(paused: Option<Res<State<IsPaused>>>, step: Res<SimulationStep>) -> bool {
+    is_running(paused) || step.stepping
+}
+
+/// run condition for the in game Update sets - the frame a step landed on
+/// runs them as if the game weren't paused
+pub fn simulation_frame(paused: Option<Res<State<IsPaused>>>, step: Res<SimulationStep>) -> bool {
+    is_running(paused) || step.stepped_this_frame
+}
+
+#[cfg(dev_tools)]
+fn time_controls_in_use(paused: Option<Res<State<IsPaused>>>, time: Res<Time<Virtual>>) -> bool {
+    is_paused(paused) || time.relative_speed() != 1.
+}
+
+// stepping while the game is running pauses it first - the next press steps
+fn time_control_keys(
+    user_input: Res<ActionState<GlobalAction>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut step: ResMut<SimulationStep>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if user_input.just_pressed(&GlobalAction::StepSimulation) {
+        if let Some(paused) = state.get().with_paused(true) {
+            next_state.set(paused);
+        } else if matches!(state.get(), GameState::InGame { paused: true, .. }) {
+            step.requested = true;
+        }
+    }
+
+    let scale = [
+        (GlobalAction::TimeScaleTenth, 0.1),
+        (GlobalAction::TimeScaleHalf, 0.5),
+        (GlobalAction::TimeScaleNormal, 1.),
+        (GlobalAction::TimeScaleDouble, 2.),
+    ]
+    .into_iter()
+    .find(|(action, _)| user_input.just_pressed(action))
+    .map(|(_, scale)| scale);
+
+    if let Some(scale) = scale {
+        time.set_relative_speed(scale);
+    }
+}
+
+// rapier is switched off while paused (see state.rs) - it gets switched back on
+// for just the one tick
+fn begin_step(
+    paused: Option<Res<State<IsPaused>>>,
+    mut step: ResMut<SimulationStep>,
+    mut q_rapier: Query<&mut RapierConfiguration>,
+) {
+    if !step.requested {
+        return;
+    }
+    step.requested = false;
+
+    // unpaused since it was asked for - nothing to step
+    if !is_paused(paused) {
+        return;
+    }
+
+    step.stepping = true;
+    for mut rapier_config in q_rapier.iter_mut() {
+        rapier_config.physics_pipeline_active = true;
+    }
+}
+
+fn finish_tick(
+    paused: Option<Res<State<IsPaused>>>,
+    mut step: ResMut<SimulationStep>,
+    mut tick: ResMut<SimulationTick>,
+    mut q_rapier: Query<&mut RapierConfiguration>,
+) {
+    if is_running(paused) || step.stepping {
+        tick.0 += 1;
+    }
+
+    if step.stepping {
+        step.stepping = false;
+        step.stepped_this_frame = true;
+        for mut rapier_config in q_rapier.iter_mut() {
+            rapier_config.physics_pipeline_active = false;
+        }
+    }
+}
+
+// after the fixed loop, so a step that just ran is counted this frame
+fn advance_game_time(
+    paused: Option<Res<State<IsPaused>>>,
+    step: Res<SimulationStep>,
+    virtual_time: Res<Time<Virtual>>,
+    fixed_time: Res<Time<Fixed>>,
+    mut game_time: ResMut<GameTime>,
+) {
+    game_time.delta = if !is_paused(paused) {
+        virtual_time.delta()
+    } else if step.stepped_this_frame {
+        fixed_time.timestep()
+    } else {
+        Duration::ZERO
+    };
+    let delta = game_time.delta;
+    game_time.elapsed += delta;
+}
+
+fn clear_stepped_frame(mut step: ResMut<SimulationStep>) { step.stepped_this_frame = false; }
+
+#[cfg(dev_tools)]
+fn draw_time_overlay(
+    mut contexts: Query<&mut EguiContext>,
+    paused: Option<Res<State<IsPaused>>>,
+    tick: Res<SimulationTick>,
+    time: Res<Time<Virtual>>,
+) {
+    let Ok(mut context) = contexts.get_single_mut() else {
+        return;
+    };
+
+    let mut text = format!("tick {}  {}x", tick.0, time.relative_speed());
+    if is_paused(paused) {
+        text.push_str("  paused");
+    }
+
+    egui::Area::new(egui::Id::new("time controls"))
+        .anchor(egui::Align2::CENTER_TOP, [0., 8.])
+        .interactable(false)
+        .show(context.get_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new(text).monospace());
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actor::{
+            teleport_at_boundary,
+            Aabb,
+            EntityLeftBoundary,
+            EntityTeleported,
+            TeleportStats,
+            Teleporter,
+        },
+        despawn::DestructionQueue,
+        mutators::Mutators,
+        playfield::Boundary,
+        schedule::{
+            InGameSet,
+            SchedulePlugin,
+        },
+        state::{
+            pause_rapier,
+            unpause_rapier,
+            PlayingGame,
+        },
+    };
+    use bevy::{
+        state::app::StatesPlugin,
+        time::TimeUpdateStrategy,
+    };
+    use bevy_rapier3d::dynamics::Velocity;
+
+    fn set_paused(app: &mut App, paused: bool) {
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::InGame {
+                paused,
+                inspecting: false,
+            });
+    }
+
+    // while paused nothing in the simulation moves - not even something sitting
+    // outside the boundary waiting to be wrapped - and neither does the clock
+    #[test]
+    fn a_paused_frame_changes_nothing() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, SchedulePlugin, TimeControlsPlugin))
+            // a little over one tick a frame, so every update after the first
+            // would run the simulation
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)))
+            .insert_state(GameState::InGame {
+                paused:     false,
+                inspecting: false,
+            })
+            .add_computed_state::<PlayingGame>()
+            .add_computed_state::<IsPaused>()
+            .add_event::<EntityTeleported>()
+            .add_event::<EntityLeftBoundary>()
+            .init_resource::<ActionState<GlobalAction>>()
+            .init_resource::<Boundary>()
+            .init_resource::<Mutators>()
+            .init_resource::<DestructionQueue>()
+            .init_resource::<TeleportStats>()
+            // where their plugins put them
+            .add_systems(FixedUpdate, teleport_at_boundary.in_set(InGameSet::EntityUpdates))
+            .add_systems(OnEnter(IsPaused::Paused), pause_rapier)
+            .add_systems(OnEnter(IsPaused::NotPaused), unpause_rapier);
+
+        let rapier = app.world_mut().spawn(RapierConfiguration::new(1.)).id();
+        let half_width = app.world().resource::<Boundary>().transform.scale.x / 2.;
+        let outside = Vec3::new(half_width + 1., 0., 0.);
+        let actor = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0., 0., 0.),
+                Velocity::linear(Vec3::X * 60.),
+                Aabb {
+                    min: Vec3::splat(-1.),
+                    max: Vec3::splat(1.),
+                },
+                Teleporter::default(),
+            ))
+            .id();
+
+        app.update();
+        set_paused(&mut app, true);
+        app.update();
+        assert!(
+            !app.world()
+                .get::<RapierConfiguration>(rapier)
+                .unwrap()
+                .physics_pipeline_active
+        );
+
+        // physics had carried it out through the face just before the pause
+        app.world_mut().get_mut::<Transform>(actor).unwrap().translation = outside;
+        let tick = app.world().resource::<SimulationTick>().0;
+        let elapsed = app.world().resource::<GameTime>().elapsed_secs();
+
+        for _ in 0..3 {
+            app.update();
+            assert_eq!(
+                *app.world().get::<Transform>(actor).unwrap(),
+                Transform::from_translation(outside)
+            );
+        }
+        assert_eq!(app.world().resource::<SimulationTick>().0, tick);
+        assert_eq!(app.world().resource::<GameTime>().elapsed_secs(), elapsed);
+
+        // and it all carries on from where it stopped
+        set_paused(&mut app, false);
+        app.update();
+        assert!(
+            app.world()
+                .get::<RapierConfiguration>(rapier)
+                .unwrap()
+                .physics_pipeline_active
+        );
+        assert!(app.world().get::<Transform>(actor).unwrap().translation.x < 0.);
+        assert!(app.world().resource::<SimulationTick>().0 > tick);
+        assert!(app.world().resource::<GameTime>().elapsed_secs() > elapsed);
+    }
+}
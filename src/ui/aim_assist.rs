@@ -0,0 +1,181 @@
+use crate::{
+    actor::{
+        Aabb,
+        ActorKind,
+        Spaceship,
+        SteeringMode,
+    },
+    camera::{
+        PrimaryCamera,
+        RenderLayer,
+    },
+    playfield::Boundary,
+    schedule::FrameSet,
+    state::{
+        is_running,
+        IsPaused,
+    },
+    ui::{
+        SettingsMenu,
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+    window::PrimaryWindow,
+};
+use bevy_rapier3d::prelude::{
+    QueryFilter,
+    ReadDefaultRapierContext,
+};
+
+const CROSSHAIR_SIZE: f32 = 20.;
+const AIM_LINE_ALPHA: f32 = 0.35;
+
+pub struct AimAssistPlugin;
+
+impl Plugin for AimAssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<AimLineGizmo>()
+            .add_systems(Startup, spawn_crosshair)
+            .add_systems(
+                Update,
+                (
+                    update_crosshair,
+                    draw_aim_line
+                        .run_if(mouse_aim_active)
+                        .run_if(in_state(IsPaused::NotPaused)),
+                )
+                    .in_set(FrameSet::Ui),
+            );
+    }
+}
+
+#[derive(Component, Debug)]
+struct Crosshair;
+
+// its own group so it stays on the game layer - the default group is drawn on
+// the physics debug layer
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct AimLineGizmo {}
+
+fn mouse_aim_active(steering: Res<SteeringMode>, menu: Res<SettingsMenu>) -> bool {
+    *steering == SteeringMode::MouseAim && !menu.is_open()
+}
+
+// a ring with a dot in the middle
+fn spawn_crosshair(mut commands: Commands, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(CROSSHAIR_SIZE),
+                height: Val::Px(CROSSHAIR_SIZE),
+                border: UiRect::all(Val::Px(2.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor(theme.accent),
+            BorderRadius::MAX,
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            Crosshair,
+            Name::new("Crosshair"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Px(2.),
+                    height: Val::Px(2.),
+                    ..default()
+                },
+                BackgroundColor(theme.accent),
+            ));
+        });
+}
+
+// the crosshair sits where the cursor ray meets the playfield plane - which is
+// where the cursor is on screen, as long as it's pointing at the playfield at
+// all
+#[allow(clippy::too_many_arguments)]
+fn update_crosshair(
+    steering: Res<SteeringMode>,
+    boundary: Res<Boundary>,
+    menu: Res<SettingsMenu>,
+    paused: Option<Res<State<IsPaused>>>,
+    ui_scale: Res<UiScale>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    mut q_crosshair: Query<(&mut Node, &mut Visibility), With<Crosshair>>,
+) {
+    let Ok((mut node, mut visibility)) = q_crosshair.get_single_mut() else {
+        return;
+    };
+
+    let playing = is_running(paused);
+    let cursor = q_window.get_single().ok().and_then(Window::cursor_position);
+
+    let on_playfield = match (cursor, q_camera.get_single()) {
+        (Some(cursor), Ok((camera, camera_transform))) => camera
+            .viewport_to_world(camera_transform, cursor)
+            .ok()
+            .and_then(|ray| boundary.play_plane_point(ray))
+            .map(|_| cursor),
+        _ => None,
+    };
+
+    let shown = match on_playfield {
+        Some(cursor) if playing && *steering == SteeringMode::MouseAim && !menu.is_open() => {
+            node.left = Val::Px(cursor.x / ui_scale.0 - CROSSHAIR_SIZE / 2.);
+            node.top = Val::Px(cursor.y / ui_scale.0 - CROSSHAIR_SIZE / 2.);
+            Visibility::Inherited
+        },
+        _ => Visibility::Hidden,
+    };
+
+    visibility.set_if_neq(shown);
+}
+
+// one raycast a frame from the nose of the ship out to the first thing in the
+// way, or the boundary if nothing is
+fn draw_aim_line(
+    mut gizmos: Gizmos<AimLineGizmo>,
+    rapier_context: ReadDefaultRapierContext,
+    boundary: Res<Boundary>,
+    theme: Res<UiTheme>,
+    q_spaceship: Query<(Entity, &Transform, &Aabb), With<Spaceship>>,
+    q_kinds: Query<&ActorKind>,
+) {
+    let Ok((entity, transform, aabb)) = q_spaceship.get_single() else {
+        return;
+    };
+
+    // missiles leave from the front of the ship the same way
+    let forward = -transform.forward();
+    let world_half_extents = transform.rotation * (aabb.half_extents() * transform.scale);
+    let hardpoint = transform.translation + forward * forward.dot(world_half_extents).abs();
+
+    let Some(edge) = boundary.raycast(hardpoint, *forward) else {
+        return;
+    };
+
+    let filter = QueryFilter::default().exclude_rigid_body(entity);
+    let hit = rapier_context
+        .single()
+        .cast_ray(hardpoint, *forward, edge.distance, true, filter);
+
+    let (end, color) = match hit {
+        Some((hit_entity, distance)) => {
+            let nateroid = q_kinds
+                .get(hit_entity)
+                .is_ok_and(|kind| *kind == ActorKind::Nateroid);
+            let color = if nateroid { theme.danger } else { theme.text_muted };
+            (hardpoint + forward * distance, color)
+        },
+        None => (edge.point, theme.text_muted),
+    };
+
+    gizmos.line(hardpoint, end, color.with_alpha(AIM_LINE_ALPHA));
+}
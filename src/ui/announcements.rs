@@ -0,0 +1,276 @@
+use crate::{
+    camera::RenderLayer,
+    schedule::FrameSet,
+    ui::{
+        hud::HudRoot,
+        layout::{
+            safe_area_node,
+            SafeArea,
+        },
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+
+// how long a banner takes to grow in and fade out
+const BANNER_ANIMATE_SECONDS: f32 = 0.25;
+const TICKER_LINES: usize = 4;
+
+pub struct AnnouncementsPlugin;
+
+impl Plugin for AnnouncementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Announcement>()
+            .init_resource::<AnnouncementQueue>()
+            .add_systems(Startup, spawn_announcements)
+            .add_systems(
+                Update,
+                (queue_announcements, show_banner, age_ticker)
+                    .chain()
+                    .in_set(FrameSet::Ui),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AnnouncementPriority {
+    // goes to the ticker at the side rather than taking over the screen
+    Low,
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnouncementStyle {
+    Info,
+    Success,
+    Warning,
+}
+
+impl AnnouncementStyle {
+    fn color(self, theme: &UiTheme) -> Color {
+        match self {
+            AnnouncementStyle::Info => theme.text,
+            AnnouncementStyle::Success => theme.accent,
+            AnnouncementStyle::Warning => theme.danger,
+        }
+    }
+}
+
+/// put something on screen for the player - at most one banner shows at a time,
+/// and a higher priority one knocks whatever's showing off until it's done
+#[derive(Event, Debug, Clone)]
+pub struct Announcement {
+    pub text:     String,
+    pub style:    AnnouncementStyle,
+    pub duration: f32,
+    pub priority: AnnouncementPriority,
+}
+
+impl Announcement {
+    pub fn new(text: impl Into<String>, style: AnnouncementStyle, priority: AnnouncementPriority) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            duration: 2.5,
+            priority,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct AnnouncementQueue {
+    showing: Option<(Announcement, f32)>,
+    waiting: Vec<Announcement>,
+}
+
+#[derive(Component, Debug)]
+struct Banner;
+
+#[derive(Component, Debug)]
+struct Ticker;
+
+#[derive(Component, Debug)]
+struct TickerLine {
+    remaining: f32,
+}
+
+fn spawn_announcements(mut commands: Commands, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                top: Val::Percent(25.),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            Name::new("Announcements"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: theme.font_size_big * 1.5,
+                    ..default()
+                },
+                TextColor(Color::NONE),
+                Banner,
+            ));
+        });
+
+    commands
+        .spawn((
+            safe_area_node(&theme),
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            SafeArea,
+            Name::new("AnnouncementTicker"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(theme.margin),
+                    top: Val::Percent(40.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    row_gap: Val::Px(theme.margin / 4.),
+                    ..default()
+                },
+                Ticker,
+            ));
+        });
+}
+
+fn queue_announcements(
+    mut commands: Commands,
+    mut announcements: EventReader<Announcement>,
+    mut queue: ResMut<AnnouncementQueue>,
+    theme: Res<UiTheme>,
+    q_ticker: Query<(Entity, Option<&Children>), With<Ticker>>,
+    q_lines: Query<&TickerLine>,
+) {
+    for announcement in announcements.read() {
+        if announcement.priority == AnnouncementPriority::Low {
+            let Ok((ticker, lines)) = q_ticker.get_single() else {
+                continue;
+            };
+
+            // make room by dropping the oldest line - an empty ticker has no
+            // Children at all
+            let live: Vec<Entity> = lines
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&line| q_lines.get(line).is_ok_and(|line| line.remaining > 0.0))
+                .collect();
+            if live.len() >= TICKER_LINES {
+                commands.entity(live[0]).despawn_recursive();
+            }
+
+            commands.entity(ticker).with_children(|parent| {
+                parent.spawn((
+                    Text::new(announcement.text.clone()),
+                    TextFont {
+                        font_size: theme.font_size,
+                        ..default()
+                    },
+                    TextColor(announcement.style.color(&theme)),
+                    TickerLine {
+                        remaining: announcement.duration,
+                    },
+                ));
+            });
+            continue;
+        }
+
+        let preempts = queue
+            .showing
+            .as_ref()
+            .is_some_and(|(showing, _)| announcement.priority > showing.priority);
+
+        if preempts {
+            // the one knocked off goes back to the front of the line and gets
+            // shown again from the start once the higher one's done with
+            if let Some((preempted, _)) = queue.showing.replace((announcement.clone(), 0.0)) {
+                queue.waiting.insert(0, preempted);
+            }
+        } else {
+            queue.waiting.push(announcement.clone());
+        }
+    }
+}
+
+fn show_banner(
+    mut queue: ResMut<AnnouncementQueue>,
+    theme: Res<UiTheme>,
+    time: Res<Time>,
+    mut q_banner: Query<(&mut Text, &mut TextColor, &mut Transform), With<Banner>>,
+) {
+    let Ok((mut text, mut color, mut transform)) = q_banner.get_single_mut() else {
+        return;
+    };
+
+    if queue.showing.is_none() && !queue.waiting.is_empty() {
+        // highest priority first, oldest first within a priority
+        let next = queue
+            .waiting
+            .iter()
+            .enumerate()
+            .max_by(|(a_index, a), (b_index, b)| a.priority.cmp(&b.priority).then(b_index.cmp(a_index)))
+            .map(|(index, _)| index);
+
+        if let Some(index) = next {
+            let announcement = queue.waiting.remove(index);
+            queue.showing = Some((announcement, 0.0));
+        }
+    }
+
+    let Some((announcement, elapsed)) = queue.showing.as_mut() else {
+        if color.0 != Color::NONE {
+            color.0 = Color::NONE;
+        }
+        return;
+    };
+
+    if text.0 != announcement.text {
+        text.0 = announcement.text.clone();
+    }
+
+    *elapsed += time.delta_secs();
+
+    // grow in, hold, then fade out
+    let fade_in = (*elapsed / BANNER_ANIMATE_SECONDS).min(1.0);
+    let fade_out = ((announcement.duration - *elapsed) / BANNER_ANIMATE_SECONDS).clamp(0.0, 1.0);
+    let visible = fade_in.min(fade_out);
+
+    color.0 = announcement.style.color(&theme).with_alpha(visible);
+    transform.scale = Vec3::splat(0.6 + 0.4 * fade_in);
+
+    if *elapsed >= announcement.duration {
+        queue.showing = None;
+    }
+}
+
+fn age_ticker(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_lines: Query<(Entity, &mut TickerLine, &mut TextColor)>,
+) {
+    for (entity, mut line, mut color) in q_lines.iter_mut() {
+        line.remaining -= time.delta_secs();
+        if line.remaining <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        } else if line.remaining < BANNER_ANIMATE_SECONDS {
+            color.0 = color.0.with_alpha(line.remaining / BANNER_ANIMATE_SECONDS);
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use crate::{
+    actor::SpaceshipControl,
+    camera::CameraControl,
+    global_input::GlobalAction,
+};
+use bevy::prelude::*;
+use leafwing_input_manager::{
+    axislike::DualAxisType,
+    prelude::*,
+};
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
+
+/// one heading's worth of "what does what" - anything that shows the player
+/// their controls builds from these so they can never disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlGroup {
+    pub title: &'static str,
+    pub rows:  Vec<ControlRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlRow {
+    pub action:   String,
+    pub bindings: String,
+}
+
+pub fn control_groups(
+    formatter: BindingFormatter,
+    ship: &InputMap<SpaceshipControl>,
+    camera: Option<&InputMap<CameraControl>>,
+    global: &InputMap<GlobalAction>,
+) -> Vec<ControlGroup> {
+    let global_rows = |category: GlobalCategory| {
+        formatter.rows(
+            global,
+            GlobalAction::iter().filter(|action| global_category(*action) == category),
+        )
+    };
+
+    let mut groups = vec![ControlGroup {
+        title: "ship",
+        rows:  formatter.rows(ship, SpaceshipControl::iter()),
+    }];
+
+    if let Some(camera) = camera {
+        groups.push(ControlGroup {
+            title: "camera",
+            rows:  formatter.rows(camera, CameraControl::iter()),
+        });
+    }
+
+    groups.push(ControlGroup {
+        title: "game",
+        rows:  global_rows(GlobalCategory::Game),
+    });
+    groups.push(ControlGroup {
+        title: "debug",
+        rows:  global_rows(GlobalCategory::Debug),
+    });
+
+    groups.retain(|group| !group.rows.is_empty());
+    groups
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GlobalCategory {
+    Game,
+    Debug,
+}
+
+fn global_category(action: GlobalAction) -> GlobalCategory {
+    match action {
+        GlobalAction::CycleBoundaryRenderMode
+        | GlobalAction::Diagnostics
+        | GlobalAction::DiagnosticsDump
+        | GlobalAction::InputHelp
+        | GlobalAction::Pause
+        | GlobalAction::RebindControls
+        | GlobalAction::Screenshot
+        | GlobalAction::Settings
+        | GlobalAction::Stars
+        | GlobalAction::SuppressNateroids
+        | GlobalAction::TogglePip
+        | GlobalAction::ToggleProjection
+        | GlobalAction::ToggleSteeringMode => GlobalCategory::Game,
+        GlobalAction::AudioInspector
+        | GlobalAction::BoundaryInspector
+        | GlobalAction::CameraConfigInspector
+        | GlobalAction::Console
+        | GlobalAction::CycleBoundaryPreset
+        | GlobalAction::CycleNateroidVariant
+        | GlobalAction::Debug
+        | GlobalAction::GizmoLines
+        | GlobalAction::LightsInspector
+        | GlobalAction::MissileInspector
+        | GlobalAction::NateroidInspector
+        | GlobalAction::PhysicsDebug
+        | GlobalAction::PlanesInspector
+        | GlobalAction::PortalInspector
+        | GlobalAction::SpaceshipInspector
+        | GlobalAction::SpaceshipControlInspector
+        | GlobalAction::StepSimulation
+        | GlobalAction::StressTest
+        | GlobalAction::TeleportStatsInspector
+        | GlobalAction::TimeScaleTenth
+        | GlobalAction::TimeScaleHalf
+        | GlobalAction::TimeScaleNormal
+        | GlobalAction::TimeScaleDouble
+        | GlobalAction::ToggleRecording => GlobalCategory::Debug,
+    }
+}
+
+/// turns whatever is in an input map into the short text a player reads -
+/// chords are joined with +, and gamepad inputs only show up (as the face
+/// button letters rather than compass names) once a controller is connected
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BindingFormatter {
+    pub gamepad_connected: bool,
+}
+
+impl BindingFormatter {
+    /// every binding for each action in order, alternatives joined with /
+    pub fn rows<A: Actionlike>(
+        self,
+        input_map: &InputMap<A>,
+        actions: impl Iterator<Item = A>,
+    ) -> Vec<ControlRow> {
+        actions
+            .filter_map(|action| {
+                let buttons = input_map
+                    .get_buttonlike(&action)
+                    .into_iter()
+                    .flatten()
+                    .map(|input| self.button(input.as_ref()));
+                let axes = input_map
+                    .get_axislike(&action)
+                    .into_iter()
+                    .flatten()
+                    .map(|input| self.axis(input.as_ref()));
+                let dual_axes = input_map
+                    .get_dual_axislike(&action)
+                    .into_iter()
+                    .flatten()
+                    .map(|input| self.dual_axis(input.as_ref()));
+
+                // left and right shift chords both come out as "Shift + B" so
+                // drop the repeats
+                let mut bindings: Vec<String> = Vec::new();
+                for binding in buttons.chain(axes).chain(dual_axes).flatten() {
+                    if !bindings.contains(&binding) {
+                        bindings.push(binding);
+                    }
+                }
+
+                (!bindings.is_empty()).then(|| ControlRow {
+                    action:   action_name(&action),
+                    bindings: bindings.join(" / "),
+                })
+            })
+            .collect()
+    }
+
+    /// None when the input is hidden - a gamepad input with no gamepad
+    pub fn button(self, input: &dyn Buttonlike) -> Option<String> {
+        let reflect = input.as_reflect();
+
+        if let Some(key) = reflect.downcast_ref::<KeyCode>() {
+            Some(key_name(*key))
+        } else if let Some(button) = reflect.downcast_ref::<MouseButton>() {
+            Some(mouse_button_name(*button))
+        } else if let Some(button) = reflect.downcast_ref::<GamepadButton>() {
+            self.gamepad_connected.then(|| gamepad_button_name(*button))
+        } else if let Some(chord) = reflect.downcast_ref::<ButtonlikeChord>() {
+            // the buttons themselves aren't public - decompose is how leafwing
+            // hands them out
+            self.join(
+                chord
+                    .decompose()
+                    .inputs()
+                    .iter()
+                    .map(|button| self.button(button.as_ref())),
+                " + ",
+            )
+        } else {
+            Some(format!("{input:?}"))
+        }
+    }
+
+    pub fn axis(self, input: &dyn Axislike) -> Option<String> {
+        let reflect = input.as_reflect();
+
+        if let Some(scroll) = reflect.downcast_ref::<MouseScrollAxis>() {
+            Some(match scroll.axis {
+                DualAxisType::X => "Scroll Wheel X".to_string(),
+                DualAxisType::Y => "Scroll Wheel".to_string(),
+            })
+        } else if let Some(movement) = reflect.downcast_ref::<MouseMoveAxis>() {
+            Some(format!("Mouse {:?}", movement.axis))
+        } else if let Some(axis) = reflect.downcast_ref::<GamepadControlAxis>() {
+            self.gamepad_connected.then(|| gamepad_axis_name(axis.axis))
+        } else if let Some(keys) = reflect.downcast_ref::<VirtualAxis>() {
+            // positive first - "W/S" reads as forwards and back
+            self.join(
+                [&keys.positive, &keys.negative]
+                    .into_iter()
+                    .map(|button| self.button(button.as_ref())),
+                "/",
+            )
+        } else {
+            Some(format!("{input:?}"))
+        }
+    }
+
+    pub fn dual_axis(self, input: &dyn DualAxislike) -> Option<String> {
+        let reflect = input.as_reflect();
+
+        if reflect.is::<MouseMove>() {
+            Some("Mouse".to_string())
+        } else if reflect.is::<MouseScroll>() {
+            Some("Scroll Wheel".to_string())
+        } else if let Some(stick) = reflect.downcast_ref::<GamepadStick>() {
+            self.gamepad_connected.then(|| match stick.x {
+                GamepadAxis::LeftStickX => "Left Stick".to_string(),
+                GamepadAxis::RightStickX => "Right Stick".to_string(),
+                _ => gamepad_axis_name(stick.x),
+            })
+        } else if let Some(dpad) = reflect.downcast_ref::<VirtualDPad>() {
+            self.join(
+                [&dpad.up, &dpad.left, &dpad.down, &dpad.right]
+                    .into_iter()
+                    .map(|button| self.button(button.as_ref())),
+                "/",
+            )
+        } else if let Some(chord) = reflect.downcast_ref::<DualAxislikeChord>() {
+            self.join(
+                [
+                    self.button(chord.button.as_ref()),
+                    self.dual_axis(chord.dual_axis.as_ref()),
+                ]
+                .into_iter(),
+                " + ",
+            )
+        } else {
+            Some(format!("{input:?}"))
+        }
+    }
+
+    // a chord with a hidden part in it is hidden too
+    fn join(self, parts: impl Iterator<Item = Option<String>>, separator: &str) -> Option<String> {
+        parts
+            .collect::<Option<Vec<_>>>()
+            .map(|parts| parts.join(separator))
+    }
+}
+
+fn key_name(key: KeyCode) -> String {
+    let name = match key {
+        KeyCode::ShiftLeft | KeyCode::ShiftRight => "Shift",
+        KeyCode::ControlLeft | KeyCode::ControlRight => "Ctrl",
+        KeyCode::AltLeft | KeyCode::AltRight => "Alt",
+        KeyCode::SuperLeft | KeyCode::SuperRight => "Super",
+        KeyCode::Escape => "Esc",
+        KeyCode::ArrowUp => "Up",
+        KeyCode::ArrowDown => "Down",
+        KeyCode::ArrowLeft => "Left",
+        KeyCode::ArrowRight => "Right",
+        _ => {
+            let name = format!("{key:?}");
+            return name
+                .strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .map_or_else(|| name.clone(), str::to_string);
+        },
+    };
+    name.to_string()
+}
+
+fn mouse_button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "Left Mouse".to_string(),
+        MouseButton::Right => "Right Mouse".to_string(),
+        MouseButton::Middle => "Middle Mouse".to_string(),
+        MouseButton::Back => "Mouse Back".to_string(),
+        MouseButton::Forward => "Mouse Forward".to_string(),
+        MouseButton::Other(index) => format!("Mouse {index}"),
+    }
+}
+
+// xbox letters - they're the ones most people know
+fn gamepad_button_name(button: GamepadButton) -> String {
+    let name = match button {
+        GamepadButton::South => "A",
+        GamepadButton::East => "B",
+        GamepadButton::West => "X",
+        GamepadButton::North => "Y",
+        GamepadButton::LeftTrigger => "LB",
+        GamepadButton::RightTrigger => "RB",
+        GamepadButton::LeftTrigger2 => "LT",
+        GamepadButton::RightTrigger2 => "RT",
+        GamepadButton::Select => "View",
+        GamepadButton::Start => "Menu",
+        GamepadButton::LeftThumb => "LS",
+        GamepadButton::RightThumb => "RS",
+        GamepadButton::DPadUp => "D-Pad Up",
+        GamepadButton::DPadDown => "D-Pad Down",
+        GamepadButton::DPadLeft => "D-Pad Left",
+        GamepadButton::DPadRight => "D-Pad Right",
+        other => return format!("{other:?}"),
+    };
+    format!("({name})")
+}
+
+fn gamepad_axis_name(axis: GamepadAxis) -> String {
+    match axis {
+        GamepadAxis::LeftStickX => "Left Stick X".to_string(),
+        GamepadAxis::LeftStickY => "Left Stick Y".to_string(),
+        GamepadAxis::RightStickX => "Right Stick X".to_string(),
+        GamepadAxis::RightStickY => "Right Stick Y".to_string(),
+        GamepadAxis::LeftZ => "LT".to_string(),
+        GamepadAxis::RightZ => "RT".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+// TurnLeft -> "turn left", and an acronym keeps its case - ShowAABBs -> "show
+// AABBs"
+pub(super) fn action_name(action: &impl Debug) -> String {
+    let debug = format!("{action:?}");
+    let mut words: Vec<String> = Vec::new();
+    let mut previous_lowercase = false;
+
+    for character in debug.chars() {
+        if words.is_empty() || (character.is_uppercase() && previous_lowercase) {
+            words.push(String::new());
+        }
+        previous_lowercase = character.is_lowercase();
+        if let Some(word) = words.last_mut() {
+            word.push(character);
+        }
+    }
+
+    words
+        .into_iter()
+        .map(|word| {
+            if word.chars().filter(|c| c.is_uppercase()).count() > 1 {
+                word
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_bindings::{
+        chorded,
+        KeyBindings,
+    };
+
+    const PAD: BindingFormatter = BindingFormatter {
+        gamepad_connected: true,
+    };
+    const NO_PAD: BindingFormatter = BindingFormatter {
+        gamepad_connected: false,
+    };
+
+    #[test]
+    fn keys() {
+        assert_eq!(PAD.button(&KeyCode::KeyW), Some("W".to_string()));
+        assert_eq!(PAD.button(&KeyCode::Digit1), Some("1".to_string()));
+        assert_eq!(PAD.button(&KeyCode::ShiftRight), Some("Shift".to_string()));
+        assert_eq!(PAD.button(&KeyCode::ArrowUp), Some("Up".to_string()));
+        assert_eq!(PAD.button(&KeyCode::Escape), Some("Esc".to_string()));
+        assert_eq!(PAD.button(&KeyCode::F10), Some("F10".to_string()));
+    }
+
+    #[test]
+    fn mouse_buttons() {
+        assert_eq!(PAD.button(&MouseButton::Middle), Some("Middle Mouse".to_string()));
+        assert_eq!(PAD.button(&MouseButton::Other(7)), Some("Mouse 7".to_string()));
+    }
+
+    #[test]
+    fn gamepad_buttons_only_with_a_gamepad() {
+        assert_eq!(PAD.button(&GamepadButton::South), Some("(A)".to_string()));
+        assert_eq!(PAD.button(&GamepadButton::LeftTrigger), Some("(LB)".to_string()));
+        assert_eq!(NO_PAD.button(&GamepadButton::South), None);
+    }
+
+    #[test]
+    fn chords() {
+        for chord in chorded(&[ModifierKey::Shift, ModifierKey::Control], KeyCode::KeyB) {
+            assert_eq!(PAD.button(&chord), Some("Shift + Ctrl + B".to_string()));
+        }
+
+        let mixed = ButtonlikeChord::new([GamepadButton::LeftTrigger]).with(KeyCode::KeyA);
+        assert_eq!(PAD.button(&mixed), Some("(LB) + A".to_string()));
+        assert_eq!(NO_PAD.button(&mixed), None);
+    }
+
+    #[test]
+    fn axes() {
+        assert_eq!(PAD.axis(&MouseScrollAxis::Y), Some("Scroll Wheel".to_string()));
+        assert_eq!(PAD.axis(&MouseScrollAxis::X), Some("Scroll Wheel X".to_string()));
+        assert_eq!(PAD.axis(&MouseMoveAxis::X), Some("Mouse X".to_string()));
+        assert_eq!(
+            PAD.axis(&VirtualAxis::new(KeyCode::KeyS, KeyCode::KeyW)),
+            Some("W/S".to_string())
+        );
+
+        let stick = GamepadControlAxis::new(GamepadAxis::LeftStickY);
+        assert_eq!(PAD.axis(&stick), Some("Left Stick Y".to_string()));
+        assert_eq!(NO_PAD.axis(&stick), None);
+    }
+
+    #[test]
+    fn dual_axes() {
+        assert_eq!(PAD.dual_axis(&MouseMove::default()), Some("Mouse".to_string()));
+        assert_eq!(
+            PAD.dual_axis(&MouseScroll::default()),
+            Some("Scroll Wheel".to_string())
+        );
+        assert_eq!(PAD.dual_axis(&GamepadStick::LEFT), Some("Left Stick".to_string()));
+        assert_eq!(NO_PAD.dual_axis(&GamepadStick::RIGHT), None);
+        assert_eq!(PAD.dual_axis(&VirtualDPad::wasd()), Some("W/A/S/D".to_string()));
+        assert_eq!(
+            PAD.dual_axis(&DualAxislikeChord::new(MouseButton::Middle, MouseMove::default())),
+            Some("Middle Mouse + Mouse".to_string())
+        );
+        assert_eq!(
+            PAD.dual_axis(&DualAxislikeChord::new(
+                ButtonlikeChord::new([KeyCode::ShiftLeft]).with(MouseButton::Middle),
+                MouseScroll::default(),
+            )),
+            Some("Shift + Middle Mouse + Scroll Wheel".to_string())
+        );
+    }
+
+    #[test]
+    fn repeated_chords_show_once() {
+        let mut input_map = InputMap::default();
+        input_map.insert_one_to_many(GlobalAction::Debug, chorded(&[ModifierKey::Shift], KeyCode::KeyD));
+
+        let rows = PAD.rows(&input_map, [GlobalAction::Debug].into_iter());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].bindings, "Shift + D");
+    }
+
+    // everything the defaults actually bind has a proper name - nothing falls
+    // through to its Debug output, and no action with a binding goes missing
+    #[test]
+    fn every_default_binding_has_a_name() {
+        fn check<A: Actionlike + Debug>(input_map: &InputMap<A>, actions: impl Iterator<Item = A> + Clone) {
+            let bound = actions
+                .clone()
+                .filter(|action| input_map.get(action).is_some_and(|inputs| !inputs.is_empty()));
+            let rows = PAD.rows(input_map, actions);
+            assert_eq!(rows.len(), bound.count());
+            for row in rows {
+                assert!(
+                    !row.bindings.contains('{') && !row.bindings.contains("::"),
+                    "{} is bound to {}",
+                    row.action,
+                    row.bindings
+                );
+            }
+        }
+
+        let key_bindings = KeyBindings::default();
+        check(
+            &key_bindings.input_map::<SpaceshipControl>(),
+            SpaceshipControl::iter(),
+        );
+        check(&key_bindings.input_map::<GlobalAction>(), GlobalAction::iter());
+        check(&CameraControl::camera_input_map(), CameraControl::iter());
+    }
+
+    #[test]
+    fn action_names() {
+        #[derive(Debug)]
+        enum Action {
+            TurnLeft,
+            ShowAABBs,
+        }
+
+        assert_eq!(action_name(&Action::TurnLeft), "turn left");
+        assert_eq!(action_name(&Action::ShowAABBs), "show AABBs");
+    }
+}
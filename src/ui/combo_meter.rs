@@ -0,0 +1,215 @@
+use crate::{
+    camera::RenderLayer,
+    schedule::FrameSet,
+    scoring::{
+        Combo,
+        ComboEvent,
+    },
+    ui::{
+        hud::HudRoot,
+        layout::{
+            safe_area_node,
+            SafeArea,
+        },
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+
+const METER_WIDTH: f32 = 120.;
+const METER_HEIGHT: f32 = 6.;
+// how quickly the drawn meter chases the combo timer - exponential so it looks
+// the same at any frame rate
+const METER_EASE_RATE: f32 = 12.;
+const PULSE_SECONDS: f32 = 0.25;
+const PULSE_ALPHA: f32 = 0.08;
+const BREAK_SECONDS: f32 = 0.6;
+
+pub struct ComboMeterPlugin;
+
+impl Plugin for ComboMeterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_combo_meter).add_systems(
+            Update,
+            (react_to_combo, animate_combo_meter).chain().in_set(FrameSet::Ui),
+        );
+    }
+}
+
+// all of the meter's animation state - the combo itself lives in scoring
+#[derive(Component, Debug, Default)]
+struct ComboMeter {
+    shown:      f32,
+    multiplier: u32,
+    pulse:      f32,
+    broken:     f32,
+}
+
+#[derive(Component, Debug)]
+struct ComboText;
+
+#[derive(Component, Debug)]
+struct ComboFill;
+
+#[derive(Component, Debug)]
+struct ComboPulse;
+
+// sits just under the score
+fn spawn_combo_meter(mut commands: Commands, theme: Res<UiTheme>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        Visibility::Hidden,
+        RenderLayers::from_layers(RenderLayer::Ui.layers()),
+        HudRoot,
+        ComboPulse,
+        Name::new("ComboPulse"),
+    ));
+
+    commands
+        .spawn((
+            Node {
+                padding: UiRect {
+                    top: Val::Px(theme.margin + theme.font_size_big + 4.),
+                    ..UiRect::all(Val::Px(theme.margin))
+                },
+                align_items: AlignItems::FlexStart,
+                ..safe_area_node(&theme)
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            SafeArea,
+            Name::new("Combo"),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(2.),
+                        ..default()
+                    },
+                    Visibility::Hidden,
+                    ComboMeter::default(),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font_size: theme.font_size,
+                            ..default()
+                        },
+                        TextColor(theme.accent),
+                        ComboText,
+                    ));
+
+                    parent
+                        .spawn((
+                            Node {
+                                width: Val::Px(METER_WIDTH),
+                                height: Val::Px(METER_HEIGHT),
+                                ..default()
+                            },
+                            BackgroundColor(theme.panel),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Node {
+                                    width: Val::Percent(0.),
+                                    height: Val::Percent(100.),
+                                    ..default()
+                                },
+                                BackgroundColor(theme.accent),
+                                ComboFill,
+                            ));
+                        });
+                });
+        });
+}
+
+fn react_to_combo(mut combo_events: EventReader<ComboEvent>, mut q_meter: Query<&mut ComboMeter>) {
+    let Ok(mut meter) = q_meter.get_single_mut() else {
+        return;
+    };
+
+    for event in combo_events.read() {
+        match event {
+            ComboEvent::Increased(multiplier) => {
+                meter.multiplier = *multiplier;
+                meter.pulse = PULSE_SECONDS;
+                meter.broken = 0.;
+            },
+            ComboEvent::Broken => meter.broken = BREAK_SECONDS,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn animate_combo_meter(
+    combo: Res<Combo>,
+    theme: Res<UiTheme>,
+    time: Res<Time>,
+    mut q_meter: Query<(&mut ComboMeter, &mut Visibility)>,
+    mut q_fill: Query<(&mut Node, &mut BackgroundColor), (With<ComboFill>, Without<ComboPulse>)>,
+    mut q_text: Query<(&mut Text, &mut TextColor), With<ComboText>>,
+    mut q_pulse: Query<&mut BackgroundColor, (With<ComboPulse>, Without<ComboFill>)>,
+) {
+    let Ok((mut meter, mut visibility)) = q_meter.get_single_mut() else {
+        return;
+    };
+
+    let delta_seconds = time.delta_secs();
+    meter.pulse = (meter.pulse - delta_seconds).max(0.);
+    meter.broken = (meter.broken - delta_seconds).max(0.);
+
+    // a broken combo keeps showing its last multiplier while it fades out, so
+    // the meter holds on to what it drew rather than reading 1 off the combo
+    let target = if meter.broken > 0. { 0. } else { combo.fraction() };
+    meter.shown += (target - meter.shown) * (1. - (-METER_EASE_RATE * delta_seconds).exp());
+
+    let active = combo.multiplier > 1 || meter.broken > 0.;
+    visibility.set_if_neq(if active {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+
+    // drains to grey as it fades out when broken
+    let color = if meter.broken > 0. {
+        let remaining = meter.broken / BREAK_SECONDS;
+        desaturate(theme.accent, 1. - remaining).with_alpha(remaining)
+    } else {
+        theme.accent
+    };
+
+    if let Ok((mut node, mut fill_color)) = q_fill.get_single_mut() {
+        node.width = Val::Percent(meter.shown * 100.);
+        fill_color.0 = color;
+    }
+
+    if let Ok((mut text, mut text_color)) = q_text.get_single_mut() {
+        let label = format!("x{}", meter.multiplier);
+        if text.0 != label {
+            text.0 = label;
+        }
+        text_color.0 = color;
+    }
+
+    if let Ok(mut pulse_color) = q_pulse.get_single_mut() {
+        pulse_color.0 = theme.accent.with_alpha(PULSE_ALPHA * meter.pulse / PULSE_SECONDS);
+    }
+}
+
+fn desaturate(color: Color, amount: f32) -> Color {
+    let hsla = Hsla::from(color);
+    hsla.with_saturation(hsla.saturation * (1. - amount)).into()
+}
@@ -0,0 +1,79 @@
+use crate::{
+    actor::SpaceshipControl,
+    camera::CameraControl,
+    global_input::GlobalAction,
+    schedule::FrameSet,
+    state::IsPaused,
+    ui::{
+        bindings::{
+            control_groups,
+            BindingFormatter,
+        },
+        SettingsMenu,
+    },
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use leafwing_input_manager::prelude::InputMap;
+
+pub struct ControlCardPlugin;
+
+impl Plugin for ControlCardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            draw_control_card
+                .in_set(FrameSet::Ui)
+                .run_if(in_state(IsPaused::Paused)),
+        );
+    }
+}
+
+// the card is only up while paused so building it fresh each frame is fine -
+// it also means a rebind or a controller plugged in mid-pause shows up at once
+fn draw_control_card(
+    mut contexts: Query<&mut EguiContext>,
+    menu: Res<SettingsMenu>,
+    ship: Res<InputMap<SpaceshipControl>>,
+    global: Res<InputMap<GlobalAction>>,
+    q_camera: Query<&InputMap<CameraControl>>,
+    q_gamepads: Query<(), With<Gamepad>>,
+) {
+    // the settings menu has the middle of the screen
+    if menu.is_open() {
+        return;
+    }
+
+    let Ok(mut context) = contexts.get_single_mut() else {
+        return;
+    };
+
+    let formatter = BindingFormatter {
+        gamepad_connected: !q_gamepads.is_empty(),
+    };
+    let groups = control_groups(formatter, &ship, q_camera.get_single().ok(), &global);
+
+    egui::Window::new("controls")
+        .collapsible(true)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_CENTER, [-16., 0.])
+        .show(context.get_mut(), |ui| {
+            for group in groups {
+                ui.strong(group.title);
+                egui::Grid::new(group.title)
+                    .num_columns(2)
+                    .spacing([12., 2.])
+                    .show(ui, |ui| {
+                        for row in group.rows {
+                            ui.label(row.action);
+                            ui.weak(row.bindings);
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(6.);
+            }
+        });
+}
@@ -0,0 +1,111 @@
+use crate::{
+    camera::RenderLayer,
+    despawn::despawn,
+    global_input::MenuAction,
+    schedule::FrameSet,
+    scoring::Score,
+    state::{
+        GameState,
+        PLAY_AGAIN,
+    },
+    ui::{
+        SettingsMenu,
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+/// what's up once the last spaceship is gone - the score the run ended on and
+/// how to go again. it stays until enter (or the gamepad's south button), and
+/// then it's straight back into the game with the same mutators, see
+/// PLAY_AGAIN. the tidying up - the actors still out there, the score -
+/// happens on the way out of GameState::GameOver, whatever it's left for
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+            .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+            .add_systems(
+                Update,
+                play_again
+                    .in_set(FrameSet::Input)
+                    .run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+#[derive(Component, Debug)]
+struct GameOverScreen;
+
+fn spawn_game_over_screen(mut commands: Commands, theme: Res<UiTheme>, score: Res<Score>) {
+    let best = if score.points > 0 && score.points == score.best {
+        format!("{} - a new best", score.points)
+    } else {
+        format!("{} (best {})", score.points, score.best)
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.margin / 2.),
+                ..default()
+            },
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            GameOverScreen,
+            Name::new("GameOver"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("game over"),
+                TextFont {
+                    font_size: theme.font_size_big,
+                    ..default()
+                },
+                TextColor(theme.danger),
+            ));
+            parent.spawn((
+                Text::new(best),
+                TextFont {
+                    font_size: theme.font_size,
+                    ..default()
+                },
+                TextColor(theme.text),
+            ));
+            parent.spawn((
+                Text::new("press enter"),
+                TextFont {
+                    font_size: theme.font_size,
+                    ..default()
+                },
+                TextColor(theme.text_muted),
+            ));
+        });
+}
+
+fn despawn_game_over_screen(mut commands: Commands, q_screen: Query<Entity, With<GameOverScreen>>) {
+    for entity in q_screen.iter() {
+        despawn(&mut commands, entity);
+    }
+}
+
+// settings can be opened from here too, and enter in there isn't for this
+fn play_again(
+    menu_input: Res<ActionState<MenuAction>>,
+    settings_menu: Res<SettingsMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if menu_input.just_pressed(&MenuAction::Select) && !settings_menu.is_open() {
+        next_state.set(PLAY_AGAIN.entered);
+    }
+}
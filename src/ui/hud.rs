@@ -0,0 +1,298 @@
+use crate::{
+    actor::{
+        ContinuousFire,
+        PlayerLives,
+        Spaceship,
+    },
+    camera::RenderLayer,
+    mutators::Mutators,
+    schedule::FrameSet,
+    scoring::Score,
+    state::PlayingGame,
+    ui::{
+        layout::{
+            safe_area_node,
+            SafeArea,
+        },
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+
+// how quickly the displayed score catches up with the real one - higher is
+// snappier
+const SCORE_ROLL_RATE: f32 = 8.;
+// a life's ship icon is this much of the hud's font size across
+const LIFE_ICON_SCALE: f32 = 0.9;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        // spawned once and shown / hidden with the game - anything that only makes
+        // sense while playing lives under HudRoot
+        app.add_systems(Startup, spawn_hud)
+            .add_systems(OnEnter(PlayingGame), show_hud)
+            .add_systems(OnExit(PlayingGame), hide_hud)
+            .add_systems(
+                Update,
+                (
+                    roll_up_score,
+                    update_lives.run_if(resource_exists_and_changed::<PlayerLives>),
+                    update_weapon_mode,
+                    update_mutators.run_if(resource_changed::<Mutators>),
+                )
+                    .in_set(FrameSet::Ui),
+            );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct HudRoot;
+
+// the score on screen rolls up toward Score rather than jumping
+#[derive(Component, Debug, Default)]
+struct ScoreText {
+    shown: f32,
+}
+
+// a ship icon under the score for each life left, the one that's out there
+// included
+#[derive(Component, Debug)]
+struct LivesRow;
+
+#[derive(Component, Debug)]
+struct WeaponText;
+
+// a modified run says so for as long as it lasts
+#[derive(Component, Debug)]
+struct MutatorsText;
+
+fn spawn_hud(mut commands: Commands, theme: Res<UiTheme>, mutators: Res<Mutators>) {
+    commands
+        .spawn((
+            Node {
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::FlexStart,
+                ..safe_area_node(&theme)
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            SafeArea,
+            Name::new("Hud"),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(theme.margin / 2.),
+                        ..default()
+                    },
+                    Name::new("HudScoreAndLives"),
+                ))
+                .with_children(|column| {
+                    column.spawn((
+                        Text::new("0"),
+                        TextFont {
+                            font_size: theme.font_size_big,
+                            ..default()
+                        },
+                        TextColor(theme.text),
+                        ScoreText::default(),
+                        Name::new("HudScore"),
+                    ));
+
+                    // filled in by update_lives
+                    column.spawn((
+                        Node {
+                            column_gap: Val::Px(theme.margin / 2.),
+                            ..default()
+                        },
+                        LivesRow,
+                        Name::new("HudLives"),
+                    ));
+                });
+
+            parent.spawn((
+                Text::new(mutators.active.summary()),
+                TextFont {
+                    font_size: theme.font_size,
+                    ..default()
+                },
+                TextColor(theme.accent),
+                MutatorsText,
+                Name::new("HudMutators"),
+            ));
+
+            parent.spawn((
+                Text::new(weapon_label(false)),
+                TextFont {
+                    font_size: theme.font_size,
+                    ..default()
+                },
+                TextColor(theme.text_muted),
+                WeaponText,
+                Name::new("HudWeapon"),
+            ));
+        });
+}
+
+fn show_hud(mut q_hud: Query<&mut Visibility, With<HudRoot>>) {
+    for mut visibility in q_hud.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn hide_hud(mut q_hud: Query<&mut Visibility, With<HudRoot>>) {
+    for mut visibility in q_hud.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn roll_up_score(score: Res<Score>, time: Res<Time>, mut q_score: Query<(&mut ScoreText, &mut Text)>) {
+    let Ok((mut score_text, mut text)) = q_score.get_single_mut() else {
+        return;
+    };
+
+    let target = score.points as f32;
+    if score_text.shown == target {
+        return;
+    }
+
+    // ease toward the target, snapping once we're close - a reset to zero just
+    // jumps there, rolling down looks odd
+    let shown = if target < score_text.shown {
+        target
+    } else {
+        let eased = score_text.shown
+            + (target - score_text.shown) * (1.0 - (-SCORE_ROLL_RATE * time.delta_secs()).exp());
+        if target - eased < 0.5 {
+            target
+        } else {
+            eased
+        }
+    };
+
+    if shown.round() != score_text.shown.round() {
+        text.0 = format!("{}", shown.round() as u32);
+    }
+    score_text.shown = shown;
+}
+
+// a life is lost or the run starts again - the icons are put back to match
+fn update_lives(
+    mut commands: Commands,
+    lives: Res<PlayerLives>,
+    theme: Res<UiTheme>,
+    q_row: Query<Entity, With<LivesRow>>,
+) {
+    let size = Val::Px(theme.font_size * LIFE_ICON_SCALE);
+    for row in q_row.iter() {
+        commands
+            .entity(row)
+            .despawn_descendants()
+            .with_children(|row| {
+                for _ in 0..lives.0 {
+                    // a nose on top of a flat base, like the spaceship from
+                    // behind
+                    row.spawn((
+                        Node {
+                            width: size,
+                            height: size,
+                            ..default()
+                        },
+                        BackgroundColor(theme.health),
+                        BorderRadius::new(Val::Percent(50.), Val::Percent(50.), Val::ZERO, Val::ZERO),
+                        Name::new("HudLife"),
+                    ));
+                }
+            });
+    }
+}
+
+// driven by ContinuousFire coming and going rather than looking at the
+// spaceship every frame - when the spaceship is despawned it counts as removed
+// so a new game starts back on single shot. once something's changed the
+// label is whatever the spaceship ended up with, so toggling twice in a frame
+// still comes out right
+fn update_weapon_mode(
+    q_added: Query<(), Added<ContinuousFire>>,
+    mut removed: RemovedComponents<ContinuousFire>,
+    q_spaceship: Query<Has<ContinuousFire>, With<Spaceship>>,
+    mut q_text: Query<&mut Text, With<WeaponText>>,
+) {
+    // all of them, so none are left over for the next frame
+    let any_removed = removed.read().count() > 0;
+    if q_added.is_empty() && !any_removed {
+        return;
+    }
+
+    let continuous = q_spaceship.get_single().unwrap_or(false);
+
+    for mut text in q_text.iter_mut() {
+        text.0 = weapon_label(continuous).to_string();
+    }
+}
+
+fn update_mutators(mutators: Res<Mutators>, mut q_text: Query<&mut Text, With<MutatorsText>>) {
+    for mut text in q_text.iter_mut() {
+        text.0 = mutators.active.summary();
+    }
+}
+
+fn weapon_label(continuous: bool) -> &'static str {
+    if continuous {
+        "continuous fire"
+    } else {
+        "single shot"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(app: &mut App) -> String {
+        let world = app.world_mut();
+        world.query_filtered::<&Text, With<WeaponText>>().single(world).0.clone()
+    }
+
+    // switched off and back on between two updates, it's still continuous -
+    // and a frame with two removals in it doesn't leave one behind to switch
+    // the label back off later
+    #[test]
+    fn weapon_mode_is_what_the_spaceship_ended_up_with() {
+        let mut app = App::new();
+        app.add_systems(Update, update_weapon_mode);
+        app.world_mut().spawn((Text::new(weapon_label(false)), WeaponText));
+        let spaceship = app.world_mut().spawn((Spaceship, ContinuousFire)).id();
+
+        app.update();
+        assert_eq!(label(&mut app), weapon_label(true));
+
+        app.world_mut().entity_mut(spaceship).remove::<ContinuousFire>();
+        app.world_mut().entity_mut(spaceship).insert(ContinuousFire);
+        app.update();
+        assert_eq!(label(&mut app), weapon_label(true));
+
+        app.world_mut().entity_mut(spaceship).remove::<ContinuousFire>();
+        app.world_mut().entity_mut(spaceship).insert(ContinuousFire);
+        app.world_mut().entity_mut(spaceship).remove::<ContinuousFire>();
+        app.update();
+        assert_eq!(label(&mut app), weapon_label(false));
+
+        app.world_mut().entity_mut(spaceship).insert(ContinuousFire);
+        app.update();
+        app.update();
+        assert_eq!(label(&mut app), weapon_label(true));
+
+        app.world_mut().despawn(spaceship);
+        app.update();
+        assert_eq!(label(&mut app), weapon_label(false));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{
+    actor::SpaceshipControl,
+    camera::CameraControl,
+    global_input::{
+        toggle_active,
+        GlobalAction,
+    },
+    schedule::FrameSet,
+    ui::bindings::BindingFormatter,
+};
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use leafwing_input_manager::prelude::*;
+use std::iter;
+use strum::IntoEnumIterator;
+
+/// every ship, camera and global action with what it's bound to right now,
+/// lit up while it's held. it reads the input maps fresh every frame it's
+/// open, so a rebind shows up as soon as it's made
+///
+/// it doesn't take the keyboard from the game the way the inspectors do -
+/// seeing what lights up while you fly is the point
+pub struct InputHelpPlugin;
+
+impl Plugin for InputHelpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            draw_input_help
+                .in_set(FrameSet::Ui)
+                .run_if(toggle_active(false, GlobalAction::InputHelp)),
+        );
+    }
+}
+
+struct HelpRow {
+    action:   String,
+    bindings: String,
+    active:   bool,
+}
+
+// a button's held, an axis is pushed off zero
+fn is_active<A: Actionlike>(action_state: &ActionState<A>, action: &A) -> bool {
+    match action.input_control_kind() {
+        InputControlKind::Button => action_state.pressed(action),
+        InputControlKind::Axis => action_state.value(action) != 0.,
+        InputControlKind::DualAxis => action_state.axis_pair(action) != Vec2::ZERO,
+        InputControlKind::TripleAxis => action_state.axis_triple(action) != Vec3::ZERO,
+    }
+}
+
+// only the actions that are bound to something - in a release build most of
+// the dev tools aren't
+fn help_rows<A: Actionlike + IntoEnumIterator>(
+    formatter: BindingFormatter,
+    input_map: &InputMap<A>,
+    action_state: &ActionState<A>,
+) -> Vec<HelpRow> {
+    A::iter()
+        .filter_map(|action| {
+            let row = formatter.rows(input_map, iter::once(action.clone())).pop()?;
+            Some(HelpRow {
+                action:   row.action,
+                bindings: row.bindings,
+                active:   is_active(action_state, &action),
+            })
+        })
+        .collect()
+}
+
+// the spaceship and the camera have their own maps and action states - the
+// ship's resource stands in while there's no spaceship
+#[allow(clippy::too_many_arguments)]
+fn draw_input_help(
+    mut q_egui: Query<&mut EguiContext, With<PrimaryWindow>>,
+    ship_map: Res<InputMap<SpaceshipControl>>,
+    ship_state: Res<ActionState<SpaceshipControl>>,
+    q_ship_states: Query<&ActionState<SpaceshipControl>>,
+    q_camera: Query<(&InputMap<CameraControl>, &ActionState<CameraControl>)>,
+    global_map: Res<InputMap<GlobalAction>>,
+    global_state: Res<ActionState<GlobalAction>>,
+    q_gamepads: Query<(), With<Gamepad>>,
+) {
+    let Ok(mut context) = q_egui.get_single_mut() else {
+        return;
+    };
+
+    let formatter = BindingFormatter {
+        gamepad_connected: !q_gamepads.is_empty(),
+    };
+    let ship_state = q_ship_states.iter().next().unwrap_or(&*ship_state);
+
+    let mut groups = vec![("ship", help_rows(formatter, &ship_map, ship_state))];
+    if let Ok((camera_map, camera_state)) = q_camera.get_single() {
+        groups.push(("camera", help_rows(formatter, camera_map, camera_state)));
+    }
+    groups.push(("global", help_rows(formatter, &global_map, &global_state)));
+
+    egui::Window::new("input help")
+        .collapsible(true)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_CENTER, [16., 0.])
+        .show(context.get_mut(), |ui| {
+            let highlight = ui.visuals().selection.stroke.color;
+            for (title, rows) in groups {
+                ui.strong(title);
+                egui::Grid::new(title)
+                    .num_columns(2)
+                    .spacing([12., 2.])
+                    .show(ui, |ui| {
+                        for row in rows {
+                            if row.active {
+                                ui.label(egui::RichText::new(row.action).strong().color(highlight));
+                                ui.label(egui::RichText::new(row.bindings).color(highlight));
+                            } else {
+                                ui.label(row.action);
+                                ui.weak(row.bindings);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(6.);
+            }
+        });
+}
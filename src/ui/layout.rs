@@ -0,0 +1,137 @@
+use crate::{
+    config::SettingsAppExt,
+    schedule::FrameSet,
+    ui::UiTheme,
+};
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::bevy_egui::{
+    EguiContext,
+    EguiContextSettings,
+};
+
+// the hud is laid out for a window this tall - anything bigger or smaller
+// scales everything up or down to match
+const REFERENCE_WINDOW_HEIGHT: f32 = 720.;
+
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 2.0;
+pub const MAX_SAFE_AREA: f32 = 10.;
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_settings::<UiSettings>("ui").add_systems(
+            Update,
+            (scale_ui, scale_menus, apply_safe_area).in_set(FrameSet::Ui),
+        );
+    }
+}
+
+// scale multiplies on top of following the window height, so 1.0 is "what it
+// looks like at 720p" at any size - safe_area is a percentage of the window
+// kept clear on every side, for tvs that crop or screens with rounded corners
+#[derive(Resource, Reflect, Debug, PartialEq, Clone, Copy)]
+#[reflect(Resource)]
+pub struct UiSettings {
+    pub scale:     f32,
+    pub safe_area: f32,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            scale:     1.0,
+            safe_area: 0.,
+        }
+    }
+}
+
+impl UiSettings {
+    /// how much of the window's width (or height) is kept clear on each side
+    pub fn safe_area_fraction(&self) -> f32 { self.safe_area.clamp(0., MAX_SAFE_AREA) / 100. }
+}
+
+/// a node pulled in from the window edges by the safe area - hud corners and
+/// edges hang off one of these rather than off the window itself
+#[derive(Component, Debug)]
+pub struct SafeArea;
+
+/// the starting point for a SafeArea node - the insets are filled in by
+/// apply_safe_area, and the theme margin keeps things off the very edge
+pub fn safe_area_node(theme: &UiTheme) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        left: Val::ZERO,
+        right: Val::ZERO,
+        top: Val::ZERO,
+        bottom: Val::ZERO,
+        padding: UiRect::all(Val::Px(theme.margin)),
+        ..default()
+    }
+}
+
+// everything in the ui is sized in Val::Px at the reference height, so
+// UiScale is the one place that stretches text, bars and margins alike
+fn scale_ui(
+    settings: Res<UiSettings>,
+    q_window: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = q_window.get_single() else {
+        return;
+    };
+
+    if !window.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let window_scale = (window.height() / REFERENCE_WINDOW_HEIGHT).max(0.5);
+    let scale = window_scale * settings.scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+// egui menus follow the scale setting too, but they wait for the mouse button
+// to come up - otherwise the slider you're dragging moves out from under you
+fn scale_menus(
+    settings: Res<UiSettings>,
+    mut q_egui: Query<(&mut EguiContextSettings, &mut EguiContext), With<PrimaryWindow>>,
+) {
+    let scale = settings.scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+
+    for (mut egui_settings, mut context) in q_egui.iter_mut() {
+        if egui_settings.scale_factor != scale && !context.get_mut().is_using_pointer() {
+            egui_settings.scale_factor = scale;
+        }
+    }
+}
+
+fn apply_safe_area(settings: Res<UiSettings>, mut q_safe_area: Query<(&mut Node, Ref<SafeArea>)>) {
+    let inset = Val::Percent(settings.safe_area_fraction() * 100.);
+
+    for (mut node, safe_area) in q_safe_area.iter_mut() {
+        if settings.is_changed() || safe_area.is_added() {
+            node.left = inset;
+            node.right = inset;
+            node.top = inset;
+            node.bottom = inset;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        assert_settings_round_trip,
+        nudged,
+    };
+
+    #[test]
+    fn ui_settings_round_trips() { assert_settings_round_trip(nudged::<UiSettings>()); }
+}
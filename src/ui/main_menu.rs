@@ -0,0 +1,306 @@
+use crate::{
+    camera::RenderLayer,
+    despawn::despawn,
+    global_input::{
+        toggle_active,
+        GlobalAction,
+        MenuAction,
+    },
+    replay::{
+        InputRecorder,
+        ReplayLog,
+    },
+    schedule::FrameSet,
+    state::GameState,
+    ui::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+        SettingsMenu,
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+use leafwing_input_manager::{
+    plugin::InputManagerSystem,
+    prelude::ActionState,
+};
+
+/// between the splash and the first run - play, replay, settings, options or
+/// quit, with the camera drifting round the boundary behind it. replay plays
+/// back the last recording (see src/replay), or in the browser whichever .ntr
+/// file is picked. the arrows (or the d-pad) and enter pick, or the mouse.
+/// options is the rebind panel, and the menu leaves the keys alone while that
+/// or the settings menu is open - enter there is for them
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MainMenu>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(OnExit(GameState::MainMenu), despawn_main_menu)
+            .add_systems(
+                Update,
+                navigate_main_menu
+                    .in_set(FrameSet::Input)
+                    .run_if(in_state(GameState::MainMenu))
+                    .run_if(toggle_active(true, GlobalAction::RebindControls)),
+            )
+            .add_systems(
+                Update,
+                show_main_menu_selection
+                    .in_set(FrameSet::Ui)
+                    .run_if(in_state(GameState::MainMenu)),
+            )
+            // before anything reads the actions, so everything that toggles on
+            // the rebind key sees the same press
+            .add_systems(
+                PreUpdate,
+                open_options
+                    .in_set(InputManagerSystem::ManualControl)
+                    .run_if(in_state(GameState::MainMenu)),
+            );
+
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(
+            Update,
+            play_picked_replay
+                .in_set(FrameSet::Input)
+                .run_if(in_state(GameState::MainMenu)),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MainMenuEntry {
+    Play,
+    Replay,
+    Settings,
+    Options,
+    Quit,
+}
+
+impl MainMenuEntry {
+    const ALL: [MainMenuEntry; 5] = [
+        MainMenuEntry::Play,
+        MainMenuEntry::Replay,
+        MainMenuEntry::Settings,
+        MainMenuEntry::Options,
+        MainMenuEntry::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MainMenuEntry::Play => "play",
+            MainMenuEntry::Replay => "replay",
+            MainMenuEntry::Settings => "settings",
+            MainMenuEntry::Options => "options",
+            MainMenuEntry::Quit => "quit",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct MainMenu {
+    selected:      usize,
+    // the rebind panel is toggled by its key - options presses it for you,
+    // next frame
+    open_options:  bool,
+    #[cfg(target_arch = "wasm32")]
+    picked_replay: Option<crate::web::PickedFile>,
+}
+
+#[derive(Component, Debug)]
+struct MainMenuRoot;
+
+#[derive(Component, Debug, Clone, Copy)]
+struct MainMenuButton(MainMenuEntry);
+
+fn spawn_main_menu(mut commands: Commands, mut menu: ResMut<MainMenu>, theme: Res<UiTheme>) {
+    menu.selected = 0;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.margin / 2.),
+                ..default()
+            },
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            MainMenuRoot,
+            Name::new("MainMenu"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("nateroids"),
+                TextFont {
+                    font_size: theme.font_size_big,
+                    ..default()
+                },
+                TextColor(theme.text),
+            ));
+            for entry in MainMenuEntry::ALL {
+                parent.spawn((
+                    Text::new(entry.label()),
+                    TextFont {
+                        font_size: theme.font_size,
+                        ..default()
+                    },
+                    TextColor(theme.text_muted),
+                    Button,
+                    MainMenuButton(entry),
+                ));
+            }
+        });
+}
+
+fn despawn_main_menu(mut commands: Commands, q_root: Query<Entity, With<MainMenuRoot>>) {
+    for entity in q_root.iter() {
+        despawn(&mut commands, entity);
+    }
+}
+
+// hovering an entry selects it, same as the arrows getting there. every run
+// starts from the run setup screen, so play goes there rather than straight
+// into the game - and so does replay, which run setup hands straight on
+#[allow(clippy::too_many_arguments)]
+fn navigate_main_menu(
+    menu_input: Res<ActionState<MenuAction>>,
+    mut menu: ResMut<MainMenu>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    #[cfg(not(target_arch = "wasm32"))] replay_config: Res<crate::replay::ReplayConfig>,
+    mut recorder: ResMut<InputRecorder>,
+    mut announcements: EventWriter<Announcement>,
+    q_buttons: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if settings_menu.is_open() {
+        return;
+    }
+
+    let count = MainMenuEntry::ALL.len();
+    if menu_input.just_pressed(&MenuAction::Up) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+    if menu_input.just_pressed(&MenuAction::Down) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    let mut clicked = None;
+    for (interaction, button) in q_buttons.iter() {
+        if *interaction == Interaction::None {
+            continue;
+        }
+        if let Some(index) = MainMenuEntry::ALL.iter().position(|entry| *entry == button.0) {
+            menu.selected = index;
+        }
+        if *interaction == Interaction::Pressed {
+            clicked = Some(button.0);
+        }
+    }
+
+    let entry = if let Some(entry) = clicked {
+        entry
+    } else if menu_input.just_pressed(&MenuAction::Select) {
+        MainMenuEntry::ALL[menu.selected]
+    } else {
+        return;
+    };
+
+    match entry {
+        MainMenuEntry::Play => next_state.set(GameState::RunSetup),
+        #[cfg(not(target_arch = "wasm32"))]
+        MainMenuEntry::Replay => play_replay(
+            crate::replay::load_replay(&replay_config.path),
+            &mut recorder,
+            &mut next_state,
+            &mut announcements,
+        ),
+        // a replay someone sent you is a download, not something in
+        // localStorage - play_picked_replay takes it from here
+        #[cfg(target_arch = "wasm32")]
+        MainMenuEntry::Replay => match crate::web::pick_file(".ntr") {
+            Ok(picked) => menu.picked_replay = Some(picked),
+            Err(e) => play_replay(Err(e), &mut recorder, &mut next_state, &mut announcements),
+        },
+        MainMenuEntry::Settings => settings_menu.request_open(),
+        MainMenuEntry::Options => menu.open_options = true,
+        MainMenuEntry::Quit => {
+            exit.send(AppExit::Success);
+        },
+    }
+}
+
+fn play_replay(
+    loaded: Result<ReplayLog, String>,
+    recorder: &mut InputRecorder,
+    next_state: &mut NextState<GameState>,
+    announcements: &mut EventWriter<Announcement>,
+) {
+    match loaded {
+        Ok(log) => {
+            recorder.play(log);
+            next_state.set(GameState::RunSetup);
+        },
+        Err(e) => {
+            announcements.send(Announcement::new(
+                e,
+                AnnouncementStyle::Warning,
+                AnnouncementPriority::Normal,
+            ));
+        },
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn play_picked_replay(
+    mut menu: ResMut<MainMenu>,
+    mut recorder: ResMut<InputRecorder>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut announcements: EventWriter<Announcement>,
+) {
+    let Some(picked) = menu.picked_replay.as_ref().and_then(|picked| picked.take()) else {
+        return;
+    };
+    menu.picked_replay = None;
+
+    play_replay(
+        picked.and_then(|text| ReplayLog::from_text(&text)),
+        &mut recorder,
+        &mut next_state,
+        &mut announcements,
+    );
+}
+
+fn open_options(mut menu: ResMut<MainMenu>, mut global_input: ResMut<ActionState<GlobalAction>>) {
+    if std::mem::take(&mut menu.open_options) {
+        global_input.press(&GlobalAction::RebindControls);
+    }
+}
+
+fn show_main_menu_selection(
+    menu: Res<MainMenu>,
+    theme: Res<UiTheme>,
+    mut q_entries: Query<(&MainMenuButton, &mut TextColor)>,
+) {
+    let selected = MainMenuEntry::ALL[menu.selected];
+    for (button, mut color) in q_entries.iter_mut() {
+        let wanted = if button.0 == selected {
+            theme.accent
+        } else {
+            theme.text_muted
+        };
+        if color.0 != wanted {
+            color.0 = wanted;
+        }
+    }
+}
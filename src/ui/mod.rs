@@ -0,0 +1,90 @@
+mod aim_assist;
+mod announcements;
+mod bindings;
+mod combo_meter;
+mod control_card;
+mod game_over;
+mod hud;
+mod input_help;
+mod layout;
+mod main_menu;
+mod pause_menu;
+#[cfg(dev_tools)]
+mod picking;
+mod rebind_panel;
+mod run_setup;
+mod settings_menu;
+mod ship_status;
+mod theme;
+mod threat_indicators;
+mod touch_controls;
+
+pub use crate::ui::{
+    announcements::{
+        Announcement,
+        AnnouncementPriority,
+        AnnouncementStyle,
+    },
+    layout::UiSettings,
+    settings_menu::SettingsMenu,
+    theme::UiTheme,
+};
+
+use crate::ui::{
+    aim_assist::AimAssistPlugin,
+    announcements::AnnouncementsPlugin,
+    combo_meter::ComboMeterPlugin,
+    control_card::ControlCardPlugin,
+    game_over::GameOverPlugin,
+    hud::HudPlugin,
+    input_help::InputHelpPlugin,
+    layout::LayoutPlugin,
+    main_menu::MainMenuPlugin,
+    pause_menu::PauseMenuPlugin,
+    rebind_panel::RebindPanelPlugin,
+    run_setup::RunSetupPlugin,
+    settings_menu::SettingsMenuPlugin,
+    ship_status::ShipStatusPlugin,
+    threat_indicators::ThreatIndicatorsPlugin,
+    touch_controls::TouchInputPlugin,
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::EguiPlugin;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        // the inspectors bring egui along in dev builds. release builds still
+        // need it for the player facing windows - settings, rebinding, run
+        // setup, the control card and the input help - as bevy_ui has no
+        // sliders or text boxes to build them from. the debug windows - time
+        // overlay, physics debug, fold labels - are dev tool builds only, and
+        // the console has no key to open it outside them
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+
+        app.init_resource::<UiTheme>()
+            .register_type::<UiTheme>()
+            .add_plugins(AimAssistPlugin)
+            .add_plugins(AnnouncementsPlugin)
+            .add_plugins(ComboMeterPlugin)
+            .add_plugins(ControlCardPlugin)
+            .add_plugins(GameOverPlugin)
+            .add_plugins(HudPlugin)
+            .add_plugins(InputHelpPlugin)
+            .add_plugins(LayoutPlugin)
+            .add_plugins(MainMenuPlugin)
+            .add_plugins(PauseMenuPlugin)
+            .add_plugins(RebindPanelPlugin)
+            .add_plugins(RunSetupPlugin)
+            .add_plugins(SettingsMenuPlugin)
+            .add_plugins(ShipStatusPlugin)
+            .add_plugins(ThreatIndicatorsPlugin)
+            .add_plugins(TouchInputPlugin);
+
+        #[cfg(dev_tools)]
+        app.add_plugins(picking::PickingPlugin);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{
+    actor::Health,
+    audio::UiSoundEvent,
+    camera::RenderLayer,
+    despawn::{
+        DestructionQueue,
+        DestructionReason,
+    },
+    global_input::MenuAction,
+    schedule::FrameSet,
+    scoring::reset_score,
+    state::{
+        is_paused,
+        GameState,
+        IsPaused,
+    },
+    ui::{
+        SettingsMenu,
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+use leafwing_input_manager::prelude::ActionState;
+
+/// what's up while the game is paused - resume, settings, restart or quit,
+/// picked with the arrows (or the d-pad) and enter. escape resumes. the
+/// settings menu pauses the game too, and has the screen to itself while it's
+/// open
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PauseMenu>()
+            .add_systems(Startup, spawn_pause_menu)
+            .add_systems(OnEnter(IsPaused::Paused), reset_selection)
+            .add_systems(
+                Update,
+                navigate_pause_menu
+                    .in_set(FrameSet::Input)
+                    .run_if(in_state(IsPaused::Paused)),
+            )
+            .add_systems(Update, show_pause_menu.in_set(FrameSet::Ui))
+            // a restart skips the run setup screen - it's the same run again
+            .add_systems(
+                OnEnter(GameState::RunSetup),
+                (reset_score, skip_run_setup).run_if(restarting),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseEntry {
+    Resume,
+    Settings,
+    Restart,
+    Quit,
+}
+
+impl PauseEntry {
+    const ALL: [PauseEntry; 4] = [
+        PauseEntry::Resume,
+        PauseEntry::Settings,
+        PauseEntry::Restart,
+        PauseEntry::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PauseEntry::Resume => "resume",
+            PauseEntry::Settings => "settings",
+            PauseEntry::Restart => "restart",
+            PauseEntry::Quit => "quit",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct PauseMenu {
+    selected:   usize,
+    restarting: bool,
+}
+
+fn restarting(menu: Res<PauseMenu>) -> bool { menu.restarting }
+
+#[derive(Component, Debug)]
+struct PauseMenuRoot;
+
+#[derive(Component, Debug, Clone, Copy)]
+struct PauseMenuEntry(PauseEntry);
+
+fn spawn_pause_menu(mut commands: Commands, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.margin / 2.),
+                ..default()
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            PauseMenuRoot,
+            Name::new("PauseMenu"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("paused"),
+                TextFont {
+                    font_size: theme.font_size_big,
+                    ..default()
+                },
+                TextColor(theme.text),
+            ));
+            for entry in PauseEntry::ALL {
+                parent.spawn((
+                    Text::new(entry.label()),
+                    TextFont {
+                        font_size: theme.font_size,
+                        ..default()
+                    },
+                    TextColor(theme.text_muted),
+                    PauseMenuEntry(entry),
+                ));
+            }
+        });
+}
+
+fn reset_selection(mut menu: ResMut<PauseMenu>) { menu.selected = 0; }
+
+#[allow(clippy::too_many_arguments)]
+fn navigate_pause_menu(
+    menu_input: Res<ActionState<MenuAction>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut menu: ResMut<PauseMenu>,
+    mut queue: ResMut<DestructionQueue>,
+    q_actors: Query<Entity, With<Health>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ui_sounds: EventWriter<UiSoundEvent>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if settings_menu.is_open() {
+        return;
+    }
+
+    let count = PauseEntry::ALL.len();
+    if menu_input.just_pressed(&MenuAction::Up) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+    if menu_input.just_pressed(&MenuAction::Down) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    let entry = if menu_input.just_pressed(&MenuAction::Back) {
+        PauseEntry::Resume
+    } else if menu_input.just_pressed(&MenuAction::Select) {
+        PauseEntry::ALL[menu.selected]
+    } else {
+        return;
+    };
+
+    match entry {
+        PauseEntry::Resume => {
+            if let Some(resumed) = state.get().with_paused(false) {
+                next_state.set(resumed);
+                ui_sounds.send(UiSoundEvent::PauseClosed);
+            }
+        },
+        // the game's already paused, so closing settings comes back here
+        PauseEntry::Settings => settings_menu.request_open(),
+        // everything goes the way it does at game over and the run starts
+        // again from the top - a new spaceship, new nateroids, the score and
+        // the teleport stats back to nothing
+        PauseEntry::Restart => {
+            for entity in q_actors.iter() {
+                queue.mark(entity, DestructionReason::GameOver);
+            }
+            menu.restarting = true;
+            next_state.set(GameState::RunSetup);
+        },
+        PauseEntry::Quit => {
+            exit.send(AppExit::Success);
+        },
+    }
+}
+
+fn skip_run_setup(mut menu: ResMut<PauseMenu>, mut next_state: ResMut<NextState<GameState>>) {
+    menu.restarting = false;
+    next_state.set(GameState::InGame {
+        paused:     false,
+        inspecting: false,
+    });
+}
+
+fn show_pause_menu(
+    menu: Res<PauseMenu>,
+    settings_menu: Res<SettingsMenu>,
+    paused: Option<Res<State<IsPaused>>>,
+    theme: Res<UiTheme>,
+    mut q_root: Query<&mut Visibility, With<PauseMenuRoot>>,
+    mut q_entries: Query<(&PauseMenuEntry, &mut TextColor)>,
+) {
+    let open = is_paused(paused) && !settings_menu.is_open();
+    let shown = if open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in q_root.iter_mut() {
+        visibility.set_if_neq(shown);
+    }
+
+    let selected = PauseEntry::ALL[menu.selected];
+    for (entry, mut color) in q_entries.iter_mut() {
+        let wanted = if entry.0 == selected {
+            theme.accent
+        } else {
+            theme.text_muted
+        };
+        if color.0 != wanted {
+            color.0 = wanted;
+        }
+    }
+}
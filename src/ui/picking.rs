@@ -0,0 +1,244 @@
+use crate::{
+    actor::{
+        Aabb,
+        ActorKind,
+        Health,
+    },
+    camera::{
+        CameraTarget,
+        PrimaryCamera,
+    },
+    schedule::FrameSet,
+    ui::UiTheme,
+};
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::{
+    bevy_egui::{
+        egui,
+        EguiContext,
+        EguiContextSettings,
+    },
+    bevy_inspector,
+};
+use bevy_rapier3d::prelude::{
+    QueryFilter,
+    ReadDefaultRapierContext,
+    Velocity,
+};
+
+// how far off the selection's outline sits so it doesn't z-fight the model
+const OUTLINE_PADDING: f32 = 1.15;
+
+/// click an actor to select it - it gets an outline, a label that floats along
+/// with it and an inspector window with a follow button. escape or a click on
+/// nothing lets go. the selection is the entity, so it survives wrapping, and
+/// it's dropped when the entity goes away
+///
+/// dev tool builds only - it's a debugging aid, like the inspectors
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Selection>()
+            .add_systems(
+                Update,
+                (deselect_on_escape, pick_with_mouse)
+                    .chain()
+                    .in_set(FrameSet::Input),
+            )
+            .add_systems(
+                Update,
+                (forget_missing_selection, draw_selection_outline)
+                    .chain()
+                    .in_set(FrameSet::Visual),
+            )
+            .add_systems(
+                Update,
+                draw_selection_label
+                    .in_set(FrameSet::Ui)
+                    .run_if(something_selected),
+            )
+            // exclusive - the inspector needs the whole world
+            .add_systems(
+                Update,
+                draw_selection_inspector
+                    .after(FrameSet::Ui)
+                    .run_if(something_selected),
+            );
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct Selection(Option<Entity>);
+
+fn something_selected(selection: Res<Selection>) -> bool { selection.0.is_some() }
+
+// escape pauses as well - there's no taking the key away from the pause menu
+fn deselect_on_escape(keys: Res<ButtonInput<KeyCode>>, mut selection: ResMut<Selection>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        selection.0 = None;
+    }
+}
+
+// a click egui wanted is egui's - clicking an inspector field shouldn't pick
+// whatever is behind it
+fn pick_with_mouse(
+    mouse: Res<ButtonInput<MouseButton>>,
+    rapier_context: ReadDefaultRapierContext,
+    mut selection: ResMut<Selection>,
+    mut q_egui: Query<&mut EguiContext, With<PrimaryWindow>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    q_actors: Query<(), With<ActorKind>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let over_egui = q_egui.iter_mut().any(|mut context| {
+        let context = context.get_mut();
+        context.is_pointer_over_area() || context.wants_pointer_input()
+    });
+    if over_egui {
+        return;
+    }
+
+    let (Ok(window), Ok((camera, camera_transform))) = (q_window.get_single(), q_camera.get_single()) else {
+        return;
+    };
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+
+    let hit = rapier_context
+        .single()
+        .cast_ray(ray.origin, *ray.direction, f32::MAX, true, QueryFilter::default())
+        .map(|(entity, _)| entity)
+        .filter(|entity| q_actors.contains(*entity));
+
+    selection.0 = hit;
+}
+
+fn forget_missing_selection(mut selection: ResMut<Selection>, q_actors: Query<(), With<ActorKind>>) {
+    if selection.0.is_some_and(|entity| !q_actors.contains(entity)) {
+        selection.0 = None;
+    }
+}
+
+fn draw_selection_outline(
+    mut gizmos: Gizmos,
+    selection: Res<Selection>,
+    theme: Res<UiTheme>,
+    q_actors: Query<(&Transform, &Aabb)>,
+) {
+    let Some((transform, aabb)) = selection.0.and_then(|entity| q_actors.get(entity).ok()) else {
+        return;
+    };
+
+    gizmos.cuboid(
+        Transform::from_translation(transform.transform_point(aabb.center()))
+            .with_rotation(transform.rotation)
+            .with_scale(aabb.size() * transform.scale * OUTLINE_PADDING),
+        theme.accent,
+    );
+}
+
+// a few numbers that float along next to the selection - the inspector has
+// the rest
+fn draw_selection_label(
+    selection: Res<Selection>,
+    mut q_egui: Query<(&mut EguiContext, &EguiContextSettings), With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    q_actors: Query<(&Transform, &ActorKind, Option<&Health>, Option<&Velocity>)>,
+) {
+    let Some(entity) = selection.0 else {
+        return;
+    };
+    let (
+        Ok((mut context, egui_settings)),
+        Ok((camera, camera_transform)),
+        Ok((transform, kind, health, velocity)),
+    ) = (
+        q_egui.get_single_mut(),
+        q_camera.get_single(),
+        q_actors.get(entity),
+    )
+    else {
+        return;
+    };
+    let Ok(viewport_position) = camera.world_to_viewport(camera_transform, transform.translation) else {
+        return;
+    };
+
+    // egui's points are logical pixels divided by its own scale
+    let position = viewport_position / egui_settings.scale_factor;
+
+    let mut lines = vec![format!("{kind} {entity}")];
+    if let Some(health) = health {
+        lines.push(format!("health {:.0}", health.0));
+    }
+    if let Some(velocity) = velocity {
+        lines.push(format!("speed {:.1}", velocity.linvel.length()));
+    }
+
+    egui::Area::new(egui::Id::new("selection label"))
+        .fixed_pos(egui::pos2(position.x + 16., position.y + 16.))
+        .interactable(false)
+        .show(context.get_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                for line in lines {
+                    ui.label(egui::RichText::new(line).monospace().small());
+                }
+            });
+        });
+}
+
+fn draw_selection_inspector(world: &mut World) {
+    let Some(entity) = world.resource::<Selection>().0 else {
+        return;
+    };
+    let Ok(mut context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    // egui contexts are cheap handles - keeping one means the world is free for
+    // the inspector
+    let context = context.get_mut().clone();
+
+    let following = world.resource::<CameraTarget>().is_following(entity);
+    let mut follow = following;
+    let mut open = true;
+
+    egui::Window::new("selection")
+        .open(&mut open)
+        .default_width(320.)
+        .anchor(egui::Align2::LEFT_TOP, [10., 10.])
+        .show(&context, |ui| {
+            ui.toggle_value(&mut follow, "follow")
+                .on_hover_text("move the camera along with it - home lets go");
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                bevy_inspector::ui_for_entity(world, entity, ui);
+            });
+        });
+
+    if follow != following {
+        let mut target = world.resource_mut::<CameraTarget>();
+        if follow {
+            target.follow(entity);
+        } else {
+            target.stop();
+        }
+    }
+    if !open {
+        world.resource_mut::<Selection>().0 = None;
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    actor::SpaceshipControl,
+    global_input::{
+        toggle_active,
+        GlobalAction,
+    },
+    key_bindings::{
+        Binding,
+        KeyBindings,
+    },
+    schedule::FrameSet,
+    ui::bindings::{
+        action_name,
+        BindingFormatter,
+        ControlRow,
+    },
+};
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use leafwing_input_manager::prelude::{
+    Actionlike,
+    InputControlKind,
+    InputMap,
+    ModifierKey,
+};
+use std::iter;
+use strum::IntoEnumIterator;
+
+/// every ship and global action with what it's bound to and a button to bind
+/// it to something else - the next key (with shift if it's held) or mouse
+/// button pressed away from the panel. escape lets go without changing
+/// anything. a rebind goes into KeyBindings, and it's saved to the settings
+/// file as soon as it's made
+pub struct RebindPanelPlugin;
+
+impl Plugin for RebindPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RebindCapture>().add_systems(
+            Update,
+            (
+                capture_rebind.in_set(FrameSet::Input),
+                draw_rebind_panel.in_set(FrameSet::Ui),
+            )
+                .run_if(toggle_active(false, GlobalAction::RebindControls)),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RebindTarget {
+    Ship(SpaceshipControl),
+    Global(GlobalAction),
+}
+
+// the action waiting for its new input, if one is
+#[derive(Resource, Debug, Default)]
+struct RebindCapture(Option<RebindTarget>);
+
+fn capture_rebind(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut capture: ResMut<RebindCapture>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut q_egui: Query<&mut EguiContext, With<PrimaryWindow>>,
+) {
+    let Some(target) = capture.0 else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        capture.0 = None;
+        return;
+    }
+
+    // a modifier on its own is only the start of a chord - with more than one
+    // held, ctrl wins over alt and alt over shift
+    let modifiers = [ModifierKey::Control, ModifierKey::Alt, ModifierKey::Shift];
+    let held = modifiers
+        .into_iter()
+        .find(|modifier| keys.any_pressed([modifier.left(), modifier.right()]));
+    let key = keys
+        .get_just_pressed()
+        .find(|&&key| {
+            !modifiers
+                .iter()
+                .any(|modifier| key == modifier.left() || key == modifier.right())
+        })
+        .map(|&key| match held {
+            Some(ModifierKey::Control) => Binding::Ctrl(key),
+            Some(ModifierKey::Alt) => Binding::Alt(key),
+            Some(_) => Binding::Shift(key),
+            None => Binding::Key(key),
+        });
+
+    // a click on the panel is a click on the panel
+    let over_egui = q_egui.iter_mut().any(|mut context| {
+        let context = context.get_mut();
+        context.is_pointer_over_area() || context.wants_pointer_input()
+    });
+    let button = mouse
+        .get_just_pressed()
+        .next()
+        .filter(|_| !over_egui)
+        .map(|&button| Binding::Mouse(button));
+
+    let Some(input) = key.or(button) else {
+        return;
+    };
+
+    match target {
+        RebindTarget::Ship(action) => key_bindings.rebind(action, input),
+        RebindTarget::Global(action) => key_bindings.rebind(action, input),
+    }
+    KeyBindings::save(&mut commands);
+    capture.0 = None;
+}
+
+fn draw_rebind_panel(
+    mut q_egui: Query<&mut EguiContext, With<PrimaryWindow>>,
+    mut capture: ResMut<RebindCapture>,
+    ship: Res<InputMap<SpaceshipControl>>,
+    global: Res<InputMap<GlobalAction>>,
+    q_gamepads: Query<(), With<Gamepad>>,
+) {
+    let Ok(mut context) = q_egui.get_single_mut() else {
+        return;
+    };
+
+    let formatter = BindingFormatter {
+        gamepad_connected: !q_gamepads.is_empty(),
+    };
+    let bound_to = |rows: Vec<ControlRow>| {
+        rows.into_iter()
+            .next()
+            .map_or_else(|| "-".to_string(), |row| row.bindings)
+    };
+
+    egui::Window::new("rebind controls")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .show(context.get_mut(), |ui| {
+            egui::Grid::new("rebind controls")
+                .num_columns(3)
+                .spacing([12., 4.])
+                .show(ui, |ui| {
+                    let ship_rows = SpaceshipControl::iter().map(|action| {
+                        (
+                            RebindTarget::Ship(action),
+                            action_name(&action),
+                            bound_to(formatter.rows(&ship, iter::once(action))),
+                        )
+                    });
+                    // the dev tool actions aren't there to bind in a release build
+                    let global_rows = GlobalAction::iter()
+                        .filter(|action| !action.default_bindings().is_empty())
+                        .map(|action| {
+                            (
+                                RebindTarget::Global(action),
+                                action_name(&action),
+                                bound_to(formatter.rows(&global, iter::once(action))),
+                            )
+                        });
+
+                    for (target, action, bindings) in ship_rows.chain(global_rows) {
+                        ui.label(action);
+                        ui.weak(bindings);
+                        // one key can't drive an axis both ways
+                        let axis = matches!(
+                            target,
+                            RebindTarget::Ship(action) if action.input_control_kind() != InputControlKind::Button
+                        );
+                        if axis {
+                            ui.add_enabled(false, egui::Button::new("rebind"))
+                                .on_disabled_hover_text("a pair of keys - change it in the settings file");
+                        } else if capture.0 == Some(target) {
+                            ui.strong("press a key...");
+                        } else if ui.button("rebind").clicked() {
+                            capture.0 = Some(target);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
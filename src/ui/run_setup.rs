@@ -0,0 +1,104 @@
+use crate::{
+    mutators::{
+        Mutator,
+        Mutators,
+    },
+    schedule::FrameSet,
+    scoring::Score,
+    state::GameState,
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use strum::IntoEnumIterator;
+
+/// the screen before every run - pick the mutators and go. whatever was picked
+/// last time is still picked
+pub struct RunSetupPlugin;
+
+impl Plugin for RunSetupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            draw_run_setup
+                .in_set(FrameSet::Ui)
+                .run_if(in_state(GameState::RunSetup)),
+        );
+    }
+}
+
+fn draw_run_setup(
+    mut contexts: Query<&mut EguiContext>,
+    mut mutators: ResMut<Mutators>,
+    score: Res<Score>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Ok(mut context) = contexts.get_single_mut() else {
+        return;
+    };
+
+    // edit a copy and only write back a real change, same as the settings menu
+    let mut draft = *mutators;
+    let mut start = false;
+
+    egui::Window::new("next run")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .show(context.get_mut(), |ui| {
+            ui.label("mutators");
+            for mutator in Mutator::iter() {
+                let mut on = draft.is_active(mutator);
+                ui.checkbox(&mut on, mutator.label())
+                    .on_hover_text(mutator.description());
+                draft.active.set(mutator, on);
+
+                if on {
+                    ui.indent(mutator.label(), |ui| strength_slider(ui, mutator, &mut draft));
+                }
+            }
+
+            if score.best > 0 {
+                ui.separator();
+                let best = if score.best_mutators.is_empty() {
+                    format!("best: {}", score.best)
+                } else {
+                    format!("best: {} ({})", score.best, score.best_mutators.summary())
+                };
+                ui.label(best);
+            }
+
+            ui.separator();
+            start = ui.button("start").clicked() || ui.input(|input| input.key_pressed(egui::Key::Enter));
+        });
+
+    mutators.set_if_neq(draft);
+
+    if start {
+        next_state.set(GameState::InGame {
+            paused:     false,
+            inspecting: false,
+        });
+    }
+}
+
+// the mutators that are just on or off have nothing to show
+fn strength_slider(ui: &mut egui::Ui, mutator: Mutator, mutators: &mut Mutators) {
+    match mutator {
+        Mutator::GravityWell => {
+            ui.add(egui::Slider::new(&mut mutators.gravity_well_strength, 5.0..=60.0).text("pull"));
+        },
+        Mutator::FastMissiles => {
+            ui.add(egui::Slider::new(&mut mutators.missile_speed, 1.5..=4.0).text("speed"));
+        },
+        Mutator::TinyShip => {
+            ui.add(egui::Slider::new(&mut mutators.ship_scale, 0.25..=0.9).text("size"));
+        },
+        Mutator::GiantRocks => {
+            ui.add(egui::Slider::new(&mut mutators.rock_scale, 1.5..=5.0).text("size"));
+        },
+        Mutator::NoWrap | Mutator::MirrorControls => (),
+    }
+}
@@ -0,0 +1,546 @@
+use crate::{
+    actor::{
+        SpaceshipControlConfig,
+        SteeringMode,
+    },
+    audio::AudioSettings,
+    camera::CameraConfig,
+    global_input::{
+        GlobalAction,
+        MenuAction,
+    },
+    schedule::FrameSet,
+    score_reporting::ScoreReportingSettings,
+    state::GameState,
+    ui::{
+        layout::{
+            MAX_SAFE_AREA,
+            MAX_UI_SCALE,
+            MIN_UI_SCALE,
+        },
+        UiSettings,
+    },
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{
+    egui,
+    EguiContext,
+};
+use leafwing_input_manager::prelude::ActionState;
+use std::ops::RangeInclusive;
+
+// how long a new control scheme gets before it goes back on its own - long
+// enough to try it, short enough that a scheme you can't drive undoes itself
+const STEERING_CONFIRM_SECS: f32 = 10.;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsMenu>()
+            .add_systems(Update, toggle_settings_menu.in_set(FrameSet::Input))
+            .add_systems(Update, draw_settings_menu.in_set(FrameSet::Ui));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum SettingsTab {
+    #[default]
+    Audio,
+    Video,
+    Controls,
+    Gameplay,
+}
+
+impl SettingsTab {
+    const ALL: [SettingsTab; 4] = [
+        SettingsTab::Audio,
+        SettingsTab::Video,
+        SettingsTab::Controls,
+        SettingsTab::Gameplay,
+    ];
+
+    fn step(self, by: isize) -> Self {
+        let count = Self::ALL.len() as isize;
+        let index = Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0) as isize;
+        Self::ALL[(index + by).rem_euclid(count) as usize]
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SettingsTab::Audio => "audio",
+            SettingsTab::Video => "video",
+            SettingsTab::Controls => "controls",
+            SettingsTab::Gameplay => "gameplay",
+        }
+    }
+}
+
+// what everything looked like when the menu opened - cancel puts it all back
+#[derive(Debug, Clone)]
+struct SettingsSnapshot {
+    audio:           AudioSettings,
+    camera:          CameraConfig,
+    control:         SpaceshipControlConfig,
+    steering:        SteeringMode,
+    score_reporting: ScoreReportingSettings,
+    ui:              UiSettings,
+}
+
+// a control scheme that's been applied but not kept yet
+#[derive(Debug, Clone, Copy)]
+struct SteeringConfirm {
+    previous:  SteeringMode,
+    remaining: f32,
+}
+
+/// the settings screen edits the real resources live so you can hear and see
+/// the change as you drag - a snapshot taken on open is what cancel goes
+/// back to. saving keeps what you've got, and the settings file picks it up
+/// from there. the control scheme is the exception - picking one only takes
+/// effect on apply, and goes back by itself unless you keep it in time
+#[derive(Resource, Debug, Default)]
+pub struct SettingsMenu {
+    tab:              SettingsTab,
+    snapshot:         Option<SettingsSnapshot>,
+    // if opening the menu paused the game, closing it unpauses
+    paused_game:      bool,
+    // set by the main and pause menus - opens the menu next time round
+    open_requested:   bool,
+    // the press that opened the menu isn't meant for the menu
+    just_opened:      bool,
+    // which row the arrows (or the d-pad) are on
+    focus:            usize,
+    pending_steering: Option<SteeringMode>,
+    steering_confirm: Option<SteeringConfirm>,
+}
+
+impl SettingsMenu {
+    pub fn is_open(&self) -> bool { self.snapshot.is_some() }
+
+    pub fn request_open(&mut self) { self.open_requested = true; }
+}
+
+enum MenuButton {
+    Save,
+    Cancel,
+    ResetTab,
+}
+
+enum SteeringButton {
+    Apply,
+    Keep,
+    Revert,
+}
+
+// keyboard and gamepad focus for one frame of the menu. every row a widget
+// goes through counts, the one the focus is on gets highlighted, left and
+// right nudge it and select flips or presses it
+struct Nav {
+    focus:  usize,
+    row:    usize,
+    nudge:  f32,
+    select: bool,
+}
+
+impl Nav {
+    fn focused(&mut self, response: &egui::Response) -> bool {
+        let focused = self.row == self.focus;
+        self.row += 1;
+        if focused {
+            response.ctx.highlight_widget(response.id);
+            response.scroll_to_me(None);
+        }
+        focused
+    }
+
+    fn slider(&mut self, ui: &mut egui::Ui, value: &mut f32, range: RangeInclusive<f32>, text: &str) {
+        let response = ui.add(egui::Slider::new(value, range.clone()).text(text));
+        if self.focused(&response) && self.nudge != 0. {
+            // twenty nudges get from one end to the other
+            let step = (range.end() - range.start()) / 20.;
+            *value = (*value + self.nudge * step).clamp(*range.start(), *range.end());
+        }
+    }
+
+    fn toggle(&mut self, ui: &mut egui::Ui, value: &mut bool, text: &str) {
+        let response = ui.checkbox(value, text);
+        if self.focused(&response) && (self.select || self.nudge != 0.) {
+            *value = !*value;
+        }
+    }
+
+    fn button(&mut self, ui: &mut egui::Ui, text: &str) -> bool {
+        let response = ui.button(text);
+        let clicked = response.clicked();
+        (self.focused(&response) && self.select) || clicked
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn toggle_settings_menu(
+    user_input: Res<ActionState<GlobalAction>>,
+    mut menu: ResMut<SettingsMenu>,
+    audio: Res<AudioSettings>,
+    camera: Res<CameraConfig>,
+    control: Res<SpaceshipControlConfig>,
+    steering: Res<SteeringMode>,
+    score_reporting: Res<ScoreReportingSettings>,
+    ui_settings: Res<UiSettings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let requested = std::mem::take(&mut menu.open_requested);
+    if !(user_input.just_pressed(&GlobalAction::Settings) || requested) || menu.is_open() {
+        return;
+    }
+
+    menu.snapshot = Some(SettingsSnapshot {
+        audio:           audio.clone(),
+        camera:          *camera,
+        control:         *control,
+        steering:        *steering,
+        score_reporting: score_reporting.clone(),
+        ui:              *ui_settings,
+    });
+    menu.just_opened = true;
+    menu.focus = 0;
+    menu.pending_steering = None;
+    menu.steering_confirm = None;
+
+    menu.paused_game = false;
+    if let Some(paused) = state.get().with_paused(true) {
+        next_state.set(paused);
+        menu.paused_game = true;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_settings_menu(
+    mut contexts: Query<&mut EguiContext>,
+    menu_input: Res<ActionState<MenuAction>>,
+    time: Res<Time<Real>>,
+    mut menu: ResMut<SettingsMenu>,
+    mut audio: ResMut<AudioSettings>,
+    mut camera: ResMut<CameraConfig>,
+    mut control: ResMut<SpaceshipControlConfig>,
+    mut steering_mode: ResMut<SteeringMode>,
+    mut score_reporting: ResMut<ScoreReportingSettings>,
+    mut ui_settings: ResMut<UiSettings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !menu.is_open() {
+        return;
+    }
+
+    let Ok(mut context) = contexts.get_single_mut() else {
+        return;
+    };
+    let context = context.get_mut();
+
+    // edit copies and only write back real changes - otherwise the resources
+    // would look changed every frame the menu is open
+    let mut draft_audio = audio.clone();
+    let mut draft_camera = *camera;
+    let mut draft_control = *control;
+    let mut draft_steering = *steering_mode;
+    let mut draft_score_reporting = score_reporting.clone();
+    let mut draft_ui = *ui_settings;
+    let mut tab = menu.tab;
+    let mut action = None;
+    let mut steering_action = None;
+
+    // the arrows are for the text box while you're typing the leaderboard url
+    let navigating = !std::mem::take(&mut menu.just_opened) && !context.wants_keyboard_input();
+    let pressed = |action: MenuAction| navigating && menu_input.just_pressed(&action);
+
+    if pressed(MenuAction::PreviousTab) {
+        tab = tab.step(-1);
+    }
+    if pressed(MenuAction::NextTab) {
+        tab = tab.step(1);
+    }
+    if tab != menu.tab {
+        menu.focus = 0;
+    }
+    if pressed(MenuAction::Up) {
+        menu.focus = menu.focus.saturating_sub(1);
+    }
+    if pressed(MenuAction::Down) {
+        menu.focus += 1;
+    }
+    if pressed(MenuAction::Back) {
+        action = Some(MenuButton::Cancel);
+    }
+
+    let mut nav = Nav {
+        focus:  menu.focus,
+        row:    0,
+        nudge:  f32::from(pressed(MenuAction::Right)) - f32::from(pressed(MenuAction::Left)),
+        select: pressed(MenuAction::Select),
+    };
+
+    // what the steering row shows - the one you've picked, even before it's
+    // applied
+    let shown_steering = menu.pending_steering.unwrap_or(draft_steering);
+    let mut steering = shown_steering;
+    let confirm_remaining = menu.steering_confirm.map(|confirm| confirm.remaining);
+
+    egui::Window::new("settings")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .show(context, |ui| {
+            ui.horizontal(|ui| {
+                for candidate in SettingsTab::ALL {
+                    ui.selectable_value(&mut tab, candidate, candidate.label());
+                }
+            });
+            ui.separator();
+
+            match tab {
+                SettingsTab::Audio => audio_tab(ui, &mut nav, &mut draft_audio),
+                SettingsTab::Video => video_tab(ui, &mut nav, &mut draft_camera, &mut draft_ui),
+                SettingsTab::Controls => {
+                    steering_action = controls_tab(
+                        ui,
+                        &mut nav,
+                        &mut draft_camera,
+                        &mut draft_control,
+                        &mut steering,
+                        menu.pending_steering.is_some(),
+                        confirm_remaining,
+                    );
+                },
+                SettingsTab::Gameplay => {
+                    gameplay_tab(ui, &mut nav, &mut draft_control, &mut draft_score_reporting)
+                },
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if nav.button(ui, "save") {
+                    action = Some(MenuButton::Save);
+                }
+                if nav.button(ui, "cancel") {
+                    action = Some(MenuButton::Cancel);
+                }
+                if nav.button(ui, "reset tab to defaults") {
+                    action = Some(MenuButton::ResetTab);
+                }
+            });
+        });
+
+    menu.tab = tab;
+    menu.focus = menu.focus.min(nav.row.saturating_sub(1));
+
+    // picking a scheme only queues it up - apply is what puts it in
+    if steering != shown_steering {
+        menu.pending_steering = (steering != draft_steering).then_some(steering);
+    }
+
+    match steering_action {
+        Some(SteeringButton::Apply) => {
+            if let Some(pending) = menu.pending_steering.take() {
+                menu.steering_confirm = Some(SteeringConfirm {
+                    previous:  draft_steering,
+                    remaining: STEERING_CONFIRM_SECS,
+                });
+                draft_steering = pending;
+            }
+        },
+        Some(SteeringButton::Keep) => menu.steering_confirm = None,
+        Some(SteeringButton::Revert) => {
+            menu.pending_steering = None;
+            if let Some(confirm) = menu.steering_confirm.take() {
+                draft_steering = confirm.previous;
+            }
+        },
+        None => (),
+    }
+
+    // real time, as the menu pauses the game
+    if let Some(confirm) = menu.steering_confirm.as_mut() {
+        confirm.remaining -= time.delta_secs();
+        if confirm.remaining <= 0. {
+            draft_steering = confirm.previous;
+            menu.steering_confirm = None;
+        }
+    }
+
+    match action {
+        Some(MenuButton::ResetTab) => match tab {
+            SettingsTab::Audio => draft_audio = AudioSettings::default(),
+            SettingsTab::Video => {
+                let defaults = CameraConfig::default();
+                draft_camera.clear_color = defaults.clear_color;
+                draft_camera.darkening_factor = defaults.darkening_factor;
+                draft_camera.bloom_intensity = defaults.bloom_intensity;
+                draft_camera.bloom_low_frequency_boost = defaults.bloom_low_frequency_boost;
+                draft_camera.bloom_high_pass_frequency = defaults.bloom_high_pass_frequency;
+                draft_ui = UiSettings::default();
+            },
+            SettingsTab::Controls => {
+                let camera_defaults = CameraConfig::default();
+                draft_camera.orbit_speed = camera_defaults.orbit_speed;
+                draft_camera.zoom_sensitivity_mouse = camera_defaults.zoom_sensitivity_mouse;
+                draft_camera.zoom_sensitivity_pinch = camera_defaults.zoom_sensitivity_pinch;
+                let control_defaults = SpaceshipControlConfig::default();
+                draft_control.rotation_speed = control_defaults.rotation_speed;
+                draft_control.aim_turn_speed = control_defaults.aim_turn_speed;
+                // the default scheme still has to be applied like any other
+                let steering_default = SteeringMode::default();
+                menu.pending_steering = (steering_default != draft_steering).then_some(steering_default);
+            },
+            SettingsTab::Gameplay => {
+                let defaults = SpaceshipControlConfig::default();
+                draft_control.acceleration = defaults.acceleration;
+                draft_control.max_speed = defaults.max_speed;
+                draft_score_reporting = ScoreReportingSettings::default();
+            },
+        },
+        Some(MenuButton::Cancel) => {
+            if let Some(snapshot) = menu.snapshot.clone() {
+                draft_audio = snapshot.audio;
+                draft_camera = snapshot.camera;
+                draft_control = snapshot.control;
+                draft_steering = snapshot.steering;
+                draft_score_reporting = snapshot.score_reporting;
+                draft_ui = snapshot.ui;
+            }
+        },
+        // saving keeps an applied scheme and drops one that never was
+        Some(MenuButton::Save) | None => (),
+    }
+
+    audio.set_if_neq(draft_audio);
+    camera.set_if_neq(draft_camera);
+    control.set_if_neq(draft_control);
+    steering_mode.set_if_neq(draft_steering);
+    score_reporting.set_if_neq(draft_score_reporting);
+    ui_settings.set_if_neq(draft_ui);
+
+    if matches!(action, Some(MenuButton::Save | MenuButton::Cancel)) {
+        menu.snapshot = None;
+        menu.pending_steering = None;
+        menu.steering_confirm = None;
+
+        if menu.paused_game {
+            if let Some(resumed) = state.get().with_paused(false) {
+                next_state.set(resumed);
+            }
+        }
+    }
+}
+
+fn audio_tab(ui: &mut egui::Ui, nav: &mut Nav, audio: &mut AudioSettings) {
+    nav.slider(ui, &mut audio.master_volume, 0.0..=100.0, "master");
+    nav.slider(ui, &mut audio.sfx_volume, 0.0..=100.0, "sound effects");
+    nav.slider(ui, &mut audio.music_volume, 0.0..=100.0, "music");
+    nav.slider(ui, &mut audio.ui_volume, 0.0..=100.0, "interface");
+    nav.toggle(ui, &mut audio.music_muted, "mute music");
+    nav.toggle(ui, &mut audio.mute_in_background, "mute in background");
+    nav.toggle(ui, &mut audio.spatial, "spatial audio");
+}
+
+fn video_tab(ui: &mut egui::Ui, nav: &mut Nav, camera: &mut CameraConfig, ui_settings: &mut UiSettings) {
+    nav.slider(
+        ui,
+        &mut ui_settings.scale,
+        MIN_UI_SCALE..=MAX_UI_SCALE,
+        "interface scale",
+    );
+    nav.slider(ui, &mut ui_settings.safe_area, 0.0..=MAX_SAFE_AREA, "safe area %");
+    nav.slider(ui, &mut camera.bloom_intensity, 0.0..=1.0, "bloom");
+    nav.slider(ui, &mut camera.bloom_low_frequency_boost, 0.0..=1.0, "bloom glow");
+    nav.slider(
+        ui,
+        &mut camera.bloom_high_pass_frequency,
+        0.0..=1.0,
+        "bloom threshold",
+    );
+}
+
+fn controls_tab(
+    ui: &mut egui::Ui,
+    nav: &mut Nav,
+    camera: &mut CameraConfig,
+    control: &mut SpaceshipControlConfig,
+    steering: &mut SteeringMode,
+    pending: bool,
+    confirm_remaining: Option<f32>,
+) -> Option<SteeringButton> {
+    let mut pressed = None;
+
+    let response = ui
+        .horizontal(|ui| {
+            ui.label("steering");
+            ui.selectable_value(steering, SteeringMode::TankControls, "tank controls");
+            ui.selectable_value(steering, SteeringMode::MouseAim, "mouse aim");
+        })
+        .response;
+    // there's only the two, so any nudge swaps them
+    if nav.focused(&response) && (nav.select || nav.nudge != 0.) {
+        *steering = match *steering {
+            SteeringMode::TankControls => SteeringMode::MouseAim,
+            SteeringMode::MouseAim => SteeringMode::TankControls,
+        };
+    }
+
+    if let Some(remaining) = confirm_remaining {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "keep these controls? going back in {}s",
+                remaining.ceil()
+            ));
+            if nav.button(ui, "keep") {
+                pressed = Some(SteeringButton::Keep);
+            }
+            if nav.button(ui, "revert") {
+                pressed = Some(SteeringButton::Revert);
+            }
+        });
+    } else if pending {
+        ui.horizontal(|ui| {
+            if nav.button(ui, "apply") {
+                pressed = Some(SteeringButton::Apply);
+            }
+            if nav.button(ui, "revert") {
+                pressed = Some(SteeringButton::Revert);
+            }
+        });
+    }
+
+    match *steering {
+        SteeringMode::TankControls => {
+            nav.slider(ui, &mut control.rotation_speed, 1.0..=10.0, "ship turn speed")
+        },
+        SteeringMode::MouseAim => nav.slider(ui, &mut control.aim_turn_speed, 1.0..=20.0, "ship turn speed"),
+    }
+    nav.slider(ui, &mut camera.orbit_speed, 0.0..=1.0, "camera orbit speed");
+    nav.slider(ui, &mut camera.zoom_sensitivity_mouse, 1.0..=20.0, "mouse zoom");
+    nav.slider(ui, &mut camera.zoom_sensitivity_pinch, 10.0..=200.0, "pinch zoom");
+
+    pressed
+}
+
+fn gameplay_tab(
+    ui: &mut egui::Ui,
+    nav: &mut Nav,
+    control: &mut SpaceshipControlConfig,
+    score_reporting: &mut ScoreReportingSettings,
+) {
+    nav.slider(ui, &mut control.acceleration, 30.0..=300.0, "ship acceleration");
+    nav.slider(ui, &mut control.max_speed, 50.0..=300.0, "ship top speed");
+
+    ui.separator();
+    nav.toggle(ui, &mut score_reporting.enabled, "submit scores");
+    // not a row - there's no typing with a d-pad
+    ui.add_enabled_ui(score_reporting.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("leaderboard url");
+            ui.text_edit_singleline(&mut score_reporting.endpoint);
+        });
+    });
+}
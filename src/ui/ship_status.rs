@@ -0,0 +1,232 @@
+use crate::{
+    actor::{
+        ActorDamaged,
+        ActorKind,
+        Spaceship,
+        SpaceshipConfig,
+    },
+    camera::RenderLayer,
+    schedule::FrameSet,
+    ui::{
+        hud::HudRoot,
+        layout::{
+            safe_area_node,
+            SafeArea,
+        },
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+
+const BAR_WIDTH: f32 = 240.;
+const BAR_HEIGHT: f32 = 10.;
+// how quickly the bar slides toward its new value - higher is snappier
+const BAR_EASE_RATE: f32 = 6.;
+const DAMAGE_FLASH_SECONDS: f32 = 0.25;
+// below this the bar pulses
+const LOW_HEALTH: f32 = 0.25;
+const PULSE_RATE: f32 = 6.;
+// the vignette starts to creep in below this and is at its strongest at zero
+const VIGNETTE_HEALTH: f32 = 0.5;
+const VIGNETTE_MAX_ALPHA: f32 = 0.6;
+// how far into the screen the red reaches
+const VIGNETTE_BLUR: f32 = 160.;
+
+pub struct ShipStatusPlugin;
+
+impl Plugin for ShipStatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_ship_status).add_systems(
+            Update,
+            (
+                reset_on_spawn,
+                take_damage.run_if(resource_exists::<SpaceshipConfig>),
+                animate_health_bar,
+                update_vignette,
+            )
+                .chain()
+                .in_set(FrameSet::Ui),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+struct HealthBar {
+    // both are fractions of the spaceship's starting health
+    shown:  f32,
+    target: f32,
+    flash:  f32,
+}
+
+impl Default for HealthBar {
+    fn default() -> Self {
+        Self {
+            shown:  1.0,
+            target: 1.0,
+            flash:  0.0,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct LowHealthVignette;
+
+fn spawn_ship_status(mut commands: Commands, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::FlexEnd,
+                ..safe_area_node(&theme)
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            SafeArea,
+            Name::new("ShipStatus"),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(theme.panel),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            width: Val::Percent(100.),
+                            height: Val::Percent(100.),
+                            ..default()
+                        },
+                        BackgroundColor(theme.health),
+                        HealthBar::default(),
+                        Name::new("HealthBar"),
+                    ));
+                });
+        });
+
+    // the vignette is four boxes sitting just off each edge of the screen - all
+    // we see is the blur of their shadows leaking in
+    let edges = [
+        (Val::Percent(-100.), Val::Auto, Val::Auto, Val::Auto),
+        (Val::Auto, Val::Percent(-100.), Val::Auto, Val::Auto),
+        (Val::Auto, Val::Auto, Val::Percent(-100.), Val::Auto),
+        (Val::Auto, Val::Auto, Val::Auto, Val::Percent(-100.)),
+    ];
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            Name::new("LowHealthVignette"),
+        ))
+        .with_children(|parent| {
+            for (top, bottom, left, right) in edges {
+                parent.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        top,
+                        bottom,
+                        left,
+                        right,
+                        ..default()
+                    },
+                    BoxShadow {
+                        color:         Color::NONE,
+                        x_offset:      Val::ZERO,
+                        y_offset:      Val::ZERO,
+                        spread_radius: Val::ZERO,
+                        blur_radius:   Val::Px(VIGNETTE_BLUR),
+                    },
+                    LowHealthVignette,
+                ));
+            }
+        });
+}
+
+// a new spaceship - whether from a respawn or a restart - always starts full
+fn reset_on_spawn(q_spawned: Query<(), Added<Spaceship>>, mut q_bar: Query<&mut HealthBar>) {
+    if q_spawned.is_empty() {
+        return;
+    }
+
+    for mut bar in q_bar.iter_mut() {
+        *bar = HealthBar::default();
+    }
+}
+
+fn take_damage(
+    mut damaged: EventReader<ActorDamaged>,
+    spaceship_config: Res<SpaceshipConfig>,
+    mut q_bar: Query<&mut HealthBar>,
+) {
+    for event in damaged.read() {
+        if event.kind != ActorKind::Spaceship {
+            continue;
+        }
+
+        for mut bar in q_bar.iter_mut() {
+            bar.target = (event.health / spaceship_config.0.health.max(1.)).clamp(0.0, 1.0);
+            bar.flash = DAMAGE_FLASH_SECONDS;
+        }
+    }
+}
+
+fn animate_health_bar(
+    theme: Res<UiTheme>,
+    time: Res<Time>,
+    mut q_bar: Query<(&mut HealthBar, &mut Node, &mut BackgroundColor)>,
+) {
+    let delta_seconds = time.delta_secs();
+
+    for (mut bar, mut node, mut background) in q_bar.iter_mut() {
+        bar.shown += (bar.target - bar.shown) * (1.0 - (-BAR_EASE_RATE * delta_seconds).exp());
+        bar.flash = (bar.flash - delta_seconds).max(0.0);
+
+        node.width = Val::Percent(bar.shown * 100.);
+
+        let mut color = theme.health;
+        if bar.shown < LOW_HEALTH {
+            let pulse = 0.5 + 0.5 * (time.elapsed_secs() * PULSE_RATE).sin();
+            color = color.mix(&theme.danger, pulse);
+        }
+        if bar.flash > 0.0 {
+            color = color.mix(&theme.danger, bar.flash / DAMAGE_FLASH_SECONDS);
+        }
+
+        background.0 = color;
+    }
+}
+
+fn update_vignette(
+    theme: Res<UiTheme>,
+    q_bar: Query<&HealthBar>,
+    mut q_vignette: Query<&mut BoxShadow, With<LowHealthVignette>>,
+) {
+    let Ok(bar) = q_bar.get_single() else {
+        return;
+    };
+
+    let strength = ((VIGNETTE_HEALTH - bar.shown) / VIGNETTE_HEALTH).clamp(0.0, 1.0);
+    let color = theme.danger.with_alpha(strength * VIGNETTE_MAX_ALPHA);
+
+    for mut shadow in q_vignette.iter_mut() {
+        shadow.color = color;
+    }
+}
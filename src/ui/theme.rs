@@ -0,0 +1,36 @@
+use bevy::{
+    color::palettes::tailwind,
+    prelude::*,
+};
+
+/// every color and font size the ui uses comes from here so the whole thing
+/// can be restyled in one place
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct UiTheme {
+    pub text:          Color,
+    pub text_muted:    Color,
+    pub accent:        Color,
+    pub danger:        Color,
+    pub health:        Color,
+    pub panel:         Color,
+    pub font_size:     f32,
+    pub font_size_big: f32,
+    pub margin:        f32,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            text:          Color::from(tailwind::SLATE_100),
+            text_muted:    Color::from(tailwind::SLATE_400),
+            accent:        Color::from(tailwind::BLUE_300),
+            danger:        Color::from(tailwind::RED_500),
+            health:        Color::from(tailwind::EMERALD_400),
+            panel:         Color::from(tailwind::SLATE_900.with_alpha(0.6)),
+            font_size:     18.,
+            font_size_big: 32.,
+            margin:        16.,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    actor::{
+        ActorKind,
+        Spaceship,
+    },
+    camera::{
+        PrimaryCamera,
+        RenderLayer,
+    },
+    playfield::SpatialIndex,
+    schedule::FrameSet,
+    state::PlayingGame,
+    ui::{
+        hud::HudRoot,
+        UiSettings,
+        UiTheme,
+    },
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+
+// only the closest few get an arrow - any more and the edges of the screen
+// turn into a porcupine
+const MAX_THREAT_INDICATORS: usize = 6;
+const INDICATOR_SIZE: f32 = 24.;
+// how far in from the edge of the safe area the arrows sit, in ui units
+const EDGE_MARGIN: f32 = 24.;
+const NEAR_SCALE: f32 = 1.4;
+const FAR_SCALE: f32 = 0.6;
+// anything further off than this isn't a threat yet - it also keeps the query
+// to the cells around the spaceship rather than the whole boundary
+const THREAT_RADIUS: f32 = 100.;
+
+pub struct ThreatIndicatorsPlugin;
+
+impl Plugin for ThreatIndicatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_threat_indicators).add_systems(
+            Update,
+            update_threat_indicators
+                .in_set(FrameSet::Ui)
+                .run_if(in_state(PlayingGame)),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+struct ThreatIndicator;
+
+#[derive(Component, Debug)]
+struct ThreatChevron;
+
+// a fixed pool of arrows that get handed out to the nearest threats each frame.
+// each arrow is a ">" made of two bars - the parent is rotated to point at
+// the threat
+fn spawn_threat_indicators(mut commands: Commands, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            HudRoot,
+            Name::new("ThreatIndicators"),
+        ))
+        .with_children(|parent| {
+            for _ in 0..MAX_THREAT_INDICATORS {
+                parent
+                    .spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Px(INDICATOR_SIZE),
+                            height: Val::Px(INDICATOR_SIZE),
+                            ..default()
+                        },
+                        Visibility::Hidden,
+                        ThreatIndicator,
+                    ))
+                    .with_children(|parent| {
+                        for (top, angle) in [(0.3, 0.7), (0.7, -0.7)] {
+                            parent.spawn((
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    width: Val::Percent(60.),
+                                    height: Val::Percent(15.),
+                                    left: Val::Percent(25.),
+                                    top: Val::Percent(top * 100. - 7.5),
+                                    ..default()
+                                },
+                                Transform::from_rotation(Quat::from_rotation_z(angle)),
+                                BackgroundColor(theme.danger),
+                                ThreatChevron,
+                            ));
+                        }
+                    });
+            }
+        });
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_threat_indicators(
+    spatial_index: Res<SpatialIndex>,
+    theme: Res<UiTheme>,
+    ui_settings: Res<UiSettings>,
+    ui_scale: Res<UiScale>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
+    q_spaceship: Query<&Transform, (With<Spaceship>, Without<ThreatIndicator>)>,
+    mut q_indicators: Query<(&mut Node, &mut Transform, &mut Visibility, &Children), With<ThreatIndicator>>,
+    mut q_chevrons: Query<&mut BackgroundColor, With<ThreatChevron>>,
+) {
+    let Ok((camera, camera_transform)) = q_camera.get_single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    // directions are measured from the spaceship when there is one - that's who
+    // is in danger - and they go the short way round through the walls
+    let origin = q_spaceship
+        .get_single()
+        .map(|transform| transform.translation)
+        .unwrap_or_else(|_| camera_transform.translation());
+
+    let mut threats: Vec<(f32, Vec3)> = spatial_index
+        .within_of_kind(origin, THREAT_RADIUS, ActorKind::Nateroid)
+        .filter(|neighbor| !is_on_screen(camera, camera_transform, neighbor.position))
+        .map(|neighbor| (neighbor.distance_squared.sqrt(), origin + neighbor.delta))
+        .collect();
+
+    threats.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let view_from_world = camera_transform.affine().inverse();
+    let half_viewport = viewport / 2.;
+    let safe_area = viewport * ui_settings.safe_area_fraction();
+    let reach = (half_viewport - safe_area - Vec2::splat(EDGE_MARGIN * ui_scale.0)).max(Vec2::ONE);
+
+    let mut threats = threats.into_iter().take(MAX_THREAT_INDICATORS);
+
+    for (mut node, mut transform, mut visibility, children) in q_indicators.iter_mut() {
+        let Some((distance, position)) = threats.next() else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+
+        // view space rather than a projection - a projection flips things that
+        // are behind the camera, but in view space x and y still say which
+        // way you'd have to turn to face it
+        let view = view_from_world.transform_point3(position);
+        let direction = Vec2::new(view.x, view.y).normalize_or(Vec2::NEG_Y);
+
+        // push out along the direction until we hit the edge rectangle
+        let to_edge = (reach / direction.abs().max(Vec2::splat(f32::EPSILON))).min_element();
+        let screen = Vec2::new(
+            half_viewport.x + direction.x * to_edge,
+            half_viewport.y - direction.y * to_edge,
+        );
+
+        // node positions are in ui units, which UiScale stretches
+        node.left = Val::Px(screen.x / ui_scale.0 - INDICATOR_SIZE / 2.);
+        node.top = Val::Px(screen.y / ui_scale.0 - INDICATOR_SIZE / 2.);
+
+        // ui y points down so the rotation goes the other way
+        let closeness = 1.0 - (distance / THREAT_RADIUS).clamp(0.0, 1.0);
+        transform.rotation = Quat::from_rotation_z(-direction.y.atan2(direction.x));
+        transform.scale = Vec3::splat(FAR_SCALE.lerp(NEAR_SCALE, closeness));
+
+        let color = theme.text_muted.mix(&theme.danger, closeness);
+        for &child in children.iter() {
+            if let Ok(mut background) = q_chevrons.get_mut(child) {
+                background.0 = color;
+            }
+        }
+    }
+}
+
+fn is_on_screen(camera: &Camera, camera_transform: &GlobalTransform, position: Vec3) -> bool {
+    camera
+        .world_to_ndc(camera_transform, position)
+        .is_some_and(|ndc| ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && (0.0..=1.0).contains(&ndc.z))
+}
@@ -0,0 +1,264 @@
+use crate::{
+    actor::SpaceshipControl,
+    camera::{
+        CameraControl,
+        PrimaryCamera,
+        RenderLayer,
+    },
+    key_bindings::RebuildInputMaps,
+    replay::is_playing_back,
+    schedule::FrameSet,
+    state::PlayingGame,
+    ui::UiTheme,
+};
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+};
+use leafwing_input_manager::{
+    plugin::InputManagerSystem,
+    prelude::*,
+};
+
+const BUTTON_SIZE: f32 = 84.;
+const STICK_SIZE: f32 = 140.;
+const ZONE_ALPHA: f32 = 0.18;
+// how far a full push of the stick orbits each frame - about what a brisk
+// mouse drag does
+const STICK_ORBIT: f32 = 12.;
+
+/// a way to play without a keyboard - buttons for turning, thrust and fire and
+/// a stick for orbiting the camera, drawn over the game. nothing has to switch
+/// it on: it shows up the first time the screen is touched and goes away
+/// again as soon as a key or a gamepad button is pressed
+///
+/// every finger on the screen counts at once, so you can turn and fire
+/// together - bevy's own Interaction only follows the first touch
+pub struct TouchInputPlugin;
+
+impl Plugin for TouchInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            .add_systems(Startup, spawn_touch_overlay)
+            .add_systems(
+                Update,
+                (detect_touch_device, show_touch_overlay)
+                    .chain()
+                    .in_set(FrameSet::Input),
+            )
+            // after leafwing has read the devices so what's pressed here sticks
+            .add_systems(
+                PreUpdate,
+                drive_touch_actions
+                    .run_if(touch_active)
+                    .run_if(not(is_playing_back))
+                    .in_set(InputManagerSystem::ManualControl),
+            );
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct TouchControls {
+    active: bool,
+}
+
+fn touch_active(touch: Res<TouchControls>) -> bool { touch.active }
+
+#[derive(Component, Debug)]
+struct TouchOverlay;
+
+// a button that holds its action down for as long as it's touched
+#[derive(Component, Debug, Clone, Copy)]
+struct TouchButton(SpaceshipControl);
+
+#[derive(Component, Debug)]
+struct TouchStick;
+
+fn spawn_touch_overlay(mut commands: Commands, theme: Res<UiTheme>) {
+    let zone = |size: f32| {
+        (
+            Node {
+                width: Val::Px(size),
+                height: Val::Px(size),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(theme.accent.with_alpha(ZONE_ALPHA)),
+            BorderRadius::MAX,
+        )
+    };
+    let label = |text: &str| {
+        (
+            Text::new(text),
+            TextFont {
+                font_size: theme.font_size,
+                ..default()
+            },
+            TextColor(theme.text_muted),
+        )
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::FlexEnd,
+                padding: UiRect::all(Val::Px(theme.margin * 2.)),
+                ..default()
+            },
+            Visibility::Hidden,
+            RenderLayers::from_layers(RenderLayer::Ui.layers()),
+            TouchOverlay,
+            Name::new("TouchOverlay"),
+        ))
+        .with_children(|parent| {
+            // turning on the left thumb
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(theme.margin),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((zone(BUTTON_SIZE), TouchButton(SpaceshipControl::TurnLeft)))
+                        .with_child(label("<"));
+                    parent
+                        .spawn((zone(BUTTON_SIZE), TouchButton(SpaceshipControl::TurnRight)))
+                        .with_child(label(">"));
+                });
+
+            parent
+                .spawn((zone(STICK_SIZE), TouchStick))
+                .with_child(label("orbit"));
+
+            // thrust and fire on the right
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(theme.margin),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((zone(BUTTON_SIZE), TouchButton(SpaceshipControl::Thrust)))
+                        .with_child(label("thrust"));
+                    parent
+                        .spawn((zone(BUTTON_SIZE), TouchButton(SpaceshipControl::Fire)))
+                        .with_child(label("fire"));
+                });
+        });
+}
+
+// a touch brings the overlay up, a key or a gamepad button puts it away
+fn detect_touch_device(
+    touches: Res<Touches>,
+    keys: Res<ButtonInput<KeyCode>>,
+    q_gamepads: Query<&Gamepad>,
+    mut touch: ResMut<TouchControls>,
+    mut rebuilds: EventWriter<RebuildInputMaps>,
+    mut ship_map: ResMut<InputMap<SpaceshipControl>>,
+    mut q_ship_maps: Query<&mut InputMap<SpaceshipControl>>,
+) {
+    let physical = keys.get_just_pressed().next().is_some()
+        || q_gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+
+    if touches.any_just_pressed() && !touch.active {
+        // with nothing bound, leafwing leaves the ship's actions to the
+        // buttons - otherwise it lets go of them every frame and they're
+        // pressed all over again
+        touch.active = true;
+        *ship_map = InputMap::default();
+        for mut input_map in q_ship_maps.iter_mut() {
+            *input_map = InputMap::default();
+        }
+    } else if physical && touch.active {
+        // apply_key_bindings puts the keyboard's maps back
+        touch.active = false;
+        rebuilds.send(RebuildInputMaps);
+    }
+}
+
+fn show_touch_overlay(
+    touch: Res<TouchControls>,
+    playing: Option<Res<State<PlayingGame>>>,
+    mut q_overlay: Query<&mut Visibility, With<TouchOverlay>>,
+) {
+    let shown = if touch.active && playing.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in q_overlay.iter_mut() {
+        visibility.set_if_neq(shown);
+    }
+}
+
+// the node's rect is in physical pixels and a touch is in logical ones
+fn touched(node: &ComputedNode, transform: &GlobalTransform, position: Vec2) -> bool {
+    Rect::from_center_size(transform.translation().truncate(), node.size())
+        .contains(position / node.inverse_scale_factor())
+}
+
+fn drive_touch_actions(
+    touches: Res<Touches>,
+    q_buttons: Query<(&TouchButton, &ComputedNode, &GlobalTransform)>,
+    q_stick: Query<(&ComputedNode, &GlobalTransform), With<TouchStick>>,
+    mut ship_state: ResMut<ActionState<SpaceshipControl>>,
+    mut q_ship_states: Query<&mut ActionState<SpaceshipControl>>,
+    mut q_camera: Query<&mut ActionState<CameraControl>, With<PrimaryCamera>>,
+) {
+    let mut held: Vec<SpaceshipControl> = Vec::new();
+    for (button, node, transform) in q_buttons.iter() {
+        if touches
+            .iter()
+            .any(|finger| touched(node, transform, finger.position()))
+        {
+            held.push(button.0);
+        }
+    }
+
+    let apply = |action_state: &mut ActionState<SpaceshipControl>| {
+        for (button, ..) in q_buttons.iter() {
+            let action = button.0;
+            // the thrust button is the throttle all the way forwards
+            if action.input_control_kind() == InputControlKind::Axis {
+                action_state.set_value(&action, if held.contains(&action) { 1.0 } else { 0.0 });
+            } else if held.contains(&action) && !action_state.pressed(&action) {
+                action_state.press(&action);
+            } else if !held.contains(&action) && action_state.pressed(&action) {
+                action_state.release(&action);
+            }
+        }
+    };
+    apply(&mut ship_state);
+    for mut action_state in q_ship_states.iter_mut() {
+        apply(&mut action_state);
+    }
+
+    // the first finger on the stick pushes it - how far from the middle, out
+    // to the edge, is how hard it orbits
+    let Ok((node, transform)) = q_stick.get_single() else {
+        return;
+    };
+    let Some(finger) = touches
+        .iter()
+        .find(|finger| touched(node, transform, finger.start_position()))
+    else {
+        return;
+    };
+    let center = transform.translation().truncate() * node.inverse_scale_factor();
+    let radius = node.size().x * node.inverse_scale_factor() / 2.;
+    let push = ((finger.position() - center) / radius).clamp_length_max(1.);
+
+    for mut action_state in q_camera.iter_mut() {
+        if let Some(dual_axis_data) = action_state.dual_axis_data_mut(&CameraControl::Orbit) {
+            dual_axis_data.pair = push * STICK_ORBIT;
+            dual_axis_data.update_pair = push * STICK_ORBIT;
+        }
+    }
+}
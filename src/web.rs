@@ -0,0 +1,148 @@
+use crate::schedule::FrameSet;
+use bevy::{
+    prelude::*,
+    window::{
+        PresentMode,
+        PrimaryWindow,
+    },
+};
+use std::sync::{
+    Arc,
+    Mutex,
+};
+use wasm_bindgen::{
+    closure::Closure,
+    JsCast,
+    JsValue,
+};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob,
+    HtmlAnchorElement,
+    HtmlInputElement,
+    Url,
+};
+
+// the canvas index.html puts on the page
+const CANVAS_SELECTOR: &str = "#nateroids";
+
+/// the browser build draws into the page's canvas and keeps it the size of the
+/// browser window, at the display's real pixel density so text stays crisp
+pub struct WebPlugin;
+
+impl Plugin for WebPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, follow_device_pixel_ratio.in_set(FrameSet::Input));
+    }
+}
+
+// fit_canvas_to_parent resizes the canvas whenever its parent changes size -
+// index.html makes the parent the whole browser window
+pub fn primary_window() -> Window {
+    Window {
+        canvas: Some(CANVAS_SELECTOR.into()),
+        fit_canvas_to_parent: true,
+        // otherwise space scrolls the page and the arrow keys move it around
+        prevent_default_event_handling: true,
+        present_mode: PresentMode::AutoNoVsync, // Reduces input lag.
+        ..default()
+    }
+}
+
+// winit reads devicePixelRatio for the scale factor, but it doesn't always
+// hear about it changing (browser zoom, dragging between monitors) - when the
+// two disagree the page's value wins. ui sizes are in logical pixels so only
+// the sharpness changes
+fn follow_device_pixel_ratio(mut q_window: Query<&mut Window, With<PrimaryWindow>>) {
+    let Some(ratio) = web_sys::window().map(|window| window.device_pixel_ratio() as f32) else {
+        return;
+    };
+    let Ok(mut window) = q_window.get_single_mut() else {
+        return;
+    };
+
+    let wanted = ((window.resolution.base_scale_factor() - ratio).abs() > 0.01).then_some(ratio);
+    if window.resolution.scale_factor_override() != wanted {
+        window.resolution.set_scale_factor_override(wanted);
+    }
+}
+
+/// hands `contents` to the browser as a file to save - localStorage isn't
+/// somewhere anyone can get a file back out of
+pub fn download(file_name: &str, contents: &str) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("there's no page to download from")?;
+
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let blob = Blob::new_with_str_sequence(&parts).map_err(js_error)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let link: HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "an <a> that isn't an anchor".to_string())?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    Url::revoke_object_url(&url).map_err(js_error)
+}
+
+/// what the player picked with `pick_file`, once the browser has read it -
+/// there's nothing in it until then, and nothing ever if they cancel
+#[derive(Debug, Clone, Default)]
+pub struct PickedFile(Arc<Mutex<Option<Result<String, String>>>>);
+
+impl PickedFile {
+    pub fn take(&self) -> Option<Result<String, String>> { self.0.lock().ok()?.take() }
+}
+
+/// the browser's file picker, for files of the types in `accept`. it's read
+/// in the background - poll what comes back
+pub fn pick_file(accept: &str) -> Result<PickedFile, String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("there's no page to pick a file from")?;
+
+    let input: HtmlInputElement = document
+        .create_element("input")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "an <input> that isn't an input".to_string())?;
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let picked = PickedFile::default();
+    let on_change = Closure::<dyn FnMut()>::new({
+        let input = input.clone();
+        let picked = picked.clone();
+        move || {
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let picked = picked.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let text = JsFuture::from(file.text())
+                    .await
+                    .map_err(js_error)
+                    .and_then(|text| {
+                        text.as_string()
+                            .ok_or_else(|| "that isn't a text file".to_string())
+                    });
+                if let Ok(mut picked) = picked.0.lock() {
+                    *picked = Some(text);
+                }
+            });
+        }
+    });
+    input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+    // the input only goes away with the page
+    on_change.forget();
+    input.click();
+
+    Ok(picked)
+}
+
+fn js_error(error: JsValue) -> String { format!("{error:?}") }
@@ -0,0 +1,419 @@
+// golden images of the boundary and portal lines, to catch the portal arc
+// math going wrong near edges and corners without having to go and play
+// there. the line meshes are the ones the game builds, drawn by bevy into an
+// offscreen image and read back off the gpu:
+//
+// `cargo test --test golden` checks against tests/golden
+// `cargo test --test golden -- --bless` rewrites tests/golden - look at them
+// before committing them
+//
+// it's a test of its own with no harness (see Cargo.toml) so it can take
+// --bless. anything that doesn't match is written to target/golden with a
+// diff. it needs a gpu, or something that passes for one - on a box without
+// one mesa's llvmpipe does: `WGPU_BACKEND=gl cargo test --test golden`
+use bevy::{
+    app::PluginsState,
+    audio::AudioPlugin,
+    color::palettes::tailwind,
+    core_pipeline::tonemapping::Tonemapping,
+    log::LogPlugin,
+    prelude::*,
+    render::{
+        camera::{
+            RenderTarget,
+            ScalingMode,
+        },
+        gpu_readback::{
+            Readback,
+            ReadbackComplete,
+        },
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            Extent3d,
+            TextureDimension,
+            TextureFormat,
+            TextureUsages,
+        },
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use image::{
+    Rgba,
+    RgbaImage,
+};
+use nateroids::{
+    boundary_grid_mesh,
+    Boundary,
+    BoundaryFace,
+    CameraOrientation,
+    LineListBuilder,
+    Portal,
+};
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::ExitCode,
+};
+
+const FAILED_DIR: &str = "target/golden";
+// a multiple of 64 pixels, so the rows come back from the gpu unpadded
+const IMAGE_SIZE: u32 = 512;
+const MARGIN: f32 = 24.;
+// tilted so that the side faces aren't edge on - from straight down a portal
+// on the right wall would just be a line
+const VIEW_YAW: f32 = 0.5;
+const VIEW_PITCH: f32 = 0.45;
+const PORTAL_RADIUS: f32 = 20.;
+const PORTAL_RESOLUTION: u32 = 128;
+// the same lines this many readbacks in a row means the pipelines have all
+// compiled and the meshes are all on the gpu
+const SETTLED_READBACKS: u32 = 3;
+const MAX_FRAMES: u32 = 600;
+// a pixel matches if the other image has something close to it within a pixel
+// - enough to shrug off a different gpu rounding a line onto the next pixel
+const CHANNEL_TOLERANCE: u8 = 48;
+const MAX_MISMATCHED_FRACTION: f32 = 0.001;
+
+struct GoldenCase {
+    name:     &'static str,
+    boundary: Boundary,
+    portal:   Portal,
+}
+
+// the default boundary is 220 x 110 x 110 around the origin
+fn golden_cases() -> Vec<GoldenCase> {
+    let translated = Boundary {
+        transform: Transform::from_translation(Vec3::new(40., -25., 10.))
+            .with_scale(Boundary::default().scale()),
+        ..default()
+    };
+    let translation = translated.transform.translation;
+
+    // the corner portal again, on a box turned a little around y - the folds
+    // have to turn with it
+    let turned = Boundary {
+        transform: Transform::from_rotation(Quat::from_rotation_y(0.4)).with_scale(Boundary::default().scale()),
+        ..default()
+    };
+    let mut turned_portal = Portal::on_face(
+        BoundaryFace::Right,
+        turned.transform.rotation * Vec3::new(110., 45., 45.),
+        PORTAL_RADIUS,
+    );
+    turned_portal.normal = turned.face_normal(BoundaryFace::Right);
+
+    vec![
+        GoldenCase {
+            name:     "portal_on_face",
+            boundary: Boundary::default(),
+            portal:   Portal::on_face(BoundaryFace::Right, Vec3::new(110., 0., 0.), PORTAL_RADIUS),
+        },
+        GoldenCase {
+            name:     "portal_over_edge",
+            boundary: Boundary::default(),
+            portal:   Portal::on_face(BoundaryFace::Right, Vec3::new(110., 45., 0.), PORTAL_RADIUS),
+        },
+        GoldenCase {
+            name:     "portal_at_corner",
+            boundary: Boundary::default(),
+            portal:   Portal::on_face(BoundaryFace::Right, Vec3::new(110., 45., 45.), PORTAL_RADIUS),
+        },
+        GoldenCase {
+            name:     "portal_on_corner",
+            boundary: Boundary::default(),
+            portal:   Portal::on_face(BoundaryFace::Right, Vec3::new(110., 55., 55.), PORTAL_RADIUS),
+        },
+        GoldenCase {
+            name:     "translated_boundary",
+            boundary: translated,
+            portal:   Portal::on_face(
+                BoundaryFace::Top,
+                translation + Vec3::new(60., 55., 45.),
+                PORTAL_RADIUS,
+            ),
+        },
+        GoldenCase {
+            name:     "turned_boundary",
+            boundary: turned,
+            portal:   turned_portal,
+        },
+    ]
+}
+
+fn main() -> ExitCode {
+    let bless = std::env::args().any(|arg| arg == "--bless");
+    let cases = golden_cases();
+    let count = cases.len();
+    let mut failed = Vec::new();
+
+    for case in cases {
+        let rendered = render(&case);
+        let golden_path = golden_dir().join(format!("{}.png", case.name));
+
+        if bless {
+            std::fs::create_dir_all(golden_dir()).expect("unable to create tests/golden");
+            rendered
+                .save(&golden_path)
+                .unwrap_or_else(|e| panic!("unable to write {}: {e}", golden_path.display()));
+            println!("blessed {}", golden_path.display());
+            continue;
+        }
+
+        let golden = match image::open(&golden_path) {
+            Ok(golden) => golden.to_rgba8(),
+            Err(e) => {
+                failed.push(format!("{}: no golden image ({e}) - run with --bless", case.name));
+                continue;
+            },
+        };
+
+        if let Err((message, diff)) = compare(&rendered, &golden) {
+            write_failure(case.name, &rendered, diff.as_ref());
+            failed.push(format!("{}: {message}", case.name));
+        }
+    }
+
+    if failed.is_empty() {
+        println!("{count} golden images checked");
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("{}", failed.join("\n"));
+    ExitCode::FAILURE
+}
+
+fn golden_dir() -> PathBuf { PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden") }
+
+// the portal the same as update_portal_lines builds it
+fn portal_mesh(case: &GoldenCase) -> Mesh {
+    let mut portal_lines = LineListBuilder::default();
+    case.boundary.draw_portal(
+        &mut portal_lines,
+        &case.portal,
+        Color::from(tailwind::BLUE_600),
+        PORTAL_RESOLUTION,
+        &CameraOrientation::default(),
+    );
+    portal_lines.build()
+}
+
+#[derive(Resource, Default)]
+struct Readbacks {
+    latest:  Option<Vec<u8>>,
+    settled: u32,
+}
+
+// the boundary grid and the portal, the same as update_boundary_lines and
+// update_portal_lines show them, through an orthographic camera into an image
+fn render(case: &GoldenCase) -> RgbaImage {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window:       None,
+                exit_condition:       ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .disable::<WinitPlugin>()
+            .disable::<AudioPlugin>()
+            .disable::<LogPlugin>(),
+    )
+    .init_resource::<Readbacks>();
+
+    // what App::run does before the first update - the gpu is found
+    // asynchronously
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+
+    let target = new_target(app.world_mut());
+    spawn_lines(app.world_mut(), case);
+    spawn_camera(app.world_mut(), case, target.clone());
+    app.world_mut().spawn(Readback::texture(target)).observe(
+        |trigger: Trigger<ReadbackComplete>, mut readbacks: ResMut<Readbacks>| {
+            let data = &trigger.event().0;
+            // the image is black until the first frame that drew anything
+            let drawn = data.chunks_exact(4).any(|pixel| pixel[..3] != [0, 0, 0]);
+            if !drawn {
+                return;
+            }
+            if readbacks.latest.as_ref() == Some(data) {
+                readbacks.settled += 1;
+            } else {
+                readbacks.latest = Some(data.clone());
+                readbacks.settled = 0;
+            }
+        },
+    );
+
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        let readbacks = app.world().resource::<Readbacks>();
+        if readbacks.settled >= SETTLED_READBACKS {
+            let data = readbacks.latest.clone().unwrap_or_default();
+            return RgbaImage::from_raw(IMAGE_SIZE, IMAGE_SIZE, data)
+                .expect("the readback is the wrong size");
+        }
+    }
+    panic!("{}: the render never settled in {MAX_FRAMES} frames", case.name);
+}
+
+fn new_target(world: &mut World) -> Handle<Image> {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width:                 IMAGE_SIZE,
+            height:                IMAGE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage |=
+        TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING;
+    world.resource_mut::<Assets<Image>>().add(image)
+}
+
+// unlit with the vertex colors, the same as LineMaterial
+fn spawn_lines(world: &mut World, case: &GoldenCase) {
+    let material = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        });
+
+    for mesh in [boundary_grid_mesh(&case.boundary), portal_mesh(case)] {
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(mesh);
+        world.spawn((Mesh3d(mesh), MeshMaterial3d(material.clone())));
+    }
+}
+
+// looking at the boundary's center from the view angle, framed on its
+// corners so that every case of the same size lines up
+fn spawn_camera(world: &mut World, case: &GoldenCase, target: Handle<Image>) {
+    let view = Quat::from_euler(EulerRot::YXZ, VIEW_YAW, VIEW_PITCH, 0.);
+    let turn = case.boundary.transform.rotation;
+    let half = case.boundary.transform.scale / 2.;
+    let extent = (0..8)
+        .map(|i| {
+            let sign = Vec3::new(
+                if i & 1 == 0 { -1. } else { 1. },
+                if i & 2 == 0 { -1. } else { 1. },
+                if i & 4 == 0 { -1. } else { 1. },
+            );
+            (view * turn * (sign * half)).truncate().abs()
+        })
+        .fold(Vec2::ZERO, Vec2::max)
+        .max_element()
+        * 2.;
+    let size = extent * IMAGE_SIZE as f32 / (IMAGE_SIZE as f32 - MARGIN * 2.);
+
+    // the view turns the world to face the camera - the camera is turned the
+    // other way and backed off along what ends up as its z
+    let rotation = view.inverse();
+    let center = case.boundary.transform.translation;
+
+    world.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Image(target),
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width:  size,
+                height: size,
+            },
+            far: 2000.,
+            ..OrthographicProjection::default_3d()
+        }),
+        Tonemapping::None,
+        Msaa::Off,
+        Transform::from_translation(center + rotation * Vec3::Z * 1000.).with_rotation(rotation),
+    ));
+}
+
+fn close(a: &Rgba<u8>, b: &Rgba<u8>) -> bool {
+    a.0.iter()
+        .zip(b.0.iter())
+        .all(|(a, b)| a.abs_diff(*b) <= CHANNEL_TOLERANCE)
+}
+
+// does anything within a pixel of (x, y) in `other` look like `pixel`
+fn matches_nearby(pixel: &Rgba<u8>, other: &RgbaImage, x: u32, y: u32) -> bool {
+    (-1..=1).any(|dy: i32| {
+        (-1..=1).any(|dx: i32| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            nx >= 0
+                && ny >= 0
+                && (nx as u32) < other.width()
+                && (ny as u32) < other.height()
+                && close(pixel, other.get_pixel(nx as u32, ny as u32))
+        })
+    })
+}
+
+// checked both ways - a line that's gone missing is as bad as a new one. the
+// diff is the golden image dimmed with every mismatched pixel in red
+fn compare(rendered: &RgbaImage, golden: &RgbaImage) -> Result<(), (String, Option<RgbaImage>)> {
+    if rendered.dimensions() != golden.dimensions() {
+        return Err((
+            format!(
+                "size {:?} doesn't match the golden {:?}",
+                rendered.dimensions(),
+                golden.dimensions()
+            ),
+            None,
+        ));
+    }
+
+    let mut diff = RgbaImage::new(golden.width(), golden.height());
+    let mut mismatched = 0;
+
+    for (x, y, golden_pixel) in golden.enumerate_pixels() {
+        let rendered_pixel = rendered.get_pixel(x, y);
+        let ok = matches_nearby(rendered_pixel, golden, x, y) && matches_nearby(golden_pixel, rendered, x, y);
+
+        let pixel = if ok {
+            Rgba([golden_pixel[0] / 3, golden_pixel[1] / 3, golden_pixel[2] / 3, 255])
+        } else {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        };
+        diff.put_pixel(x, y, pixel);
+    }
+
+    let fraction = mismatched as f32 / (golden.width() * golden.height()) as f32;
+    if fraction > MAX_MISMATCHED_FRACTION {
+        Err((
+            format!(
+                "{mismatched} pixels differ ({:.3}%) - see {FAILED_DIR}",
+                fraction * 100.
+            ),
+            Some(diff),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn write_failure(name: &str, rendered: &RgbaImage, diff: Option<&RgbaImage>) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(FAILED_DIR);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        println!("unable to create {}: {e}", dir.display());
+        return;
+    }
+
+    let _ = rendered.save(dir.join(format!("{name}.png")));
+    if let Some(diff) = diff {
+        let _ = diff.save(dir.join(format!("{name}.diff.png")));
+    }
+}
@@ -0,0 +1,48 @@
+// every replay in tests/replays has to play back the way it was recorded -
+// the same checksums all the way through and the same score at the end. each
+// one is a headless run, see HeadlessPlugin - a minute of the autopilot is
+// long enough to score and short enough to play back in a debug build. a new
+// one is recorded with
+//
+// `cargo run --example balance_sim -- --seed 7 --seconds 60 --record tests/replays/7.ntr`
+//
+// 11.ntr was recorded the same way with `--seed 11 --mutator "fast missiles"
+// --mutator "giant rocks"`, so the mutators get played back too. they go
+// stale whenever the simulation changes on purpose - record them again then.
+// a replay that fails without anyone meaning to change anything is the
+// thing this is here to catch
+use bevy::app::AppExit;
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/replays");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == "ntr"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn recorded_replays_play_back_the_same() {
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "there are no replays in tests/replays");
+
+    for path in fixtures {
+        let path = path.to_string_lossy();
+        let exit = nateroids::headless_app(&["--replay", &path]).run();
+        assert_eq!(
+            exit,
+            AppExit::Success,
+            "{path} didn't play back the way it was recorded - its line above says how"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// an hour of the headless game, one game after another, to catch anything
+// that piles up over a long session - trails, popups, portals, events that
+// nobody reads. it's the same run as
+//
+// `cargo run --release --features headless -- --seconds 3600 --soak`
+//
+// with the counts checked along the way as well as the memory budgets at the
+// end. it takes a while, so it only runs when asked:
+//
+// `cargo test --release --test soak -- --ignored`
+//
+// NATEROIDS_SOAK_SECONDS makes it shorter (or longer)
+use bevy::{
+    app::{
+        AppExit,
+        PluginsState,
+    },
+    prelude::*,
+};
+use nateroids::{
+    ActorPortals,
+    EntityTeleported,
+};
+
+const SOAK_SECONDS: &str = "3600";
+// the first part of the run sets what the counts should stay around. the
+// nateroids keep arriving for the first few minutes, each one a scene of a
+// hundred and more entities, so it's five minutes - or the first half of a
+// run shorter than ten
+const WARM_UP_SECONDS: f32 = 300.;
+// later on they can go this far past the warm up's peak, since a busy moment
+// late in a game is allowed to be busier than any early one - a leak keeps
+// going well past it
+const GROWTH_ALLOWED: usize = 2;
+const SLACK: usize = 50;
+const SAMPLE_EVERY: u32 = 60;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counts {
+    entities: usize,
+    portals:  usize,
+    wraps:    usize,
+}
+
+impl Counts {
+    fn of(world: &mut World) -> Self {
+        let portals = world
+            .query::<&ActorPortals>()
+            .iter(world)
+            .map(|portals| usize::from(portals.approaching.is_some()) + usize::from(portals.emerging.is_some()))
+            .sum();
+        Self {
+            entities: world.entities().len() as usize,
+            portals,
+            wraps: world.resource::<Events<EntityTeleported>>().len(),
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            entities: self.entities.max(other.entities),
+            portals:  self.portals.max(other.portals),
+            wraps:    self.wraps.max(other.wraps),
+        }
+    }
+}
+
+fn bounded(count: usize, peak: usize) -> bool { count <= peak * GROWTH_ALLOWED + SLACK }
+
+#[test]
+#[ignore = "an hour of game time - run it with --ignored"]
+fn counts_stay_bounded_for_an_hour() {
+    let seconds = std::env::var("NATEROIDS_SOAK_SECONDS").unwrap_or_else(|_| SOAK_SECONDS.to_string());
+    let run_seconds = seconds.parse::<f32>().expect("NATEROIDS_SOAK_SECONDS needs a number");
+    let mut app = nateroids::headless_app(&["--soak", "--seconds", &seconds]);
+
+    // what App::run does before the first update
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+
+    let warm_up_frames = (WARM_UP_SECONDS.min(run_seconds / 2.) * 60.) as u32;
+    let mut frame = 0;
+    let mut warm_up_peak = Counts::default();
+    let exit = loop {
+        app.update();
+        if let Some(exit) = app.should_exit() {
+            break exit;
+        }
+
+        frame += 1;
+        if frame % SAMPLE_EVERY != 0 {
+            continue;
+        }
+        let counts = Counts::of(app.world_mut());
+        if frame <= warm_up_frames {
+            warm_up_peak = warm_up_peak.max(counts);
+            continue;
+        }
+
+        assert!(
+            bounded(counts.entities, warm_up_peak.entities)
+                && bounded(counts.portals, warm_up_peak.portals)
+                && bounded(counts.wraps, warm_up_peak.wraps),
+            "frame {frame}: {counts:?} has grown well past the warm up's {warm_up_peak:?}"
+        );
+    };
+
+    assert_eq!(
+        exit,
+        AppExit::Success,
+        "the run went over a memory budget - its line above says which"
+    );
+}
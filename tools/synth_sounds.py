@@ -0,0 +1,389 @@
+#!/usr/bin/env python3
+# makes every sound under assets/sounds and assets/music - they're all
+# synthesized, so nothing here needs licensing and they can be changed by
+# changing the numbers. standard library only:
+#
+#   python3 tools/synth_sounds.py
+#
+# mono 16 bit wav at 22050hz. the music loops are cut exactly on the bar so
+# PlaybackSettings::LOOP goes round without a gap, and the danger stem is the
+# same length as gameplay so the two stay locked together - see
+# src/audio/music.rs
+
+import math
+import os
+import random
+import struct
+import wave
+
+RATE = 22050
+ROOT = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "assets")
+
+# 4 bars of 4/4 at 120bpm
+BEAT = 0.5
+LOOP = 16 * BEAT
+
+
+def samples(seconds):
+    return int(round(seconds * RATE))
+
+
+def write(path, data, peak=0.8):
+    top = max(1e-9, max(abs(x) for x in data))
+    scale = peak / top
+    full = os.path.join(ROOT, path)
+    os.makedirs(os.path.dirname(full), exist_ok=True)
+    with wave.open(full, "wb") as out:
+        out.setnchannels(1)
+        out.setsampwidth(2)
+        out.setframerate(RATE)
+        out.writeframes(b"".join(struct.pack("<h", int(max(-1.0, min(1.0, x * scale)) * 32767)) for x in data))
+    print(f"{path}: {len(data) / RATE:.2f}s")
+
+
+def silence(seconds):
+    return [0.0] * samples(seconds)
+
+
+def mix(into, sound, at=0.0, gain=1.0):
+    start = samples(at)
+    for i, x in enumerate(sound):
+        j = start + i
+        if j >= len(into):
+            break
+        into[j] += x * gain
+    return into
+
+
+def envelope(n, attack, release, curve=1.0):
+    # attack and release in seconds, linear up and a curved decay down
+    a = max(1, samples(attack))
+    r = max(1, samples(release))
+    out = []
+    for i in range(n):
+        if i < a:
+            out.append(i / a)
+        elif i >= n - r:
+            out.append(((n - i) / r) ** curve)
+        else:
+            out.append(1.0)
+    return out
+
+
+def decay(n, seconds):
+    return [math.exp(-i / (seconds * RATE)) for i in range(n)]
+
+
+def sweep(seconds, start_hz, end_hz, shape="sine", exponential=True):
+    n = samples(seconds)
+    phase = 0.0
+    out = []
+    for i in range(n):
+        t = i / max(1, n - 1)
+        if exponential:
+            hz = start_hz * (end_hz / start_hz) ** t
+        else:
+            hz = start_hz + (end_hz - start_hz) * t
+        phase += hz / RATE
+        out.append(wave_shape(shape, phase))
+    return out
+
+
+def tone(seconds, hz, shape="sine", detune=0.0):
+    n = samples(seconds)
+    out = []
+    for i in range(n):
+        phase = i * hz / RATE
+        x = wave_shape(shape, phase)
+        if detune:
+            x = 0.5 * (x + wave_shape(shape, i * hz * (1 + detune) / RATE))
+        out.append(x)
+    return out
+
+
+def wave_shape(shape, phase):
+    p = phase % 1.0
+    if shape == "sine":
+        return math.sin(2 * math.pi * p)
+    if shape == "square":
+        return 1.0 if p < 0.5 else -1.0
+    if shape == "saw":
+        return 2.0 * p - 1.0
+    if shape == "triangle":
+        return 4.0 * abs(p - 0.5) - 1.0
+    raise ValueError(shape)
+
+
+def noise(seconds, rng):
+    return [rng.uniform(-1.0, 1.0) for _ in range(samples(seconds))]
+
+
+def lowpass(data, hz):
+    # one pole - plenty for taking the fizz off noise
+    k = 1.0 - math.exp(-2 * math.pi * hz / RATE)
+    y = 0.0
+    out = []
+    for x in data:
+        y += k * (x - y)
+        out.append(y)
+    return out
+
+
+def sweeping_lowpass(data, start_hz, end_hz):
+    n = len(data)
+    y = 0.0
+    out = []
+    for i, x in enumerate(data):
+        hz = start_hz * (end_hz / start_hz) ** (i / max(1, n - 1))
+        k = 1.0 - math.exp(-2 * math.pi * hz / RATE)
+        y += k * (x - y)
+        out.append(y)
+    return out
+
+
+def times(*parts):
+    return [math.prod(values) for values in zip(*parts)]
+
+
+def note_hz(semitones_from_a4):
+    return 440.0 * 2 ** (semitones_from_a4 / 12)
+
+
+# -- sound effects --------------------------------------------------------------
+
+
+def missile_fired():
+    zap = sweep(0.16, 1800, 220, "square")
+    body = sweep(0.16, 900, 140, "triangle")
+    n = len(zap)
+    return times([0.35 * a + 0.65 * b for a, b in zip(zap, body)], envelope(n, 0.002, 0.14, 2.0))
+
+
+def missile_hit(rng):
+    crack = lowpass(noise(0.14, rng), 3500)
+    knock = sweep(0.14, 420, 90)
+    n = len(crack)
+    return times([0.6 * a + 0.5 * b for a, b in zip(crack, knock)], decay(n, 0.035))
+
+
+def explosion(seconds, rng, thump_hz, brightness):
+    rumble = sweeping_lowpass(noise(seconds, rng), brightness, 120)
+    thump = sweep(seconds, thump_hz, 30)
+    n = len(rumble)
+    shaped = times([0.8 * a + 0.6 * b for a, b in zip(rumble, thump)], decay(n, seconds / 4))
+    return times(shaped, envelope(n, 0.004, seconds / 3))
+
+
+def ship_damaged(rng):
+    n = samples(0.35)
+    partials = [(310, 1.0), (587, 0.6), (893, 0.45), (1420, 0.3)]
+    clang = [0.0] * n
+    for hz, gain in partials:
+        mix(clang, times(tone(0.35, hz), decay(n, 0.09 + 0.02 * gain)), gain=gain)
+    grit = times(lowpass(noise(0.35, rng), 2500), decay(n, 0.03))
+    return [a + 0.5 * b for a, b in zip(clang, grit)]
+
+
+def heartbeat():
+    def beat(hz, seconds):
+        n = samples(seconds)
+        return times(sweep(seconds, hz * 1.6, hz), decay(n, seconds / 5), envelope(n, 0.003, 0.02))
+
+    out = silence(0.55)
+    mix(out, beat(70, 0.16), 0.0, 1.0)
+    mix(out, beat(58, 0.2), 0.2, 0.7)
+    return out
+
+
+def weapon_overheated(rng):
+    # a hiss of steam under two falling beeps
+    n = samples(0.5)
+    hiss = times(sweeping_lowpass(noise(0.5, rng), 6000, 900), decay(n, 0.15))
+    out = silence(0.5)
+    mix(out, hiss, 0.0, 0.5)
+    for at, hz in ((0.0, 880), (0.14, 620)):
+        beep = times(tone(0.1, hz, "square"), envelope(samples(0.1), 0.003, 0.05))
+        mix(out, beep, at, 0.35)
+    return out
+
+
+def shield_broken(rng):
+    # glass - a crack up front and a shimmer that falls away
+    n = samples(0.7)
+    out = [0.0] * n
+    for hz, gain in [(1320, 0.5), (1980, 0.3), (2790, 0.2)]:
+        mix(out, times(sweep(0.7, hz, hz * 0.5), decay(n, 0.18)), gain=gain)
+    crack = times(lowpass(noise(0.08, rng), 5000), decay(samples(0.08), 0.015))
+    mix(out, crack, 0.0, 0.8)
+    return out
+
+
+def wrap_pop(variant):
+    # a bright pop as something comes back out of a portal
+    start = [300, 340, 270][variant]
+    end = [1400, 1250, 1550][variant]
+    body = sweep(0.12 + 0.02 * variant, start, end, "sine")
+    n = len(body)
+    return times(body, envelope(n, 0.004, 0.09, 2.0))
+
+
+def wrap_suck(variant, rng):
+    # air going down a drain - falling, filtered noise with a tone under it
+    seconds = 0.22 + 0.03 * variant
+    air = sweeping_lowpass(noise(seconds, rng), 4000 - 600 * variant, 300)
+    under = sweep(seconds, [900, 1000, 820][variant], 160)
+    n = len(air)
+    return times([0.7 * a + 0.4 * b for a, b in zip(air, under)], envelope(n, seconds * 0.6, seconds * 0.35))
+
+
+def ui_blip(notes):
+    out = []
+    for hz in notes:
+        part = tone(0.07, hz, "triangle")
+        out += times(part, envelope(len(part), 0.003, 0.05))
+    return out
+
+
+def ui_toggle(rng):
+    click = times(lowpass(noise(0.03, rng), 5000), decay(samples(0.03), 0.004))
+    tick = tone(0.03, 2200, "sine")
+    return [a + 0.4 * b for a, b in zip(click, times(tick, decay(len(tick), 0.008)))]
+
+
+def engine():
+    # a whole number of cycles of everything so the loop has no seam - 2 seconds
+    # of 55hz, 110hz and a slow 2hz wobble
+    seconds = 2.0
+    n = samples(seconds)
+    out = []
+    for i in range(n):
+        t = i / RATE
+        wobble = 1.0 + 0.15 * math.sin(2 * math.pi * 2 * t)
+        x = 0.6 * wave_shape("saw", 55 * t) + 0.35 * wave_shape("square", 110 * t) + 0.2 * math.sin(2 * math.pi * 165 * t)
+        out.append(x * wobble)
+    # filter twice round so the start of the loop sees the end's filter state
+    warm = lowpass(out + out, 700)[n:]
+    return warm
+
+
+# -- music ----------------------------------------------------------------------
+
+
+def pluck(seconds, hz, shape="saw", brightness=2400):
+    n = samples(seconds)
+    return times(lowpass(tone(seconds, hz, shape, detune=0.004), brightness), decay(n, seconds / 3), envelope(n, 0.005, 0.05))
+
+
+def pad(seconds, chord):
+    n = samples(seconds)
+    out = [0.0] * n
+    for hz in chord:
+        mix(out, lowpass(tone(seconds, hz, "saw", detune=0.006), 900), gain=1.0 / len(chord))
+    return times(out, envelope(n, seconds * 0.3, seconds * 0.4))
+
+
+def kick():
+    n = samples(0.25)
+    return times(sweep(0.25, 150, 42), decay(n, 0.07))
+
+
+def hat(rng, seconds=0.05):
+    n = samples(seconds)
+    hiss = noise(seconds, rng)
+    # a crude high pass - the noise minus its own low end
+    low = lowpass(hiss, 6000)
+    return times([a - b for a, b in zip(hiss, low)], decay(n, seconds / 4))
+
+
+# i - vi - iv - v in a minor-ish key, one bar each
+PROGRESSION = [
+    [-12, -9, -5],  # a
+    [-16, -12, -9],  # f
+    [-19, -15, -12],  # d
+    [-17, -13, -10],  # e
+]
+
+
+def menu_music():
+    out = silence(LOOP)
+    bar = 4 * BEAT
+    for i, chord in enumerate(PROGRESSION):
+        mix(out, pad(bar, [note_hz(s) for s in chord]), i * bar, 0.7)
+        # a slow arpeggio over the top, on the beat
+        for beat in range(4):
+            s = chord[beat % 3] + 12
+            mix(out, pluck(BEAT, note_hz(s), "triangle", 1800), i * bar + beat * BEAT, 0.25)
+    return out
+
+
+def gameplay_music(rng):
+    out = silence(LOOP)
+    bar = 4 * BEAT
+    eighth = BEAT / 2
+    for i, chord in enumerate(PROGRESSION):
+        root = chord[0] - 12
+        for step in range(8):
+            at = i * bar + step * eighth
+            # driving eighth note bass, octave up on the off beats
+            mix(out, pluck(eighth, note_hz(root + (12 if step % 2 else 0)), "saw", 700), at, 0.45)
+            if step % 4 == 0:
+                mix(out, kick(), at, 0.7)
+            mix(out, hat(rng), at + eighth / 2, 0.12)
+        for step in range(8):
+            s = chord[step % 3] + (12 if step >= 4 else 0)
+            mix(out, pluck(eighth, note_hz(s), "square", 1600), i * bar + step * eighth, 0.12)
+    return out
+
+
+def danger_stem(rng):
+    # sixteenths and a pulsing high line - it only ever plays on top of
+    # gameplay, so it's the same length and in the same key
+    out = silence(LOOP)
+    bar = 4 * BEAT
+    sixteenth = BEAT / 4
+    for i, chord in enumerate(PROGRESSION):
+        for step in range(16):
+            at = i * bar + step * sixteenth
+            mix(out, hat(rng, 0.03), at, 0.18 if step % 4 else 0.3)
+            s = chord[(step // 2) % 3] + 24
+            mix(out, pluck(sixteenth, note_hz(s), "saw", 3000), at, 0.14)
+    return out
+
+
+def game_over_sting():
+    out = silence(2.8)
+    # falling, then a low minor chord left to ring
+    for i, s in enumerate([0, -2, -4, -5]):
+        mix(out, pluck(0.35, note_hz(s), "saw", 1800), i * 0.25, 0.5)
+    mix(out, pad(1.8, [note_hz(s) for s in (-24, -21, -17)]), 1.0, 0.9)
+    return out
+
+
+def main():
+    rng = random.Random(7)
+
+    write("sounds/missile_fired.wav", missile_fired())
+    write("sounds/missile_hit.wav", missile_hit(rng))
+    write("sounds/nateroid_destroyed.wav", explosion(0.8, rng, 110, 2600))
+    write("sounds/ship_damaged.wav", ship_damaged(rng))
+    write("sounds/ship_destroyed.wav", explosion(1.6, rng, 80, 4000), peak=0.9)
+    write("sounds/heartbeat.wav", heartbeat())
+    # their own rng so adding them didn't change any of the others
+    write("sounds/weapon_overheated.wav", weapon_overheated(random.Random(3)), peak=0.7)
+    write("sounds/shield_broken.wav", shield_broken(random.Random(5)), peak=0.8)
+    write("sounds/engine.wav", engine(), peak=0.6)
+    for variant in range(3):
+        write(f"sounds/wrap_pop_{variant + 1}.wav", wrap_pop(variant), peak=0.6)
+        write(f"sounds/wrap_suck_{variant + 1}.wav", wrap_suck(variant, rng), peak=0.6)
+    write("sounds/ui_pause_opened.wav", ui_blip([660, 990]), peak=0.5)
+    write("sounds/ui_pause_closed.wav", ui_blip([990, 660]), peak=0.5)
+    write("sounds/ui_toggle.wav", ui_toggle(rng), peak=0.5)
+
+    write("music/menu.wav", menu_music(), peak=0.7)
+    write("music/gameplay.wav", gameplay_music(rng), peak=0.7)
+    write("music/danger.wav", danger_stem(rng), peak=0.6)
+    write("music/game_over.wav", game_over_sting(), peak=0.7)
+
+
+if __name__ == "__main__":
+    main()